@@ -4,14 +4,17 @@
 // for use in tests without requiring actual portal/PipeWire integration.
 
 use crate::capture::{
-    CaptureBackend, CaptureBackendError, RecordingResult, ScreenshotResult, SelectionResult,
+    timelapse, CaptureBackend, CaptureBackendError, RecordingResult, SelectionResult,
 };
 use crate::config::CaptureConfig;
-use image::{ImageBuffer, Rgb};
+use image::RgbaImage;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 
+use super::frames::{placeholder_frame, RECORDING_SIZE};
+use super::FakeOperation;
+
 /// Configurable fake backend for testing
 #[derive(Debug, Clone)]
 pub struct FakeCaptureBackend {
@@ -25,10 +28,14 @@ pub struct FakeCaptureBackend {
     selection_count: Arc<AtomicU32>,
     /// Count of cancel requests
     cancel_count: Arc<AtomicU32>,
+    /// Count of close_session calls
+    close_session_count: Arc<AtomicU32>,
     /// Whether recording is in progress
-    is_recording: Arc<AtomicBool>,
+    pub(super) is_recording: Arc<AtomicBool>,
     /// Whether recording is paused
     is_paused: Arc<AtomicBool>,
+    /// Whether the current recording is audio-only
+    pub(super) is_audio_only: Arc<AtomicBool>,
     /// Recording start time (for duration calculation)
     recording_start: Arc<std::sync::Mutex<Option<std::time::Instant>>>,
     /// Output path for fake recording
@@ -41,6 +48,14 @@ pub struct FakeCaptureBackend {
     pause_recording_count: Arc<AtomicU32>,
     /// Count of resume_recording calls
     resume_recording_count: Arc<AtomicU32>,
+    /// Replay buffer start time and window in seconds (if buffering)
+    replay_buffer: Arc<std::sync::Mutex<Option<(std::time::Instant, u32)>>>,
+    /// Count of save_replay calls
+    save_replay_count: Arc<AtomicU32>,
+    /// Count of capture_recording_frame calls
+    pub(super) recording_frame_count: Arc<AtomicU32>,
+    /// Operations made to fail by `fail_operation`
+    pub(super) failing_operations: Arc<std::sync::Mutex<Vec<FakeOperation>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -64,14 +79,20 @@ impl FakeCaptureBackend {
             fake_node_id: Arc::new(AtomicU32::new(42)),
             selection_count: Arc::new(AtomicU32::new(0)),
             cancel_count: Arc::new(AtomicU32::new(0)),
+            close_session_count: Arc::new(AtomicU32::new(0)),
             is_recording: Arc::new(AtomicBool::new(false)),
             is_paused: Arc::new(AtomicBool::new(false)),
+            is_audio_only: Arc::new(AtomicBool::new(false)),
             recording_start: Arc::new(std::sync::Mutex::new(None)),
             recording_output_path: Arc::new(std::sync::Mutex::new(None)),
             start_recording_count: Arc::new(AtomicU32::new(0)),
             stop_recording_count: Arc::new(AtomicU32::new(0)),
             pause_recording_count: Arc::new(AtomicU32::new(0)),
             resume_recording_count: Arc::new(AtomicU32::new(0)),
+            replay_buffer: Arc::new(std::sync::Mutex::new(None)),
+            save_replay_count: Arc::new(AtomicU32::new(0)),
+            recording_frame_count: Arc::new(AtomicU32::new(0)),
+            failing_operations: Arc::new(std::sync::Mutex::new(Vec::new())),
         }
     }
 
@@ -118,6 +139,11 @@ impl FakeCaptureBackend {
         self.cancel_count.load(Ordering::SeqCst)
     }
 
+    /// Get count of close_session calls
+    pub fn close_session_count(&self) -> u32 {
+        self.close_session_count.load(Ordering::SeqCst)
+    }
+
     /// Get count of start_recording calls
     pub fn start_recording_count(&self) -> u32 {
         self.start_recording_count.load(Ordering::SeqCst)
@@ -147,6 +173,21 @@ impl FakeCaptureBackend {
     pub fn is_paused(&self) -> bool {
         self.is_paused.load(Ordering::SeqCst)
     }
+
+    /// Check if the current recording is audio-only
+    pub fn is_audio_only(&self) -> bool {
+        self.is_audio_only.load(Ordering::SeqCst)
+    }
+
+    /// Check if the replay buffer is running
+    pub fn is_buffering(&self) -> bool {
+        self.replay_buffer.lock().unwrap().is_some()
+    }
+
+    /// Get count of save_replay calls
+    pub fn save_replay_count(&self) -> u32 {
+        self.save_replay_count.load(Ordering::SeqCst)
+    }
 }
 
 impl CaptureBackend for FakeCaptureBackend {
@@ -184,11 +225,14 @@ impl CaptureBackend for FakeCaptureBackend {
         Ok(())
     }
 
+    async fn close_session(&self) {
+        self.close_session_count.fetch_add(1, Ordering::SeqCst);
+    }
+
     async fn capture_screenshot(
         &self,
         selection: &SelectionResult,
-        output_path: &Path,
-    ) -> Result<ScreenshotResult, CaptureBackendError> {
+    ) -> Result<RgbaImage, CaptureBackendError> {
         if !self.should_succeed.load(Ordering::SeqCst) {
             let error = self.error_type.lock().unwrap().clone();
             return Err(match error {
@@ -207,21 +251,11 @@ impl CaptureBackend for FakeCaptureBackend {
         // Use dimensions from selection if available, otherwise default
         let width = selection.width.unwrap_or(100);
         let height = selection.height.unwrap_or(100);
+        Ok(placeholder_frame(width, height))
+    }
 
-        // Generate a solid-color placeholder PNG (cornflower blue)
-        let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_fn(width, height, |_, _| {
-            Rgb([100u8, 149u8, 237u8]) // cornflower blue
-        });
-
-        img.save(output_path).map_err(|e| {
-            CaptureBackendError::Internal(format!("Failed to save placeholder PNG: {}", e))
-        })?;
-
-        Ok(ScreenshotResult {
-            path: output_path.to_string_lossy().to_string(),
-            width,
-            height,
-        })
+    async fn capture_recording_frame(&self) -> Result<RgbaImage, CaptureBackendError> {
+        self.grab_recording_frame()
     }
 
     async fn start_recording(
@@ -230,6 +264,7 @@ impl CaptureBackend for FakeCaptureBackend {
         config: &CaptureConfig,
     ) -> Result<(), CaptureBackendError> {
         self.start_recording_count.fetch_add(1, Ordering::SeqCst);
+        self.injected_failure(FakeOperation::StartRecording)?;
 
         if !self.should_succeed.load(Ordering::SeqCst) {
             let error = self.error_type.lock().unwrap().clone();
@@ -255,7 +290,7 @@ impl CaptureBackend for FakeCaptureBackend {
         // Store recording state
         self.is_recording.store(true, Ordering::SeqCst);
         *self.recording_start.lock().unwrap() = Some(std::time::Instant::now());
-        *self.recording_output_path.lock().unwrap() = Some(config.output_path.clone());
+        *self.recording_output_path.lock().unwrap() = Some(config.recording_path());
 
         // Store dimensions for later use (we don't actually record, just track state)
         let _ = selection; // acknowledge we received it
@@ -263,8 +298,35 @@ impl CaptureBackend for FakeCaptureBackend {
         Ok(())
     }
 
+    async fn start_audio_recording(
+        &self,
+        config: &CaptureConfig,
+    ) -> Result<(), CaptureBackendError> {
+        self.start_recording_count.fetch_add(1, Ordering::SeqCst);
+
+        if !self.should_succeed.load(Ordering::SeqCst) {
+            return Err(CaptureBackendError::Internal(
+                "No audio source available".to_string(),
+            ));
+        }
+
+        if self.is_recording.load(Ordering::SeqCst) {
+            return Err(CaptureBackendError::Internal(
+                "Recording already in progress".to_string(),
+            ));
+        }
+
+        self.is_recording.store(true, Ordering::SeqCst);
+        self.is_audio_only.store(true, Ordering::SeqCst);
+        *self.recording_start.lock().unwrap() = Some(std::time::Instant::now());
+        *self.recording_output_path.lock().unwrap() = Some(config.recording_path());
+
+        Ok(())
+    }
+
     async fn stop_recording(&self) -> Result<RecordingResult, CaptureBackendError> {
         self.stop_recording_count.fetch_add(1, Ordering::SeqCst);
+        self.injected_failure(FakeOperation::StopRecording)?;
 
         if !self.is_recording.load(Ordering::SeqCst) {
             return Err(CaptureBackendError::Internal(
@@ -287,21 +349,32 @@ impl CaptureBackend for FakeCaptureBackend {
             .take()
             .unwrap_or_else(|| "/tmp/fake_recording.mp4".to_string());
 
+        // Audio-only recordings have no video dimensions
+        let (width, height) = if self.is_audio_only.load(Ordering::SeqCst) {
+            (0, 0)
+        } else {
+            RECORDING_SIZE
+        };
+
         // Reset recording state
         self.is_recording.store(false, Ordering::SeqCst);
         self.is_paused.store(false, Ordering::SeqCst);
+        self.is_audio_only.store(false, Ordering::SeqCst);
         *self.recording_start.lock().unwrap() = None;
 
         Ok(RecordingResult {
             path: output_path,
             duration_ms,
-            width: 1920,
-            height: 1080,
+            width,
+            height,
+            max_av_drift_ms: None,
+            segments: Vec::new(),
         })
     }
 
     async fn pause_recording(&self) -> Result<(), CaptureBackendError> {
         self.pause_recording_count.fetch_add(1, Ordering::SeqCst);
+        self.injected_failure(FakeOperation::PauseRecording)?;
 
         if !self.is_recording.load(Ordering::SeqCst) {
             return Err(CaptureBackendError::Internal(
@@ -321,6 +394,7 @@ impl CaptureBackend for FakeCaptureBackend {
 
     async fn resume_recording(&self) -> Result<(), CaptureBackendError> {
         self.resume_recording_count.fetch_add(1, Ordering::SeqCst);
+        self.injected_failure(FakeOperation::ResumeRecording)?;
 
         if !self.is_recording.load(Ordering::SeqCst) {
             return Err(CaptureBackendError::Internal(
@@ -337,4 +411,83 @@ impl CaptureBackend for FakeCaptureBackend {
         self.is_paused.store(false, Ordering::SeqCst);
         Ok(())
     }
+
+    async fn start_replay_buffer(
+        &self,
+        _selection: &SelectionResult,
+        config: &CaptureConfig,
+    ) -> Result<(), CaptureBackendError> {
+        if !self.should_succeed.load(Ordering::SeqCst) {
+            return Err(CaptureBackendError::PortalError(
+                "Replay buffer portal error".to_string(),
+            ));
+        }
+
+        let mut replay = self.replay_buffer.lock().unwrap();
+        if replay.is_some() || self.is_recording.load(Ordering::SeqCst) {
+            return Err(CaptureBackendError::Internal(
+                "Recording already in progress".to_string(),
+            ));
+        }
+
+        *replay = Some((std::time::Instant::now(), config.replay_buffer_secs));
+        Ok(())
+    }
+
+    async fn save_replay(
+        &self,
+        output_path: &Path,
+    ) -> Result<RecordingResult, CaptureBackendError> {
+        self.save_replay_count.fetch_add(1, Ordering::SeqCst);
+
+        let (started, window_secs) = self.replay_buffer.lock().unwrap().ok_or_else(|| {
+            CaptureBackendError::Internal("Replay buffer is not running".to_string())
+        })?;
+
+        // A replay holds at most the window, less if buffering started recently
+        let duration_ms = (started.elapsed().as_millis() as u64).min(u64::from(window_secs) * 1000);
+
+        Ok(RecordingResult {
+            path: output_path.to_string_lossy().to_string(),
+            duration_ms,
+            width: 1920,
+            height: 1080,
+            max_av_drift_ms: None,
+            segments: Vec::new(),
+        })
+    }
+
+    async fn stop_replay_buffer(&self) -> Result<(), CaptureBackendError> {
+        self.injected_failure(FakeOperation::StopReplayBuffer)?;
+        self.replay_buffer
+            .lock()
+            .unwrap()
+            .take()
+            .map(|_| ())
+            .ok_or_else(|| {
+                CaptureBackendError::Internal("Replay buffer is not running".to_string())
+            })
+    }
+
+    async fn encode_timelapse(
+        &self,
+        frames_dir: &Path,
+        frame_count: u32,
+        config: &CaptureConfig,
+    ) -> Result<RecordingResult, CaptureBackendError> {
+        // Frames are saved from capture_screenshot, so read their size back
+        let first = timelapse::frame_path(frames_dir, 1);
+        let (width, height) = image::image_dimensions(&first).map_err(|e| {
+            CaptureBackendError::Internal(format!("No timelapse frames to encode: {}", e))
+        })?;
+
+        Ok(RecordingResult {
+            path: config.output_path.clone(),
+            duration_ms: u64::from(frame_count) * 1000 / u64::from(config.fps.max(1)),
+            width,
+            height,
+            max_av_drift_ms: None,
+            segments: Vec::new(),
+        })
+    }
 }
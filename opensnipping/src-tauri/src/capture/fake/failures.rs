@@ -0,0 +1,50 @@
+// Per-operation failure injection for FakeCaptureBackend
+//
+// `set_should_succeed` fails every call; these fail one backend operation
+// so tests can hit each failure point of a flow on its own.
+
+use super::FakeCaptureBackend;
+use crate::capture::CaptureBackendError;
+
+/// Backend operation that can be made to fail on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FakeOperation {
+    StartRecording,
+    StopRecording,
+    PauseRecording,
+    ResumeRecording,
+    CaptureRecordingFrame,
+    StopReplayBuffer,
+}
+
+impl FakeCaptureBackend {
+    /// Make every later call to `operation` fail with an internal error
+    pub fn fail_operation(&self, operation: FakeOperation) {
+        let mut failing = self.failing_operations.lock().unwrap();
+        if !failing.contains(&operation) {
+            failing.push(operation);
+        }
+    }
+
+    /// Let `operation` succeed again
+    pub fn restore_operation(&self, operation: FakeOperation) {
+        self.failing_operations
+            .lock()
+            .unwrap()
+            .retain(|failing| *failing != operation);
+    }
+
+    /// The injected error for `operation`, if it was made to fail
+    pub(super) fn injected_failure(
+        &self,
+        operation: FakeOperation,
+    ) -> Result<(), CaptureBackendError> {
+        if self.failing_operations.lock().unwrap().contains(&operation) {
+            return Err(CaptureBackendError::Internal(format!(
+                "Injected {:?} failure",
+                operation
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+// Still frames for FakeCaptureBackend
+//
+// Screenshots and frames grabbed from a running recording are solid-color
+// placeholder images of the selection or recording size.
+
+use std::sync::atomic::Ordering;
+
+use image::{Rgba, RgbaImage};
+
+use super::{FakeCaptureBackend, FakeOperation};
+use crate::capture::CaptureBackendError;
+
+/// Size of fake video recordings, as reported by `stop_recording`
+pub(super) const RECORDING_SIZE: (u32, u32) = (1920, 1080);
+
+impl FakeCaptureBackend {
+    /// Count of capture_recording_frame calls
+    pub fn recording_frame_count(&self) -> u32 {
+        self.recording_frame_count.load(Ordering::SeqCst)
+    }
+
+    /// `capture_recording_frame`: a placeholder frame of the running recording
+    pub(super) fn grab_recording_frame(&self) -> Result<RgbaImage, CaptureBackendError> {
+        self.recording_frame_count.fetch_add(1, Ordering::SeqCst);
+        self.injected_failure(FakeOperation::CaptureRecordingFrame)?;
+
+        if !self.is_recording.load(Ordering::SeqCst) {
+            return Err(CaptureBackendError::Internal(
+                "No recording in progress".to_string(),
+            ));
+        }
+        if self.is_audio_only.load(Ordering::SeqCst) {
+            return Err(CaptureBackendError::NotSupported(
+                "Audio-only recordings have no frames to capture".to_string(),
+            ));
+        }
+
+        let (width, height) = RECORDING_SIZE;
+        Ok(placeholder_frame(width, height))
+    }
+}
+
+/// A solid cornflower-blue frame of `width`x`height`
+pub(super) fn placeholder_frame(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba([100, 149, 237, 255]))
+}
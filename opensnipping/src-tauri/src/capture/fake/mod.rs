@@ -4,8 +4,11 @@
 // without requiring actual portal/PipeWire integration.
 
 mod backend;
+mod failures;
+mod frames;
 
 pub use backend::{FakeCaptureBackend, FakeError};
+pub use failures::FakeOperation;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,92 @@
+use super::*;
+
+// Audio-only recording tests
+
+#[tokio::test]
+async fn test_fake_backend_audio_recording_needs_no_selection() {
+    let backend = FakeCaptureBackend::succeeding();
+    let config = audio_only_config();
+
+    let result = backend.start_audio_recording(&config).await;
+    assert!(result.is_ok());
+    assert!(backend.is_recording());
+    assert!(backend.is_audio_only());
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(backend.start_recording_count(), 1);
+}
+
+#[tokio::test]
+async fn test_fake_backend_audio_recording_stop_has_no_dimensions() {
+    let backend = FakeCaptureBackend::succeeding();
+    let config = audio_only_config();
+
+    backend.start_audio_recording(&config).await.unwrap();
+    let result = backend.stop_recording().await.unwrap();
+
+    assert_eq!(result.path, config.output_path);
+    assert_eq!(result.width, 0);
+    assert_eq!(result.height, 0);
+    assert!(!backend.is_recording());
+    assert!(!backend.is_audio_only());
+}
+
+#[tokio::test]
+async fn test_fake_backend_audio_recording_pause_resume() {
+    let backend = FakeCaptureBackend::succeeding();
+    let config = audio_only_config();
+
+    backend.start_audio_recording(&config).await.unwrap();
+
+    backend.pause_recording().await.unwrap();
+    assert!(backend.is_paused());
+
+    backend.resume_recording().await.unwrap();
+    assert!(!backend.is_paused());
+    assert!(backend.is_recording());
+}
+
+#[tokio::test]
+async fn test_fake_backend_audio_recording_fails_if_already_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let selection = backend.request_selection(&test_config()).await.unwrap();
+
+    backend
+        .start_recording(&selection, &test_config())
+        .await
+        .unwrap();
+
+    let result = backend.start_audio_recording(&audio_only_config()).await;
+    assert!(matches!(
+        result.unwrap_err(),
+        CaptureBackendError::Internal(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_fake_backend_audio_recording_fails_when_configured() {
+    let backend = FakeCaptureBackend::permission_denied();
+
+    let result = backend.start_audio_recording(&audio_only_config()).await;
+    assert!(result.is_err());
+    assert!(!backend.is_recording());
+}
+
+#[tokio::test]
+async fn test_fake_backend_video_recording_after_audio_has_dimensions() {
+    let backend = FakeCaptureBackend::succeeding();
+
+    backend
+        .start_audio_recording(&audio_only_config())
+        .await
+        .unwrap();
+    backend.stop_recording().await.unwrap();
+
+    let selection = backend.request_selection(&test_config()).await.unwrap();
+    backend
+        .start_recording(&selection, &test_config())
+        .await
+        .unwrap();
+    let result = backend.stop_recording().await.unwrap();
+    assert_eq!(result.width, 1920);
+    assert_eq!(result.height, 1080);
+}
@@ -1,21 +1,27 @@
 use super::*;
 use crate::capture::{CaptureBackend, CaptureBackendError, SelectionResult};
-use crate::config::{AudioConfig, CaptureConfig, CaptureSource, ContainerFormat};
+use crate::config::{AudioConfig, CaptureConfig, CaptureMode, ContainerFormat, ScreenshotFormat};
 
 pub(super) fn test_config() -> CaptureConfig {
+    CaptureConfig::for_test("opensnipping-fake-test")
+}
+
+pub(super) fn audio_only_config() -> CaptureConfig {
     CaptureConfig {
-        source: CaptureSource::Screen,
-        fps: 30,
-        include_cursor: true,
         audio: AudioConfig {
-            system: false,
-            mic: false,
+            system: true,
+            mic: true,
         },
-        container: ContainerFormat::Mp4,
-        output_path: "/tmp/test.mp4".to_string(),
+        container: ContainerFormat::Ogg,
+        output_path: "/tmp/test.ogg".to_string(),
+        mode: CaptureMode::AudioOnly,
+        ..test_config()
     }
 }
 
+mod audio;
 mod recording;
+mod replay;
 mod screenshot;
 mod selection;
+mod timelapse;
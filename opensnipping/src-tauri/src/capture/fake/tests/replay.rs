@@ -0,0 +1,100 @@
+use super::*;
+use crate::config::CaptureMode;
+use std::path::Path;
+
+// Replay buffer tests
+
+fn replay_config() -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        replay_buffer_secs: 10,
+        ..test_config()
+    }
+}
+
+fn selection() -> SelectionResult {
+    SelectionResult {
+        node_id: 42,
+        stream_fd: None,
+        width: Some(1920),
+        height: Some(1080),
+    }
+}
+
+#[tokio::test]
+async fn test_fake_backend_replay_buffer_lifecycle() {
+    let backend = FakeCaptureBackend::succeeding();
+
+    backend
+        .start_replay_buffer(&selection(), &replay_config())
+        .await
+        .unwrap();
+    assert!(backend.is_buffering());
+    assert!(!backend.is_recording());
+
+    // Saving leaves the buffer running, so replays can be saved repeatedly
+    let first = backend.save_replay(Path::new("/tmp/replay-1.mp4")).await;
+    let second = backend.save_replay(Path::new("/tmp/replay-2.mp4")).await;
+    assert_eq!(first.unwrap().path, "/tmp/replay-1.mp4");
+    assert_eq!(second.unwrap().path, "/tmp/replay-2.mp4");
+    assert_eq!(backend.save_replay_count(), 2);
+    assert!(backend.is_buffering());
+
+    backend.stop_replay_buffer().await.unwrap();
+    assert!(!backend.is_buffering());
+}
+
+#[tokio::test]
+async fn test_fake_backend_replay_duration_is_capped_by_window() {
+    let backend = FakeCaptureBackend::succeeding();
+    let config = CaptureConfig {
+        replay_buffer_secs: 5,
+        ..replay_config()
+    };
+    backend
+        .start_replay_buffer(&selection(), &config)
+        .await
+        .unwrap();
+
+    let result = backend
+        .save_replay(Path::new("/tmp/replay.mp4"))
+        .await
+        .unwrap();
+    assert!(result.duration_ms <= 5_000);
+    assert!(result.segments.is_empty());
+}
+
+#[tokio::test]
+async fn test_fake_backend_save_replay_requires_buffer() {
+    let backend = FakeCaptureBackend::succeeding();
+
+    let result = backend.save_replay(Path::new("/tmp/replay.mp4")).await;
+    assert!(matches!(result, Err(CaptureBackendError::Internal(_))));
+    assert!(backend.stop_replay_buffer().await.is_err());
+}
+
+#[tokio::test]
+async fn test_fake_backend_replay_buffer_excludes_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend
+        .start_recording(&selection(), &test_config())
+        .await
+        .unwrap();
+
+    let result = backend
+        .start_replay_buffer(&selection(), &replay_config())
+        .await;
+    assert!(result.is_err());
+    assert!(!backend.is_buffering());
+}
+
+#[tokio::test]
+async fn test_fake_backend_replay_buffer_fails_when_configured() {
+    let backend = FakeCaptureBackend::portal_error();
+
+    let result = backend
+        .start_replay_buffer(&selection(), &replay_config())
+        .await;
+    assert!(matches!(result, Err(CaptureBackendError::PortalError(_))));
+    assert!(!backend.is_buffering());
+}
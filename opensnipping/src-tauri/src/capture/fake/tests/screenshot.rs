@@ -1,7 +1,7 @@
 use super::*;
 
 #[tokio::test]
-async fn test_fake_backend_screenshot_creates_file() {
+async fn test_fake_backend_screenshot_returns_frame() {
     let backend = FakeCaptureBackend::succeeding();
     let selection = SelectionResult {
         node_id: 42,
@@ -10,19 +10,12 @@ async fn test_fake_backend_screenshot_creates_file() {
         height: Some(48),
     };
 
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join(format!("test_screenshot_{}.png", uuid::Uuid::new_v4()));
-
-    let result = backend.capture_screenshot(&selection, &output_path).await;
+    let result = backend.capture_screenshot(&selection).await;
     assert!(result.is_ok());
 
-    let screenshot = result.unwrap();
-    assert_eq!(screenshot.width, 64);
-    assert_eq!(screenshot.height, 48);
-    assert!(std::path::Path::new(&screenshot.path).exists());
-
-    // Cleanup
-    let _ = std::fs::remove_file(&output_path);
+    let frame = result.unwrap();
+    assert_eq!(frame.dimensions(), (64, 48));
+    assert_eq!(frame.get_pixel(10, 10).0, [100, 149, 237, 255]);
 }
 
 #[tokio::test]
@@ -35,18 +28,12 @@ async fn test_fake_backend_screenshot_uses_default_dimensions() {
         height: None,
     };
 
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join(format!("test_screenshot_{}.png", uuid::Uuid::new_v4()));
-
-    let result = backend.capture_screenshot(&selection, &output_path).await;
+    let result = backend.capture_screenshot(&selection).await;
     assert!(result.is_ok());
 
-    let screenshot = result.unwrap();
-    assert_eq!(screenshot.width, 100); // default
-    assert_eq!(screenshot.height, 100); // default
-
-    // Cleanup
-    let _ = std::fs::remove_file(&output_path);
+    let frame = result.unwrap();
+    assert_eq!(frame.width(), 100); // default
+    assert_eq!(frame.height(), 100); // default
 }
 
 #[tokio::test]
@@ -59,24 +46,20 @@ async fn test_fake_backend_screenshot_fails_when_configured() {
         height: Some(100),
     };
 
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join(format!("test_screenshot_{}.png", uuid::Uuid::new_v4()));
-
-    let result = backend.capture_screenshot(&selection, &output_path).await;
+    let result = backend.capture_screenshot(&selection).await;
     assert!(result.is_err());
     assert!(matches!(
         result.unwrap_err(),
         CaptureBackendError::PermissionDenied(_)
     ));
-
-    // File should not exist
-    assert!(!output_path.exists());
 }
 
-/// Test that ScreenshotResult has all fields required for ScreenshotCompleteEvent emission
+/// Test that a saved frame has all fields required for ScreenshotCompleteEvent emission
 #[tokio::test]
 async fn test_screenshot_result_has_all_event_fields() {
+    use crate::config::CaptureSource;
     use crate::events::ScreenshotCompleteEvent;
+    use crate::screenshot::{save_screenshot, CaptureInfo};
 
     let backend = FakeCaptureBackend::succeeding();
     let selection = SelectionResult {
@@ -86,19 +69,19 @@ async fn test_screenshot_result_has_all_event_fields() {
         height: Some(600),
     };
 
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join(format!("test_screenshot_{}.png", uuid::Uuid::new_v4()));
-
-    let result = backend
-        .capture_screenshot(&selection, &output_path)
-        .await
-        .unwrap();
+    let frame = backend.capture_screenshot(&selection).await.unwrap();
+    let info = CaptureInfo::now(CaptureSource::Screen);
+    let result = save_screenshot(frame, info, &test_config().screenshot)
+        .unwrap()
+        .saved;
 
     // Verify we can construct a ScreenshotCompleteEvent from the result
     let event = ScreenshotCompleteEvent {
         path: result.path.clone(),
         width: result.width,
         height: result.height,
+        format: result.format,
+        byte_size: result.byte_size,
     };
 
     // Verify event has expected values
@@ -109,9 +92,11 @@ async fn test_screenshot_result_has_all_event_fields() {
     );
     assert_eq!(event.width, 800);
     assert_eq!(event.height, 600);
+    assert_eq!(event.format, ScreenshotFormat::Png);
+    assert!(event.byte_size > 0);
 
     // Cleanup
-    let _ = std::fs::remove_file(&output_path);
+    let _ = std::fs::remove_file(&result.path);
 }
 
 /// Test the full selection → screenshot flow (mirrors take_screenshot command logic)
@@ -127,21 +112,11 @@ async fn test_full_screenshot_flow_selection_to_capture() {
     assert_eq!(backend.selection_count(), 1);
 
     // Step 2: Capture screenshot using selection result
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join(format!("test_screenshot_{}.png", uuid::Uuid::new_v4()));
-
-    let screenshot = backend
-        .capture_screenshot(&selection, &output_path)
-        .await
-        .unwrap();
+    let frame = backend.capture_screenshot(&selection).await.unwrap();
 
     // Verify screenshot uses selection dimensions
-    assert_eq!(screenshot.width, selection.width.unwrap());
-    assert_eq!(screenshot.height, selection.height.unwrap());
-    assert!(std::path::Path::new(&screenshot.path).exists());
-
-    // Cleanup
-    let _ = std::fs::remove_file(&output_path);
+    assert_eq!(frame.width(), selection.width.unwrap());
+    assert_eq!(frame.height(), selection.height.unwrap());
 }
 
 /// Test that screenshot failure doesn't affect subsequent selection requests
@@ -157,9 +132,7 @@ async fn test_screenshot_failure_is_isolated() {
     backend.set_should_succeed(false);
 
     // Screenshot fails
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join(format!("test_screenshot_{}.png", uuid::Uuid::new_v4()));
-    let screenshot_result = backend.capture_screenshot(&selection1, &output_path).await;
+    let screenshot_result = backend.capture_screenshot(&selection1).await;
     assert!(screenshot_result.is_err());
 
     // Configure to succeed again
@@ -0,0 +1,74 @@
+use super::*;
+use crate::capture::timelapse::{frame_path, TimelapseSchedule};
+use crate::config::TimelapseConfig;
+use std::time::Duration;
+
+// Timelapse tests
+
+fn timelapse_config(frames_dir: &std::path::Path) -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::Timelapse,
+        fps: 10,
+        timelapse: Some(TimelapseConfig {
+            interval_secs: 5,
+            frame_count: 3,
+            encode_video: true,
+            frames_dir: Some(frames_dir.to_string_lossy().to_string()),
+        }),
+        ..test_config()
+    }
+}
+
+fn selection() -> SelectionResult {
+    SelectionResult {
+        node_id: 42,
+        stream_fd: None,
+        width: Some(64),
+        height: Some(48),
+    }
+}
+
+/// Drive the schedule the way the capture loop does, grabbing every due frame
+#[tokio::test]
+async fn test_fake_backend_timelapse_frames_and_encode() {
+    let frames_dir = std::env::temp_dir().join(format!("timelapse-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&frames_dir).unwrap();
+    let config = timelapse_config(&frames_dir);
+    let backend = FakeCaptureBackend::succeeding();
+
+    let mut schedule = TimelapseSchedule::new(config.timelapse.as_ref().unwrap());
+    while !schedule.is_complete() {
+        if let Some(index) = schedule.frame_due() {
+            let frame = backend.capture_screenshot(&selection()).await.unwrap();
+            frame.save(frame_path(&frames_dir, index)).unwrap();
+            schedule.frame_captured();
+        }
+        schedule.advance(Duration::from_secs(1), true);
+    }
+
+    for index in 1..=3 {
+        assert!(frame_path(&frames_dir, index).exists());
+    }
+    assert!(!frame_path(&frames_dir, 4).exists());
+
+    let result = backend
+        .encode_timelapse(&frames_dir, schedule.captured(), &config)
+        .await
+        .unwrap();
+    assert_eq!(result.path, config.output_path);
+    assert_eq!(result.duration_ms, 300);
+    assert_eq!((result.width, result.height), (64, 48));
+
+    std::fs::remove_dir_all(&frames_dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_fake_backend_timelapse_encode_without_frames_fails() {
+    let frames_dir = std::env::temp_dir().join(format!("timelapse-{}", uuid::Uuid::new_v4()));
+    let backend = FakeCaptureBackend::succeeding();
+
+    let result = backend
+        .encode_timelapse(&frames_dir, 3, &timelapse_config(&frames_dir))
+        .await;
+    assert!(matches!(result, Err(CaptureBackendError::Internal(_))));
+}
@@ -1,49 +1,34 @@
 use crate::capture::{
-    CaptureBackend, CaptureBackendError, RecordingResult, ScreenshotResult, SelectionResult,
+    CaptureBackend, CaptureBackendError, DriftCallback, RecordingResult, SegmentCallback,
+    SelectionResult, SessionClosedCallback,
 };
 use crate::config::{CaptureConfig, CaptureSource};
 use ashpd::desktop::screencast::{CursorMode, Screencast, SourceType};
-use ashpd::desktop::{PersistMode, Session};
-use std::os::fd::{AsRawFd, OwnedFd};
+use ashpd::desktop::PersistMode;
+use image::RgbaImage;
+use std::os::fd::AsRawFd;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info};
 
-use gstreamer::prelude::*;
-
-use super::RecordingPipeline;
+use super::screenshot::capture_frame;
+use super::session::ActiveSession;
+use super::{encode_timelapse_frames, RecordingPipeline, ReplayPipeline};
 
 pub struct LinuxCaptureBackend {
     /// Active screencast session (if any)
     pub(super) session: Arc<Mutex<Option<ActiveSession>>>,
     /// Active recording pipeline (if recording)
     pub(super) recording: Arc<Mutex<Option<RecordingPipeline>>>,
-}
-
-/// Holds an active screencast session
-pub(super) struct ActiveSession {
-    /// The ashpd screencast proxy - MUST be kept alive (leaked for 'static)
-    #[allow(dead_code)]
-    _screencast: &'static Screencast<'static>,
-    /// The ashpd session - MUST be kept alive for the stream to remain valid
-    #[allow(dead_code)]
-    _session: Session<'static, Screencast<'static>>,
-    /// PipeWire node ID (stored for future use in recording pipeline)
-    #[allow(dead_code)]
-    node_id: u32,
-    /// PipeWire remote fd - this is the key to keeping the stream alive
-    pipewire_fd: OwnedFd,
-}
-
-impl std::fmt::Debug for ActiveSession {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ActiveSession")
-            .field("node_id", &self.node_id)
-            .field("pipewire_fd", &self.pipewire_fd.as_raw_fd())
-            .finish()
-    }
+    /// Active replay buffer (if buffering)
+    pub(super) replay: Arc<Mutex<Option<ReplayPipeline>>>,
+    /// Handed to each segmented recording pipeline
+    segment_callback: Arc<std::sync::Mutex<Option<SegmentCallback>>>,
+    /// Handed to each recording pipeline with both audio and video
+    drift_callback: Arc<std::sync::Mutex<Option<DriftCallback>>>,
+    /// Handed to each portal session for its `Closed` signal
+    session_closed_callback: Arc<std::sync::Mutex<Option<SessionClosedCallback>>>,
 }
 
 impl LinuxCaptureBackend {
@@ -51,6 +36,19 @@ impl LinuxCaptureBackend {
         Self {
             session: Arc::new(Mutex::new(None)),
             recording: Arc::new(Mutex::new(None)),
+            replay: Arc::new(Mutex::new(None)),
+            segment_callback: Arc::new(std::sync::Mutex::new(None)),
+            drift_callback: Arc::new(std::sync::Mutex::new(None)),
+            session_closed_callback: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    fn attach_callbacks(&self, pipeline: &RecordingPipeline) {
+        if let Some(callback) = self.segment_callback.lock().unwrap().clone() {
+            pipeline.set_segment_callback(callback);
+        }
+        if let Some(callback) = self.drift_callback.lock().unwrap().clone() {
+            pipeline.set_drift_callback(callback);
         }
     }
 
@@ -71,6 +69,7 @@ impl std::fmt::Debug for LinuxCaptureBackend {
         f.debug_struct("LinuxCaptureBackend")
             .field("session", &"<session>")
             .field("recording", &"<recording>")
+            .field("replay", &"<replay>")
             .finish()
     }
 }
@@ -182,13 +181,15 @@ impl CaptureBackend for LinuxCaptureBackend {
         info!("Got PipeWire fd: {}", fd_raw);
 
         // Store session to keep the portal stream alive (with leaked screencast)
+        let on_closed = self.session_closed_callback.lock().unwrap().clone();
         let mut session_lock = self.session.lock().await;
-        *session_lock = Some(ActiveSession {
-            _screencast: screencast,
-            _session: session,
+        *session_lock = Some(ActiveSession::new(
+            screencast,
+            session,
             node_id,
             pipewire_fd,
-        });
+            on_closed,
+        ));
 
         let (width, height) = stream
             .size()
@@ -213,266 +214,81 @@ impl CaptureBackend for LinuxCaptureBackend {
     async fn capture_screenshot(
         &self,
         selection: &SelectionResult,
-        output_path: &Path,
-    ) -> Result<ScreenshotResult, CaptureBackendError> {
-        info!(
-            "Capturing screenshot from node {} (fd={:?}) to {:?}",
-            selection.node_id, selection.stream_fd, output_path
-        );
-
-        // Initialize GStreamer (safe to call multiple times)
-        gstreamer::init().map_err(|e| {
-            CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
-        })?;
-
-        // Variables to capture frame dimensions
-        let width = Arc::new(AtomicU32::new(0));
-        let height = Arc::new(AtomicU32::new(0));
-        let got_frame = Arc::new(AtomicBool::new(false));
-
-        // Build the pipeline: pipewiresrc ! videoconvert ! pngenc ! filesink
-        // Use fd if available (portal streams require it), otherwise fall back to path
-        let pipewiresrc_props = if let Some(fd) = selection.stream_fd {
-            format!("pipewiresrc fd={} path={} num-buffers=1", fd, selection.node_id)
-        } else {
-            format!("pipewiresrc path={} num-buffers=1", selection.node_id)
-        };
-        let pipeline_str = format!(
-            "{} ! videoconvert ! pngenc ! filesink location={}",
-            pipewiresrc_props,
-            output_path.display()
-        );
-
-        debug!("Creating GStreamer pipeline: {}", pipeline_str);
-
-        let pipeline = gstreamer::parse::launch(&pipeline_str).map_err(|e| {
-            CaptureBackendError::Internal(format!("Failed to create pipeline: {}", e))
-        })?;
-
-        let pipeline = pipeline.downcast::<gstreamer::Pipeline>().map_err(|_| {
-            CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string())
-        })?;
-
-        // Add a pad probe to capture frame dimensions from videoconvert's sink pad
-        let width_clone = Arc::clone(&width);
-        let height_clone = Arc::clone(&height);
-        let got_frame_clone = Arc::clone(&got_frame);
-
-        // Get the videoconvert element to add a probe
-        // We iterate over elements to find videoconvert
-        for element in pipeline.iterate_elements() {
-            if let Ok(elem) = element {
-                let factory = elem.factory();
-                if let Some(factory) = factory {
-                    if factory.name() == "videoconvert" {
-                        // Add probe to the sink pad
-                        if let Some(pad) = elem.static_pad("sink") {
-                            pad.add_probe(gstreamer::PadProbeType::BUFFER, move |_pad, info| {
-                                if got_frame_clone.load(Ordering::SeqCst) {
-                                    return gstreamer::PadProbeReturn::Ok;
-                                }
-
-                                // Try to get caps from the pad
-                                if let Some(caps) = _pad.current_caps() {
-                                    if let Some(s) = caps.structure(0) {
-                                        if let (Ok(w), Ok(h)) =
-                                            (s.get::<i32>("width"), s.get::<i32>("height"))
-                                        {
-                                            width_clone.store(w as u32, Ordering::SeqCst);
-                                            height_clone.store(h as u32, Ordering::SeqCst);
-                                            got_frame_clone.store(true, Ordering::SeqCst);
-                                            debug!("Captured frame dimensions: {}x{}", w, h);
-                                        }
-                                    }
-                                }
-
-                                // Also try from probe info buffer
-                                if let gstreamer::PadProbeInfo {
-                                    data: Some(gstreamer::PadProbeData::Buffer(_)),
-                                    ..
-                                } = info
-                                {
-                                    got_frame_clone.store(true, Ordering::SeqCst);
-                                }
-
-                                gstreamer::PadProbeReturn::Ok
-                            });
-                        }
-                        break;
-                    }
-                }
-            }
-        }
-
-        // Start the pipeline
-        pipeline.set_state(gstreamer::State::Playing).map_err(|e| {
-            CaptureBackendError::Internal(format!("Failed to start pipeline: {}", e))
-        })?;
-
-        // Wait for EOS or error
-        let bus = pipeline.bus().ok_or_else(|| {
-            CaptureBackendError::Internal("Failed to get pipeline bus".to_string())
-        })?;
-
-        let result = loop {
-            match bus.timed_pop(gstreamer::ClockTime::from_seconds(10)) {
-                Some(msg) => {
-                    use gstreamer::MessageView;
-                    match msg.view() {
-                        MessageView::Eos(..) => {
-                            debug!("Pipeline reached EOS");
-                            break Ok(());
-                        }
-                        MessageView::Error(err) => {
-                            let debug_info = err
-                                .debug()
-                                .map(|d| format!(" ({:?})", d))
-                                .unwrap_or_default();
-                            error!("Pipeline error: {}{}", err.error(), debug_info);
-                            break Err(CaptureBackendError::Internal(format!(
-                                "Pipeline error: {}{}",
-                                err.error(),
-                                debug_info
-                            )));
-                        }
-                        MessageView::StateChanged(state_changed) => {
-                            // Only log if from the pipeline itself
-                            if state_changed
-                                .src()
-                                .map(|s| s == pipeline.upcast_ref::<gstreamer::Object>())
-                                .unwrap_or(false)
-                            {
-                                debug!(
-                                    "Pipeline state: {:?} -> {:?}",
-                                    state_changed.old(),
-                                    state_changed.current()
-                                );
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                None => {
-                    warn!("Pipeline timed out waiting for EOS");
-                    break Err(CaptureBackendError::Internal(
-                        "Pipeline timed out".to_string(),
-                    ));
-                }
-            }
-        };
-
-        // Cleanup: stop the pipeline
-        let _ = pipeline.set_state(gstreamer::State::Null);
+    ) -> Result<RgbaImage, CaptureBackendError> {
+        capture_frame(selection)
+    }
 
-        // Check result
-        result?;
+    async fn capture_recording_frame(&self) -> Result<RgbaImage, CaptureBackendError> {
+        let recording_lock = self.recording.lock().await;
+        let pipeline = recording_lock
+            .as_ref()
+            .ok_or_else(|| CaptureBackendError::Internal("No recording in progress".to_string()))?;
 
-        // Get final dimensions
-        let final_width = width.load(Ordering::SeqCst);
-        let final_height = height.load(Ordering::SeqCst);
+        pipeline.grab_frame()
+    }
 
-        // If we couldn't get dimensions from the probe, try from selection
-        let (final_width, final_height) = if final_width == 0 || final_height == 0 {
-            selection
-                .width
-                .zip(selection.height)
-                .unwrap_or((1920, 1080)) // fallback defaults
-        } else {
-            (final_width, final_height)
-        };
+    async fn start_recording(
+        &self,
+        selection: &SelectionResult,
+        config: &CaptureConfig,
+    ) -> Result<(), CaptureBackendError> {
+        debug!("Starting recording from node {} to {}", selection.node_id, config.output_path);
+        debug!("Portal session open: {}", self.session.lock().await.is_some());
 
-        // Verify the output file was created
-        if !output_path.exists() {
+        // Check if already recording
+        if self.recording.lock().await.is_some() {
             return Err(CaptureBackendError::Internal(
-                "Screenshot file was not created".to_string(),
+                "Recording already in progress".to_string(),
             ));
         }
 
-        info!(
-            "Screenshot captured: {}x{} at {:?}",
-            final_width, final_height, output_path
-        );
+        let mut pipeline = RecordingPipeline::new(selection, config)?;
+        self.attach_callbacks(&pipeline);
+        pipeline.start()?;
+        *self.recording.lock().await = Some(pipeline);
 
-        Ok(ScreenshotResult {
-            path: output_path.to_string_lossy().to_string(),
-            width: final_width,
-            height: final_height,
-        })
+        info!("Recording started successfully");
+        Ok(())
     }
 
-    async fn start_recording(
+    async fn start_audio_recording(
         &self,
-        selection: &SelectionResult,
         config: &CaptureConfig,
     ) -> Result<(), CaptureBackendError> {
-        eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Starting from node {}", selection.node_id);
-
-        // Check if session is still alive
-        {
-            let session_lock = self.session.lock().await;
-            eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Session exists={}", session_lock.is_some());
-        }
+        info!("Starting audio-only recording to {}", config.output_path);
 
-        // Check if already recording
-        {
-            let recording_lock = self.recording.lock().await;
-            eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Checked recording lock, is_some={}", recording_lock.is_some());
-            if recording_lock.is_some() {
-                return Err(CaptureBackendError::Internal(
-                    "Recording already in progress".to_string(),
-                ));
-            }
+        let mut recording_lock = self.recording.lock().await;
+        if recording_lock.is_some() {
+            return Err(CaptureBackendError::Internal(
+                "Recording already in progress".to_string(),
+            ));
         }
 
-        // Create recording pipeline
-        let output_path = std::path::PathBuf::from(&config.output_path);
-        eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Creating pipeline to {:?}", output_path);
-        let mut pipeline = RecordingPipeline::new(
-            selection.node_id,
-            selection.stream_fd,
-            output_path,
-            config.fps,
-            config.container,
-            &config.audio,
-            selection.width,
-            selection.height,
-        )?;
-
-        // Start the pipeline
-        eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Starting pipeline...");
+        let mut pipeline = RecordingPipeline::new_audio_only(config)?;
+        self.attach_callbacks(&pipeline);
         pipeline.start()?;
 
-        // Store the pipeline
-        eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Storing pipeline...");
-        let mut recording_lock = self.recording.lock().await;
         *recording_lock = Some(pipeline);
-        eprintln!("[DEBUG] LinuxCaptureBackend::start_recording: Pipeline stored, is_some={}", recording_lock.is_some());
 
-        info!("Recording started successfully");
+        info!("Audio-only recording started successfully");
         Ok(())
     }
 
     async fn stop_recording(&self) -> Result<RecordingResult, CaptureBackendError> {
-        eprintln!("[DEBUG] LinuxCaptureBackend::stop_recording: Stopping recording...");
+        debug!("Stopping recording...");
 
         // Take the recording pipeline from storage
         let mut pipeline = {
             let mut recording_lock = self.recording.lock().await;
-            eprintln!("[DEBUG] LinuxCaptureBackend::stop_recording: Got lock, is_some={}", recording_lock.is_some());
             recording_lock.take().ok_or_else(|| {
                 CaptureBackendError::Internal("No recording in progress".to_string())
             })?
         };
 
-        // Stop the pipeline and get the result
+        // Stop the pipeline and get the result; the portal session stays
+        // open for further captures until `close_session`
         let result = pipeline.stop()?;
 
-        // Clear the session - portal stream is no longer needed
-        {
-            let mut session_lock = self.session.lock().await;
-            *session_lock = None;
-        }
-
         info!(
             "Recording stopped: {} ({} ms)",
             result.path, result.duration_ms
@@ -502,4 +318,101 @@ impl CaptureBackend for LinuxCaptureBackend {
 
         pipeline.resume()
     }
+
+    async fn start_replay_buffer(
+        &self,
+        selection: &SelectionResult,
+        config: &CaptureConfig,
+    ) -> Result<(), CaptureBackendError> {
+        info!(
+            "Starting {}s replay buffer from node {}",
+            config.replay_buffer_secs, selection.node_id
+        );
+
+        if self.recording.lock().await.is_some() {
+            return Err(CaptureBackendError::Internal(
+                "Recording already in progress".to_string(),
+            ));
+        }
+        let mut replay_lock = self.replay.lock().await;
+        if replay_lock.is_some() {
+            return Err(CaptureBackendError::Internal(
+                "Replay buffer already running".to_string(),
+            ));
+        }
+
+        let pipeline = ReplayPipeline::new(selection, config)?;
+        pipeline.start()?;
+        *replay_lock = Some(pipeline);
+        Ok(())
+    }
+
+    async fn save_replay(
+        &self,
+        output_path: &Path,
+    ) -> Result<RecordingResult, CaptureBackendError> {
+        let replay_lock = self.replay.lock().await;
+        let pipeline = replay_lock.as_ref().ok_or_else(|| {
+            CaptureBackendError::Internal("Replay buffer is not running".to_string())
+        })?;
+
+        pipeline.save(output_path)
+    }
+
+    async fn stop_replay_buffer(&self) -> Result<(), CaptureBackendError> {
+        let pipeline = self.replay.lock().await.take().ok_or_else(|| {
+            CaptureBackendError::Internal("Replay buffer is not running".to_string())
+        })?;
+        pipeline.stop();
+        Ok(())
+    }
+
+    async fn encode_timelapse(
+        &self,
+        frames_dir: &Path,
+        frame_count: u32,
+        config: &CaptureConfig,
+    ) -> Result<RecordingResult, CaptureBackendError> {
+        // Encoding can take a while, keep it off the async workers
+        let frames_dir = frames_dir.to_path_buf();
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || {
+            encode_timelapse_frames(&frames_dir, frame_count, &config)
+        })
+        .await
+        .map_err(|e| CaptureBackendError::Internal(format!("Timelapse encoder panicked: {}", e)))?
+    }
+
+    fn on_segment_closed(&self, callback: SegmentCallback) {
+        *self.segment_callback.lock().unwrap() = Some(callback);
+    }
+
+    fn on_av_drift(&self, callback: DriftCallback) {
+        *self.drift_callback.lock().unwrap() = Some(callback);
+    }
+
+    /// Must be set before `request_selection` to cover that session
+    fn on_session_closed(&self, callback: SessionClosedCallback) {
+        *self.session_closed_callback.lock().unwrap() = Some(callback);
+    }
+
+    async fn recorded_bytes(&self) -> Option<u64> {
+        self.recording
+            .lock()
+            .await
+            .as_ref()
+            .map(RecordingPipeline::recorded_bytes)
+    }
+
+    async fn has_session(&self) -> bool {
+        self.session.lock().await.is_some()
+    }
+
+    async fn close_session(&self) {
+        let session = self.session.lock().await.take();
+        if let Some(session) = session {
+            info!("Closing portal session");
+            session.close().await;
+        }
+    }
 }
@@ -0,0 +1,241 @@
+use crate::capture::timelapse::FRAME_PATTERN;
+use crate::capture::SelectionResult;
+use crate::config::{AudioConfig, CaptureConfig, ContainerFormat};
+use tracing::debug;
+
+use super::segments::splitmux_sink_description;
+use super::sync::{
+    AUDIO_ENCODER_NAME, AUDIO_SOURCE_SYNC_PROPS, VIDEO_ENCODER_NAME, VIDEO_SOURCE_SYNC_PROPS,
+};
+use super::{
+    crash_safe_muxer_properties, get_muxer_for_container, get_system_audio_source,
+    keyframe_interval_props, video_encoder_props,
+};
+
+/// appsink/appsrc names used by the replay buffer and its writer
+pub(super) const REPLAY_VIDEO_SINK: &str = "replay_video";
+pub(super) const REPLAY_AUDIO_SINK: &str = "replay_audio";
+pub(super) const REPLAY_VIDEO_SRC: &str = "replay_video_src";
+pub(super) const REPLAY_AUDIO_SRC: &str = "replay_audio_src";
+
+/// tee and appsink that keep the latest raw frame of a recording
+pub(super) const STILL_FRAME_TEE: &str = "stills";
+pub(super) const STILL_FRAME_SINK: &str = "still_frames";
+
+/// `value` as a double-quoted property value for `gstreamer::parse::launch`
+///
+/// Unquoted values end at the first space, and output paths may contain
+/// spaces (custom templates, localized XDG directories).
+pub(super) fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Split `source` so a recording can be screenshotted while it runs
+///
+/// The returned chain continues the main branch (link the encoder after
+/// it); `still_frame_branch` must be added to the same description.
+pub(super) fn still_frame_tee(source: &str) -> String {
+    format!("{source} ! tee name={STILL_FRAME_TEE} ! queue")
+}
+
+/// Branch keeping the latest frame of `STILL_FRAME_TEE` as RGBA in an appsink
+///
+/// The leaky queue and `drop=true` mean it never holds up the recording;
+/// the frame is read from the appsink's last sample.
+pub(super) fn still_frame_branch() -> String {
+    format!(
+        "{STILL_FRAME_TEE}. ! queue leaky=downstream max-size-buffers=1 ! \
+         videoconvert ! video/x-raw,format=RGBA ! \
+         appsink name={STILL_FRAME_SINK} max-buffers=1 drop=true sync=false"
+    )
+}
+
+/// pipewiresrc element for the portal stream of `selection`
+pub(super) fn pipewire_source(selection: &SelectionResult) -> String {
+    // When using the portal fd, pass both fd (the PipeWire connection)
+    // and path (the node); client-name helps when debugging
+    match selection.stream_fd {
+        Some(fd) => {
+            debug!("pipewiresrc using fd={} path={}", fd, selection.node_id);
+            format!(
+                "pipewiresrc fd={} path={} client-name=opensnipping {}",
+                fd, selection.node_id, VIDEO_SOURCE_SYNC_PROPS
+            )
+        }
+        None => {
+            debug!("pipewiresrc using path={} only (no fd)", selection.node_id);
+            format!(
+                "pipewiresrc path={} client-name=opensnipping {}",
+                selection.node_id, VIDEO_SOURCE_SYNC_PROPS
+            )
+        }
+    }
+}
+
+/// Build the audio capture branch of a pipeline description
+///
+/// Captures the microphone and/or system audio (mixed through `audiomixer`
+/// when both are enabled), then links the result to `tail`.
+pub(super) fn audio_branch(audio: &AudioConfig, tail: &str) -> String {
+    let mic = format!(
+        "pulsesrc {} ! audioconvert ! audioresample",
+        AUDIO_SOURCE_SYNC_PROPS
+    );
+    let system = format!(
+        "pulsesrc device={} {} ! audioconvert ! audioresample",
+        get_system_audio_source(),
+        AUDIO_SOURCE_SYNC_PROPS
+    );
+
+    if audio.mic && audio.system {
+        format!(
+            "audiomixer name=mix ! audioconvert ! audioresample ! {tail} \
+             {mic} ! mix. \
+             {system} ! mix."
+        )
+    } else if audio.system {
+        format!("{system} ! {tail}")
+    } else {
+        format!("{mic} ! {tail}")
+    }
+}
+
+/// Build the pipeline description for an audio-only recording
+///
+/// `audio_encoder` is ignored for WAV, which stores raw PCM.
+pub(super) fn audio_only_pipeline_description(
+    config: &CaptureConfig,
+    audio_encoder: Option<&str>,
+) -> String {
+    let sink = output_sink(config);
+    let tail = match audio_encoder {
+        Some(encoder) if config.container != ContainerFormat::Wav => {
+            format!("{} ! {}", encoder, sink)
+        }
+        _ => sink,
+    };
+
+    audio_branch(&config.audio, &tail)
+}
+
+/// Muxer element for the container, with crash-safe properties when requested
+pub(super) fn muxer_element(container: ContainerFormat, crash_safe: bool) -> String {
+    let muxer = get_muxer_for_container(container);
+    let props = crash_safe_muxer_properties(container);
+    if crash_safe && !props.is_empty() {
+        format!("{} {}", muxer, props)
+    } else {
+        muxer.to_string()
+    }
+}
+
+/// Video encoder element with the bitrate and preset `config` asks for
+pub(super) fn video_encoder_element(encoder: &str, config: &CaptureConfig) -> String {
+    let props = video_encoder_props(encoder, &config.encoder.unwrap_or_default());
+    if props.is_empty() {
+        encoder.to_string()
+    } else {
+        format!("{} {}", encoder, props)
+    }
+}
+
+/// Tail of the pipeline that writes the output file(s), named `mux`
+///
+/// A single `muxer ! filesink`, or a `splitmuxsink` when segmenting.
+pub(super) fn output_sink(config: &CaptureConfig) -> String {
+    match &config.segments {
+        Some(segments) => splitmux_sink_description(
+            config.container,
+            config.crash_safe,
+            segments,
+            &config.output_path,
+        ),
+        None => format!(
+            "{} name=mux ! filesink location={}",
+            muxer_element(config.container, config.crash_safe),
+            quoted(&config.output_path)
+        ),
+    }
+}
+
+/// Build the pipeline description for a replay buffer
+///
+/// Encodes `source` with a one-second GOP and hands the encoded buffers to
+/// appsinks instead of a muxer. With `audio_encoder`, the audio branch
+/// feeds a second appsink.
+pub(super) fn replay_pipeline_description(
+    source: &str,
+    video_encoder: &str,
+    audio_encoder: Option<&str>,
+    config: &CaptureConfig,
+) -> String {
+    let video = format!(
+        "{source} ! videoconvert ! videoscale ! \
+         {encoder} name={VIDEO_ENCODER_NAME} {keyframes} ! \
+         h264parse config-interval=-1 ! \
+         appsink name={REPLAY_VIDEO_SINK} sync=false",
+        encoder = video_encoder_element(video_encoder, config),
+        keyframes = keyframe_interval_props(video_encoder, u32::from(config.fps.max(1)))
+    );
+
+    match audio_encoder {
+        Some(encoder) => format!(
+            "{} {}",
+            video,
+            audio_branch(
+                &config.audio,
+                &format!(
+                    "{} name={} ! appsink name={} sync=false",
+                    encoder, AUDIO_ENCODER_NAME, REPLAY_AUDIO_SINK
+                )
+            )
+        ),
+        None => video,
+    }
+}
+
+/// Build the pipeline that writes a saved replay from appsrcs to a file
+pub(super) fn replay_writer_description(
+    container: ContainerFormat,
+    with_audio: bool,
+    output_path: &str,
+) -> String {
+    let mut desc = format!(
+        "appsrc name={} format=time ! h264parse ! {} name=mux ! filesink location={}",
+        REPLAY_VIDEO_SRC,
+        get_muxer_for_container(container),
+        quoted(output_path)
+    );
+    if with_audio {
+        desc.push_str(&format!(
+            " appsrc name={} format=time ! queue ! mux.",
+            REPLAY_AUDIO_SRC
+        ));
+    }
+    desc
+}
+
+/// Build the pipeline that encodes numbered timelapse frames into a video
+///
+/// Frames are scaled to `width`x`height` so a source that changes size
+/// mid-timelapse still produces one stream, and play back at `config.fps`.
+pub(super) fn timelapse_encode_description(
+    frames_dir: &str,
+    frame_count: u32,
+    video_encoder: &str,
+    width: u32,
+    height: u32,
+    config: &CaptureConfig,
+) -> String {
+    format!(
+        "multifilesrc location={frames} start-index=1 \
+         stop-index={frame_count} caps=\"image/png,framerate={fps}/1\" ! \
+         pngdec ! videoconvert ! videoscale ! video/x-raw,width={width},height={height} ! \
+         {encoder} ! h264parse ! {muxer} name=mux ! filesink location={output}",
+        encoder = video_encoder_element(video_encoder, config),
+        frames = quoted(&format!("{frames_dir}/{FRAME_PATTERN}")),
+        fps = config.fps.max(1),
+        muxer = get_muxer_for_container(config.container),
+        output = quoted(&config.output_path),
+    )
+}
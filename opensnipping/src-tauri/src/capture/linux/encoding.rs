@@ -3,7 +3,8 @@
 // This module integrates with the Freedesktop portal for screen capture
 // on Linux (Wayland and X11).
 
-use crate::config::ContainerFormat;
+use crate::capture::CaptureBackendError;
+use crate::config::{ContainerFormat, EncoderConfig, EncoderPreset, VideoEncoder};
 use tracing::{debug, warn};
 
 /// H.264 encoders in order of preference (hardware first, then software fallback)
@@ -38,12 +39,9 @@ pub fn detect_available_encoder() -> Option<&'static str> {
     }
 
     for encoder in H264_ENCODERS {
-        if let Some(factory) = gstreamer::ElementFactory::find(encoder) {
-            // Verify the factory can create an element (plugin is fully loaded)
-            if factory.create().build().is_ok() {
-                debug!("Found available H.264 encoder: {}", encoder);
-                return Some(encoder);
-            }
+        if element_available(encoder) {
+            debug!("Found available H.264 encoder: {}", encoder);
+            return Some(encoder);
         }
     }
 
@@ -51,19 +49,109 @@ pub fn detect_available_encoder() -> Option<&'static str> {
     None
 }
 
+/// Whether GStreamer can create a `name` element (its plugin is fully loaded)
+fn element_available(name: &str) -> bool {
+    gstreamer::ElementFactory::find(name).is_some_and(|factory| factory.create().build().is_ok())
+}
+
+/// Element factory of the H.264 encoder `encoder` asks for
+///
+/// `Auto` detects the best available encoder. An explicit choice whose
+/// plugin is missing fails rather than recording with another encoder.
+pub fn select_video_encoder(encoder: VideoEncoder) -> Result<&'static str, CaptureBackendError> {
+    let factory = match encoder {
+        VideoEncoder::Auto => {
+            return detect_available_encoder().ok_or_else(|| {
+                CaptureBackendError::Internal("No H.264 encoder available".to_string())
+            })
+        }
+        VideoEncoder::X264 => "x264enc",
+        VideoEncoder::Vaapi => "vaapih264enc",
+        VideoEncoder::Nvenc => "nvh264enc",
+    };
+    if gstreamer::init().is_ok() && element_available(factory) {
+        Ok(factory)
+    } else {
+        Err(CaptureBackendError::Internal(format!(
+            "The selected H.264 encoder ({}) is not available",
+            factory
+        )))
+    }
+}
+
+/// Encoder properties for the bitrate and preset of `preferences`
+///
+/// VA-API and NVENC ignore a bitrate in their default constant-QP mode,
+/// so a bitrate also switches them to constant bitrate. `Balanced` keeps
+/// each encoder's own default preset.
+pub fn video_encoder_props(encoder: &str, preferences: &EncoderConfig) -> String {
+    let mut props = Vec::new();
+    if let Some(kbps) = preferences.bitrate_kbps {
+        match encoder {
+            "x264enc" => props.push(format!("bitrate={}", kbps)),
+            "vaapih264enc" => props.push(format!("rate-control=cbr bitrate={}", kbps)),
+            "nvh264enc" => props.push(format!("rc-mode=cbr bitrate={}", kbps)),
+            _ => {}
+        }
+    }
+    let preset = match (encoder, preferences.preset) {
+        (_, EncoderPreset::Balanced) => None,
+        ("x264enc", EncoderPreset::Fast) => Some("speed-preset=ultrafast"),
+        ("x264enc", EncoderPreset::Quality) => Some("speed-preset=slow"),
+        ("vaapih264enc", EncoderPreset::Fast) => Some("quality-level=7"),
+        ("vaapih264enc", EncoderPreset::Quality) => Some("quality-level=1"),
+        ("nvh264enc", EncoderPreset::Fast) => Some("preset=hp"),
+        ("nvh264enc", EncoderPreset::Quality) => Some("preset=hq"),
+        _ => None,
+    };
+    props.extend(preset.map(str::to_string));
+    props.join(" ")
+}
+
 /// Get the GStreamer muxer element name for the given container format
 pub fn get_muxer_for_container(container: ContainerFormat) -> &'static str {
     match container {
         ContainerFormat::Mp4 => "mp4mux",
         ContainerFormat::Mkv => "matroskamux",
+        ContainerFormat::Ogg => "oggmux",
+        ContainerFormat::M4a => "mp4mux",
+        // wavenc wraps raw PCM directly, no separate encoder needed
+        ContainerFormat::Wav => "wavenc",
+    }
+}
+
+/// Muxer properties that keep a partially written file recoverable
+///
+/// MP4/M4A get fragmented output (a moov header up front, one fragment per
+/// second) and MKV is written in streamable mode. OGG pages are already
+/// self-contained and WAV is raw PCM, so neither needs anything.
+pub fn crash_safe_muxer_properties(container: ContainerFormat) -> &'static str {
+    match container {
+        ContainerFormat::Mp4 | ContainerFormat::M4a => "fragment-duration=1000",
+        ContainerFormat::Mkv => "streamable=true",
+        ContainerFormat::Ogg | ContainerFormat::Wav => "",
+    }
+}
+
+/// Encoder properties forcing a keyframe at least every `frames` frames
+///
+/// The replay buffer can only be trimmed and saved at keyframes, so it
+/// needs a short, fixed GOP instead of the encoder's default.
+pub fn keyframe_interval_props(encoder: &str, frames: u32) -> String {
+    match encoder {
+        "x264enc" => format!("key-int-max={}", frames),
+        "vaapih264enc" => format!("keyframe-period={}", frames),
+        "nvh264enc" => format!("gop-size={}", frames),
+        _ => String::new(),
     }
 }
 
 /// Detect the best available audio encoder for the given container format
 ///
-/// For MP4: prefers AAC encoders
-/// For MKV: prefers Opus encoder
-/// Returns None if no suitable audio encoder is available.
+/// For MP4/M4A: prefers AAC encoders
+/// For MKV/OGG: prefers Opus encoder
+/// Returns None if no suitable audio encoder is available, or for WAV
+/// which stores uncompressed PCM.
 pub fn detect_available_audio_encoder(container: ContainerFormat) -> Option<&'static str> {
     // Ensure GStreamer is initialized (safe to call multiple times)
     if gstreamer::init().is_err() {
@@ -72,8 +160,12 @@ pub fn detect_available_audio_encoder(container: ContainerFormat) -> Option<&'st
     }
 
     let encoders: &[&str] = match container {
-        ContainerFormat::Mp4 => AAC_ENCODERS,
-        ContainerFormat::Mkv => OPUS_ENCODERS,
+        ContainerFormat::Mp4 | ContainerFormat::M4a => AAC_ENCODERS,
+        ContainerFormat::Mkv | ContainerFormat::Ogg => OPUS_ENCODERS,
+        ContainerFormat::Wav => {
+            debug!("WAV stores raw PCM, no audio encoder needed");
+            return None;
+        }
     };
 
     for encoder in encoders {
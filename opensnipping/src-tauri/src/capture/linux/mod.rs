@@ -4,15 +4,31 @@
 // on Linux (Wayland and X11).
 
 mod backend;
+mod description;
 mod encoding;
 mod pipeline;
+mod remux;
+mod replay;
+mod screenshot;
+mod segments;
+mod session;
+mod sync;
+mod timelapse;
+mod webp;
 
 pub use backend::LinuxCaptureBackend;
 pub use encoding::{
-    detect_available_audio_encoder, detect_available_encoder, get_muxer_for_container,
-    get_system_audio_source,
+    crash_safe_muxer_properties, detect_available_audio_encoder, detect_available_encoder,
+    get_muxer_for_container, get_system_audio_source, keyframe_interval_props,
+    select_video_encoder, video_encoder_props,
 };
 pub use pipeline::RecordingPipeline;
+pub use remux::remux_recording;
+pub use replay::ReplayPipeline;
+pub use segments::SegmentTracker;
+pub use sync::{DriftMonitor, StreamKind};
+pub use timelapse::encode_timelapse_frames;
+pub use webp::{encode_webp, webp_encoder_available};
 
 #[cfg(test)]
 mod tests;
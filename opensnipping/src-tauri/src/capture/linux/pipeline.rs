@@ -1,23 +1,36 @@
-use crate::capture::{CaptureBackendError, RecordingResult};
-use crate::config::{AudioConfig, ContainerFormat};
+use crate::capture::{
+    CaptureBackendError, DriftCallback, RecordingResult, SegmentCallback, SelectionResult,
+};
+use crate::config::{CaptureConfig, ContainerFormat};
 use gstreamer::prelude::*;
+use image::RgbaImage;
 use tracing::{debug, error, info, warn};
 
-use super::{
-    detect_available_audio_encoder, detect_available_encoder, get_muxer_for_container,
-    get_system_audio_source,
+use super::description::{
+    audio_branch, audio_only_pipeline_description, output_sink, pipewire_source,
+    still_frame_branch, still_frame_tee, video_encoder_element, STILL_FRAME_SINK,
 };
+use super::screenshot::sample_to_image;
+use super::segments::{watch_segments, SegmentTracker};
+use super::sync::{attach_drift_probe, AUDIO_ENCODER_NAME, VIDEO_ENCODER_NAME};
+use super::{detect_available_audio_encoder, select_video_encoder, DriftMonitor, StreamKind};
+use std::sync::Arc;
 
 pub struct RecordingPipeline {
     /// The GStreamer pipeline
     pipeline: gstreamer::Pipeline,
-    /// Output file path
+    /// Output file path, or the `{index}` template of a segmented
+    /// recording's files
     output_path: std::path::PathBuf,
     /// Recording start time (set when pipeline starts playing)
     start_time: Option<std::time::Instant>,
     /// Video dimensions (captured from pipeline)
     width: u32,
     height: u32,
+    /// A/V drift tracking (None for pipelines without both audio and video)
+    drift: Option<Arc<DriftMonitor>>,
+    /// Closed segment files (None unless the recording is segmented)
+    segments: Option<Arc<SegmentTracker>>,
 }
 
 impl RecordingPipeline {
@@ -27,47 +40,44 @@ impl RecordingPipeline {
     /// - Video: pipewiresrc ! videoconvert ! videoscale ! encoder ! muxer ! filesink
     /// - Audio (if mic enabled): pulsesrc ! audioconvert ! audioresample ! audio_encoder ! muxer
     /// - Audio (if system enabled): pulsesrc device=@DEFAULT_MONITOR@ ! audioconvert ! audioresample ! audio_encoder ! muxer
-    /// - Audio (if both enabled): mix handled separately (see task 22)
+    /// - Audio (if both enabled): both sources mixed through audiomixer (see `audio_branch`)
+    ///
+    /// The video source is teed into an appsink holding the latest frame, so
+    /// `grab_frame` can take a screenshot without interrupting the recording.
+    ///
+    /// With `config.crash_safe`, the muxer writes fragmented MP4 / streamable MKV.
+    /// With `config.segments`, the muxer and filesink are replaced by a
+    /// splitmuxsink that rotates the output file.
     pub fn new(
-        node_id: u32,
-        stream_fd: Option<i32>,
-        output_path: std::path::PathBuf,
-        _fps: u8,
-        container: ContainerFormat,
-        audio: &AudioConfig,
-        width: Option<u32>,
-        height: Option<u32>,
+        selection: &SelectionResult,
+        config: &CaptureConfig,
     ) -> Result<Self, CaptureBackendError> {
+        let output_path = std::path::PathBuf::from(config.recording_path());
+        let fps = config.fps;
+        let container = config.container;
+        let audio = &config.audio;
+
         // Initialize GStreamer
         gstreamer::init().map_err(|e| {
             CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
         })?;
 
-        // Detect video encoder
-        let video_encoder = detect_available_encoder().ok_or_else(|| {
-            CaptureBackendError::Internal("No H.264 encoder available".to_string())
-        })?;
+        // Preferred (or best available) video encoder with its bitrate and preset
+        let video_encoder = video_encoder_element(
+            select_video_encoder(config.encoder.unwrap_or_default().encoder)?,
+            config,
+        );
 
-        // Get muxer for container format
-        let muxer = get_muxer_for_container(container);
+        // Muxer + filesink, or splitmuxsink for segmented recordings
+        let sink = output_sink(config);
 
         // Determine audio configuration
         let has_mic = audio.mic;
         let has_system = audio.system;
         let has_any_audio = has_mic || has_system;
 
-        // Build pipewiresrc element string with fd if available
-        // NOTE: When using portal fd, we should use fd alone OR fd+path
-        // Testing shows fd alone may work better with portal streams
-        let pipewiresrc = if let Some(fd) = stream_fd {
-            eprintln!("[DEBUG] RecordingPipeline: Using fd={} path={}", fd, node_id);
-            // Use both fd and path - fd is the pipewire connection, path is the node
-            // Add client-name for debugging
-            format!("pipewiresrc fd={} path={} client-name=opensnipping", fd, node_id)
-        } else {
-            eprintln!("[DEBUG] RecordingPipeline: Using path={} only (no fd)", node_id);
-            format!("pipewiresrc path={} client-name=opensnipping", node_id)
-        };
+        let pipewiresrc = still_frame_tee(&pipewire_source(selection));
+        let stills = still_frame_branch();
 
         // Build pipeline description
         // When audio is enabled, we use a named muxer so both branches can link to it
@@ -77,78 +87,38 @@ impl RecordingPipeline {
                 CaptureBackendError::Internal("No audio encoder available".to_string())
             })?;
 
-            // Build audio pipeline based on configuration
             if has_mic && has_system {
-                // Both mic and system audio: use audiomixer to combine both sources
                 info!(
                     "Recording with mic + system audio (mixed), encoder: {}",
                     audio_encoder
                 );
-                format!(
-                    "{pipewiresrc} ! \
-                     videoconvert ! \
-                     videoscale ! \
-                     {video_encoder} ! mux. \
-                     audiomixer name=mix ! \
-                     audioconvert ! \
-                     audioresample ! \
-                     {audio_encoder} ! mux. \
-                     pulsesrc ! audioconvert ! audioresample ! mix. \
-                     pulsesrc device={system_device} ! audioconvert ! audioresample ! mix. \
-                     {muxer} name=mux ! \
-                     filesink location={output_path}",
-                    pipewiresrc = pipewiresrc,
-                    video_encoder = video_encoder,
-                    audio_encoder = audio_encoder,
-                    system_device = get_system_audio_source(),
-                    muxer = muxer,
-                    output_path = output_path.display()
-                )
             } else if has_system {
-                // System audio only
                 info!("Recording with system audio, encoder: {}", audio_encoder);
-                format!(
-                    "{pipewiresrc} ! \
-                     videoconvert ! \
-                     videoscale ! \
-                     {video_encoder} ! mux. \
-                     pulsesrc device={system_device} ! \
-                     audioconvert ! \
-                     audioresample ! \
-                     {audio_encoder} ! mux. \
-                     {muxer} name=mux ! \
-                     filesink location={output_path}",
-                    pipewiresrc = pipewiresrc,
-                    video_encoder = video_encoder,
-                    system_device = get_system_audio_source(),
-                    audio_encoder = audio_encoder,
-                    muxer = muxer,
-                    output_path = output_path.display()
-                )
             } else {
-                // Mic only
                 info!(
                     "Recording with microphone audio, encoder: {}",
                     audio_encoder
                 );
-                format!(
-                    "{pipewiresrc} ! \
-                     videoconvert ! \
-                     videoscale ! \
-                     {video_encoder} ! mux. \
-                     pulsesrc ! \
-                     audioconvert ! \
-                     audioresample ! \
-                     {audio_encoder} ! mux. \
-                     {muxer} name=mux ! \
-                     filesink location={output_path}",
-                    pipewiresrc = pipewiresrc,
-                    video_encoder = video_encoder,
-                    audio_encoder = audio_encoder,
-                    muxer = muxer,
-                    output_path = output_path.display()
-                )
             }
+
+            format!(
+                "{pipewiresrc} ! \
+                 videoconvert ! \
+                 videoscale ! \
+                 {video_encoder} name={video_encoder_name} ! mux. \
+                 {audio_branch} \
+                 {sink} \
+                 {stills}",
+                pipewiresrc = pipewiresrc,
+                video_encoder = video_encoder,
+                video_encoder_name = VIDEO_ENCODER_NAME,
+                audio_branch = audio_branch(
+                    audio,
+                    &format!("{} name={} ! mux.", audio_encoder, AUDIO_ENCODER_NAME)
+                ),
+                sink = sink,
+                stills = stills
+            )
         } else {
             // Video-only pipeline
             format!(
@@ -156,17 +126,16 @@ impl RecordingPipeline {
                  videoconvert ! \
                  videoscale ! \
                  {video_encoder} ! \
-                 {muxer} ! \
-                 filesink location={output_path}",
+                 {sink} \
+                 {stills}",
                 pipewiresrc = pipewiresrc,
                 video_encoder = video_encoder,
-                muxer = muxer,
-                output_path = output_path.display()
+                sink = sink,
+                stills = stills
             )
         };
 
         debug!("Creating recording pipeline: {}", pipeline_str);
-        eprintln!("[DEBUG] RecordingPipeline::new: Pipeline desc: {}", pipeline_str);
 
         let pipeline = gstreamer::parse::launch(&pipeline_str).map_err(|e| {
             CaptureBackendError::Internal(format!("Failed to create pipeline: {}", e))
@@ -176,21 +145,121 @@ impl RecordingPipeline {
             CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string())
         })?;
 
+        // Run every branch on one shared clock so audio and video timestamps
+        // are comparable (sources are configured not to provide their own)
+        pipeline.use_clock(Some(&gstreamer::SystemClock::obtain()));
+
+        let drift = if has_any_audio {
+            let monitor = Arc::new(DriftMonitor::for_fps(fps));
+            let video =
+                attach_drift_probe(&pipeline, VIDEO_ENCODER_NAME, StreamKind::Video, &monitor);
+            let audio =
+                attach_drift_probe(&pipeline, AUDIO_ENCODER_NAME, StreamKind::Audio, &monitor);
+            (video && audio).then_some(monitor)
+        } else {
+            None
+        };
+
+        let segments = track_segments(&pipeline, config);
+
+        Ok(Self {
+            pipeline,
+            output_path,
+            start_time: None,
+            width: selection.width.unwrap_or(1920),
+            height: selection.height.unwrap_or(1080),
+            drift,
+            segments,
+        })
+    }
+
+    /// Create a new audio-only recording pipeline
+    ///
+    /// Uses the same audio branches as video recordings, but without a
+    /// PipeWire video source, so no portal session is required:
+    /// - OGG: audio_branch ! opusenc ! oggmux ! filesink
+    /// - M4A: audio_branch ! aac_encoder ! mp4mux ! filesink
+    /// - WAV: audio_branch ! wavenc ! filesink
+    pub fn new_audio_only(config: &CaptureConfig) -> Result<Self, CaptureBackendError> {
+        let output_path = std::path::PathBuf::from(config.recording_path());
+        let container = config.container;
+
+        gstreamer::init().map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
+        })?;
+
+        if !container.is_audio_only() {
+            return Err(CaptureBackendError::Internal(format!(
+                "{:?} is not an audio-only container",
+                container
+            )));
+        }
+
+        let audio_encoder = if container == ContainerFormat::Wav {
+            None
+        } else {
+            Some(detect_available_audio_encoder(container).ok_or_else(|| {
+                CaptureBackendError::Internal("No audio encoder available".to_string())
+            })?)
+        };
+
+        let pipeline_str = audio_only_pipeline_description(config, audio_encoder);
+
+        info!("Creating audio-only recording pipeline: {}", pipeline_str);
+
+        let pipeline = gstreamer::parse::launch(&pipeline_str).map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to create pipeline: {}", e))
+        })?;
+
+        let pipeline = pipeline.downcast::<gstreamer::Pipeline>().map_err(|_| {
+            CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string())
+        })?;
+
+        let segments = track_segments(&pipeline, config);
+
         Ok(Self {
             pipeline,
             output_path,
             start_time: None,
-            width: width.unwrap_or(1920),
-            height: height.unwrap_or(1080),
+            width: 0,
+            height: 0,
+            drift: None,
+            segments,
         })
     }
 
+    /// Notify `callback` each time a segment file is closed
+    ///
+    /// Has no effect on recordings that are not segmented.
+    pub fn set_segment_callback(&self, callback: SegmentCallback) {
+        if let Some(segments) = &self.segments {
+            segments.set_callback(callback);
+        }
+    }
+
+    /// Report the A/V drift to `callback` while recording
+    ///
+    /// Has no effect on recordings without both audio and video.
+    pub fn set_drift_callback(&self, callback: DriftCallback) {
+        if let Some(drift) = &self.drift {
+            drift.set_reporter(callback);
+        }
+    }
+
+    /// Bytes written so far, across every segment of a segmented recording
+    pub fn recorded_bytes(&self) -> u64 {
+        match &self.segments {
+            Some(tracker) => tracker.bytes_written(),
+            None => std::fs::metadata(&self.output_path).map_or(0, |meta| meta.len()),
+        }
+    }
+
     /// Start recording
     pub fn start(&mut self) -> Result<(), CaptureBackendError> {
         info!("Starting recording pipeline to {:?}", self.output_path);
 
         // First try PAUSED to check if pipeline can link
-        eprintln!("[DEBUG] RecordingPipeline::start: Setting pipeline to PAUSED first...");
+        debug!("Setting pipeline to PAUSED to check linking...");
         self.pipeline
             .set_state(gstreamer::State::Paused)
             .map_err(|e| {
@@ -198,14 +267,14 @@ impl RecordingPipeline {
                 if let Some(bus) = self.pipeline.bus() {
                     while let Some(msg) = bus.pop() {
                         if let gstreamer::MessageView::Error(err) = msg.view() {
-                            eprintln!("[DEBUG] GStreamer error: {:?} - {:?}", err.error(), err.debug());
+                            error!("GStreamer error: {:?} - {:?}", err.error(), err.debug());
                         }
                     }
                 }
                 CaptureBackendError::Internal(format!("Failed to pause pipeline for linking: {}", e))
             })?;
 
-        eprintln!("[DEBUG] RecordingPipeline::start: PAUSED succeeded, now PLAYING...");
+        debug!("Pipeline linked, setting it to PLAYING...");
         self.pipeline
             .set_state(gstreamer::State::Playing)
             .map_err(|e| {
@@ -213,7 +282,7 @@ impl RecordingPipeline {
                 if let Some(bus) = self.pipeline.bus() {
                     while let Some(msg) = bus.pop() {
                         if let gstreamer::MessageView::Error(err) = msg.view() {
-                            eprintln!("[DEBUG] GStreamer error: {:?} - {:?}", err.error(), err.debug());
+                            error!("GStreamer error: {:?} - {:?}", err.error(), err.debug());
                         }
                     }
                 }
@@ -221,7 +290,7 @@ impl RecordingPipeline {
             })?;
 
         self.start_time = Some(std::time::Instant::now());
-        eprintln!("[DEBUG] RecordingPipeline::start: Pipeline started successfully");
+        debug!("Recording pipeline started");
         Ok(())
     }
 
@@ -257,6 +326,28 @@ impl RecordingPipeline {
         Ok(())
     }
 
+    /// Copy the latest recorded frame into memory
+    ///
+    /// Reads the still-frame appsink, so the recording keeps running. While
+    /// paused, this is the frame shown when the recording was paused.
+    pub fn grab_frame(&self) -> Result<RgbaImage, CaptureBackendError> {
+        let sink = self
+            .pipeline
+            .by_name(STILL_FRAME_SINK)
+            .and_then(|element| element.downcast::<gstreamer_app::AppSink>().ok())
+            .ok_or_else(|| {
+                CaptureBackendError::NotSupported(
+                    "Audio-only recordings have no frames to capture".to_string(),
+                )
+            })?;
+        let sample = sink
+            .property::<Option<gstreamer::Sample>>("last-sample")
+            .ok_or_else(|| {
+                CaptureBackendError::Internal("The recording has no frame yet".to_string())
+            })?;
+        sample_to_image(&sample)
+    }
+
     /// Stop recording and finalize output file
     ///
     /// Sends EOS to pipeline, waits for finalization, and returns the recording result.
@@ -293,11 +384,11 @@ impl RecordingPipeline {
                                 .map(|d| format!(" ({:?})", d))
                                 .unwrap_or_default();
                             error!("Recording pipeline error: {}{}", err.error(), debug_info);
-                            break Err(CaptureBackendError::Internal(format!(
-                                "Pipeline error: {}{}",
-                                err.error(),
-                                debug_info
-                            )));
+                            let message = format!("Pipeline error: {}{}", err.error(), debug_info);
+                            if err.error().matches(gstreamer::ResourceError::NoSpaceLeft) {
+                                break Err(CaptureBackendError::DiskFull(message));
+                            }
+                            break Err(CaptureBackendError::Internal(message));
                         }
                         _ => {}
                     }
@@ -314,23 +405,55 @@ impl RecordingPipeline {
 
         result?;
 
-        // Verify output file exists
-        if !self.output_path.exists() {
-            return Err(CaptureBackendError::Internal(
-                "Recording file was not created".to_string(),
-            ));
-        }
+        // Verify output file(s) exist; a segmented recording reports its
+        // segment template, and its files in `segments`
+        let segments = self
+            .segments
+            .as_ref()
+            .map(|tracker| tracker.segments())
+            .unwrap_or_default();
+        let path = match (&self.segments, segments.is_empty()) {
+            (Some(_), false) => self.output_path.to_string_lossy().to_string(),
+            (Some(_), true) => {
+                return Err(CaptureBackendError::Internal(
+                    "No recording segments were written".to_string(),
+                ));
+            }
+            (None, _) if self.output_path.exists() => {
+                self.output_path.to_string_lossy().to_string()
+            }
+            (None, _) => {
+                return Err(CaptureBackendError::Internal(
+                    "Recording file was not created".to_string(),
+                ));
+            }
+        };
 
         info!(
-            "Recording complete: {:?} ({} ms)",
-            self.output_path, duration_ms
+            "Recording complete: {} ({} ms, {} segments)",
+            path,
+            duration_ms,
+            segments.len()
         );
 
+        let max_av_drift_ms = self.drift.as_ref().map(|drift| {
+            if drift.exceeded_tolerance() {
+                warn!(
+                    "Recording had A/V drift up to {} ms (tolerance {} ms)",
+                    drift.max_drift_ms(),
+                    drift.tolerance().as_millis()
+                );
+            }
+            drift.max_drift_ms()
+        });
+
         Ok(RecordingResult {
-            path: self.output_path.to_string_lossy().to_string(),
+            path,
             duration_ms,
             width: self.width,
             height: self.height,
+            max_av_drift_ms,
+            segments,
         })
     }
 }
@@ -342,6 +465,21 @@ impl std::fmt::Debug for RecordingPipeline {
             .field("start_time", &self.start_time)
             .field("width", &self.width)
             .field("height", &self.height)
+            .field("drift", &self.drift)
+            .field("segments", &self.segments)
             .finish()
     }
 }
+
+/// Start collecting segments if the recording writes through splitmuxsink
+fn track_segments(
+    pipeline: &gstreamer::Pipeline,
+    config: &CaptureConfig,
+) -> Option<Arc<SegmentTracker>> {
+    if config.segments.is_none() {
+        return None;
+    }
+    let tracker = Arc::new(SegmentTracker::new());
+    watch_segments(pipeline, &tracker);
+    Some(tracker)
+}
@@ -0,0 +1,178 @@
+use crate::capture::CaptureBackendError;
+use crate::config::ContainerFormat;
+use gstreamer::prelude::*;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use super::get_muxer_for_container;
+
+/// Upper bound for a single remux; it only copies packets, so this is generous
+const REMUX_TIMEOUT_SECS: u64 = 300;
+
+/// Demuxer able to read back a (possibly truncated) file of this container
+pub(super) fn demuxer_for_container(container: ContainerFormat) -> &'static str {
+    match container {
+        ContainerFormat::Mp4 | ContainerFormat::M4a => "qtdemux",
+        ContainerFormat::Mkv => "matroskademux",
+        ContainerFormat::Ogg => "oggdemux",
+        ContainerFormat::Wav => "wavparse",
+    }
+}
+
+/// Muxer request pad template for a demuxer source pad, None to drop the stream
+pub(super) fn mux_pad_template(demux_pad_name: &str) -> Option<&'static str> {
+    if demux_pad_name.starts_with("video") {
+        Some("video_%u")
+    } else if demux_pad_name.starts_with("audio") {
+        Some("audio_%u")
+    } else {
+        None
+    }
+}
+
+/// Temporary file the remuxed output is written to before replacing the original
+pub(super) fn recovering_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".recovering");
+    PathBuf::from(name)
+}
+
+/// Rewrite an interrupted recording into a properly finalized file
+///
+/// Demuxes whatever the crashed pipeline managed to write and muxes it again
+/// without re-encoding, then atomically replaces the original file.
+pub fn remux_recording(path: &Path, container: ContainerFormat) -> Result<(), CaptureBackendError> {
+    gstreamer::init().map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
+    })?;
+
+    let output = recovering_path(path);
+    info!("Remuxing interrupted recording {:?}", path);
+
+    let result = run_remux(path, &output, container);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&output);
+        return result;
+    }
+
+    std::fs::rename(&output, path).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to replace recovered file: {}", e))
+    })
+}
+
+fn make_element(
+    factory: &str,
+    location: Option<&Path>,
+) -> Result<gstreamer::Element, CaptureBackendError> {
+    let mut builder = gstreamer::ElementFactory::make(factory);
+    if let Some(location) = location {
+        builder = builder.property("location", location.to_string_lossy().to_string());
+    }
+    builder
+        .build()
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to create {}: {}", factory, e)))
+}
+
+fn run_remux(
+    input: &Path,
+    output: &Path,
+    container: ContainerFormat,
+) -> Result<(), CaptureBackendError> {
+    let pipeline = gstreamer::Pipeline::new();
+    let src = make_element("filesrc", Some(input))?;
+    let demux = make_element(demuxer_for_container(container), None)?;
+    let mux = make_element(get_muxer_for_container(container), None)?;
+    let sink = make_element("filesink", Some(output))?;
+
+    pipeline
+        .add_many([&src, &demux, &mux, &sink])
+        .and_then(|_| src.link(&demux))
+        .and_then(|_| mux.link(&sink))
+        .map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to build remux pipeline: {}", e))
+        })?;
+
+    if container == ContainerFormat::Wav {
+        // wavparse and wavenc carry a single raw stream, no request pads
+        demux.connect_pad_added({
+            let mux = mux.downgrade();
+            move |_, pad| {
+                let Some(mux) = mux.upgrade() else { return };
+                if let Some(sink_pad) = mux.static_pad("sink") {
+                    if let Err(e) = pad.link(&sink_pad) {
+                        warn!("Failed to link WAV stream: {:?}", e);
+                    }
+                }
+            }
+        });
+    } else {
+        demux.connect_pad_added({
+            let pipeline = pipeline.downgrade();
+            let mux = mux.downgrade();
+            move |_, pad| {
+                let (Some(pipeline), Some(mux)) = (pipeline.upgrade(), mux.upgrade()) else {
+                    return;
+                };
+                if let Err(e) = link_stream(&pipeline, pad, &mux) {
+                    warn!("Dropping stream {}: {}", pad.name(), e);
+                }
+            }
+        });
+    }
+
+    pipeline.set_state(gstreamer::State::Playing).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to start remux pipeline: {}", e))
+    })?;
+
+    let result = wait_for_eos(&pipeline);
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    result
+}
+
+/// Route one demuxed stream through a queue into a fresh muxer pad
+fn link_stream(
+    pipeline: &gstreamer::Pipeline,
+    pad: &gstreamer::Pad,
+    mux: &gstreamer::Element,
+) -> Result<(), String> {
+    let template = mux_pad_template(&pad.name()).ok_or("unsupported stream type")?;
+    let queue = gstreamer::ElementFactory::make("queue")
+        .build()
+        .map_err(|e| e.to_string())?;
+    pipeline.add(&queue).map_err(|e| e.to_string())?;
+    queue.sync_state_with_parent().map_err(|e| e.to_string())?;
+
+    let queue_sink = queue.static_pad("sink").ok_or("queue has no sink pad")?;
+    pad.link(&queue_sink).map_err(|e| format!("{:?}", e))?;
+
+    let mux_pad = mux
+        .request_pad_simple(template)
+        .ok_or("muxer refused pad")?;
+    let queue_src = queue.static_pad("src").ok_or("queue has no src pad")?;
+    queue_src.link(&mux_pad).map_err(|e| format!("{:?}", e))?;
+
+    debug!("Linked {} to {}", pad.name(), mux_pad.name());
+    Ok(())
+}
+
+fn wait_for_eos(pipeline: &gstreamer::Pipeline) -> Result<(), CaptureBackendError> {
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| CaptureBackendError::Internal("Failed to get pipeline bus".to_string()))?;
+
+    let msg = bus.timed_pop_filtered(
+        gstreamer::ClockTime::from_seconds(REMUX_TIMEOUT_SECS),
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+
+    match msg.as_ref().map(|m| m.view()) {
+        Some(gstreamer::MessageView::Eos(..)) => Ok(()),
+        Some(gstreamer::MessageView::Error(err)) => Err(CaptureBackendError::Internal(format!(
+            "Remux failed: {}",
+            err.error()
+        ))),
+        _ => Err(CaptureBackendError::Internal(
+            "Timed out remuxing recording".to_string(),
+        )),
+    }
+}
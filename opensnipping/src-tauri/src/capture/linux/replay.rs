@@ -0,0 +1,303 @@
+use crate::capture::replay::{ReplayChunk, ReplayRing, ReplaySnapshot};
+use crate::capture::{CaptureBackendError, RecordingResult, SelectionResult};
+use crate::config::{CaptureConfig, ContainerFormat};
+use gstreamer::prelude::*;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::description::{
+    pipewire_source, replay_pipeline_description, replay_writer_description, REPLAY_AUDIO_SINK,
+    REPLAY_AUDIO_SRC, REPLAY_VIDEO_SINK, REPLAY_VIDEO_SRC,
+};
+use super::{detect_available_audio_encoder, select_video_encoder, StreamKind};
+
+/// Upper bound on writing a saved replay to disk
+const SAVE_TIMEOUT_SECS: u64 = 60;
+
+/// An encoded buffer pulled from a replay appsink
+#[derive(Debug, Clone)]
+struct EncodedChunk {
+    sample: gstreamer::Sample,
+    pts_ns: u64,
+    keyframe: bool,
+}
+
+impl EncodedChunk {
+    fn from_sample(sample: gstreamer::Sample) -> Option<Self> {
+        let buffer = sample.buffer()?;
+        let pts_ns = buffer.pts()?.nseconds();
+        let keyframe = !buffer.flags().contains(gstreamer::BufferFlags::DELTA_UNIT);
+        Some(Self {
+            sample,
+            pts_ns,
+            keyframe,
+        })
+    }
+
+    /// The buffer with timestamps shifted so the replay starts at zero
+    fn rebased(&self, start_ns: u64) -> Option<gstreamer::Buffer> {
+        let mut buffer = self.sample.buffer_owned()?;
+        {
+            let buffer = buffer.make_mut();
+            buffer.set_pts(gstreamer::ClockTime::from_nseconds(
+                self.pts_ns.saturating_sub(start_ns),
+            ));
+            if let Some(dts) = buffer.dts() {
+                buffer.set_dts(gstreamer::ClockTime::from_nseconds(
+                    dts.nseconds().saturating_sub(start_ns),
+                ));
+            }
+        }
+        Some(buffer)
+    }
+}
+
+impl ReplayChunk for EncodedChunk {
+    fn pts_ns(&self) -> u64 {
+        self.pts_ns
+    }
+
+    fn is_keyframe(&self) -> bool {
+        self.keyframe
+    }
+}
+
+/// Encodes a live source into an in-memory ring of the last N seconds
+pub struct ReplayPipeline {
+    pipeline: gstreamer::Pipeline,
+    ring: Arc<Mutex<ReplayRing<EncodedChunk>>>,
+    container: ContainerFormat,
+    width: u32,
+    height: u32,
+}
+
+impl ReplayPipeline {
+    /// Create a replay buffer for the portal stream of `selection`
+    pub fn new(
+        selection: &SelectionResult,
+        config: &CaptureConfig,
+    ) -> Result<Self, CaptureBackendError> {
+        Self::with_source(
+            &pipewire_source(selection),
+            config,
+            selection.width.unwrap_or(1920),
+            selection.height.unwrap_or(1080),
+        )
+    }
+
+    /// Create a replay buffer fed by an arbitrary live video source
+    ///
+    /// `source` is a pipeline fragment ending in raw video, e.g. a
+    /// `videotestsrc is-live=true` in tests.
+    pub fn with_source(
+        source: &str,
+        config: &CaptureConfig,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, CaptureBackendError> {
+        gstreamer::init().map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
+        })?;
+
+        let video_encoder = select_video_encoder(config.encoder.unwrap_or_default().encoder)?;
+        let audio_encoder = if config.audio.mic || config.audio.system {
+            Some(
+                detect_available_audio_encoder(config.container).ok_or_else(|| {
+                    CaptureBackendError::Internal("No audio encoder available".to_string())
+                })?,
+            )
+        } else {
+            None
+        };
+
+        let pipeline_str =
+            replay_pipeline_description(source, video_encoder, audio_encoder, config);
+        info!("Creating replay buffer pipeline: {}", pipeline_str);
+
+        let pipeline = gstreamer::parse::launch(&pipeline_str)
+            .map_err(|e| {
+                CaptureBackendError::Internal(format!("Failed to create pipeline: {}", e))
+            })?
+            .downcast::<gstreamer::Pipeline>()
+            .map_err(|_| {
+                CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string())
+            })?;
+        pipeline.use_clock(Some(&gstreamer::SystemClock::obtain()));
+
+        let window = Duration::from_secs(u64::from(config.replay_buffer_secs));
+        let ring = Arc::new(Mutex::new(ReplayRing::new(window)));
+        feed_ring(&pipeline, REPLAY_VIDEO_SINK, StreamKind::Video, &ring)?;
+        if audio_encoder.is_some() {
+            feed_ring(&pipeline, REPLAY_AUDIO_SINK, StreamKind::Audio, &ring)?;
+        }
+
+        Ok(Self {
+            pipeline,
+            ring,
+            container: config.container,
+            width,
+            height,
+        })
+    }
+
+    /// Start filling the buffer
+    pub fn start(&self) -> Result<(), CaptureBackendError> {
+        self.pipeline
+            .set_state(gstreamer::State::Playing)
+            .map_err(|e| {
+                CaptureBackendError::Internal(format!("Failed to start replay buffer: {}", e))
+            })?;
+        info!("Replay buffer started");
+        Ok(())
+    }
+
+    /// Write the buffered window to `output_path`
+    ///
+    /// The capture pipeline keeps running while the file is written.
+    pub fn save(&self, output_path: &Path) -> Result<RecordingResult, CaptureBackendError> {
+        let snapshot = self.ring.lock().unwrap().snapshot().ok_or_else(|| {
+            CaptureBackendError::Internal("Replay buffer has no keyframe yet".to_string())
+        })?;
+
+        write_snapshot(&snapshot, self.container, output_path)?;
+
+        let result = RecordingResult {
+            path: output_path.to_string_lossy().to_string(),
+            duration_ms: snapshot.duration_ms(),
+            width: self.width,
+            height: self.height,
+            max_av_drift_ms: None,
+            segments: Vec::new(),
+        };
+        info!("Replay saved: {} ({} ms)", result.path, result.duration_ms);
+        Ok(result)
+    }
+
+    /// Stop capturing and drop the buffered data
+    pub fn stop(&self) {
+        let _ = self.pipeline.set_state(gstreamer::State::Null);
+        self.ring.lock().unwrap().clear();
+        info!("Replay buffer stopped");
+    }
+}
+
+impl Drop for ReplayPipeline {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(gstreamer::State::Null);
+    }
+}
+
+impl std::fmt::Debug for ReplayPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayPipeline")
+            .field("buffered", &self.ring.lock().unwrap().len())
+            .field("container", &self.container)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+/// Push every sample reaching the appsink `name` into the ring
+fn feed_ring(
+    pipeline: &gstreamer::Pipeline,
+    name: &str,
+    kind: StreamKind,
+    ring: &Arc<Mutex<ReplayRing<EncodedChunk>>>,
+) -> Result<(), CaptureBackendError> {
+    let sink = pipeline
+        .by_name(name)
+        .and_then(|element| element.downcast::<gstreamer_app::AppSink>().ok())
+        .ok_or_else(|| CaptureBackendError::Internal(format!("Missing appsink {}", name)))?;
+
+    let ring = Arc::clone(ring);
+    sink.set_callbacks(
+        gstreamer_app::AppSinkCallbacks::builder()
+            .new_sample(move |sink| {
+                let sample = sink.pull_sample().map_err(|_| gstreamer::FlowError::Eos)?;
+                if let Some(chunk) = EncodedChunk::from_sample(sample) {
+                    let mut ring = ring.lock().unwrap();
+                    match kind {
+                        StreamKind::Video => ring.push_video(chunk),
+                        StreamKind::Audio => ring.push_audio(chunk),
+                    }
+                }
+                Ok(gstreamer::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+    Ok(())
+}
+
+/// Mux a snapshot into a file through a short-lived appsrc pipeline
+fn write_snapshot(
+    snapshot: &ReplaySnapshot<EncodedChunk>,
+    container: ContainerFormat,
+    output_path: &Path,
+) -> Result<(), CaptureBackendError> {
+    let with_audio = !snapshot.audio.is_empty();
+    let desc = replay_writer_description(container, with_audio, &output_path.to_string_lossy());
+    debug!("Writing replay: {}", desc);
+
+    let pipeline = gstreamer::parse::launch(&desc)
+        .map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to create replay writer: {}", e))
+        })?
+        .downcast::<gstreamer::Pipeline>()
+        .map_err(|_| CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string()))?;
+
+    let mut streams = vec![(REPLAY_VIDEO_SRC, &snapshot.video)];
+    if with_audio {
+        streams.push((REPLAY_AUDIO_SRC, &snapshot.audio));
+    }
+    let sources = streams
+        .into_iter()
+        .map(|(name, chunks)| {
+            let src = pipeline
+                .by_name(name)
+                .and_then(|element| element.downcast::<gstreamer_app::AppSrc>().ok())
+                .ok_or_else(|| CaptureBackendError::Internal(format!("Missing appsrc {}", name)))?;
+            let caps = chunks.first().and_then(|chunk| chunk.sample.caps_owned());
+            src.set_caps(caps.as_ref());
+            Ok((src, chunks))
+        })
+        .collect::<Result<Vec<_>, CaptureBackendError>>()?;
+
+    pipeline.set_state(gstreamer::State::Playing).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to start replay writer: {}", e))
+    })?;
+
+    for (src, chunks) in sources {
+        for buffer in chunks
+            .iter()
+            .filter_map(|chunk| chunk.rebased(snapshot.start_pts_ns))
+        {
+            src.push_buffer(buffer).map_err(|e| {
+                CaptureBackendError::Internal(format!("Failed to write replay: {:?}", e))
+            })?;
+        }
+        let _ = src.end_of_stream();
+    }
+
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| CaptureBackendError::Internal("Failed to get pipeline bus".to_string()))?;
+    let msg = bus.timed_pop_filtered(
+        gstreamer::ClockTime::from_seconds(SAVE_TIMEOUT_SECS),
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    match msg.as_ref().map(|msg| msg.view()) {
+        Some(gstreamer::MessageView::Eos(..)) => Ok(()),
+        Some(gstreamer::MessageView::Error(err)) => Err(CaptureBackendError::Internal(format!(
+            "Replay writer error: {}",
+            err.error()
+        ))),
+        _ => Err(CaptureBackendError::Internal(
+            "Timed out writing replay".to_string(),
+        )),
+    }
+}
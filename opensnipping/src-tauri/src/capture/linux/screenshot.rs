@@ -0,0 +1,128 @@
+use crate::capture::{CaptureBackendError, SelectionResult};
+use image::RgbaImage;
+use tracing::{debug, info};
+
+use gstreamer::prelude::*;
+
+/// appsink a screenshot's frame is pulled from
+pub(super) const SCREENSHOT_SINK: &str = "screenshot";
+
+/// How long to wait for the PipeWire stream's first frame
+const FRAME_TIMEOUT_SECS: u64 = 10;
+
+/// Pipeline pulling one RGBA frame from the PipeWire stream of `selection`
+///
+/// Uses the fd when there is one (portal streams require it), otherwise
+/// the node path alone.
+pub(super) fn screenshot_description(selection: &SelectionResult) -> String {
+    let source = match selection.stream_fd {
+        Some(fd) => format!(
+            "pipewiresrc fd={} path={} num-buffers=1",
+            fd, selection.node_id
+        ),
+        None => format!("pipewiresrc path={} num-buffers=1", selection.node_id),
+    };
+    format!(
+        "{source} ! videoconvert ! video/x-raw,format=RGBA ! \
+         appsink name={SCREENSHOT_SINK} max-buffers=1 sync=false"
+    )
+}
+
+/// Capture a single frame from the PipeWire stream into memory
+///
+/// Runs `pipewiresrc num-buffers=1 ! videoconvert ! appsink` and pulls
+/// the raw RGBA frame; the frame size comes from its caps.
+pub(super) fn capture_frame(selection: &SelectionResult) -> Result<RgbaImage, CaptureBackendError> {
+    info!(
+        "Capturing screenshot from node {} (fd={:?})",
+        selection.node_id, selection.stream_fd
+    );
+
+    // Initialize GStreamer (safe to call multiple times)
+    gstreamer::init().map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
+    })?;
+
+    let pipeline_str = screenshot_description(selection);
+    debug!("Creating GStreamer pipeline: {}", pipeline_str);
+
+    let pipeline = gstreamer::parse::launch(&pipeline_str)
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to create pipeline: {}", e)))?
+        .downcast::<gstreamer::Pipeline>()
+        .map_err(|_| CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string()))?;
+    let sink = pipeline
+        .by_name(SCREENSHOT_SINK)
+        .and_then(|element| element.downcast::<gstreamer_app::AppSink>().ok())
+        .ok_or_else(|| {
+            CaptureBackendError::Internal(format!("Missing appsink {}", SCREENSHOT_SINK))
+        })?;
+
+    pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to start pipeline: {}", e)))?;
+
+    let sample = sink.try_pull_sample(gstreamer::ClockTime::from_seconds(FRAME_TIMEOUT_SECS));
+    let pipeline_error = pipeline
+        .bus()
+        .and_then(|bus| bus.pop_filtered(&[gstreamer::MessageType::Error]))
+        .and_then(|msg| match msg.view() {
+            gstreamer::MessageView::Error(err) => Some(err.error().to_string()),
+            _ => None,
+        });
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    let sample = match (sample, pipeline_error) {
+        (Some(sample), _) => sample,
+        (None, Some(error)) => {
+            return Err(CaptureBackendError::Internal(format!(
+                "Pipeline error: {}",
+                error
+            )))
+        }
+        (None, None) => {
+            return Err(CaptureBackendError::Internal(
+                "Timed out waiting for a frame".to_string(),
+            ))
+        }
+    };
+
+    let image = sample_to_image(&sample)?;
+    info!("Screenshot captured: {}x{}", image.width(), image.height());
+    Ok(image)
+}
+
+/// Copy a raw RGBA video sample into an `RgbaImage`
+///
+/// Used for screenshots and for frames taken from a running pipeline.
+pub(super) fn sample_to_image(
+    sample: &gstreamer::Sample,
+) -> Result<RgbaImage, CaptureBackendError> {
+    let caps = sample
+        .caps()
+        .ok_or_else(|| CaptureBackendError::Internal("Frame has no caps".to_string()))?;
+    let info = gstreamer_video::VideoInfo::from_caps(caps)
+        .map_err(|e| CaptureBackendError::Internal(format!("Unexpected frame caps: {}", e)))?;
+    let buffer = sample
+        .buffer()
+        .ok_or_else(|| CaptureBackendError::Internal("Frame has no buffer".to_string()))?;
+    let frame = gstreamer_video::VideoFrameRef::from_buffer_ref_readable(buffer, &info)
+        .map_err(|_| CaptureBackendError::Internal("Failed to map frame".to_string()))?;
+
+    // Rows may be padded; keep only the pixels
+    let width = info.width();
+    let height = info.height();
+    let stride = frame.plane_stride()[0] as usize;
+    let row_len = width as usize * 4;
+    let data = frame
+        .plane_data(0)
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to read frame: {}", e)))?;
+    let pixels: Vec<u8> = data
+        .chunks(stride)
+        .take(height as usize)
+        .flat_map(|row| &row[..row_len])
+        .copied()
+        .collect();
+
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| CaptureBackendError::Internal("Frame is smaller than its caps".to_string()))
+}
@@ -0,0 +1,167 @@
+use crate::capture::{RecordingSegment, SegmentCallback};
+use crate::config::{ContainerFormat, SegmentConfig};
+use gstreamer::prelude::*;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+use super::description::quoted;
+use super::{crash_safe_muxer_properties, get_muxer_for_container};
+
+const FRAGMENT_OPENED: &str = "splitmuxsink-fragment-opened";
+const FRAGMENT_CLOSED: &str = "splitmuxsink-fragment-closed";
+
+/// Convert a `{index}` segment template into a splitmuxsink printf location
+pub(super) fn splitmux_location(template: &str) -> String {
+    template
+        .replace('%', "%%")
+        .replace(SegmentConfig::INDEX_PLACEHOLDER, "%05d")
+}
+
+/// splitmuxsink description that replaces `muxer ! filesink`
+///
+/// The element is named `mux` so audio branches can link to it.
+pub(super) fn splitmux_sink_description(
+    container: ContainerFormat,
+    crash_safe: bool,
+    segments: &SegmentConfig,
+    output_path: &str,
+) -> String {
+    let mut desc = format!(
+        "splitmuxsink name=mux location={} muxer-factory={}",
+        quoted(&splitmux_location(&segments.template_for(output_path))),
+        get_muxer_for_container(container)
+    );
+
+    if let Some(secs) = segments.max_duration_secs {
+        desc.push_str(&format!(
+            " max-size-time={}",
+            secs.saturating_mul(1_000_000_000)
+        ));
+    }
+    if let Some(bytes) = segments.max_size_bytes {
+        desc.push_str(&format!(" max-size-bytes={}", bytes));
+    }
+
+    let props = crash_safe_muxer_properties(container);
+    if crash_safe && !props.is_empty() {
+        desc.push_str(&format!(" muxer-properties=\"properties,{}\"", props));
+    }
+
+    desc
+}
+
+#[derive(Default)]
+struct TrackerState {
+    /// Location and start running time of the segment being written
+    open: Option<(String, u64)>,
+    closed: Vec<RecordingSegment>,
+    on_closed: Option<SegmentCallback>,
+}
+
+/// Collects splitmuxsink fragment messages into finished segments
+#[derive(Default)]
+pub struct SegmentTracker {
+    state: Mutex<TrackerState>,
+}
+
+impl SegmentTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notify `callback` of every segment closed from now on
+    pub fn set_callback(&self, callback: SegmentCallback) {
+        self.state.lock().unwrap().on_closed = Some(callback);
+    }
+
+    /// A new segment file was opened at `running_time_ns`
+    pub fn opened(&self, location: &str, running_time_ns: u64) {
+        debug!("Segment opened: {}", location);
+        self.state.lock().unwrap().open = Some((location.to_string(), running_time_ns));
+    }
+
+    /// A segment file was finalized at `running_time_ns`
+    pub fn closed(&self, location: &str, running_time_ns: u64) -> RecordingSegment {
+        let (segment, callback) = {
+            let mut state = self.state.lock().unwrap();
+            let start = match state.open.take() {
+                Some((open, start)) if open == location => start,
+                _ => running_time_ns,
+            };
+            let segment = RecordingSegment {
+                index: state.closed.len() as u32,
+                path: location.to_string(),
+                duration_ms: running_time_ns.saturating_sub(start) / 1_000_000,
+            };
+            state.closed.push(segment.clone());
+            (segment, state.on_closed.clone())
+        };
+
+        info!(
+            "Segment {} closed: {} ({} ms)",
+            segment.index, segment.path, segment.duration_ms
+        );
+        // Call outside the lock so the callback may query the tracker
+        if let Some(callback) = callback {
+            callback(&segment);
+        }
+        segment
+    }
+
+    /// Every segment closed so far, in order
+    pub fn segments(&self) -> Vec<RecordingSegment> {
+        self.state.lock().unwrap().closed.clone()
+    }
+
+    /// Size on disk of every segment so far, including the open one
+    pub fn bytes_written(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        state
+            .closed
+            .iter()
+            .map(|segment| segment.path.as_str())
+            .chain(state.open.as_ref().map(|(location, _)| location.as_str()))
+            .map(|path| std::fs::metadata(path).map_or(0, |meta| meta.len()))
+            .sum()
+    }
+}
+
+impl std::fmt::Debug for SegmentTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("SegmentTracker")
+            .field("open", &state.open)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+/// Feed splitmuxsink fragment messages from the pipeline bus into `tracker`
+///
+/// Uses a sync handler that passes every message on, so the bus can still
+/// be polled for EOS and errors when the recording stops.
+pub(super) fn watch_segments(pipeline: &gstreamer::Pipeline, tracker: &Arc<SegmentTracker>) {
+    let Some(bus) = pipeline.bus() else {
+        return;
+    };
+    let tracker = Arc::clone(tracker);
+
+    bus.set_sync_handler(move |_, msg| {
+        if let gstreamer::MessageView::Element(element) = msg.view() {
+            if let Some(s) = element.structure() {
+                let location = s.get::<String>("location");
+                let running_time = s.get::<u64>("running-time");
+                if let (Ok(location), Ok(running_time)) = (location, running_time) {
+                    match s.name().as_str() {
+                        FRAGMENT_OPENED => tracker.opened(&location, running_time),
+                        FRAGMENT_CLOSED => {
+                            tracker.closed(&location, running_time);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        gstreamer::BusSyncReply::Pass
+    });
+}
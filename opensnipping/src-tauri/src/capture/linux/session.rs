@@ -0,0 +1,103 @@
+// Portal screencast session held by the Linux backend
+//
+// The portal can end a session on its own (the user stops sharing from the
+// desktop's indicator, the shared window goes away). The session's `Closed`
+// signal is forwarded to a callback so the IPC layer can stop whatever is
+// still using the stream.
+
+use crate::capture::SessionClosedCallback;
+use ashpd::desktop::screencast::Screencast;
+use ashpd::desktop::Session;
+use futures_util::StreamExt;
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use tracing::{debug, info};
+
+/// Holds an active screencast session
+pub(super) struct ActiveSession {
+    /// The ashpd screencast proxy - MUST be kept alive (leaked for 'static)
+    #[allow(dead_code)]
+    _screencast: &'static Screencast<'static>,
+    /// The ashpd session - MUST be kept alive for the stream to remain valid
+    session: Arc<Session<'static, Screencast<'static>>>,
+    /// PipeWire node ID (stored for future use in recording pipeline)
+    node_id: u32,
+    /// PipeWire remote fd - this is the key to keeping the stream alive
+    pipewire_fd: OwnedFd,
+    /// Dropping this stops the `Closed` watcher
+    stop_watching: oneshot::Sender<()>,
+}
+
+impl ActiveSession {
+    pub(super) fn new(
+        screencast: &'static Screencast<'static>,
+        session: Session<'static, Screencast<'static>>,
+        node_id: u32,
+        pipewire_fd: OwnedFd,
+        on_closed: Option<SessionClosedCallback>,
+    ) -> Self {
+        let session = Arc::new(session);
+        let (stop_watching, stopped) = oneshot::channel();
+        if let Some(on_closed) = on_closed {
+            watch_closed(Arc::clone(&session), stopped, on_closed);
+        }
+        Self {
+            _screencast: screencast,
+            session,
+            node_id,
+            pipewire_fd,
+            stop_watching,
+        }
+    }
+
+    /// Ask the portal to end the session
+    ///
+    /// The watcher is stopped first: closing on purpose is not reported.
+    pub(super) async fn close(self) {
+        let Self {
+            session,
+            stop_watching,
+            ..
+        } = self;
+        drop(stop_watching);
+        if let Err(e) = session.close().await {
+            // Already closed by the portal
+            debug!("Closing portal session failed: {}", e);
+        }
+    }
+}
+
+impl std::fmt::Debug for ActiveSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActiveSession")
+            .field("node_id", &self.node_id)
+            .field("pipewire_fd", &self.pipewire_fd.as_raw_fd())
+            .finish()
+    }
+}
+
+/// Call `on_closed` when the portal emits `Closed`, until `stopped` resolves
+fn watch_closed(
+    session: Arc<Session<'static, Screencast<'static>>>,
+    stopped: oneshot::Receiver<()>,
+    on_closed: SessionClosedCallback,
+) {
+    tokio::spawn(async move {
+        let closed = match session.receive_closed().await {
+            Ok(closed) => closed,
+            Err(e) => {
+                debug!("Cannot watch portal session for Closed: {}", e);
+                return;
+            }
+        };
+        let mut closed = std::pin::pin!(closed);
+        tokio::select! {
+            Some(_) = closed.next() => {
+                info!("Portal closed the screencast session");
+                on_closed();
+            }
+            _ = stopped => {}
+        }
+    });
+}
@@ -0,0 +1,198 @@
+// A/V sync helpers for recording pipelines
+//
+// Video (pipewiresrc) and audio (pulsesrc) run on different device clocks.
+// Both are slaved to the pipeline's system clock, and a drift monitor
+// compares the running times of the buffers reaching each encoder and
+// reports the drift while the recording runs.
+
+use crate::capture::{AvDrift, DriftCallback};
+use gstreamer::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Drift beyond which audio and video are perceptibly out of sync
+pub(super) const AV_SYNC_TOLERANCE: Duration = Duration::from_millis(40);
+
+/// Recording time between two drift reports
+pub(super) const DRIFT_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// pipewiresrc: stamp buffers with the pipeline clock on capture
+pub(super) const VIDEO_SOURCE_SYNC_PROPS: &str = "do-timestamp=true";
+
+/// pulsesrc: don't offer the sound card clock to the pipeline, and skew
+/// the audio timestamps back onto the pipeline clock when they drift
+pub(super) const AUDIO_SOURCE_SYNC_PROPS: &str = "provide-clock=false slave-method=skew";
+
+/// Element names used to find the encoder inputs for drift probes
+pub(super) const VIDEO_ENCODER_NAME: &str = "venc";
+pub(super) const AUDIO_ENCODER_NAME: &str = "aenc";
+
+/// Which branch of the pipeline a buffer came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    Video,
+    Audio,
+}
+
+/// Tracks the drift between audio and video running times
+///
+/// Updated from pad probes on the streaming threads, so the measurements
+/// are atomic. Once both streams have data, the reporter is called every
+/// `DRIFT_REPORT_INTERVAL` of running time.
+pub struct DriftMonitor {
+    tolerance: Duration,
+    video_end_ns: AtomicU64,
+    audio_end_ns: AtomicU64,
+    max_drift_ns: AtomicU64,
+    warned: AtomicBool,
+    /// Running time at which the next report is due
+    next_report_ns: AtomicU64,
+    reporter: Mutex<Option<DriftCallback>>,
+}
+
+impl std::fmt::Debug for DriftMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DriftMonitor")
+            .field("tolerance", &self.tolerance)
+            .field("drift_ns", &self.drift_ns())
+            .field("max_drift_ms", &self.max_drift_ms())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DriftMonitor {
+    pub fn new(tolerance: Duration) -> Self {
+        Self {
+            tolerance,
+            video_end_ns: AtomicU64::new(0),
+            audio_end_ns: AtomicU64::new(0),
+            max_drift_ns: AtomicU64::new(0),
+            warned: AtomicBool::new(false),
+            next_report_ns: AtomicU64::new(0),
+            reporter: Mutex::new(None),
+        }
+    }
+
+    /// Tolerance for a recording at `fps`
+    ///
+    /// Video only advances one frame at a time, so a frame interval of
+    /// jitter is added on top of the perceptual threshold.
+    pub fn for_fps(fps: u8) -> Self {
+        let frame = Duration::from_secs(1) / u32::from(fps.max(1));
+        Self::new(AV_SYNC_TOLERANCE + frame)
+    }
+
+    /// Call `reporter` with the drift while the recording runs
+    pub fn set_reporter(&self, reporter: DriftCallback) {
+        *self.reporter.lock().unwrap() = Some(reporter);
+    }
+
+    /// Record the end running time of a buffer
+    pub fn record(&self, kind: StreamKind, end_ns: u64) {
+        match kind {
+            StreamKind::Video => self.video_end_ns.store(end_ns, Ordering::SeqCst),
+            StreamKind::Audio => self.audio_end_ns.store(end_ns, Ordering::SeqCst),
+        }
+
+        // Drift is only meaningful once both streams have produced data
+        let video_end_ns = self.video_end_ns.load(Ordering::SeqCst);
+        let audio_end_ns = self.audio_end_ns.load(Ordering::SeqCst);
+        if video_end_ns == 0 || audio_end_ns == 0 {
+            return;
+        }
+
+        let drift = self.drift_ns().unsigned_abs();
+        self.max_drift_ns.fetch_max(drift, Ordering::SeqCst);
+
+        if drift > self.tolerance.as_nanos() as u64 && !self.warned.swap(true, Ordering::SeqCst) {
+            warn!(
+                "A/V drift of {} ms exceeds tolerance of {} ms",
+                drift / 1_000_000,
+                self.tolerance.as_millis()
+            );
+        }
+
+        self.report_if_due(video_end_ns.max(audio_end_ns));
+    }
+
+    /// Call the reporter when `now_ns` has reached the next report time
+    fn report_if_due(&self, now_ns: u64) {
+        let due_ns = self.next_report_ns.load(Ordering::SeqCst);
+        let next_ns = now_ns + DRIFT_REPORT_INTERVAL.as_nanos() as u64;
+        // Both streaming threads get here; only one of them reports
+        if now_ns < due_ns
+            || self
+                .next_report_ns
+                .compare_exchange(due_ns, next_ns, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err()
+        {
+            return;
+        }
+        let reporter = self.reporter.lock().unwrap().clone();
+        if let Some(reporter) = reporter {
+            reporter(&self.snapshot());
+        }
+    }
+
+    /// The drift so far, as reported while recording
+    pub fn snapshot(&self) -> AvDrift {
+        AvDrift {
+            drift_ms: self.drift_ns() / 1_000_000,
+            max_drift_ms: self.max_drift_ms(),
+            exceeded_tolerance: self.exceeded_tolerance(),
+        }
+    }
+
+    /// Current drift in nanoseconds (positive when audio is ahead)
+    pub fn drift_ns(&self) -> i64 {
+        self.audio_end_ns.load(Ordering::SeqCst) as i64
+            - self.video_end_ns.load(Ordering::SeqCst) as i64
+    }
+
+    /// Largest absolute drift seen so far, in milliseconds
+    pub fn max_drift_ms(&self) -> u64 {
+        self.max_drift_ns.load(Ordering::SeqCst) / 1_000_000
+    }
+
+    /// Whether the drift ever went beyond the tolerance
+    pub fn exceeded_tolerance(&self) -> bool {
+        self.max_drift_ns.load(Ordering::SeqCst) > self.tolerance.as_nanos() as u64
+    }
+
+    pub fn tolerance(&self) -> Duration {
+        self.tolerance
+    }
+}
+
+/// Add a buffer probe on `element_name`'s sink pad that feeds `monitor`
+///
+/// Returns false if the element or pad does not exist in the pipeline.
+pub(super) fn attach_drift_probe(
+    pipeline: &gstreamer::Pipeline,
+    element_name: &str,
+    kind: StreamKind,
+    monitor: &Arc<DriftMonitor>,
+) -> bool {
+    let Some(pad) = pipeline
+        .by_name(element_name)
+        .and_then(|element| element.static_pad("sink"))
+    else {
+        debug!("No sink pad on '{}' for drift probe", element_name);
+        return false;
+    };
+
+    let monitor = Arc::clone(monitor);
+    pad.add_probe(gstreamer::PadProbeType::BUFFER, move |_pad, info| {
+        if let Some(gstreamer::PadProbeData::Buffer(buffer)) = &info.data {
+            if let Some(pts) = buffer.pts() {
+                let end = pts + buffer.duration().unwrap_or(gstreamer::ClockTime::ZERO);
+                monitor.record(kind, end.nseconds());
+            }
+        }
+        gstreamer::PadProbeReturn::Ok
+    });
+
+    true
+}
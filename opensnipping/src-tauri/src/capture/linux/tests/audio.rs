@@ -0,0 +1,120 @@
+use super::*;
+use crate::capture::linux::description::{audio_branch, audio_only_pipeline_description};
+use crate::config::{CaptureConfig, CaptureMode};
+
+#[test]
+fn test_muxers_for_audio_only_containers() {
+    assert_eq!(get_muxer_for_container(ContainerFormat::Ogg), "oggmux");
+    assert_eq!(get_muxer_for_container(ContainerFormat::M4a), "mp4mux");
+    assert_eq!(get_muxer_for_container(ContainerFormat::Wav), "wavenc");
+}
+
+#[test]
+fn test_detect_audio_encoder_ogg_returns_opus() {
+    // OGG cannot carry AAC, so there is no fallback
+    if let Some(encoder) = detect_available_audio_encoder(ContainerFormat::Ogg) {
+        assert!(
+            OPUS_ENCODERS.contains(&encoder),
+            "OGG audio encoder '{}' should be Opus",
+            encoder
+        );
+    }
+}
+
+#[test]
+fn test_detect_audio_encoder_wav_needs_none() {
+    assert_eq!(detect_available_audio_encoder(ContainerFormat::Wav), None);
+}
+
+// --- Audio-only pipeline tests ---
+
+#[test]
+fn test_audio_branch_mic_only() {
+    let audio = AudioConfig {
+        mic: true,
+        system: false,
+    };
+    let branch = audio_branch(&audio, "opusenc ! mux.");
+    assert_eq!(
+        branch,
+        "pulsesrc provide-clock=false slave-method=skew ! \
+         audioconvert ! audioresample ! opusenc ! mux."
+    );
+}
+
+#[test]
+fn test_audio_branch_system_only_uses_monitor() {
+    let audio = AudioConfig {
+        mic: false,
+        system: true,
+    };
+    let branch = audio_branch(&audio, "opusenc ! mux.");
+    assert!(branch.starts_with("pulsesrc device=@DEFAULT_MONITOR@"));
+    assert!(!branch.contains("audiomixer"));
+}
+
+#[test]
+fn test_audio_branch_both_sources_are_mixed() {
+    let audio = AudioConfig {
+        mic: true,
+        system: true,
+    };
+    let branch = audio_branch(&audio, "opusenc ! mux.");
+    assert!(branch.contains("audiomixer name=mix"));
+    assert_eq!(branch.matches("! mix.").count(), 2);
+    assert_eq!(branch.matches("opusenc").count(), 1);
+}
+
+fn audio_only(container: ContainerFormat, audio: AudioConfig, output_path: &str) -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::AudioOnly,
+        container,
+        audio,
+        output_path: output_path.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_audio_only_pipeline_has_no_video_source() {
+    let config = audio_only(
+        ContainerFormat::Ogg,
+        AudioConfig {
+            mic: true,
+            system: false,
+        },
+        "/tmp/memo.ogg",
+    );
+    let desc = audio_only_pipeline_description(&config, Some("opusenc"));
+    assert!(!desc.contains("pipewiresrc"));
+    assert!(desc.contains("opusenc ! oggmux name=mux ! filesink location=\"/tmp/memo.ogg\""));
+}
+
+#[test]
+fn test_audio_only_pipeline_m4a_uses_aac_and_mp4mux() {
+    let config = audio_only(
+        ContainerFormat::M4a,
+        AudioConfig {
+            mic: false,
+            system: true,
+        },
+        "/tmp/meeting.m4a",
+    );
+    let desc = audio_only_pipeline_description(&config, Some("voaacenc"));
+    assert!(desc.contains("voaacenc ! mp4mux name=mux ! filesink location=\"/tmp/meeting.m4a\""));
+}
+
+#[test]
+fn test_audio_only_pipeline_wav_skips_encoder() {
+    let config = audio_only(
+        ContainerFormat::Wav,
+        AudioConfig {
+            mic: true,
+            system: true,
+        },
+        "/tmp/memo.wav",
+    );
+    let desc = audio_only_pipeline_description(&config, Some("opusenc"));
+    assert!(!desc.contains("opusenc"));
+    assert!(desc.contains("audioresample ! wavenc name=mux ! filesink location=\"/tmp/memo.wav\""));
+}
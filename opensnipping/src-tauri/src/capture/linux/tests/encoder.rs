@@ -0,0 +1,93 @@
+use super::*;
+use crate::capture::linux::description::{replay_pipeline_description, video_encoder_element};
+use crate::config::{CaptureConfig, CaptureMode, EncoderConfig, EncoderPreset, VideoEncoder};
+
+fn with_encoder(bitrate_kbps: Option<u32>, preset: EncoderPreset) -> CaptureConfig {
+    CaptureConfig {
+        encoder: Some(EncoderConfig {
+            encoder: VideoEncoder::Auto,
+            bitrate_kbps,
+            preset,
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_video_encoder_props_per_encoder() {
+    let preferences = EncoderConfig {
+        encoder: VideoEncoder::Auto,
+        bitrate_kbps: Some(6000),
+        preset: EncoderPreset::Fast,
+    };
+    assert_eq!(
+        video_encoder_props("x264enc", &preferences),
+        "bitrate=6000 speed-preset=ultrafast"
+    );
+    assert_eq!(
+        video_encoder_props("vaapih264enc", &preferences),
+        "rate-control=cbr bitrate=6000 quality-level=7"
+    );
+    assert_eq!(
+        video_encoder_props("nvh264enc", &preferences),
+        "rc-mode=cbr bitrate=6000 preset=hp"
+    );
+    assert_eq!(video_encoder_props("unknownenc", &preferences), "");
+}
+
+#[test]
+fn test_default_preferences_keep_encoder_defaults() {
+    for encoder in H264_ENCODERS {
+        assert_eq!(video_encoder_props(encoder, &EncoderConfig::default()), "");
+    }
+    assert_eq!(
+        video_encoder_element("x264enc", &CaptureConfig::default()),
+        "x264enc"
+    );
+}
+
+#[test]
+fn test_quality_preset_without_bitrate() {
+    let config = with_encoder(None, EncoderPreset::Quality);
+    assert_eq!(
+        video_encoder_element("x264enc", &config),
+        "x264enc speed-preset=slow"
+    );
+    assert_eq!(
+        video_encoder_element("nvh264enc", &config),
+        "nvh264enc preset=hq"
+    );
+}
+
+#[test]
+fn test_replay_description_applies_preferences() {
+    let config = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..with_encoder(Some(4000), EncoderPreset::Balanced)
+    };
+    let desc = replay_pipeline_description("videotestsrc", "x264enc", None, &config);
+    assert!(desc.contains("x264enc bitrate=4000 name=venc key-int-max=30"));
+}
+
+#[test]
+fn test_explicit_encoder_must_be_available() {
+    if gstreamer::init().is_err() {
+        return;
+    }
+    for (choice, factory) in [
+        (VideoEncoder::X264, "x264enc"),
+        (VideoEncoder::Vaapi, "vaapih264enc"),
+        (VideoEncoder::Nvenc, "nvh264enc"),
+    ] {
+        let available =
+            gstreamer::ElementFactory::find(factory).is_some_and(|f| f.create().build().is_ok());
+        match select_video_encoder(choice) {
+            Ok(selected) => assert_eq!(selected, factory),
+            Err(e) => assert!(!available, "{} available but rejected: {}", factory, e),
+        }
+    }
+    assert_eq!(
+        select_video_encoder(VideoEncoder::Auto).ok(),
+        detect_available_encoder()
+    );
+}
@@ -122,7 +122,13 @@ fn test_encoder_detection_is_deterministic() {
 #[test]
 fn test_all_container_formats_have_muxers() {
     // Ensure every ContainerFormat variant has a corresponding muxer
-    let formats = [ContainerFormat::Mp4, ContainerFormat::Mkv];
+    let formats = [
+        ContainerFormat::Mp4,
+        ContainerFormat::Mkv,
+        ContainerFormat::Ogg,
+        ContainerFormat::M4a,
+        ContainerFormat::Wav,
+    ];
     for format in formats {
         let muxer = get_muxer_for_container(format);
         assert!(
@@ -209,15 +215,13 @@ fn test_recording_smoke_start_stop() {
 
     // In a real test with portal access:
     // let node_id = <get from portal session>;
-    // let mut pipeline = RecordingPipeline::new(
-    //     node_id,
-    //     _output_path.clone(),
-    //     30, // fps
-    //     ContainerFormat::Mp4,
-    //     &AudioConfig::default(),
-    //     Some(1920),
-    //     Some(1080),
-    // ).expect("Failed to create pipeline");
+    // let selection = SelectionResult { node_id, width: Some(1920), height: Some(1080), .. };
+    // let config = CaptureConfig {
+    //     output_path: _output_path.to_string_lossy().to_string(),
+    //     ..Default::default()
+    // };
+    // let mut pipeline = RecordingPipeline::new(&selection, &config)
+    //     .expect("Failed to create pipeline");
     //
     // pipeline.start().expect("Failed to start recording");
     // std::thread::sleep(std::time::Duration::from_secs(3));
@@ -405,3 +409,14 @@ fn test_audiomixer_element_availability() {
         println!("audiomixer element not found - audio mixing requires gst-plugins-base");
     }
 }
+
+mod audio;
+mod encoder;
+mod paths;
+mod recovery;
+mod replay;
+mod segments;
+mod stills;
+mod sync;
+mod timelapse;
+mod webp;
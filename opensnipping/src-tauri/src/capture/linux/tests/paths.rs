@@ -0,0 +1,137 @@
+use super::*;
+use crate::capture::linux::description::{
+    audio_only_pipeline_description, output_sink, quoted, replay_writer_description,
+    timelapse_encode_description,
+};
+use crate::capture::linux::webp::webp_encode_description;
+use crate::config::{CaptureConfig, CaptureMode, SegmentConfig};
+use gstreamer::prelude::*;
+use std::path::Path;
+
+/// Output directory with a space, like a localized XDG Videos directory
+const DIR: &str = "/tmp/Opensnipping Videos";
+
+/// `location` of the `factory` element in `desc` once parsed, or `None`
+/// when an element of `desc` is not installed
+fn parsed_location(desc: &str, factory: &str) -> Option<String> {
+    gstreamer::init().ok()?;
+    let bin = match gstreamer::parse::bin_from_description(desc, false) {
+        Ok(bin) => bin,
+        Err(err) if err.matches(gstreamer::ParseError::NoSuchElement) => {
+            println!("Skipping: {}", err);
+            return None;
+        }
+        Err(err) => panic!("{} does not parse: {}", desc, err),
+    };
+    let element = bin
+        .iterate_recurse()
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|element| {
+            element
+                .factory()
+                .is_some_and(|f| f.name().as_str() == factory)
+        })
+        .unwrap_or_else(|| panic!("no {} in {}", factory, desc));
+    element.property::<Option<String>>("location")
+}
+
+fn config_in_dir(container: ContainerFormat) -> CaptureConfig {
+    CaptureConfig {
+        container,
+        output_path: format!(
+            "{}/Recording 2024-05-01 12-30-00.{}",
+            DIR,
+            container.extension()
+        ),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_quoted_escapes_quotes_and_backslashes() {
+    assert_eq!(quoted("/tmp/a b.mp4"), "\"/tmp/a b.mp4\"");
+    assert_eq!(quoted(r#"/tmp/"x"\y"#), r#""/tmp/\"x\"\\y""#);
+}
+
+#[test]
+fn test_filesink_path_with_spaces_parses() {
+    let config = config_in_dir(ContainerFormat::Mkv);
+
+    let location = parsed_location(&output_sink(&config), "filesink");
+
+    if let Some(location) = location {
+        assert_eq!(location, config.output_path);
+    }
+}
+
+#[test]
+fn test_splitmuxsink_path_with_spaces_parses() {
+    let config = CaptureConfig {
+        segments: Some(SegmentConfig {
+            max_duration_secs: Some(600),
+            ..Default::default()
+        }),
+        ..config_in_dir(ContainerFormat::Mkv)
+    };
+
+    let location = parsed_location(&output_sink(&config), "splitmuxsink");
+
+    if let Some(location) = location {
+        assert_eq!(
+            location,
+            format!("{}/Recording 2024-05-01 12-30-00-%05d.mkv", DIR)
+        );
+    }
+}
+
+#[test]
+fn test_audio_only_path_with_spaces_parses() {
+    let config = CaptureConfig {
+        mode: CaptureMode::AudioOnly,
+        audio: AudioConfig {
+            mic: true,
+            system: false,
+        },
+        ..config_in_dir(ContainerFormat::Wav)
+    };
+    let desc = audio_only_pipeline_description(&config, None);
+
+    if let Some(location) = parsed_location(&desc, "filesink") {
+        assert_eq!(location, config.output_path);
+    }
+}
+
+#[test]
+fn test_replay_writer_path_with_spaces_parses() {
+    let path = format!("{}/Replay 2024-05-01 12-30-00.mkv", DIR);
+    let desc = replay_writer_description(ContainerFormat::Mkv, true, &path);
+
+    if let Some(location) = parsed_location(&desc, "filesink") {
+        assert_eq!(location, path);
+    }
+}
+
+#[test]
+fn test_timelapse_encode_paths_with_spaces_parse() {
+    let config = config_in_dir(ContainerFormat::Mp4);
+    let frames_dir = format!("{}/Timelapse frames", DIR);
+    let desc = timelapse_encode_description(&frames_dir, 5, "x264enc", 640, 480, &config);
+
+    if let Some(location) = parsed_location(&desc, "multifilesrc") {
+        assert_eq!(location, format!("{}/frame-%05d.png", frames_dir));
+    }
+    if let Some(location) = parsed_location(&desc, "filesink") {
+        assert_eq!(location, config.output_path);
+    }
+}
+
+#[test]
+fn test_webp_path_with_spaces_parses() {
+    let path = format!("{}/Screenshot 2024-05-01 12-30-00.webp", DIR);
+    let desc = webp_encode_description(64, 48, 75, Path::new(&path));
+
+    if let Some(location) = parsed_location(&desc, "filesink") {
+        assert_eq!(location, path);
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+use crate::capture::linux::description::{audio_only_pipeline_description, muxer_element};
+use crate::capture::linux::remux::{demuxer_for_container, mux_pad_template, recovering_path};
+use crate::config::{CaptureConfig, CaptureMode};
+use gstreamer::prelude::*;
+
+#[test]
+fn test_crash_safe_muxer_properties() {
+    assert_eq!(
+        crash_safe_muxer_properties(ContainerFormat::Mp4),
+        "fragment-duration=1000"
+    );
+    assert_eq!(
+        crash_safe_muxer_properties(ContainerFormat::M4a),
+        "fragment-duration=1000"
+    );
+    assert_eq!(
+        crash_safe_muxer_properties(ContainerFormat::Mkv),
+        "streamable=true"
+    );
+    assert_eq!(crash_safe_muxer_properties(ContainerFormat::Ogg), "");
+    assert_eq!(crash_safe_muxer_properties(ContainerFormat::Wav), "");
+}
+
+#[test]
+fn test_muxer_element_only_adds_properties_when_crash_safe() {
+    assert_eq!(muxer_element(ContainerFormat::Mp4, false), "mp4mux");
+    assert_eq!(
+        muxer_element(ContainerFormat::Mp4, true),
+        "mp4mux fragment-duration=1000"
+    );
+    assert_eq!(
+        muxer_element(ContainerFormat::Mkv, true),
+        "matroskamux streamable=true"
+    );
+    assert_eq!(muxer_element(ContainerFormat::Ogg, true), "oggmux");
+}
+
+#[test]
+fn test_audio_only_pipeline_crash_safe_m4a_is_fragmented() {
+    let config = CaptureConfig {
+        mode: CaptureMode::AudioOnly,
+        container: ContainerFormat::M4a,
+        audio: AudioConfig {
+            mic: true,
+            system: false,
+        },
+        output_path: "/tmp/memo.m4a".to_string(),
+        crash_safe: true,
+        ..Default::default()
+    };
+    let desc = audio_only_pipeline_description(&config, Some("voaacenc"));
+    assert!(desc.contains("voaacenc ! mp4mux fragment-duration=1000 name=mux ! filesink"));
+}
+
+#[test]
+fn test_demuxer_matches_each_container() {
+    assert_eq!(demuxer_for_container(ContainerFormat::Mp4), "qtdemux");
+    assert_eq!(demuxer_for_container(ContainerFormat::M4a), "qtdemux");
+    assert_eq!(demuxer_for_container(ContainerFormat::Mkv), "matroskademux");
+    assert_eq!(demuxer_for_container(ContainerFormat::Ogg), "oggdemux");
+    assert_eq!(demuxer_for_container(ContainerFormat::Wav), "wavparse");
+}
+
+#[test]
+fn test_mux_pad_template_keeps_audio_and_video_only() {
+    assert_eq!(mux_pad_template("video_0"), Some("video_%u"));
+    assert_eq!(mux_pad_template("audio_1"), Some("audio_%u"));
+    assert_eq!(mux_pad_template("subtitle_0"), None);
+}
+
+#[test]
+fn test_recovering_path_appends_suffix() {
+    assert_eq!(
+        recovering_path(std::path::Path::new("/tmp/rec.mkv")),
+        std::path::PathBuf::from("/tmp/rec.mkv.recovering")
+    );
+}
+
+/// Write a streamable MKV, cut it short as a crash would, then remux it
+#[test]
+fn test_remux_truncated_streamable_mkv() {
+    if gstreamer::init().is_err() {
+        return;
+    }
+    let required = ["audiotestsrc", "opusenc", "matroskamux", "matroskademux"];
+    if required
+        .iter()
+        .any(|name| gstreamer::ElementFactory::find(name).is_none())
+    {
+        println!("Skipping: required GStreamer elements not available");
+        return;
+    }
+
+    let path =
+        std::env::temp_dir().join(format!("opensnipping-crash-{}.mkv", uuid::Uuid::new_v4()));
+    let desc = format!(
+        "audiotestsrc num-buffers=200 ! audioconvert ! opusenc ! {} ! filesink location={}",
+        muxer_element(ContainerFormat::Mkv, true),
+        path.display()
+    );
+    let pipeline = gstreamer::parse::launch(&desc).unwrap();
+    pipeline.set_state(gstreamer::State::Playing).unwrap();
+    let bus = pipeline.bus().unwrap();
+    let _ = bus.timed_pop_filtered(
+        gstreamer::ClockTime::from_seconds(10),
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+    pipeline.set_state(gstreamer::State::Null).unwrap();
+
+    let full_len = std::fs::metadata(&path).unwrap().len();
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(full_len * 2 / 3).unwrap();
+    drop(file);
+
+    remux_recording(&path, ContainerFormat::Mkv).expect("remux should succeed");
+    assert!(std::fs::metadata(&path).unwrap().len() > 0);
+    assert!(!recovering_path(&path).exists());
+
+    let _ = std::fs::remove_file(&path);
+}
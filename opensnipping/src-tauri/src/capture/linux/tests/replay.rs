@@ -0,0 +1,108 @@
+use super::*;
+use crate::capture::linux::description::{replay_pipeline_description, replay_writer_description};
+use crate::config::{CaptureConfig, CaptureMode};
+
+fn replay_config() -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        container: ContainerFormat::Mkv,
+        replay_buffer_secs: 5,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_keyframe_interval_props_per_encoder() {
+    assert_eq!(keyframe_interval_props("x264enc", 30), "key-int-max=30");
+    assert_eq!(
+        keyframe_interval_props("vaapih264enc", 30),
+        "keyframe-period=30"
+    );
+    assert_eq!(keyframe_interval_props("nvh264enc", 30), "gop-size=30");
+    assert_eq!(keyframe_interval_props("unknownenc", 30), "");
+}
+
+#[test]
+fn test_replay_pipeline_description_video_only() {
+    let desc = replay_pipeline_description("videotestsrc", "x264enc", None, &replay_config());
+    assert!(desc.starts_with("videotestsrc ! videoconvert"));
+    assert!(desc.contains("x264enc name=venc key-int-max=30"));
+    assert!(desc.contains("appsink name=replay_video"));
+    assert!(!desc.contains("replay_audio"));
+    assert!(!desc.contains("filesink"));
+}
+
+#[test]
+fn test_replay_pipeline_description_with_audio() {
+    let config = CaptureConfig {
+        audio: AudioConfig {
+            mic: true,
+            system: false,
+        },
+        ..replay_config()
+    };
+    let desc = replay_pipeline_description("videotestsrc", "x264enc", Some("opusenc"), &config);
+    assert!(desc.contains("pulsesrc"));
+    assert!(desc.contains("opusenc name=aenc ! appsink name=replay_audio"));
+}
+
+#[test]
+fn test_replay_writer_description() {
+    let desc = replay_writer_description(ContainerFormat::Mp4, false, "/tmp/replay.mp4");
+    assert_eq!(
+        desc,
+        "appsrc name=replay_video_src format=time ! h264parse ! mp4mux name=mux ! \
+         filesink location=\"/tmp/replay.mp4\""
+    );
+
+    let desc = replay_writer_description(ContainerFormat::Mkv, true, "/tmp/replay.mkv");
+    assert!(desc.contains("matroskamux name=mux"));
+    assert!(desc.ends_with("appsrc name=replay_audio_src format=time ! queue ! mux."));
+}
+
+/// Buffer a live test source, then save twice while it keeps running
+#[test]
+fn test_replay_buffer_saves_test_source() {
+    if gstreamer::init().is_err() || detect_available_encoder() != Some("x264enc") {
+        println!("Skipping: x264enc not available");
+        return;
+    }
+    let required = [
+        "videotestsrc",
+        "h264parse",
+        "appsink",
+        "appsrc",
+        "matroskamux",
+    ];
+    if required
+        .iter()
+        .any(|name| gstreamer::ElementFactory::find(name).is_none())
+    {
+        println!("Skipping: required GStreamer elements not available");
+        return;
+    }
+
+    let source = "videotestsrc is-live=true ! video/x-raw,width=320,height=240,framerate=30/1";
+    let replay = ReplayPipeline::with_source(source, &replay_config(), 320, 240).unwrap();
+    replay.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(7));
+
+    let dir = std::env::temp_dir();
+    let first = dir.join(format!("replay-{}.mkv", uuid::Uuid::new_v4()));
+    let second = dir.join(format!("replay-{}.mkv", uuid::Uuid::new_v4()));
+
+    let result = replay.save(&first).unwrap();
+    // Trimmed at a keyframe, so up to one GOP longer than the window
+    assert!(result.duration_ms >= 4_000, "got {} ms", result.duration_ms);
+    assert!(result.duration_ms <= 6_100, "got {} ms", result.duration_ms);
+    assert!(std::fs::metadata(&first).unwrap().len() > 0);
+
+    // The buffer is still running after a save
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    replay.save(&second).unwrap();
+    assert!(std::fs::metadata(&second).unwrap().len() > 0);
+
+    replay.stop();
+    let _ = std::fs::remove_file(&first);
+    let _ = std::fs::remove_file(&second);
+}
@@ -0,0 +1,197 @@
+use super::*;
+use crate::capture::linux::description::output_sink;
+use crate::capture::linux::segments::{
+    splitmux_location, splitmux_sink_description, watch_segments,
+};
+use crate::capture::RecordingSegment;
+use crate::config::{CaptureConfig, SegmentConfig};
+use gstreamer::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn every_ten_minutes() -> SegmentConfig {
+    SegmentConfig {
+        max_duration_secs: Some(600),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_splitmux_location_uses_printf_index() {
+    assert_eq!(
+        splitmux_location("/tmp/rec-{index}.mkv"),
+        "/tmp/rec-%05d.mkv"
+    );
+    // Literal percent signs must not be read as format specifiers
+    assert_eq!(
+        splitmux_location("/tmp/100%-{index}.mkv"),
+        "/tmp/100%%-%05d.mkv"
+    );
+}
+
+#[test]
+fn test_splitmux_sink_by_duration() {
+    let desc = splitmux_sink_description(
+        ContainerFormat::Mkv,
+        false,
+        &every_ten_minutes(),
+        "/tmp/long.mkv",
+    );
+    assert!(desc.starts_with("splitmuxsink name=mux location=\"/tmp/long-%05d.mkv\""));
+    assert!(desc.contains("muxer-factory=matroskamux"));
+    assert!(desc.contains("max-size-time=600000000000"));
+    assert!(!desc.contains("max-size-bytes"));
+    assert!(!desc.contains("muxer-properties"));
+}
+
+#[test]
+fn test_splitmux_sink_by_size_with_crash_safe_muxer() {
+    let segments = SegmentConfig {
+        max_size_bytes: Some(2_000_000_000),
+        filename_template: Some("/media/part-{index}.mp4".to_string()),
+        ..Default::default()
+    };
+    let desc = splitmux_sink_description(ContainerFormat::Mp4, true, &segments, "/tmp/x.mp4");
+    assert!(desc.contains("location=\"/media/part-%05d.mp4\""));
+    assert!(desc.contains("max-size-bytes=2000000000"));
+    assert!(!desc.contains("max-size-time"));
+    assert!(desc.contains("muxer-properties=\"properties,fragment-duration=1000\""));
+}
+
+#[test]
+fn test_output_sink_switches_to_splitmuxsink() {
+    let mut config = CaptureConfig {
+        container: ContainerFormat::Mkv,
+        output_path: "/tmp/long.mkv".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        output_sink(&config),
+        "matroskamux name=mux ! filesink location=\"/tmp/long.mkv\""
+    );
+
+    config.segments = Some(every_ten_minutes());
+    let sink = output_sink(&config);
+    assert!(sink.starts_with("splitmuxsink name=mux"));
+    assert!(!sink.contains("filesink"));
+}
+
+#[test]
+fn test_segment_tracker_measures_each_segment() {
+    let tracker = SegmentTracker::new();
+    tracker.opened("/tmp/rec-00000.mkv", 0);
+    let first = tracker.closed("/tmp/rec-00000.mkv", 600_000_000_000);
+    tracker.opened("/tmp/rec-00001.mkv", 600_000_000_000);
+    let second = tracker.closed("/tmp/rec-00001.mkv", 725_500_000_000);
+
+    assert_eq!(first.index, 0);
+    assert_eq!(first.duration_ms, 600_000);
+    assert_eq!(second.index, 1);
+    assert_eq!(second.duration_ms, 125_500);
+    assert_eq!(tracker.segments(), vec![first, second]);
+}
+
+#[test]
+fn test_segment_tracker_sums_segment_sizes() {
+    let dir = std::env::temp_dir().join(format!("opensnipping-segments-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let first = dir.join("rec-00000.mkv").to_string_lossy().to_string();
+    let second = dir.join("rec-00001.mkv").to_string_lossy().to_string();
+    std::fs::write(&first, vec![0u8; 300]).unwrap();
+    std::fs::write(&second, vec![0u8; 120]).unwrap();
+
+    let tracker = SegmentTracker::new();
+    assert_eq!(tracker.bytes_written(), 0);
+    tracker.opened(&first, 0);
+    assert_eq!(tracker.bytes_written(), 300);
+    tracker.closed(&first, 1_000_000_000);
+    tracker.opened(&second, 1_000_000_000);
+    assert_eq!(tracker.bytes_written(), 420);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_segment_tracker_close_without_open_has_no_duration() {
+    let tracker = SegmentTracker::new();
+    let segment = tracker.closed("/tmp/rec-00000.mkv", 5_000_000_000);
+    assert_eq!(segment.duration_ms, 0);
+}
+
+#[test]
+fn test_segment_tracker_notifies_callback() {
+    let tracker = SegmentTracker::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    tracker.set_callback({
+        let calls = Arc::clone(&calls);
+        Arc::new(move |segment: &RecordingSegment| {
+            assert_eq!(segment.path, "/tmp/rec-00000.mkv");
+            calls.fetch_add(1, Ordering::SeqCst);
+        })
+    });
+
+    tracker.opened("/tmp/rec-00000.mkv", 0);
+    tracker.closed("/tmp/rec-00000.mkv", 1_000_000_000);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+/// Record a few seconds of test audio in one-second segments
+#[test]
+fn test_splitmuxsink_rotates_test_source() {
+    if gstreamer::init().is_err() {
+        return;
+    }
+    let required = ["audiotestsrc", "opusenc", "matroskamux", "splitmuxsink"];
+    if required
+        .iter()
+        .any(|name| gstreamer::ElementFactory::find(name).is_none())
+    {
+        println!("Skipping: required GStreamer elements not available");
+        return;
+    }
+
+    let dir = std::env::temp_dir().join(format!("opensnipping-seg-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = CaptureConfig {
+        container: ContainerFormat::Mkv,
+        output_path: dir.join("rec.mkv").to_string_lossy().to_string(),
+        segments: Some(SegmentConfig {
+            max_duration_secs: Some(1),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // ~3.5s of audio at the audiotestsrc defaults (1024 samples at 44.1 kHz)
+    let desc = format!(
+        "audiotestsrc num-buffers=150 ! audioconvert ! opusenc ! {}",
+        output_sink(&config)
+    );
+    let pipeline = gstreamer::parse::launch(&desc)
+        .unwrap()
+        .downcast::<gstreamer::Pipeline>()
+        .unwrap();
+    let tracker = Arc::new(SegmentTracker::new());
+    watch_segments(&pipeline, &tracker);
+
+    pipeline.set_state(gstreamer::State::Playing).unwrap();
+    let msg = pipeline.bus().unwrap().timed_pop_filtered(
+        gstreamer::ClockTime::from_seconds(10),
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+    pipeline.set_state(gstreamer::State::Null).unwrap();
+    assert_eq!(msg.map(|m| m.type_()), Some(gstreamer::MessageType::Eos));
+
+    let segments = tracker.segments();
+    assert!(
+        segments.len() >= 3,
+        "expected several segments, got {:?}",
+        segments
+    );
+    for (i, segment) in segments.iter().enumerate() {
+        assert_eq!(segment.index as usize, i);
+        assert!(std::path::Path::new(&segment.path).exists());
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,74 @@
+use super::*;
+use crate::capture::linux::description::{still_frame_branch, still_frame_tee, STILL_FRAME_SINK};
+use crate::capture::linux::screenshot::{sample_to_image, screenshot_description};
+use crate::capture::SelectionResult;
+
+#[test]
+fn test_still_frame_descriptions() {
+    assert_eq!(
+        still_frame_tee("videotestsrc"),
+        "videotestsrc ! tee name=stills ! queue"
+    );
+    let branch = still_frame_branch();
+    assert!(branch.starts_with("stills. ! queue leaky=downstream"));
+    assert!(branch.contains("video/x-raw,format=RGBA"));
+    assert!(branch.ends_with("appsink name=still_frames max-buffers=1 drop=true sync=false"));
+}
+
+#[test]
+fn test_screenshot_description_pulls_rgba_into_appsink() {
+    let selection = SelectionResult {
+        node_id: 42,
+        stream_fd: Some(7),
+        width: None,
+        height: None,
+    };
+    let description = screenshot_description(&selection);
+
+    assert!(description.starts_with("pipewiresrc fd=7 path=42 num-buffers=1 ! videoconvert"));
+    assert!(description.contains("video/x-raw,format=RGBA"));
+    assert!(description.ends_with("appsink name=screenshot max-buffers=1 sync=false"));
+    assert!(!description.contains("filesink"));
+}
+
+/// Tee a live test source like a recording, then copy its latest frame
+#[test]
+fn test_still_frame_from_running_test_source() {
+    if gstreamer::init().is_err() {
+        println!("Skipping: GStreamer not available");
+        return;
+    }
+    let required = ["videotestsrc", "tee", "appsink", "fakesink"];
+    if required
+        .iter()
+        .any(|name| gstreamer::ElementFactory::find(name).is_none())
+    {
+        println!("Skipping: required GStreamer elements not available");
+        return;
+    }
+
+    let source = "videotestsrc is-live=true ! video/x-raw,width=320,height=240,framerate=30/1";
+    let description = format!(
+        "{} ! fakesink sync=false {}",
+        still_frame_tee(source),
+        still_frame_branch()
+    );
+    let pipeline = gstreamer::parse::launch(&description)
+        .unwrap()
+        .downcast::<gstreamer::Pipeline>()
+        .unwrap();
+    pipeline.set_state(gstreamer::State::Playing).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+
+    let sample = pipeline
+        .by_name(STILL_FRAME_SINK)
+        .unwrap()
+        .property::<Option<gstreamer::Sample>>("last-sample")
+        .expect("appsink kept no frame");
+    let result = sample_to_image(&sample);
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    let frame = result.unwrap();
+    assert_eq!(frame.dimensions(), (320, 240));
+    assert_eq!(frame.as_raw().len(), 320 * 240 * 4);
+}
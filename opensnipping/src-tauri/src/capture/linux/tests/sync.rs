@@ -0,0 +1,348 @@
+use super::*;
+use crate::capture::linux::sync::{
+    attach_drift_probe, AUDIO_ENCODER_NAME, AV_SYNC_TOLERANCE, DRIFT_REPORT_INTERVAL,
+    VIDEO_ENCODER_NAME, VIDEO_SOURCE_SYNC_PROPS,
+};
+use crate::capture::AvDrift;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const FRAME_NS_30FPS: u64 = 33_333_333;
+const AUDIO_BUFFER_NS: u64 = 20_000_000;
+
+/// Feed a monitor with interleaved 30fps video and 20ms audio buffers
+///
+/// `audio_clock_ratio` scales the audio timestamps to simulate a sound card
+/// clock running faster (> 1.0) or slower (< 1.0) than the video clock.
+fn simulate(monitor: &DriftMonitor, duration: Duration, audio_clock_ratio: f64) {
+    let end = duration.as_nanos() as u64;
+    let (mut video_t, mut audio_t) = (0u64, 0u64);
+
+    while video_t.min(audio_t) < end {
+        if audio_t <= video_t {
+            audio_t += AUDIO_BUFFER_NS;
+            let stamped = (audio_t as f64 * audio_clock_ratio) as u64;
+            monitor.record(StreamKind::Audio, stamped);
+        } else {
+            video_t += FRAME_NS_30FPS;
+            monitor.record(StreamKind::Video, video_t);
+        }
+    }
+}
+
+#[test]
+fn test_drift_tolerance_includes_frame_interval() {
+    let monitor = DriftMonitor::for_fps(30);
+    assert_eq!(
+        monitor.tolerance(),
+        AV_SYNC_TOLERANCE + Duration::from_nanos(FRAME_NS_30FPS)
+    );
+
+    // Guard against division by zero
+    let monitor = DriftMonitor::for_fps(0);
+    assert_eq!(
+        monitor.tolerance(),
+        AV_SYNC_TOLERANCE + Duration::from_secs(1)
+    );
+}
+
+#[test]
+fn test_drift_ignored_until_both_streams_have_data() {
+    let monitor = DriftMonitor::for_fps(30);
+    monitor.record(StreamKind::Video, 10_000_000_000);
+    assert_eq!(monitor.max_drift_ms(), 0);
+    assert!(!monitor.exceeded_tolerance());
+}
+
+#[test]
+fn test_drift_sign_shows_which_stream_is_ahead() {
+    let monitor = DriftMonitor::for_fps(30);
+    monitor.record(StreamKind::Video, 1_000_000_000);
+    monitor.record(StreamKind::Audio, 1_250_000_000);
+    assert_eq!(monitor.drift_ns(), 250_000_000);
+
+    monitor.record(StreamKind::Video, 1_500_000_000);
+    assert_eq!(monitor.drift_ns(), -250_000_000);
+    assert_eq!(monitor.max_drift_ms(), 250);
+}
+
+#[test]
+fn test_shared_clock_stays_in_sync_over_twenty_minutes() {
+    let monitor = DriftMonitor::for_fps(30);
+    simulate(&monitor, Duration::from_secs(20 * 60), 1.0);
+
+    assert!(
+        !monitor.exceeded_tolerance(),
+        "max drift {} ms should be within {:?}",
+        monitor.max_drift_ms(),
+        monitor.tolerance()
+    );
+}
+
+#[test]
+fn test_unsynced_audio_clock_is_detected() {
+    // A 100 ppm fast sound card drifts ~120 ms over 20 minutes
+    let monitor = DriftMonitor::for_fps(30);
+    simulate(&monitor, Duration::from_secs(20 * 60), 1.0001);
+
+    assert!(monitor.exceeded_tolerance());
+    assert!(monitor.max_drift_ms() >= 110);
+}
+
+/// Collect every drift report `monitor` makes
+fn collect_reports(monitor: &DriftMonitor) -> Arc<Mutex<Vec<AvDrift>>> {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&reports);
+    monitor.set_reporter(Arc::new(move |drift: &AvDrift| {
+        sink.lock().unwrap().push(drift.clone())
+    }));
+    reports
+}
+
+#[test]
+fn test_drift_reported_once_per_interval_while_recording() {
+    let monitor = DriftMonitor::for_fps(30);
+    let reports = collect_reports(&monitor);
+    simulate(&monitor, Duration::from_secs(10), 1.0);
+
+    let reports = reports.lock().unwrap();
+    let expected = (Duration::from_secs(10).as_secs() / DRIFT_REPORT_INTERVAL.as_secs()) as usize;
+    assert!(
+        (expected - 1..=expected + 1).contains(&reports.len()),
+        "expected about {} reports, got {}",
+        expected,
+        reports.len()
+    );
+    assert!(reports.iter().all(|drift| !drift.exceeded_tolerance));
+}
+
+#[test]
+fn test_drift_reports_show_growing_drift_before_stop() {
+    // A 1% fast sound card is 100 ms ahead after 10 seconds
+    let monitor = DriftMonitor::for_fps(30);
+    let reports = collect_reports(&monitor);
+    simulate(&monitor, Duration::from_secs(10), 1.01);
+
+    let reports = reports.lock().unwrap();
+    let (first, last) = (reports.first().unwrap(), reports.last().unwrap());
+    assert!(!first.exceeded_tolerance);
+    assert!(last.exceeded_tolerance);
+    assert!(last.drift_ms > first.drift_ms);
+}
+
+// --- Test-source pipeline harness ---
+
+/// Run videotestsrc + audiotestsrc through fakesinks as fast as possible
+///
+/// The fakesinks take the encoder names so the production drift probes
+/// attach to them. Returns None if GStreamer or the test elements are
+/// unavailable.
+fn run_test_source_pipeline(
+    minutes: u64,
+    fps: u8,
+    extra_audio_buffers: u64,
+) -> Option<Arc<DriftMonitor>> {
+    if gstreamer::init().is_err() {
+        return None;
+    }
+    for factory in ["videotestsrc", "audiotestsrc", "fakesink"] {
+        if gstreamer::ElementFactory::find(factory).is_none() {
+            return None;
+        }
+    }
+
+    let frames = minutes * 60 * u64::from(fps);
+    // 960 samples at 48 kHz = 20 ms per buffer
+    let audio_buffers = minutes * 60 * 50 + extra_audio_buffers;
+    let desc = format!(
+        "videotestsrc num-buffers={frames} pattern=black ! \
+         video/x-raw,width=64,height=64,framerate={fps}/1 ! \
+         fakesink name={VIDEO_ENCODER_NAME} sync=false \
+         audiotestsrc num-buffers={audio_buffers} samplesperbuffer=960 wave=silence ! \
+         audio/x-raw,rate=48000,channels=1 ! \
+         fakesink name={AUDIO_ENCODER_NAME} sync=false"
+    );
+
+    let pipeline = gstreamer::parse::launch(&desc)
+        .ok()?
+        .downcast::<gstreamer::Pipeline>()
+        .ok()?;
+
+    let monitor = Arc::new(DriftMonitor::for_fps(fps));
+    assert!(attach_drift_probe(
+        &pipeline,
+        VIDEO_ENCODER_NAME,
+        StreamKind::Video,
+        &monitor
+    ));
+    assert!(attach_drift_probe(
+        &pipeline,
+        AUDIO_ENCODER_NAME,
+        StreamKind::Audio,
+        &monitor
+    ));
+
+    pipeline.set_state(gstreamer::State::Playing).ok()?;
+    let bus = pipeline.bus()?;
+    let msg = bus.timed_pop_filtered(
+        gstreamer::ClockTime::from_seconds(120),
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    match msg.map(|m| m.type_()) {
+        Some(gstreamer::MessageType::Eos) => Some(monitor),
+        other => panic!("Test-source pipeline did not reach EOS: {:?}", other),
+    }
+}
+
+#[test]
+fn test_test_sources_stay_in_sync_over_simulated_minutes() {
+    let Some(monitor) = run_test_source_pipeline(5, 30, 0) else {
+        println!("Skipping: GStreamer test sources not available");
+        return;
+    };
+
+    let final_drift = Duration::from_nanos(monitor.drift_ns().unsigned_abs());
+    assert!(
+        final_drift <= monitor.tolerance(),
+        "Final A/V drift {:?} should be within {:?}",
+        final_drift,
+        monitor.tolerance()
+    );
+}
+
+#[test]
+fn test_test_sources_detect_extra_audio() {
+    // One extra second of audio at the end must show up as drift
+    let Some(monitor) = run_test_source_pipeline(2, 30, 50) else {
+        println!("Skipping: GStreamer test sources not available");
+        return;
+    };
+
+    assert!(monitor.drift_ns() >= 1_000_000_000 - AUDIO_BUFFER_NS as i64);
+    assert!(monitor.exceeded_tolerance());
+}
+
+// --- Live pipeline harness ---
+
+/// Run a live videotestsrc against audio pushed in real time for `duration`
+///
+/// With `device_clock_ratio` None, the audio is stamped with the pipeline
+/// clock on arrival, like a slaved pulsesrc. Otherwise it carries timestamps
+/// from a device clock running `ratio` times as fast as the system clock.
+/// Returns None if GStreamer or the test elements are unavailable.
+fn run_live_pipeline(
+    duration: Duration,
+    device_clock_ratio: Option<f64>,
+) -> Option<(Arc<DriftMonitor>, Vec<AvDrift>)> {
+    if gstreamer::init().is_err() {
+        return None;
+    }
+    for factory in ["videotestsrc", "appsrc", "fakesink"] {
+        if gstreamer::ElementFactory::find(factory).is_none() {
+            return None;
+        }
+    }
+
+    let do_timestamp = device_clock_ratio.is_none();
+    let desc = format!(
+        "videotestsrc is-live=true pattern=black {VIDEO_SOURCE_SYNC_PROPS} ! \
+         video/x-raw,width=64,height=64,framerate=30/1 ! \
+         fakesink name={VIDEO_ENCODER_NAME} sync=false \
+         appsrc name=asrc is-live=true format=time do-timestamp={do_timestamp} \
+         caps=audio/x-raw,format=S16LE,layout=interleaved,rate=48000,channels=1 ! \
+         fakesink name={AUDIO_ENCODER_NAME} sync=false"
+    );
+
+    let pipeline = gstreamer::parse::launch(&desc)
+        .ok()?
+        .downcast::<gstreamer::Pipeline>()
+        .ok()?;
+    pipeline.use_clock(Some(&gstreamer::SystemClock::obtain()));
+    let appsrc = pipeline
+        .by_name("asrc")?
+        .downcast::<gstreamer_app::AppSrc>()
+        .ok()?;
+
+    let monitor = Arc::new(DriftMonitor::for_fps(30));
+    let reports = collect_reports(&monitor);
+    assert!(attach_drift_probe(
+        &pipeline,
+        VIDEO_ENCODER_NAME,
+        StreamKind::Video,
+        &monitor
+    ));
+    assert!(attach_drift_probe(
+        &pipeline,
+        AUDIO_ENCODER_NAME,
+        StreamKind::Audio,
+        &monitor
+    ));
+
+    pipeline.set_state(gstreamer::State::Playing).ok()?;
+    let (result, _, _) = pipeline.state(gstreamer::ClockTime::from_seconds(5));
+    result.ok()?;
+
+    // 960 samples at 48 kHz = 20 ms per buffer, pushed at real-time pace
+    let start = Instant::now();
+    let buffers = duration.as_nanos() as u64 / AUDIO_BUFFER_NS;
+    for n in 0..buffers {
+        let mut buffer = gstreamer::Buffer::from_mut_slice(vec![0u8; 960 * 2]);
+        let buffer_mut = buffer.get_mut().unwrap();
+        if let Some(ratio) = device_clock_ratio {
+            let device_ns = |i: u64| (i as f64 * AUDIO_BUFFER_NS as f64 * ratio) as u64;
+            buffer_mut.set_pts(gstreamer::ClockTime::from_nseconds(device_ns(n)));
+            buffer_mut.set_duration(gstreamer::ClockTime::from_nseconds(
+                device_ns(n + 1) - device_ns(n),
+            ));
+        } else {
+            buffer_mut.set_duration(gstreamer::ClockTime::from_nseconds(AUDIO_BUFFER_NS));
+        }
+        appsrc.push_buffer(buffer).ok()?;
+
+        let due = start + Duration::from_nanos((n + 1) * AUDIO_BUFFER_NS);
+        std::thread::sleep(due.saturating_duration_since(Instant::now()));
+    }
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    let reports = reports.lock().unwrap().clone();
+    Some((monitor, reports))
+}
+
+#[test]
+fn test_live_sources_on_pipeline_clock_stay_in_sync() {
+    let Some((monitor, reports)) = run_live_pipeline(Duration::from_secs(3), None) else {
+        println!("Skipping: GStreamer live test sources not available");
+        return;
+    };
+
+    assert!(
+        reports.len() >= 2,
+        "drift should be reported while recording, got {:?}",
+        reports
+    );
+    assert!(
+        !monitor.exceeded_tolerance(),
+        "max drift {} ms should be within {:?}",
+        monitor.max_drift_ms(),
+        monitor.tolerance()
+    );
+}
+
+#[test]
+fn test_live_audio_on_skewed_clock_is_reported_while_recording() {
+    // A 5% fast device clock is ~100 ms ahead after 2 seconds
+    let Some((monitor, reports)) = run_live_pipeline(Duration::from_secs(3), Some(1.05)) else {
+        println!("Skipping: GStreamer live test sources not available");
+        return;
+    };
+
+    assert!(monitor.exceeded_tolerance());
+    assert!(monitor.drift_ns() > 0, "audio should run ahead of video");
+    assert!(
+        reports.iter().any(|drift| drift.exceeded_tolerance),
+        "drift beyond tolerance should be reported before stop, got {:?}",
+        reports
+    );
+}
@@ -0,0 +1,77 @@
+use super::*;
+use crate::capture::linux::description::timelapse_encode_description;
+use crate::capture::timelapse::frame_path;
+use crate::config::{CaptureConfig, CaptureMode, TimelapseConfig};
+use image::{ImageBuffer, Rgb};
+
+fn timelapse_config(output_path: &str) -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::Timelapse,
+        fps: 10,
+        output_path: output_path.to_string(),
+        timelapse: Some(TimelapseConfig {
+            interval_secs: 1,
+            frame_count: 5,
+            encode_video: true,
+            frames_dir: None,
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_timelapse_encode_description() {
+    let desc = timelapse_encode_description(
+        "/tmp/lapse-frames",
+        5,
+        "x264enc",
+        640,
+        480,
+        &timelapse_config("/tmp/lapse.mp4"),
+    );
+    assert!(desc.starts_with(
+        "multifilesrc location=\"/tmp/lapse-frames/frame-%05d.png\" start-index=1 stop-index=5"
+    ));
+    assert!(desc.contains("caps=\"image/png,framerate=10/1\""));
+    assert!(desc.contains("video/x-raw,width=640,height=480 ! x264enc ! h264parse"));
+    assert!(desc.ends_with("mp4mux name=mux ! filesink location=\"/tmp/lapse.mp4\""));
+}
+
+/// Encode a handful of generated PNGs into an MKV
+#[test]
+fn test_encode_timelapse_frames() {
+    if gstreamer::init().is_err() || detect_available_encoder() != Some("x264enc") {
+        println!("Skipping: x264enc not available");
+        return;
+    }
+    let required = ["multifilesrc", "pngdec", "h264parse", "matroskamux"];
+    if required
+        .iter()
+        .any(|name| gstreamer::ElementFactory::find(name).is_none())
+    {
+        println!("Skipping: required GStreamer elements not available");
+        return;
+    }
+
+    let dir = std::env::temp_dir().join(format!("timelapse-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for index in 1..=5u32 {
+        // Odd size: the encoder gets it rounded down to even dimensions
+        let shade = (index * 40) as u8;
+        let frame: ImageBuffer<Rgb<u8>, Vec<u8>> =
+            ImageBuffer::from_fn(161, 121, |_, _| Rgb([shade, shade, shade]));
+        frame.save(frame_path(&dir, index)).unwrap();
+    }
+
+    let output = dir.join("lapse.mkv");
+    let config = CaptureConfig {
+        container: ContainerFormat::Mkv,
+        ..timelapse_config(&output.to_string_lossy())
+    };
+    let result = encode_timelapse_frames(&dir, 5, &config).unwrap();
+    assert_eq!(result.duration_ms, 500);
+    assert_eq!((result.width, result.height), (160, 120));
+    assert!(std::fs::metadata(&output).unwrap().len() > 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,39 @@
+use super::*;
+use crate::capture::linux::webp::{webp_encode_description, WEBP_SRC};
+use std::path::Path;
+
+#[test]
+fn test_webp_encode_description() {
+    let desc = webp_encode_description(640, 480, 75, Path::new("/tmp/shot.webp"));
+
+    assert!(desc.starts_with(&format!("appsrc name={} format=time", WEBP_SRC)));
+    assert!(desc.contains("video/x-raw,format=RGBA,width=640,height=480"));
+    assert!(desc.contains("webpenc lossless=false quality=75"));
+    assert!(desc.ends_with("filesink location=\"/tmp/shot.webp\""));
+}
+
+/// Encode a generated frame through webpenc when it is installed
+#[test]
+fn test_encode_webp_writes_lossy_webp() {
+    if !webp_encoder_available() {
+        println!("Skipping: webpenc not available");
+        return;
+    }
+
+    let image = image::RgbaImage::from_fn(64, 48, |x, y| {
+        image::Rgba([(x * 4) as u8, (y * 5) as u8, 128, 255])
+    });
+    let output_path =
+        std::env::temp_dir().join(format!("opensnipping-lossy-{}.webp", uuid::Uuid::new_v4()));
+
+    let result = encode_webp(&image, 60, &output_path);
+    let bytes = std::fs::read(&output_path);
+    let _ = std::fs::remove_file(&output_path);
+
+    result.unwrap();
+    let bytes = bytes.unwrap();
+    assert_eq!(&bytes[..4], b"RIFF");
+    assert_eq!(&bytes[8..12], b"WEBP");
+    let decoded = image::load_from_memory(&bytes).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (64, 48));
+}
@@ -0,0 +1,92 @@
+use crate::capture::timelapse::frame_path;
+use crate::capture::{CaptureBackendError, RecordingResult};
+use crate::config::CaptureConfig;
+use gstreamer::prelude::*;
+use std::path::Path;
+use tracing::{debug, info};
+
+use super::description::timelapse_encode_description;
+use super::select_video_encoder;
+
+/// Upper bound on encoding a timelapse, per frame
+const ENCODE_TIMEOUT_MS_PER_FRAME: u64 = 500;
+
+/// Encode `frame_count` numbered PNGs from `frames_dir` into `config.output_path`
+///
+/// The video takes the size of the first frame, rounded down to even
+/// dimensions for the H.264 encoders.
+pub fn encode_timelapse_frames(
+    frames_dir: &Path,
+    frame_count: u32,
+    config: &CaptureConfig,
+) -> Result<RecordingResult, CaptureBackendError> {
+    gstreamer::init().map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
+    })?;
+
+    let (width, height) = image::image_dimensions(frame_path(frames_dir, 1)).map_err(|e| {
+        CaptureBackendError::Internal(format!("No timelapse frames to encode: {}", e))
+    })?;
+    let (width, height) = (width & !1, height & !1);
+
+    let encoder = select_video_encoder(config.encoder.unwrap_or_default().encoder)?;
+    let desc = timelapse_encode_description(
+        &frames_dir.to_string_lossy(),
+        frame_count,
+        encoder,
+        width,
+        height,
+        config,
+    );
+    debug!("Encoding timelapse: {}", desc);
+
+    let pipeline = gstreamer::parse::launch(&desc)
+        .map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to create timelapse encoder: {}", e))
+        })?
+        .downcast::<gstreamer::Pipeline>()
+        .map_err(|_| CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string()))?;
+
+    pipeline.set_state(gstreamer::State::Playing).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to start timelapse encoder: {}", e))
+    })?;
+
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| CaptureBackendError::Internal("Failed to get pipeline bus".to_string()))?;
+    let timeout_ms = ENCODE_TIMEOUT_MS_PER_FRAME * u64::from(frame_count) + 10_000;
+    let msg = bus.timed_pop_filtered(
+        gstreamer::ClockTime::from_mseconds(timeout_ms),
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    match msg.as_ref().map(|msg| msg.view()) {
+        Some(gstreamer::MessageView::Eos(..)) => {}
+        Some(gstreamer::MessageView::Error(err)) => {
+            return Err(CaptureBackendError::Internal(format!(
+                "Timelapse encoder error: {}",
+                err.error()
+            )))
+        }
+        _ => {
+            return Err(CaptureBackendError::Internal(
+                "Timed out encoding timelapse".to_string(),
+            ))
+        }
+    }
+
+    let result = RecordingResult {
+        path: config.output_path.clone(),
+        duration_ms: u64::from(frame_count) * 1000 / u64::from(config.fps.max(1)),
+        width,
+        height,
+        max_av_drift_ms: None,
+        segments: Vec::new(),
+    };
+    info!(
+        "Timelapse encoded: {} ({} frames, {} ms)",
+        result.path, frame_count, result.duration_ms
+    );
+    Ok(result)
+}
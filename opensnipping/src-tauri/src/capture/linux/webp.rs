@@ -0,0 +1,102 @@
+// Lossy WebP encoding through GStreamer
+//
+// The image crate only writes lossless WebP; `webpenc` (gst-plugins-bad)
+// wraps libwebp's lossy encoder.
+
+use crate::capture::CaptureBackendError;
+use image::RgbaImage;
+use std::path::Path;
+use tracing::debug;
+
+use gstreamer::prelude::*;
+
+use super::description::quoted;
+
+/// appsrc the RGBA frame is pushed into
+pub(super) const WEBP_SRC: &str = "webp_src";
+
+const ENCODE_TIMEOUT_SECS: u64 = 10;
+
+/// Whether `webpenc` is installed
+pub fn webp_encoder_available() -> bool {
+    gstreamer::init().is_ok() && gstreamer::ElementFactory::find("webpenc").is_some()
+}
+
+/// Pipeline encoding one `width`x`height` RGBA frame to a lossy WebP file
+pub(super) fn webp_encode_description(
+    width: u32,
+    height: u32,
+    quality: u8,
+    output_path: &Path,
+) -> String {
+    format!(
+        "appsrc name={WEBP_SRC} format=time \
+         caps=video/x-raw,format=RGBA,width={width},height={height},framerate=0/1 ! \
+         videoconvert ! webpenc lossless=false quality={quality} ! \
+         filesink location={}",
+        quoted(&output_path.to_string_lossy())
+    )
+}
+
+/// Encode `image` to `output_path` as lossy WebP at `quality` (1-100)
+pub fn encode_webp(
+    image: &RgbaImage,
+    quality: u8,
+    output_path: &Path,
+) -> Result<(), CaptureBackendError> {
+    gstreamer::init().map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to initialize GStreamer: {}", e))
+    })?;
+    if !webp_encoder_available() {
+        return Err(CaptureBackendError::NotSupported(
+            "Lossy WebP needs the GStreamer webpenc element".to_string(),
+        ));
+    }
+
+    let desc = webp_encode_description(image.width(), image.height(), quality, output_path);
+    debug!("Encoding WebP: {}", desc);
+
+    let pipeline = gstreamer::parse::launch(&desc)
+        .map_err(|e| {
+            CaptureBackendError::Internal(format!("Failed to create WebP encoder: {}", e))
+        })?
+        .downcast::<gstreamer::Pipeline>()
+        .map_err(|_| CaptureBackendError::Internal("Failed to downcast to Pipeline".to_string()))?;
+    let src = pipeline
+        .by_name(WEBP_SRC)
+        .and_then(|element| element.downcast::<gstreamer_app::AppSrc>().ok())
+        .ok_or_else(|| CaptureBackendError::Internal(format!("Missing appsrc {}", WEBP_SRC)))?;
+
+    pipeline.set_state(gstreamer::State::Playing).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to start WebP encoder: {}", e))
+    })?;
+
+    let pushed = src
+        .push_buffer(gstreamer::Buffer::from_slice(image.as_raw().clone()))
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to encode WebP: {:?}", e)));
+    let _ = src.end_of_stream();
+
+    let msg = pushed.and_then(|_| {
+        pipeline
+            .bus()
+            .ok_or_else(|| CaptureBackendError::Internal("Failed to get pipeline bus".to_string()))
+            .map(|bus| {
+                bus.timed_pop_filtered(
+                    gstreamer::ClockTime::from_seconds(ENCODE_TIMEOUT_SECS),
+                    &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+                )
+            })
+    });
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    match msg?.as_ref().map(|msg| msg.view()) {
+        Some(gstreamer::MessageView::Eos(..)) => Ok(()),
+        Some(gstreamer::MessageView::Error(err)) => Err(CaptureBackendError::Internal(format!(
+            "WebP encoder error: {}",
+            err.error()
+        ))),
+        _ => Err(CaptureBackendError::Internal(
+            "Timed out encoding WebP".to_string(),
+        )),
+    }
+}
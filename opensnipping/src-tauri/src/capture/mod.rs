@@ -9,10 +9,18 @@ pub mod linux;
 #[cfg(test)]
 pub mod fake;
 
-use crate::config::CaptureConfig;
+pub mod replay;
+mod shared;
+pub mod timelapse;
+
+pub use shared::{default_backend, DynCaptureBackend, SharedBackend};
+
+use crate::config::{CaptureConfig, ScreenshotFormat};
+use image::RgbaImage;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Result of a successful screen/window/region selection from portal
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,12 +44,51 @@ pub struct ScreenshotResult {
     pub width: u32,
     /// Height of the screenshot in pixels
     pub height: u32,
+    /// Image format of the file
+    #[serde(default)]
+    pub format: ScreenshotFormat,
+    /// Size of the file in bytes
+    #[serde(default)]
+    pub byte_size: u64,
+}
+
+/// One file of a segmented recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSegment {
+    /// Position of the segment in the recording, starting at 0
+    pub index: u32,
+    /// Path to the segment file
+    pub path: String,
+    /// Duration of the segment in milliseconds
+    pub duration_ms: u64,
 }
 
+/// Called each time a segmented recording closes a file
+pub type SegmentCallback = Arc<dyn Fn(&RecordingSegment) + Send + Sync>;
+
+/// A/V drift of a running recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AvDrift {
+    /// Current drift in milliseconds (positive when audio is ahead)
+    pub drift_ms: i64,
+    /// Largest absolute drift so far in milliseconds
+    pub max_drift_ms: u64,
+    /// Whether the drift has gone beyond what is perceptible
+    pub exceeded_tolerance: bool,
+}
+
+/// Called about once a second with a recording's A/V drift
+pub type DriftCallback = Arc<dyn Fn(&AvDrift) + Send + Sync>;
+
+/// Called once when the capture source ends a selection's session on its own
+pub type SessionClosedCallback = Arc<dyn Fn() + Send + Sync>;
+
 /// Result of a completed recording
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingResult {
-    /// Path to the saved recording file
+    /// Path to the saved recording file, or for a segmented recording the
+    /// `{index}` template of the files listed in `segments` (the path its
+    /// journal entry is kept under)
     pub path: String,
     /// Duration of the recording in milliseconds
     pub duration_ms: u64,
@@ -49,6 +96,12 @@ pub struct RecordingResult {
     pub width: u32,
     /// Height of the recording in pixels
     pub height: u32,
+    /// Largest A/V drift seen during the recording (None without both streams)
+    #[serde(default)]
+    pub max_av_drift_ms: Option<u64>,
+    /// Every file written, in order (empty unless the recording was segmented)
+    #[serde(default)]
+    pub segments: Vec<RecordingSegment>,
 }
 
 /// Errors that can occur during capture operations
@@ -62,6 +115,8 @@ pub enum CaptureBackendError {
     NoSourceAvailable(String),
     /// Backend not available on this platform
     NotSupported(String),
+    /// The output disk is full or too full to keep recording
+    DiskFull(String),
     /// Internal error
     Internal(String),
 }
@@ -73,6 +128,7 @@ impl fmt::Display for CaptureBackendError {
             Self::PortalError(msg) => write!(f, "Portal error: {}", msg),
             Self::NoSourceAvailable(msg) => write!(f, "No source available: {}", msg),
             Self::NotSupported(msg) => write!(f, "Not supported: {}", msg),
+            Self::DiskFull(msg) => write!(f, "Disk full: {}", msg),
             Self::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -99,15 +155,21 @@ pub trait CaptureBackend: Send + Sync {
         &self,
     ) -> impl std::future::Future<Output = Result<(), CaptureBackendError>> + Send;
 
-    /// Capture a screenshot from the given selection and save to output_path
+    /// Capture a single frame from the given selection
     ///
-    /// Uses GStreamer pipeline to capture a single frame from the PipeWire stream
-    /// and encode it as PNG.
+    /// Pulls one raw frame from the PipeWire stream into memory through an
+    /// appsink; encoding and saving it is up to the caller.
     fn capture_screenshot(
         &self,
         selection: &SelectionResult,
-        output_path: &Path,
-    ) -> impl std::future::Future<Output = Result<ScreenshotResult, CaptureBackendError>> + Send;
+    ) -> impl std::future::Future<Output = Result<RgbaImage, CaptureBackendError>> + Send;
+
+    /// Grab the running recording's latest frame
+    ///
+    /// The recording keeps running. Fails for audio-only recordings.
+    fn capture_recording_frame(
+        &self,
+    ) -> impl std::future::Future<Output = Result<RgbaImage, CaptureBackendError>> + Send;
 
     /// Start recording video from the given selection
     ///
@@ -119,6 +181,15 @@ pub trait CaptureBackend: Send + Sync {
         config: &CaptureConfig,
     ) -> impl std::future::Future<Output = Result<(), CaptureBackendError>> + Send;
 
+    /// Start an audio-only recording (mic and/or system audio)
+    ///
+    /// Does not need a portal selection. The recording is stopped, paused
+    /// and resumed through the same methods as video recordings.
+    fn start_audio_recording(
+        &self,
+        config: &CaptureConfig,
+    ) -> impl std::future::Future<Output = Result<(), CaptureBackendError>> + Send;
+
     /// Stop the current recording and finalize the output file
     ///
     /// Sends EOS to the pipeline, waits for finalization, and returns the result.
@@ -139,18 +210,70 @@ pub trait CaptureBackend: Send + Sync {
     fn resume_recording(
         &self,
     ) -> impl std::future::Future<Output = Result<(), CaptureBackendError>> + Send;
-}
 
-/// Get the appropriate capture backend for the current platform
-#[cfg(target_os = "linux")]
-pub fn get_backend() -> impl CaptureBackend {
-    linux::LinuxCaptureBackend::new()
-}
+    /// Start keeping the last `config.replay_buffer_secs` of the selection in memory
+    ///
+    /// Nothing is written to disk until save_replay is called.
+    fn start_replay_buffer(
+        &self,
+        selection: &SelectionResult,
+        config: &CaptureConfig,
+    ) -> impl std::future::Future<Output = Result<(), CaptureBackendError>> + Send;
 
-/// Stub backend for unsupported platforms
-#[cfg(not(target_os = "linux"))]
-pub fn get_backend() -> impl CaptureBackend {
-    StubBackend
+    /// Write the buffered replay to output_path, starting on a keyframe
+    ///
+    /// The buffer keeps running, so replays can be saved repeatedly.
+    fn save_replay(
+        &self,
+        output_path: &Path,
+    ) -> impl std::future::Future<Output = Result<RecordingResult, CaptureBackendError>> + Send;
+
+    /// Stop the replay buffer and discard anything not yet saved
+    fn stop_replay_buffer(
+        &self,
+    ) -> impl std::future::Future<Output = Result<(), CaptureBackendError>> + Send;
+
+    /// Encode the first `frame_count` timelapse frames in `frames_dir`
+    ///
+    /// Frames are named after `timelapse::FRAME_PATTERN` and played back at
+    /// `config.fps` into `config.output_path`.
+    fn encode_timelapse(
+        &self,
+        frames_dir: &Path,
+        frame_count: u32,
+        config: &CaptureConfig,
+    ) -> impl std::future::Future<Output = Result<RecordingResult, CaptureBackendError>> + Send;
+
+    /// Call `callback` each time a segmented recording closes a file
+    fn on_segment_closed(&self, _callback: SegmentCallback) {}
+
+    /// Call `callback` with the A/V drift of recordings that have both audio
+    /// and video, while they record
+    fn on_av_drift(&self, _callback: DriftCallback) {}
+
+    /// Call `callback` if the source ends the selection's session on its own
+    ///
+    /// On Linux this is the portal's `Closed` signal (sharing stopped from
+    /// the desktop).
+    fn on_session_closed(&self, _callback: SessionClosedCallback) {}
+
+    /// Bytes the running recording has written so far, summed over its
+    /// segments (None when not recording)
+    fn recorded_bytes(&self) -> impl std::future::Future<Output = Option<u64>> + Send {
+        async { None }
+    }
+
+    /// Whether the last selection's stream is still open
+    fn has_session(&self) -> impl std::future::Future<Output = bool> + Send {
+        async { true }
+    }
+
+    /// End the last selection's session, if any
+    ///
+    /// Stop recordings and replay buffers first; they read from its stream.
+    fn close_session(&self) -> impl std::future::Future<Output = ()> + Send {
+        async {}
+    }
 }
 
 /// Stub backend for unsupported platforms
@@ -176,13 +299,18 @@ impl CaptureBackend for StubBackend {
     async fn capture_screenshot(
         &self,
         _selection: &SelectionResult,
-        _output_path: &Path,
-    ) -> Result<ScreenshotResult, CaptureBackendError> {
+    ) -> Result<RgbaImage, CaptureBackendError> {
         Err(CaptureBackendError::NotSupported(
             "Screenshot not implemented for this platform".to_string(),
         ))
     }
 
+    async fn capture_recording_frame(&self) -> Result<RgbaImage, CaptureBackendError> {
+        Err(CaptureBackendError::NotSupported(
+            "Recording not implemented for this platform".to_string(),
+        ))
+    }
+
     async fn start_recording(
         &self,
         _selection: &SelectionResult,
@@ -193,6 +321,15 @@ impl CaptureBackend for StubBackend {
         ))
     }
 
+    async fn start_audio_recording(
+        &self,
+        _config: &CaptureConfig,
+    ) -> Result<(), CaptureBackendError> {
+        Err(CaptureBackendError::NotSupported(
+            "Recording not implemented for this platform".to_string(),
+        ))
+    }
+
     async fn stop_recording(&self) -> Result<RecordingResult, CaptureBackendError> {
         Err(CaptureBackendError::NotSupported(
             "Recording not implemented for this platform".to_string(),
@@ -210,4 +347,40 @@ impl CaptureBackend for StubBackend {
             "Recording not implemented for this platform".to_string(),
         ))
     }
+
+    async fn start_replay_buffer(
+        &self,
+        _selection: &SelectionResult,
+        _config: &CaptureConfig,
+    ) -> Result<(), CaptureBackendError> {
+        Err(CaptureBackendError::NotSupported(
+            "Replay buffer not implemented for this platform".to_string(),
+        ))
+    }
+
+    async fn save_replay(
+        &self,
+        _output_path: &Path,
+    ) -> Result<RecordingResult, CaptureBackendError> {
+        Err(CaptureBackendError::NotSupported(
+            "Replay buffer not implemented for this platform".to_string(),
+        ))
+    }
+
+    async fn stop_replay_buffer(&self) -> Result<(), CaptureBackendError> {
+        Err(CaptureBackendError::NotSupported(
+            "Replay buffer not implemented for this platform".to_string(),
+        ))
+    }
+
+    async fn encode_timelapse(
+        &self,
+        _frames_dir: &Path,
+        _frame_count: u32,
+        _config: &CaptureConfig,
+    ) -> Result<RecordingResult, CaptureBackendError> {
+        Err(CaptureBackendError::NotSupported(
+            "Timelapse encoding not implemented for this platform".to_string(),
+        ))
+    }
 }
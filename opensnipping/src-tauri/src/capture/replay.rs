@@ -0,0 +1,240 @@
+// Replay buffer bookkeeping
+//
+// Keeps the most recent encoded audio and video in memory so the last N
+// seconds can be written out on demand. Backend-agnostic: backends wrap
+// their encoded buffers in a type implementing `ReplayChunk`.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// An encoded buffer held by the replay ring
+pub trait ReplayChunk: Clone {
+    /// Presentation timestamp in nanoseconds
+    fn pts_ns(&self) -> u64;
+    /// Whether decoding can start at this buffer
+    fn is_keyframe(&self) -> bool;
+}
+
+/// Buffers to write when a replay is saved
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaySnapshot<T> {
+    /// Video, starting on a keyframe
+    pub video: Vec<T>,
+    /// Audio from the first video buffer onwards
+    pub audio: Vec<T>,
+    /// Timestamp of the first video buffer, subtracted when writing
+    pub start_pts_ns: u64,
+}
+
+impl<T: ReplayChunk> ReplaySnapshot<T> {
+    /// Span from the first to the last video buffer, in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        self.video
+            .last()
+            .map(|last| last.pts_ns().saturating_sub(self.start_pts_ns) / 1_000_000)
+            .unwrap_or(0)
+    }
+}
+
+/// Ring of encoded audio and video covering a fixed time window
+///
+/// Video is trimmed at keyframes, so the ring holds slightly more than the
+/// window: everything from the last keyframe at or before the window start.
+#[derive(Debug)]
+pub struct ReplayRing<T> {
+    window_ns: u64,
+    video: VecDeque<T>,
+    audio: VecDeque<T>,
+}
+
+impl<T: ReplayChunk> ReplayRing<T> {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window_ns: window.as_nanos() as u64,
+            video: VecDeque::new(),
+            audio: VecDeque::new(),
+        }
+    }
+
+    pub fn push_video(&mut self, chunk: T) {
+        self.video.push_back(chunk);
+        self.trim();
+    }
+
+    pub fn push_audio(&mut self, chunk: T) {
+        self.audio.push_back(chunk);
+        self.trim();
+    }
+
+    /// Number of buffered video and audio chunks
+    pub fn len(&self) -> (usize, usize) {
+        (self.video.len(), self.audio.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.video.is_empty() && self.audio.is_empty()
+    }
+
+    /// Drop everything buffered so far
+    pub fn clear(&mut self) {
+        self.video.clear();
+        self.audio.clear();
+    }
+
+    /// Copy out the buffered window, starting on a keyframe
+    ///
+    /// Returns None until a keyframe has been buffered.
+    pub fn snapshot(&self) -> Option<ReplaySnapshot<T>> {
+        let first = self.video.iter().position(ReplayChunk::is_keyframe)?;
+        let start_pts_ns = self.video[first].pts_ns();
+        Some(ReplaySnapshot {
+            video: self.video.iter().skip(first).cloned().collect(),
+            audio: self
+                .audio
+                .iter()
+                .filter(|chunk| chunk.pts_ns() >= start_pts_ns)
+                .cloned()
+                .collect(),
+            start_pts_ns,
+        })
+    }
+
+    fn trim(&mut self) {
+        let newest = self
+            .video
+            .back()
+            .map(ReplayChunk::pts_ns)
+            .into_iter()
+            .chain(self.audio.back().map(ReplayChunk::pts_ns))
+            .max()
+            .unwrap_or(0);
+        let cutoff = newest.saturating_sub(self.window_ns);
+
+        // Keep the last keyframe at or before the cutoff so saves can decode
+        let keep_from = self
+            .video
+            .iter()
+            .rposition(|chunk| chunk.is_keyframe() && chunk.pts_ns() <= cutoff);
+        if let Some(index) = keep_from {
+            self.video.drain(..index);
+        }
+
+        let audio_cutoff = self.video.front().map_or(cutoff, |front| front.pts_ns());
+        while self
+            .audio
+            .front()
+            .is_some_and(|chunk| chunk.pts_ns() < audio_cutoff)
+        {
+            self.audio.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Chunk {
+        pts_ns: u64,
+        keyframe: bool,
+    }
+
+    impl ReplayChunk for Chunk {
+        fn pts_ns(&self) -> u64 {
+            self.pts_ns
+        }
+
+        fn is_keyframe(&self) -> bool {
+            self.keyframe
+        }
+    }
+
+    /// 10 fps video with a keyframe every second, plus 20 ms audio packets
+    fn fill(ring: &mut ReplayRing<Chunk>, secs: u64) {
+        for frame in 0..secs * 10 {
+            ring.push_video(Chunk {
+                pts_ns: frame * SECOND / 10,
+                keyframe: frame % 10 == 0,
+            });
+            for packet in 0..5 {
+                ring.push_audio(Chunk {
+                    pts_ns: frame * SECOND / 10 + packet * SECOND / 50,
+                    keyframe: true,
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_ring_keeps_only_the_window() {
+        let mut ring = ReplayRing::new(Duration::from_secs(5));
+        fill(&mut ring, 60);
+
+        let snapshot = ring.snapshot().unwrap();
+        // Newest frame is at 59.9s; the window starts on the 54s keyframe
+        assert_eq!(snapshot.start_pts_ns, 54 * SECOND);
+        assert!(snapshot.video[0].keyframe);
+        assert_eq!(snapshot.video.len(), 60);
+        assert_eq!(snapshot.duration_ms(), 5_900);
+    }
+
+    #[test]
+    fn test_snapshot_audio_starts_with_video() {
+        let mut ring = ReplayRing::new(Duration::from_secs(5));
+        fill(&mut ring, 20);
+
+        let snapshot = ring.snapshot().unwrap();
+        assert!(snapshot
+            .audio
+            .iter()
+            .all(|chunk| chunk.pts_ns >= snapshot.start_pts_ns));
+        assert_eq!(snapshot.audio[0].pts_ns, snapshot.start_pts_ns);
+    }
+
+    #[test]
+    fn test_snapshot_requires_keyframe() {
+        let mut ring = ReplayRing::new(Duration::from_secs(5));
+        assert!(ring.snapshot().is_none());
+
+        ring.push_video(Chunk {
+            pts_ns: 0,
+            keyframe: false,
+        });
+        assert!(ring.snapshot().is_none());
+
+        ring.push_video(Chunk {
+            pts_ns: SECOND / 10,
+            keyframe: true,
+        });
+        let snapshot = ring.snapshot().unwrap();
+        assert_eq!(snapshot.start_pts_ns, SECOND / 10);
+        assert_eq!(snapshot.video.len(), 1);
+    }
+
+    #[test]
+    fn test_ring_without_keyframes_is_not_trimmed() {
+        let mut ring = ReplayRing::new(Duration::from_secs(1));
+        for frame in 0..50 {
+            ring.push_video(Chunk {
+                pts_ns: frame * SECOND / 10,
+                keyframe: false,
+            });
+        }
+        // Dropping frames would leave nothing decodable, so keep them all
+        assert_eq!(ring.len(), (50, 0));
+    }
+
+    #[test]
+    fn test_clear_empties_ring() {
+        let mut ring = ReplayRing::new(Duration::from_secs(5));
+        fill(&mut ring, 3);
+        assert!(!ring.is_empty());
+
+        ring.clear();
+        assert!(ring.is_empty());
+        assert!(ring.snapshot().is_none());
+    }
+}
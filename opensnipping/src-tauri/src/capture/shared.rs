@@ -0,0 +1,209 @@
+// Object-safe view of `CaptureBackend`
+//
+// `CaptureBackend` returns `impl Future`, so it cannot be used as a trait
+// object. `DynCaptureBackend` boxes those futures and is implemented for
+// every backend, which lets `AppState` hold one backend chosen at startup
+// (the platform backend, or the fake in tests).
+
+use super::{
+    CaptureBackend, CaptureBackendError, DriftCallback, RecordingResult, SegmentCallback,
+    SelectionResult, SessionClosedCallback,
+};
+use crate::config::CaptureConfig;
+use image::RgbaImage;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Boxed future returned by `DynCaptureBackend` methods
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The backend shared by every IPC command
+pub type SharedBackend = Arc<dyn DynCaptureBackend>;
+
+/// `CaptureBackend` with boxed futures; see that trait for each method
+pub trait DynCaptureBackend: Send + Sync {
+    fn request_selection<'a>(
+        &'a self,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<SelectionResult, CaptureBackendError>>;
+
+    fn cancel_selection(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>>;
+
+    fn capture_screenshot<'a>(
+        &'a self,
+        selection: &'a SelectionResult,
+    ) -> BackendFuture<'a, Result<RgbaImage, CaptureBackendError>>;
+
+    fn capture_recording_frame(&self) -> BackendFuture<'_, Result<RgbaImage, CaptureBackendError>>;
+
+    fn start_recording<'a>(
+        &'a self,
+        selection: &'a SelectionResult,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<(), CaptureBackendError>>;
+
+    fn start_audio_recording<'a>(
+        &'a self,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<(), CaptureBackendError>>;
+
+    fn stop_recording(&self) -> BackendFuture<'_, Result<RecordingResult, CaptureBackendError>>;
+
+    fn pause_recording(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>>;
+
+    fn resume_recording(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>>;
+
+    fn start_replay_buffer<'a>(
+        &'a self,
+        selection: &'a SelectionResult,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<(), CaptureBackendError>>;
+
+    fn save_replay<'a>(
+        &'a self,
+        output_path: &'a Path,
+    ) -> BackendFuture<'a, Result<RecordingResult, CaptureBackendError>>;
+
+    fn stop_replay_buffer(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>>;
+
+    fn encode_timelapse<'a>(
+        &'a self,
+        frames_dir: &'a Path,
+        frame_count: u32,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<RecordingResult, CaptureBackendError>>;
+
+    fn on_segment_closed(&self, callback: SegmentCallback);
+
+    fn on_av_drift(&self, callback: DriftCallback);
+
+    fn on_session_closed(&self, callback: SessionClosedCallback);
+
+    fn recorded_bytes(&self) -> BackendFuture<'_, Option<u64>>;
+
+    fn has_session(&self) -> BackendFuture<'_, bool>;
+
+    fn close_session(&self) -> BackendFuture<'_, ()>;
+}
+
+impl<B: CaptureBackend> DynCaptureBackend for B {
+    fn request_selection<'a>(
+        &'a self,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<SelectionResult, CaptureBackendError>> {
+        Box::pin(CaptureBackend::request_selection(self, config))
+    }
+
+    fn cancel_selection(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::cancel_selection(self))
+    }
+
+    fn capture_screenshot<'a>(
+        &'a self,
+        selection: &'a SelectionResult,
+    ) -> BackendFuture<'a, Result<RgbaImage, CaptureBackendError>> {
+        Box::pin(CaptureBackend::capture_screenshot(self, selection))
+    }
+
+    fn capture_recording_frame(&self) -> BackendFuture<'_, Result<RgbaImage, CaptureBackendError>> {
+        Box::pin(CaptureBackend::capture_recording_frame(self))
+    }
+
+    fn start_recording<'a>(
+        &'a self,
+        selection: &'a SelectionResult,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::start_recording(self, selection, config))
+    }
+
+    fn start_audio_recording<'a>(
+        &'a self,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::start_audio_recording(self, config))
+    }
+
+    fn stop_recording(&self) -> BackendFuture<'_, Result<RecordingResult, CaptureBackendError>> {
+        Box::pin(CaptureBackend::stop_recording(self))
+    }
+
+    fn pause_recording(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::pause_recording(self))
+    }
+
+    fn resume_recording(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::resume_recording(self))
+    }
+
+    fn start_replay_buffer<'a>(
+        &'a self,
+        selection: &'a SelectionResult,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::start_replay_buffer(self, selection, config))
+    }
+
+    fn save_replay<'a>(
+        &'a self,
+        output_path: &'a Path,
+    ) -> BackendFuture<'a, Result<RecordingResult, CaptureBackendError>> {
+        Box::pin(CaptureBackend::save_replay(self, output_path))
+    }
+
+    fn stop_replay_buffer(&self) -> BackendFuture<'_, Result<(), CaptureBackendError>> {
+        Box::pin(CaptureBackend::stop_replay_buffer(self))
+    }
+
+    fn encode_timelapse<'a>(
+        &'a self,
+        frames_dir: &'a Path,
+        frame_count: u32,
+        config: &'a CaptureConfig,
+    ) -> BackendFuture<'a, Result<RecordingResult, CaptureBackendError>> {
+        Box::pin(CaptureBackend::encode_timelapse(
+            self,
+            frames_dir,
+            frame_count,
+            config,
+        ))
+    }
+
+    fn on_segment_closed(&self, callback: SegmentCallback) {
+        CaptureBackend::on_segment_closed(self, callback)
+    }
+
+    fn on_av_drift(&self, callback: DriftCallback) {
+        CaptureBackend::on_av_drift(self, callback)
+    }
+
+    fn on_session_closed(&self, callback: SessionClosedCallback) {
+        CaptureBackend::on_session_closed(self, callback)
+    }
+
+    fn recorded_bytes(&self) -> BackendFuture<'_, Option<u64>> {
+        Box::pin(CaptureBackend::recorded_bytes(self))
+    }
+
+    fn has_session(&self) -> BackendFuture<'_, bool> {
+        Box::pin(CaptureBackend::has_session(self))
+    }
+
+    fn close_session(&self) -> BackendFuture<'_, ()> {
+        Box::pin(CaptureBackend::close_session(self))
+    }
+}
+
+/// The capture backend for the current platform
+#[cfg(target_os = "linux")]
+pub fn default_backend() -> SharedBackend {
+    Arc::new(super::linux::LinuxCaptureBackend::new())
+}
+
+/// The capture backend for the current platform
+#[cfg(not(target_os = "linux"))]
+pub fn default_backend() -> SharedBackend {
+    Arc::new(super::StubBackend)
+}
@@ -0,0 +1,142 @@
+// Frame timing for timelapse captures
+//
+// Pure bookkeeping so the capture loop can be tested without a portal
+// session: frames are numbered from 1 and only time spent recording
+// (not paused) moves the schedule forward. Slots missed because a grab ran
+// long are skipped rather than shot back to back.
+
+use crate::config::TimelapseConfig;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// printf-style frame file name, as understood by `multifilesrc`
+pub const FRAME_PATTERN: &str = "frame-%05d.png";
+
+/// Path of frame `index` (1-based) inside `frames_dir`
+pub fn frame_path(frames_dir: &Path, index: u32) -> PathBuf {
+    frames_dir.join(format!("frame-{:05}.png", index))
+}
+
+/// Tracks which timelapse frame is due next
+#[derive(Debug, Clone)]
+pub struct TimelapseSchedule {
+    interval: Duration,
+    frame_count: u32,
+    active: Duration,
+    next_due: Duration,
+    captured: u32,
+}
+
+impl TimelapseSchedule {
+    pub fn new(config: &TimelapseConfig) -> Self {
+        Self {
+            interval: Duration::from_secs(u64::from(config.interval_secs)),
+            frame_count: config.frame_count,
+            active: Duration::ZERO,
+            next_due: Duration::ZERO,
+            captured: 0,
+        }
+    }
+
+    /// Account for `elapsed` wall time, which only counts while recording
+    pub fn advance(&mut self, elapsed: Duration, recording: bool) {
+        if recording {
+            self.active += elapsed;
+        }
+    }
+
+    /// Index of the frame to grab now, if one is due
+    ///
+    /// The first frame is due immediately, then one every interval.
+    pub fn frame_due(&self) -> Option<u32> {
+        if self.is_complete() || self.active < self.next_due {
+            return None;
+        }
+        Some(self.captured + 1)
+    }
+
+    /// Record that the due frame was written
+    pub fn frame_captured(&mut self) {
+        self.captured += 1;
+        self.next_due += self.interval;
+        while self.next_due <= self.active {
+            self.next_due += self.interval;
+        }
+    }
+
+    /// Frames written so far
+    pub fn captured(&self) -> u32 {
+        self.captured
+    }
+
+    /// Whether every requested frame has been written
+    pub fn is_complete(&self) -> bool {
+        self.captured >= self.frame_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(interval_secs: u32, frame_count: u32) -> TimelapseSchedule {
+        TimelapseSchedule::new(&TimelapseConfig {
+            interval_secs,
+            frame_count,
+            encode_video: false,
+            frames_dir: None,
+        })
+    }
+
+    #[test]
+    fn test_frame_path_is_zero_padded() {
+        assert_eq!(
+            frame_path(Path::new("/tmp/lapse"), 7),
+            PathBuf::from("/tmp/lapse/frame-00007.png")
+        );
+    }
+
+    #[test]
+    fn test_frames_follow_the_interval() {
+        let mut schedule = schedule(10, 3);
+        assert_eq!(schedule.frame_due(), Some(1));
+        schedule.frame_captured();
+        assert_eq!(schedule.frame_due(), None);
+
+        schedule.advance(Duration::from_secs(9), true);
+        assert_eq!(schedule.frame_due(), None);
+        schedule.advance(Duration::from_secs(1), true);
+        assert_eq!(schedule.frame_due(), Some(2));
+        schedule.frame_captured();
+
+        schedule.advance(Duration::from_secs(10), true);
+        assert_eq!(schedule.frame_due(), Some(3));
+        schedule.frame_captured();
+
+        schedule.advance(Duration::from_secs(60), true);
+        assert_eq!(schedule.frame_due(), None);
+        assert!(schedule.is_complete());
+        assert_eq!(schedule.captured(), 3);
+    }
+
+    #[test]
+    fn test_paused_time_does_not_advance() {
+        let mut schedule = schedule(10, 5);
+        schedule.frame_captured();
+        schedule.advance(Duration::from_secs(60), false);
+        assert_eq!(schedule.frame_due(), None);
+    }
+
+    #[test]
+    fn test_missed_slots_are_skipped() {
+        let mut schedule = schedule(10, 5);
+        schedule.frame_captured();
+        schedule.advance(Duration::from_secs(25), true);
+        assert_eq!(schedule.frame_due(), Some(2));
+        schedule.frame_captured();
+        // The 20 s slot was missed, so the next frame waits for 30 s
+        assert_eq!(schedule.frame_due(), None);
+        schedule.advance(Duration::from_secs(5), true);
+        assert_eq!(schedule.frame_due(), Some(3));
+    }
+}
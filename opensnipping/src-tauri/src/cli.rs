@@ -0,0 +1,276 @@
+use crate::config::{ScreenshotConfig, ScreenshotFormat};
+use crate::screenshot::{redact, save_image, Rect, Redaction, RedactionStyle, StrokeColor};
+use std::path::{Path, PathBuf};
+
+/// Blur radius and pixelate block size when an area gives none
+pub const DEFAULT_REDACTION_BLOCK: u32 = 16;
+
+pub const USAGE: &str = "\
+Usage: opensnipping redact <input> [-o <output>] <area>...
+
+Areas (X,Y,W,H in image pixels; repeat as needed):
+  --blur X,Y,W,H[:RADIUS]       blur the area (radius 16 by default)
+  --pixelate X,Y,W,H[:SIZE]     pixelate the area (16-pixel blocks by default)
+  --fill X,Y,W,H[:#RRGGBB]      paint the area over (black by default)
+
+The output format follows the output extension (png, jpg, webp, avif);
+without -o, <input>-redacted.<ext> is written next to the input.";
+
+/// Work the binary does without opening a window
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Help,
+    Redact(RedactArgs),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedactArgs {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub redactions: Vec<Redaction>,
+}
+
+/// Run a CLI command when the arguments (without the program name) ask
+/// for one
+///
+/// Returns the process exit code, or `None` to start the app as usual.
+pub fn run_from_args(args: impl IntoIterator<Item = String>) -> Option<i32> {
+    let args: Vec<String> = args.into_iter().collect();
+    match parse(&args) {
+        Ok(None) => None,
+        Ok(Some(command)) => Some(match run(command) {
+            Ok(()) => 0,
+            Err(message) => {
+                eprintln!("opensnipping: {}", message);
+                1
+            }
+        }),
+        Err(message) => {
+            eprintln!("opensnipping: {}\n\n{}", message, USAGE);
+            Some(2)
+        }
+    }
+}
+
+/// Parse the arguments after the program name; `Ok(None)` when they are not
+/// a CLI command
+pub fn parse(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+    if command != "redact" {
+        return Ok(None);
+    }
+
+    let mut input = None;
+    let mut output = None;
+    let mut redactions = Vec::new();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        let mut value = |flag: &str| {
+            rest.next()
+                .ok_or_else(|| format!("{} needs a value", flag))
+                .cloned()
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Some(CliCommand::Help)),
+            "-o" | "--output" => output = Some(PathBuf::from(value(arg)?)),
+            "--blur" | "--pixelate" | "--fill" => {
+                let redaction = parse_area(arg, &value(arg)?)?;
+                redaction.validate()?;
+                redactions.push(redaction);
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option {}", flag)),
+            path if input.is_none() => input = Some(PathBuf::from(path)),
+            extra => return Err(format!("Unexpected argument {}", extra)),
+        }
+    }
+
+    let input = input.ok_or("redact needs an input image")?;
+    if redactions.is_empty() {
+        return Err("redact needs at least one --blur, --pixelate or --fill area".to_string());
+    }
+    let output = output.unwrap_or_else(|| redacted_path(&input));
+    Ok(Some(CliCommand::Redact(RedactArgs {
+        input,
+        output,
+        redactions,
+    })))
+}
+
+pub fn run(command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        CliCommand::Redact(args) => {
+            let format = args
+                .output
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(ScreenshotFormat::from_extension)
+                .ok_or_else(|| format!("Unknown output format for {}", args.output.display()))?;
+            let image = image::open(&args.input)
+                .map_err(|e| format!("Failed to read {}: {}", args.input.display(), e))?
+                .into_rgba8();
+            let settings = ScreenshotConfig {
+                format,
+                ..Default::default()
+            };
+            save_image(&redact(&image, &args.redactions), &settings, &args.output)
+                .map_err(|e| e.to_string())?;
+            println!("{}", args.output.display());
+            Ok(())
+        }
+    }
+}
+
+/// `X,Y,W,H[:OPTION]` after `flag`
+fn parse_area(flag: &str, value: &str) -> Result<Redaction, String> {
+    let invalid = || format!("{} expects X,Y,W,H[:OPTION], got {:?}", flag, value);
+    let (area, option) = match value.split_once(':') {
+        Some((area, option)) => (area, Some(option)),
+        None => (value, None),
+    };
+    let numbers = area
+        .split(',')
+        .map(|n| n.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let &[x, y, width, height] = numbers.as_slice() else {
+        return Err(invalid());
+    };
+    let block = || {
+        option
+            .map(|o| o.parse::<u32>().map_err(|_| invalid()))
+            .unwrap_or(Ok(DEFAULT_REDACTION_BLOCK))
+    };
+
+    let style = match flag {
+        "--blur" => RedactionStyle::Blur { radius: block()? },
+        "--pixelate" => RedactionStyle::Pixelate {
+            block_size: block()?,
+        },
+        _ => RedactionStyle::Fill {
+            color: match option {
+                Some(color) => StrokeColor::try_from(color.to_string())?,
+                None => StrokeColor([0, 0, 0, 255]),
+            },
+        },
+    };
+    Ok(Redaction {
+        rect: Rect {
+            x,
+            y,
+            width,
+            height,
+        },
+        style,
+    })
+}
+
+/// `shot.png` → `shot-redacted.png`, next to the input
+fn redacted_path(input: &Path) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match input.extension() {
+        Some(extension) => format!("{}-redacted.{}", stem, extension.to_string_lossy()),
+        None => format!("{}-redacted.png", stem),
+    };
+    input.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_app_arguments_are_not_cli_commands() {
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(parse(&args("--some-webview-flag")), Ok(None));
+        assert_eq!(run_from_args(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_parse_redact_areas_and_defaults() {
+        let command = parse(&args(
+            "redact /tmp/shot.png --blur 1,2,30,40 --pixelate 0,0,8,8:4 --fill 5,5,10,10:#ff0000",
+        ))
+        .unwrap();
+
+        let Some(CliCommand::Redact(parsed)) = command else {
+            panic!("expected a redact command");
+        };
+        assert_eq!(parsed.input, PathBuf::from("/tmp/shot.png"));
+        assert_eq!(parsed.output, PathBuf::from("/tmp/shot-redacted.png"));
+        let styles: Vec<_> = parsed.redactions.iter().map(|r| r.style).collect();
+        assert_eq!(
+            styles,
+            [
+                RedactionStyle::Blur {
+                    radius: DEFAULT_REDACTION_BLOCK
+                },
+                RedactionStyle::Pixelate { block_size: 4 },
+                RedactionStyle::Fill {
+                    color: StrokeColor([255, 0, 0, 255])
+                },
+            ]
+        );
+        assert_eq!(
+            parsed.redactions[0].rect,
+            Rect {
+                x: 1,
+                y: 2,
+                width: 30,
+                height: 40
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_redact_arguments() {
+        for line in [
+            "redact",
+            "redact /tmp/shot.png",
+            "redact /tmp/shot.png --blur 1,2,3",
+            "redact /tmp/shot.png --blur 1,2,3,4:1",
+            "redact /tmp/shot.png --fill 0,0,0,4",
+            "redact /tmp/shot.png --fill 0,0,4,4:red",
+            "redact /tmp/shot.png --pixelate",
+            "redact /tmp/shot.png --sharpen 0,0,4,4",
+            "redact /tmp/a.png /tmp/b.png --fill 0,0,4,4",
+        ] {
+            assert!(parse(&args(line)).is_err(), "{:?} should be rejected", line);
+        }
+        assert_eq!(parse(&args("redact --help")), Ok(Some(CliCommand::Help)));
+    }
+
+    #[test]
+    fn test_run_redact_writes_output_image() {
+        let dir = std::env::temp_dir().join(format!("opensnipping-cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("shot.png");
+        RgbaImage::from_pixel(20, 10, Rgba([255, 255, 255, 255]))
+            .save(&input)
+            .unwrap();
+        let line = format!("redact {} --fill 0,0,5,5:#00ff00", input.display());
+
+        let code = run_from_args(args(&line));
+
+        assert_eq!(code, Some(0));
+        let output = image::open(dir.join("shot-redacted.png"))
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(*output.get_pixel(2, 2), Rgba([0, 255, 0, 255]));
+        assert_eq!(*output.get_pixel(10, 5), Rgba([255, 255, 255, 255]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// H.264 encoder used for video
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoEncoder {
+    /// Best available: VA-API, then NVENC, then x264
+    #[default]
+    Auto,
+    /// Software encoding with libx264
+    X264,
+    /// Intel/AMD GPUs through VA-API
+    Vaapi,
+    /// NVIDIA GPUs through NVENC
+    Nvenc,
+}
+
+/// Speed against quality, mapped onto each encoder's own presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EncoderPreset {
+    /// Least CPU/GPU time, larger files
+    Fast,
+    /// The encoder's own default
+    #[default]
+    Balanced,
+    /// Best quality for the bitrate, most CPU/GPU time
+    Quality,
+}
+
+/// Video encoder preferences of a capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct EncoderConfig {
+    #[serde(default)]
+    pub encoder: VideoEncoder,
+    /// Target bitrate in kbit/s (None keeps the encoder's default)
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,
+    #[serde(default)]
+    pub preset: EncoderPreset,
+}
+
+impl EncoderConfig {
+    pub const MIN_BITRATE_KBPS: u32 = 100;
+    pub const MAX_BITRATE_KBPS: u32 = 100_000;
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self.bitrate_kbps {
+            Some(kbps) if !(Self::MIN_BITRATE_KBPS..=Self::MAX_BITRATE_KBPS).contains(&kbps) => {
+                Err(format!(
+                    "Bitrate must be between {} and {} kbit/s",
+                    Self::MIN_BITRATE_KBPS,
+                    Self::MAX_BITRATE_KBPS
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
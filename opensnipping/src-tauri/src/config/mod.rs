@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+
+mod encoder;
+mod output;
+mod screenshot;
+mod segments;
+mod timelapse;
+
+pub use encoder::{EncoderConfig, EncoderPreset, VideoEncoder};
+pub use output::OutputNaming;
+pub use screenshot::{
+    AutoCropConfig, AutoCropMode, MetadataFields, MetadataPolicy, ScreenshotConfig,
+    ScreenshotFormat, MAX_CROP_PADDING,
+};
+pub use segments::SegmentConfig;
+pub use timelapse::TimelapseConfig;
+
+/// Source type for capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSource {
+    #[default]
+    Screen,
+    Monitor,
+    Window,
+    Region,
+}
+
+impl CaptureSource {
+    /// Lowercase name, as in JSON
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Screen => "screen",
+            Self::Monitor => "monitor",
+            Self::Window => "window",
+            Self::Region => "region",
+        }
+    }
+}
+
+/// What a recording captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// Screen video with optional audio (requires portal selection)
+    #[default]
+    Video,
+    /// Mic and/or system audio only (no portal selection)
+    AudioOnly,
+    /// Keep the last `replay_buffer_secs` of video in memory, saved on demand
+    ReplayBuffer,
+    /// Grab a screenshot every few seconds, optionally encoded into a video
+    Timelapse,
+}
+
+/// Container format for recordings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerFormat {
+    #[default]
+    Mp4,
+    Mkv,
+    /// Opus in OGG (audio-only)
+    Ogg,
+    /// AAC in MPEG-4 audio (audio-only)
+    M4a,
+    /// Uncompressed PCM (audio-only)
+    Wav,
+}
+
+impl ContainerFormat {
+    /// Whether this container can only hold audio
+    pub fn is_audio_only(&self) -> bool {
+        matches!(self, Self::Ogg | Self::M4a | Self::Wav)
+    }
+
+    /// File extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "mkv",
+            Self::Ogg => "ogg",
+            Self::M4a => "m4a",
+            Self::Wav => "wav",
+        }
+    }
+}
+
+/// Audio configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct AudioConfig {
+    /// Capture system audio
+    pub system: bool,
+    /// Capture microphone
+    pub mic: bool,
+}
+
+/// Configuration for a capture session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureConfig {
+    /// Type of capture source
+    pub source: CaptureSource,
+    /// Frames per second (1-60)
+    pub fps: u8,
+    /// Include cursor in capture
+    pub include_cursor: bool,
+    /// Audio settings
+    pub audio: AudioConfig,
+    /// Output container format
+    pub container: ContainerFormat,
+    /// Output file path; when empty, a new file is named from `naming`
+    #[serde(default)]
+    pub output_path: String,
+    /// Save directory and filename template of recordings without an
+    /// `output_path`
+    #[serde(default)]
+    pub naming: OutputNaming,
+    /// Video or audio-only recording
+    #[serde(default)]
+    pub mode: CaptureMode,
+    /// Write fragmented MP4 / streamable MKV so a crash leaves a playable file
+    #[serde(default)]
+    pub crash_safe: bool,
+    /// Split the output into several files (None records a single file)
+    #[serde(default)]
+    pub segments: Option<SegmentConfig>,
+    /// Seconds kept in memory in replay buffer mode (5-600)
+    #[serde(default = "default_replay_buffer_secs")]
+    pub replay_buffer_secs: u32,
+    /// Stop the recording after this many seconds, not counting pauses
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// Stop the recording once its output reaches this size (all segments
+    /// together when segmented)
+    #[serde(default)]
+    pub max_file_size_bytes: Option<u64>,
+    /// Interval and frame count for timelapse mode
+    #[serde(default)]
+    pub timelapse: Option<TimelapseConfig>,
+    /// Format, quality and compression of screenshots
+    #[serde(default)]
+    pub screenshot: ScreenshotConfig,
+    /// Video encoder, bitrate and preset; None takes the default profile's
+    /// preferences, or picks the best available encoder without any
+    #[serde(default)]
+    pub encoder: Option<EncoderConfig>,
+}
+
+fn default_replay_buffer_secs() -> u32 {
+    30
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            source: CaptureSource::default(),
+            fps: 30,
+            include_cursor: true,
+            audio: AudioConfig::default(),
+            container: ContainerFormat::default(),
+            output_path: String::new(),
+            naming: OutputNaming::default(),
+            mode: CaptureMode::default(),
+            crash_safe: false,
+            segments: None,
+            replay_buffer_secs: default_replay_buffer_secs(),
+            max_duration_secs: None,
+            max_file_size_bytes: None,
+            timelapse: None,
+            screenshot: ScreenshotConfig::default(),
+            encoder: None,
+        }
+    }
+}
+
+/// Validation error for CaptureConfig
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigError {
+    pub field: String,
+    pub message: String,
+}
+
+impl CaptureConfig {
+    /// Path a recording of this config writes: `output_path`, or the
+    /// `{index}` template of its files when segmented
+    pub fn recording_path(&self) -> String {
+        match &self.segments {
+            Some(segments) => segments.template_for(&self.output_path),
+            None => self.output_path.clone(),
+        }
+    }
+
+    /// Screen video to `<temp dir>/<prefix>-<uuid>.mp4`, naming other
+    /// captures into the temp directory too
+    #[cfg(test)]
+    pub(crate) fn for_test(prefix: &str) -> Self {
+        let output_path =
+            std::env::temp_dir().join(format!("{}-{}.mp4", prefix, uuid::Uuid::new_v4()));
+        Self {
+            output_path: output_path.to_string_lossy().to_string(),
+            naming: crate::naming::temp_naming(),
+            screenshot: ScreenshotConfig {
+                naming: crate::naming::temp_naming(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.fps == 0 || self.fps > 60 {
+            return Err(ConfigError {
+                field: "fps".to_string(),
+                message: "FPS must be between 1 and 60".to_string(),
+            });
+        }
+
+        if let Err(message) = self.naming.validate() {
+            return Err(ConfigError {
+                field: "naming".to_string(),
+                message,
+            });
+        }
+
+        match self.mode {
+            CaptureMode::Video | CaptureMode::ReplayBuffer if self.container.is_audio_only() => {
+                return Err(ConfigError {
+                    field: "container".to_string(),
+                    message: "Audio-only containers require audio-only mode".to_string(),
+                });
+            }
+            CaptureMode::AudioOnly if !self.container.is_audio_only() => {
+                return Err(ConfigError {
+                    field: "container".to_string(),
+                    message: "Audio-only mode requires an OGG, M4A or WAV container".to_string(),
+                });
+            }
+            CaptureMode::AudioOnly if !self.audio.mic && !self.audio.system => {
+                return Err(ConfigError {
+                    field: "audio".to_string(),
+                    message: "Audio-only mode requires mic or system audio".to_string(),
+                });
+            }
+            CaptureMode::ReplayBuffer if !(5..=600).contains(&self.replay_buffer_secs) => {
+                return Err(ConfigError {
+                    field: "replay_buffer_secs".to_string(),
+                    message: "Replay buffer must hold between 5 and 600 seconds".to_string(),
+                });
+            }
+            CaptureMode::ReplayBuffer if self.segments.is_some() => {
+                return Err(ConfigError {
+                    field: "segments".to_string(),
+                    message: "Replay buffer mode cannot be segmented".to_string(),
+                });
+            }
+            _ => {}
+        }
+
+        if self.max_duration_secs == Some(0) {
+            return Err(ConfigError {
+                field: "max_duration_secs".to_string(),
+                message: "Maximum duration must be at least one second".to_string(),
+            });
+        }
+
+        if self.max_file_size_bytes == Some(0) {
+            return Err(ConfigError {
+                field: "max_file_size_bytes".to_string(),
+                message: "Maximum file size must be greater than zero".to_string(),
+            });
+        }
+
+        if self.mode == CaptureMode::Timelapse {
+            timelapse::validate(self).map_err(|message| ConfigError {
+                field: "timelapse".to_string(),
+                message,
+            })?;
+        }
+
+        self.screenshot.validate().map_err(|message| ConfigError {
+            field: "screenshot".to_string(),
+            message,
+        })?;
+
+        if let Some(encoder) = &self.encoder {
+            encoder.validate().map_err(|message| ConfigError {
+                field: "encoder".to_string(),
+                message,
+            })?;
+        }
+
+        if let Some(segments) = &self.segments {
+            segments
+                .validate(self.container)
+                .map_err(|message| ConfigError {
+                    field: "segments".to_string(),
+                    message,
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::naming::Template;
+
+/// Where new captures are saved and how their files are named
+///
+/// Unset fields use the defaults of the capture kind: the XDG Pictures or
+/// Videos directory and `naming::DEFAULT_SCREENSHOT_TEMPLATE` or
+/// `naming::DEFAULT_RECORDING_TEMPLATE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct OutputNaming {
+    /// Save directory; a leading `~/` is the home directory
+    pub directory: Option<String>,
+    /// Filename template with `{date}`, `{time}`, `{source}`, `{counter}`
+    /// and `{ext}` tokens
+    pub template: Option<String>,
+}
+
+impl OutputNaming {
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .directory
+            .as_ref()
+            .is_some_and(|directory| directory.trim().is_empty())
+        {
+            return Err("Save directory cannot be empty".to_string());
+        }
+        match &self.template {
+            Some(template) => Template::parse(template).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
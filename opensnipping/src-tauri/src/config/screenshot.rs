@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+
+use super::OutputNaming;
+use crate::screenshot::StrokeColor;
+
+/// Widest padding `AutoCropConfig` adds around trimmed content, in pixels
+pub const MAX_CROP_PADDING: u32 = 512;
+
+/// Image format screenshots are saved in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// Lossy WebP (needs GStreamer's `webpenc`)
+    Webp,
+    WebpLossless,
+    Avif,
+}
+
+impl ScreenshotFormat {
+    pub const ALL: [Self; 5] = [
+        Self::Png,
+        Self::Jpeg,
+        Self::Webp,
+        Self::WebpLossless,
+        Self::Avif,
+    ];
+
+    /// File extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp | Self::WebpLossless => "webp",
+            Self::Avif => "avif",
+        }
+    }
+
+    /// Format written for a file extension (case-insensitive, without the
+    /// dot); `webp` is lossless since it needs no GStreamer
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebpLossless),
+            "avif" => Some(Self::Avif),
+            _ => None,
+        }
+    }
+
+    /// Whether `ScreenshotConfig::quality` applies to this format
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, Self::Jpeg | Self::Webp | Self::Avif)
+    }
+}
+
+/// What a saved screenshot says about its capture
+///
+/// PNG files get `tEXt` chunks and JPEG files an EXIF block; WebP and AVIF
+/// are always written without metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum MetadataPolicy {
+    /// No text chunks and no EXIF
+    #[default]
+    Strip,
+    /// The chosen capture details
+    Include(MetadataFields),
+}
+
+/// Capture details written with `MetadataPolicy::Include` (all by default)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataFields {
+    /// When the frame was grabbed, in UTC
+    pub capture_time: bool,
+    /// Screen, monitor, window or region
+    pub source: bool,
+    /// App name and version
+    pub app_version: bool,
+}
+
+impl Default for MetadataFields {
+    fn default() -> Self {
+        Self {
+            capture_time: true,
+            source: true,
+            app_version: true,
+        }
+    }
+}
+
+/// Which screenshots have their borders trimmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoCropMode {
+    #[default]
+    Off,
+    /// Only `CaptureSource::Window` captures, which carry shadow margins
+    Windows,
+    /// Every screenshot
+    Always,
+}
+
+/// Trimming of transparent shadow margins and solid borders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCropConfig {
+    pub mode: AutoCropMode,
+    /// Largest per-channel difference from the border color that still
+    /// counts as border (0 trims exact matches only)
+    pub tolerance: u8,
+    /// Pixels added back on every side of the trimmed content
+    pub padding: u32,
+    /// Color of the padding and of any translucent corners left; fully
+    /// transparent when unset
+    pub background: Option<StrokeColor>,
+}
+
+impl Default for AutoCropConfig {
+    fn default() -> Self {
+        Self {
+            mode: AutoCropMode::default(),
+            tolerance: 8,
+            padding: 0,
+            background: None,
+        }
+    }
+}
+
+/// How screenshots are encoded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotConfig {
+    pub format: ScreenshotFormat,
+    /// Quality of lossy formats (1-100)
+    pub quality: u8,
+    /// PNG deflate level (0 stores uncompressed, 9 is smallest)
+    pub png_compression: u8,
+    /// Copy each screenshot to the clipboard once it is saved
+    pub copy_to_clipboard: bool,
+    /// Offer the saved file as a `text/uri-list` next to the image data
+    pub copy_file_uri: bool,
+    /// Capture details embedded in the file
+    pub metadata: MetadataPolicy,
+    /// Border trimming before the screenshot is saved
+    pub auto_crop: AutoCropConfig,
+    /// Save directory and filename template
+    pub naming: OutputNaming,
+}
+
+impl Default for ScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            format: ScreenshotFormat::default(),
+            quality: 90,
+            png_compression: 6,
+            copy_to_clipboard: false,
+            copy_file_uri: false,
+            metadata: MetadataPolicy::default(),
+            auto_crop: AutoCropConfig::default(),
+            naming: OutputNaming::default(),
+        }
+    }
+}
+
+impl ScreenshotConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.quality) {
+            return Err("Screenshot quality must be between 1 and 100".to_string());
+        }
+        if self.png_compression > 9 {
+            return Err("PNG compression level must be between 0 and 9".to_string());
+        }
+        self.naming.validate()?;
+        if self.auto_crop.padding > MAX_CROP_PADDING {
+            return Err(format!(
+                "Auto-crop padding must be at most {} pixels",
+                MAX_CROP_PADDING
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CaptureConfig;
+
+    fn screenshot_config(screenshot: ScreenshotConfig) -> CaptureConfig {
+        CaptureConfig {
+            output_path: "/tmp/recording.mp4".to_string(),
+            screenshot,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_screenshot_defaults_to_png() {
+        let config: CaptureConfig = serde_json::from_str(
+            r#"{"source":"screen","fps":30,"include_cursor":true,
+                "audio":{"system":false,"mic":false},"container":"mp4",
+                "output_path":"/tmp/recording.mp4"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.screenshot, ScreenshotConfig::default());
+        assert_eq!(config.screenshot.format.extension(), "png");
+    }
+
+    #[test]
+    fn test_screenshot_format_names_and_extensions() {
+        let names: Vec<_> = ScreenshotFormat::ALL
+            .iter()
+            .map(|format| serde_json::to_string(format).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "\"png\"",
+                "\"jpeg\"",
+                "\"webp\"",
+                "\"webp_lossless\"",
+                "\"avif\""
+            ]
+        );
+        assert_eq!(ScreenshotFormat::Jpeg.extension(), "jpg");
+        assert_eq!(ScreenshotFormat::WebpLossless.extension(), "webp");
+        assert!(!ScreenshotFormat::WebpLossless.is_lossy());
+        assert_eq!(
+            ScreenshotFormat::from_extension("JPEG"),
+            Some(ScreenshotFormat::Jpeg)
+        );
+        assert_eq!(
+            ScreenshotFormat::from_extension("webp"),
+            Some(ScreenshotFormat::WebpLossless)
+        );
+        assert_eq!(ScreenshotFormat::from_extension("gif"), None);
+    }
+
+    #[test]
+    fn test_metadata_policy_json() {
+        let strip: ScreenshotConfig = serde_json::from_str(r#"{"format":"png"}"#).unwrap();
+        assert_eq!(strip.metadata, MetadataPolicy::Strip);
+
+        let include: ScreenshotConfig =
+            serde_json::from_str(r#"{"metadata":{"policy":"include","source":false}}"#).unwrap();
+        assert_eq!(
+            include.metadata,
+            MetadataPolicy::Include(MetadataFields {
+                source: false,
+                ..Default::default()
+            })
+        );
+        let json = serde_json::to_value(include.metadata).unwrap();
+        assert_eq!(json["policy"], "include");
+        assert_eq!(json["capture_time"], true);
+    }
+
+    #[test]
+    fn test_validate_screenshot_quality_and_compression() {
+        for screenshot in [
+            ScreenshotConfig {
+                quality: 0,
+                ..Default::default()
+            },
+            ScreenshotConfig {
+                quality: 101,
+                ..Default::default()
+            },
+            ScreenshotConfig {
+                png_compression: 10,
+                ..Default::default()
+            },
+            ScreenshotConfig {
+                auto_crop: AutoCropConfig {
+                    padding: MAX_CROP_PADDING + 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ] {
+            let err = screenshot_config(screenshot).validate().unwrap_err();
+            assert_eq!(err.field, "screenshot");
+        }
+
+        let fastest = ScreenshotConfig {
+            format: ScreenshotFormat::Avif,
+            quality: 1,
+            png_compression: 0,
+            ..Default::default()
+        };
+        assert!(screenshot_config(fastest).validate().is_ok());
+    }
+}
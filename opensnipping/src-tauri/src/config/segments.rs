@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+use super::ContainerFormat;
+
+/// Rotate a long recording into multiple files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct SegmentConfig {
+    /// Start a new file after this many seconds
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// Start a new file after this many bytes
+    #[serde(default)]
+    pub max_size_bytes: Option<u64>,
+    /// Path of each segment, with `{index}` replaced by the segment number
+    /// (defaults to the output path with `-{index}` before the extension)
+    #[serde(default)]
+    pub filename_template: Option<String>,
+}
+
+impl SegmentConfig {
+    pub const INDEX_PLACEHOLDER: &'static str = "{index}";
+
+    /// Segment filename template, derived from `output_path` if not set
+    pub fn template_for(&self, output_path: &str) -> String {
+        if let Some(template) = &self.filename_template {
+            return template.clone();
+        }
+
+        let name_start = output_path.rfind('/').map_or(0, |i| i + 1);
+        match output_path[name_start..].rfind('.') {
+            Some(dot) if dot > 0 => {
+                let (stem, ext) = output_path.split_at(name_start + dot);
+                format!("{}-{}{}", stem, Self::INDEX_PLACEHOLDER, ext)
+            }
+            _ => format!("{}-{}", output_path, Self::INDEX_PLACEHOLDER),
+        }
+    }
+
+    /// Path of segment `index` of `template`, numbered as splitmuxsink does
+    pub fn segment_path(template: &str, index: u32) -> String {
+        template.replace(Self::INDEX_PLACEHOLDER, &format!("{:05}", index))
+    }
+
+    pub(super) fn validate(&self, container: ContainerFormat) -> Result<(), String> {
+        if container == ContainerFormat::Wav {
+            return Err("WAV recordings cannot be segmented".to_string());
+        }
+        if self.max_duration_secs.is_none() && self.max_size_bytes.is_none() {
+            return Err("Segmenting requires a maximum duration or size".to_string());
+        }
+        if self.max_duration_secs == Some(0) || self.max_size_bytes == Some(0) {
+            return Err("Segment limits must be greater than zero".to_string());
+        }
+        if let Some(template) = &self.filename_template {
+            if template.matches(Self::INDEX_PLACEHOLDER).count() != 1 {
+                return Err(format!(
+                    "Segment filename template must contain {} exactly once",
+                    Self::INDEX_PLACEHOLDER
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AudioConfig, CaptureConfig, CaptureMode};
+
+    fn segmented_config(segments: SegmentConfig) -> CaptureConfig {
+        CaptureConfig {
+            container: ContainerFormat::Mkv,
+            output_path: "/tmp/long.mkv".to_string(),
+            segments: Some(segments),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_segments_by_duration_or_size() {
+        let by_duration = segmented_config(SegmentConfig {
+            max_duration_secs: Some(600),
+            ..Default::default()
+        });
+        assert!(by_duration.validate().is_ok());
+
+        let by_size = segmented_config(SegmentConfig {
+            max_size_bytes: Some(2 * 1024 * 1024 * 1024),
+            ..Default::default()
+        });
+        assert!(by_size.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_segments_requires_positive_limit() {
+        let no_limit = segmented_config(SegmentConfig::default());
+        assert_eq!(no_limit.validate().unwrap_err().field, "segments");
+
+        let zero = segmented_config(SegmentConfig {
+            max_duration_secs: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(zero.validate().unwrap_err().field, "segments");
+    }
+
+    #[test]
+    fn test_validate_segment_template_needs_one_index() {
+        for template in ["/tmp/part.mkv", "/tmp/{index}-{index}.mkv"] {
+            let config = segmented_config(SegmentConfig {
+                max_duration_secs: Some(600),
+                filename_template: Some(template.to_string()),
+                ..Default::default()
+            });
+            assert_eq!(config.validate().unwrap_err().field, "segments");
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_segmented_wav() {
+        let config = CaptureConfig {
+            mode: CaptureMode::AudioOnly,
+            container: ContainerFormat::Wav,
+            audio: AudioConfig {
+                system: false,
+                mic: true,
+            },
+            output_path: "/tmp/memo.wav".to_string(),
+            segments: Some(SegmentConfig {
+                max_duration_secs: Some(60),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(config.validate().unwrap_err().field, "segments");
+    }
+
+    #[test]
+    fn test_segment_template_defaults_from_output_path() {
+        let segments = SegmentConfig::default();
+        assert_eq!(
+            segments.template_for("/tmp/long.mkv"),
+            "/tmp/long-{index}.mkv"
+        );
+        assert_eq!(
+            segments.template_for("/tmp/v1.2/long"),
+            "/tmp/v1.2/long-{index}"
+        );
+        assert_eq!(
+            segments.template_for("/tmp/.hidden"),
+            "/tmp/.hidden-{index}"
+        );
+
+        let custom = SegmentConfig {
+            filename_template: Some("/media/part-{index}.mp4".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            custom.template_for("/tmp/long.mkv"),
+            "/media/part-{index}.mp4"
+        );
+    }
+
+    #[test]
+    fn test_segment_paths_follow_the_template() {
+        let config = segmented_config(SegmentConfig {
+            max_duration_secs: Some(600),
+            ..Default::default()
+        });
+
+        assert_eq!(config.recording_path(), "/tmp/long-{index}.mkv");
+        assert_eq!(
+            SegmentConfig::segment_path(&config.recording_path(), 12),
+            "/tmp/long-00012.mkv"
+        );
+        let single = CaptureConfig {
+            segments: None,
+            ..config
+        };
+        assert_eq!(single.recording_path(), "/tmp/long.mkv");
+    }
+}
@@ -0,0 +1,300 @@
+use super::*;
+
+#[test]
+fn test_default_config() {
+    let config = CaptureConfig::default();
+    assert_eq!(config.fps, 30);
+    assert!(config.include_cursor);
+    assert_eq!(config.source, CaptureSource::Screen);
+    assert_eq!(config.container, ContainerFormat::Mp4);
+}
+
+#[test]
+fn test_validate_valid_config() {
+    let config = CaptureConfig {
+        output_path: "/tmp/recording.mp4".to_string(),
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_fps_zero() {
+    let config = CaptureConfig {
+        fps: 0,
+        output_path: "/tmp/recording.mp4".to_string(),
+        ..Default::default()
+    };
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.field, "fps");
+}
+
+#[test]
+fn test_validate_fps_too_high() {
+    let config = CaptureConfig {
+        fps: 61,
+        output_path: "/tmp/recording.mp4".to_string(),
+        ..Default::default()
+    };
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.field, "fps");
+}
+
+#[test]
+fn test_validate_empty_output_path_is_named() {
+    let config = CaptureConfig::default();
+    assert!(config.validate().is_ok());
+
+    let config = CaptureConfig {
+        naming: OutputNaming {
+            directory: None,
+            template: Some("{date}/{nope}".to_string()),
+        },
+        ..Default::default()
+    };
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.field, "naming");
+    assert!(err.message.contains("{nope}"), "{}", err.message);
+}
+
+#[test]
+fn test_serialize_deserialize() {
+    let config = CaptureConfig {
+        source: CaptureSource::Window,
+        fps: 60,
+        include_cursor: false,
+        audio: AudioConfig {
+            system: true,
+            mic: true,
+        },
+        container: ContainerFormat::Mkv,
+        output_path: "/tmp/test.mkv".to_string(),
+        naming: OutputNaming {
+            directory: Some("~/Videos/OpenSnipping".to_string()),
+            template: Some("{source}/{date} {counter}.{ext}".to_string()),
+        },
+        mode: CaptureMode::Video,
+        crash_safe: true,
+        segments: Some(SegmentConfig {
+            max_duration_secs: Some(600),
+            max_size_bytes: None,
+            filename_template: Some("/tmp/test-{index}.mkv".to_string()),
+        }),
+        replay_buffer_secs: 45,
+        max_duration_secs: Some(3600),
+        max_file_size_bytes: None,
+        timelapse: None,
+        screenshot: ScreenshotConfig::default(),
+        encoder: Some(EncoderConfig {
+            encoder: VideoEncoder::Nvenc,
+            bitrate_kbps: Some(8000),
+            preset: EncoderPreset::Quality,
+        }),
+    };
+
+    let json = serde_json::to_string(&config).unwrap();
+    let deserialized: CaptureConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(config, deserialized);
+}
+
+#[test]
+fn test_default_mode_is_video() {
+    let config = CaptureConfig::default();
+    assert_eq!(config.mode, CaptureMode::Video);
+}
+
+#[test]
+fn test_mode_defaults_when_missing_from_json() {
+    let json = r#"{
+        "source": "screen",
+        "fps": 30,
+        "include_cursor": true,
+        "audio": { "system": false, "mic": false },
+        "container": "mp4",
+        "output_path": "/tmp/recording.mp4"
+    }"#;
+    let config: CaptureConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.mode, CaptureMode::Video);
+    assert!(!config.crash_safe);
+    assert!(config.segments.is_none());
+    assert_eq!(config.replay_buffer_secs, 30);
+    assert!(config.max_duration_secs.is_none());
+    assert!(config.max_file_size_bytes.is_none());
+    assert!(config.encoder.is_none());
+}
+
+#[test]
+fn test_validate_audio_only_config() {
+    for container in [
+        ContainerFormat::Ogg,
+        ContainerFormat::M4a,
+        ContainerFormat::Wav,
+    ] {
+        let config = CaptureConfig {
+            mode: CaptureMode::AudioOnly,
+            container,
+            audio: AudioConfig {
+                system: false,
+                mic: true,
+            },
+            output_path: "/tmp/memo.audio".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok(), "{:?} should be valid", container);
+    }
+}
+
+#[test]
+fn test_validate_audio_only_requires_audio_source() {
+    let config = CaptureConfig {
+        mode: CaptureMode::AudioOnly,
+        container: ContainerFormat::Ogg,
+        output_path: "/tmp/memo.ogg".to_string(),
+        ..Default::default()
+    };
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.field, "audio");
+}
+
+#[test]
+fn test_validate_audio_only_rejects_video_container() {
+    let config = CaptureConfig {
+        mode: CaptureMode::AudioOnly,
+        container: ContainerFormat::Mp4,
+        audio: AudioConfig {
+            system: true,
+            mic: false,
+        },
+        output_path: "/tmp/memo.mp4".to_string(),
+        ..Default::default()
+    };
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.field, "container");
+}
+
+#[test]
+fn test_validate_replay_buffer_config() {
+    let mut config = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        container: ContainerFormat::Mkv,
+        output_path: "/tmp/replay.mkv".to_string(),
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+
+    config.replay_buffer_secs = 2;
+    assert_eq!(config.validate().unwrap_err().field, "replay_buffer_secs");
+    config.replay_buffer_secs = 601;
+    assert_eq!(config.validate().unwrap_err().field, "replay_buffer_secs");
+
+    config.replay_buffer_secs = 30;
+    config.container = ContainerFormat::Ogg;
+    assert_eq!(config.validate().unwrap_err().field, "container");
+
+    config.container = ContainerFormat::Mkv;
+    config.segments = Some(SegmentConfig {
+        max_duration_secs: Some(60),
+        ..Default::default()
+    });
+    assert_eq!(config.validate().unwrap_err().field, "segments");
+}
+
+#[test]
+fn test_validate_recording_limits() {
+    let mut config = CaptureConfig {
+        output_path: "/tmp/recording.mp4".to_string(),
+        max_duration_secs: Some(60),
+        max_file_size_bytes: Some(100 * 1024 * 1024),
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+
+    config.max_duration_secs = Some(0);
+    assert_eq!(config.validate().unwrap_err().field, "max_duration_secs");
+
+    config.max_duration_secs = None;
+    config.max_file_size_bytes = Some(0);
+    assert_eq!(config.validate().unwrap_err().field, "max_file_size_bytes");
+
+    // Segmented recordings are limited by the size of all segments together
+    config.max_file_size_bytes = Some(1024);
+    config.segments = Some(SegmentConfig {
+        max_duration_secs: Some(60),
+        ..Default::default()
+    });
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_encoder_bitrate() {
+    let mut config = CaptureConfig {
+        output_path: "/tmp/recording.mp4".to_string(),
+        encoder: Some(EncoderConfig {
+            bitrate_kbps: Some(6000),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+
+    for kbps in [0, EncoderConfig::MAX_BITRATE_KBPS + 1] {
+        config.encoder = Some(EncoderConfig {
+            bitrate_kbps: Some(kbps),
+            ..Default::default()
+        });
+        assert_eq!(config.validate().unwrap_err().field, "encoder");
+    }
+}
+
+#[test]
+fn test_validate_video_rejects_audio_container() {
+    let config = CaptureConfig {
+        container: ContainerFormat::Wav,
+        output_path: "/tmp/recording.wav".to_string(),
+        ..Default::default()
+    };
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.field, "container");
+}
+
+#[test]
+fn test_audio_only_containers() {
+    assert!(!ContainerFormat::Mp4.is_audio_only());
+    assert!(!ContainerFormat::Mkv.is_audio_only());
+    assert!(ContainerFormat::Ogg.is_audio_only());
+    assert!(ContainerFormat::M4a.is_audio_only());
+    assert!(ContainerFormat::Wav.is_audio_only());
+}
+
+#[test]
+fn test_audio_config_combinations() {
+    // Test that AudioConfig correctly represents all audio states
+    let no_audio = AudioConfig {
+        system: false,
+        mic: false,
+    };
+    let mic_only = AudioConfig {
+        system: false,
+        mic: true,
+    };
+    let system_only = AudioConfig {
+        system: true,
+        mic: false,
+    };
+    let both_audio = AudioConfig {
+        system: true,
+        mic: true,
+    };
+
+    // No audio
+    assert!(!no_audio.system && !no_audio.mic);
+
+    // Mic only
+    assert!(!mic_only.system && mic_only.mic);
+
+    // System only
+    assert!(system_only.system && !system_only.mic);
+
+    // Both
+    assert!(both_audio.system && both_audio.mic);
+}
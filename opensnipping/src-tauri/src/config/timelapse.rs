@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::CaptureConfig;
+
+/// Grab a frame every `interval_secs` and optionally encode them into a video
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelapseConfig {
+    /// Seconds between frames (1-86400)
+    pub interval_secs: u32,
+    /// Frames to capture before the timelapse ends (1-99999)
+    pub frame_count: u32,
+    /// Encode the frames into `output_path`, played back at `fps`
+    #[serde(default)]
+    pub encode_video: bool,
+    /// Directory for the numbered PNGs
+    /// (defaults to the output path without extension, plus `-frames`)
+    #[serde(default)]
+    pub frames_dir: Option<String>,
+}
+
+impl TimelapseConfig {
+    /// Upper bound keeping frame numbers within the five-digit file names
+    pub const MAX_FRAMES: u32 = 99_999;
+
+    /// Frame directory, derived from `output_path` if not set
+    pub fn frames_dir_for(&self, output_path: &str) -> PathBuf {
+        if let Some(dir) = &self.frames_dir {
+            return PathBuf::from(dir);
+        }
+
+        let name_start = output_path.rfind('/').map_or(0, |i| i + 1);
+        let stem = match output_path[name_start..].rfind('.') {
+            Some(dot) if dot > 0 => &output_path[..name_start + dot],
+            _ => output_path,
+        };
+        PathBuf::from(format!("{}-frames", stem))
+    }
+}
+
+/// Checks for `CaptureMode::Timelapse` configs
+pub(super) fn validate(config: &CaptureConfig) -> Result<(), String> {
+    let Some(timelapse) = &config.timelapse else {
+        return Err("Timelapse mode requires timelapse settings".to_string());
+    };
+    if !(1..=86_400).contains(&timelapse.interval_secs) {
+        return Err("Timelapse interval must be between 1 and 86400 seconds".to_string());
+    }
+    if !(1..=TimelapseConfig::MAX_FRAMES).contains(&timelapse.frame_count) {
+        return Err(format!(
+            "Timelapse frame count must be between 1 and {}",
+            TimelapseConfig::MAX_FRAMES
+        ));
+    }
+    if timelapse.encode_video && config.container.is_audio_only() {
+        return Err("Timelapse videos need a video container".to_string());
+    }
+    if config.segments.is_some() {
+        return Err("Timelapse mode cannot be segmented".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CaptureMode, ContainerFormat, SegmentConfig};
+
+    fn timelapse_config(timelapse: TimelapseConfig) -> CaptureConfig {
+        CaptureConfig {
+            mode: CaptureMode::Timelapse,
+            output_path: "/tmp/dashboard.mp4".to_string(),
+            timelapse: Some(timelapse),
+            ..Default::default()
+        }
+    }
+
+    fn every_minute() -> TimelapseConfig {
+        TimelapseConfig {
+            interval_secs: 60,
+            frame_count: 120,
+            encode_video: true,
+            frames_dir: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_timelapse() {
+        assert!(timelapse_config(every_minute()).validate().is_ok());
+
+        let missing = CaptureConfig {
+            timelapse: None,
+            ..timelapse_config(every_minute())
+        };
+        assert_eq!(missing.validate().unwrap_err().field, "timelapse");
+
+        for (interval_secs, frame_count) in [(0, 10), (86_401, 10), (60, 0), (60, 100_000)] {
+            let config = timelapse_config(TimelapseConfig {
+                interval_secs,
+                frame_count,
+                ..every_minute()
+            });
+            assert_eq!(config.validate().unwrap_err().field, "timelapse");
+        }
+    }
+
+    #[test]
+    fn test_validate_timelapse_container_and_segments() {
+        let audio_container = CaptureConfig {
+            container: ContainerFormat::Ogg,
+            ..timelapse_config(every_minute())
+        };
+        assert_eq!(audio_container.validate().unwrap_err().field, "timelapse");
+
+        // Frames only: the container is never used
+        let frames_only = CaptureConfig {
+            container: ContainerFormat::Ogg,
+            ..timelapse_config(TimelapseConfig {
+                encode_video: false,
+                ..every_minute()
+            })
+        };
+        assert!(frames_only.validate().is_ok());
+
+        let segmented = CaptureConfig {
+            segments: Some(SegmentConfig {
+                max_duration_secs: Some(60),
+                ..Default::default()
+            }),
+            ..timelapse_config(every_minute())
+        };
+        assert_eq!(segmented.validate().unwrap_err().field, "timelapse");
+    }
+
+    #[test]
+    fn test_frames_dir_defaults_from_output_path() {
+        let timelapse = every_minute();
+        assert_eq!(
+            timelapse.frames_dir_for("/tmp/dashboard.mp4"),
+            PathBuf::from("/tmp/dashboard-frames")
+        );
+        assert_eq!(
+            timelapse.frames_dir_for("/tmp/v1.2/lapse"),
+            PathBuf::from("/tmp/v1.2/lapse-frames")
+        );
+
+        let custom = TimelapseConfig {
+            frames_dir: Some("/data/frames".to_string()),
+            ..every_minute()
+        };
+        assert_eq!(
+            custom.frames_dir_for("/tmp/dashboard.mp4"),
+            PathBuf::from("/data/frames")
+        );
+    }
+}
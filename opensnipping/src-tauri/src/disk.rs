@@ -0,0 +1,265 @@
+use crate::capture::SelectionResult;
+use crate::config::{CaptureConfig, CaptureMode, ContainerFormat};
+use std::path::{Path, PathBuf};
+
+/// Space kept free so the muxer can still write its index when stopping
+pub const RESERVE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Minimum recording time the disk must hold before a recording may start
+pub const PREFLIGHT_SECS: u64 = 60;
+
+/// Remaining recording time at which a low-space warning is emitted
+pub const WARNING_SECS: u64 = 5 * 60;
+
+/// Remaining recording time at which the recording is stopped
+pub const STOP_SECS: u64 = 10;
+
+/// How often free space is polled while recording
+pub const POLL_INTERVAL_SECS: u64 = 2;
+
+/// Conservative H.264 bits per pixel for screen content
+///
+/// The encoders run at their default rate control, which stays well
+/// below this for typical desktop content, so estimates err on the safe side.
+const VIDEO_BITS_PER_PIXEL: f64 = 0.1;
+
+/// Upper bound on compressed audio (AAC/Opus at their defaults)
+const COMPRESSED_AUDIO_BYTES_PER_SEC: u64 = 192_000 / 8;
+
+/// 48 kHz stereo 16-bit PCM
+const WAV_BYTES_PER_SEC: u64 = 48_000 * 2 * 2;
+
+/// How much room is left for the recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskStatus {
+    Ok,
+    /// Less than `WARNING_SECS` of recording left
+    Low {
+        remaining_secs: u64,
+    },
+    /// Less than `STOP_SECS` left: stop and finalize now
+    Critical,
+}
+
+/// Estimated output rate of a recording of `config` at `width`x`height`
+pub fn estimate_bytes_per_sec(config: &CaptureConfig, width: u32, height: u32) -> u64 {
+    let has_audio = config.audio.mic || config.audio.system;
+    let audio = match config.container {
+        _ if !has_audio => 0,
+        ContainerFormat::Wav => WAV_BYTES_PER_SEC,
+        _ => COMPRESSED_AUDIO_BYTES_PER_SEC,
+    };
+    if config.mode == CaptureMode::AudioOnly {
+        return audio;
+    }
+
+    let pixels_per_sec = f64::from(width) * f64::from(height) * f64::from(config.fps);
+    let video = (pixels_per_sec * VIDEO_BITS_PER_PIXEL / 8.0) as u64;
+    video + audio
+}
+
+/// `estimate_bytes_per_sec` for the stream picked in `selection`
+///
+/// Streams without known dimensions count as 1920x1080. Without a
+/// selection (audio-only) the video part is zero.
+pub fn estimate_for_selection(config: &CaptureConfig, selection: Option<&SelectionResult>) -> u64 {
+    let (width, height) = selection
+        .map(|s| (s.width.unwrap_or(1920), s.height.unwrap_or(1080)))
+        .unwrap_or((0, 0));
+    estimate_bytes_per_sec(config, width, height)
+}
+
+/// Seconds of recording `free_bytes` can hold once the reserve is set aside
+pub fn remaining_secs(free_bytes: u64, bytes_per_sec: u64) -> u64 {
+    free_bytes.saturating_sub(RESERVE_BYTES) / bytes_per_sec.max(1)
+}
+
+/// Whether a recording may start with `free_bytes` available
+pub fn preflight(free_bytes: u64, bytes_per_sec: u64) -> Result<(), String> {
+    let remaining = remaining_secs(free_bytes, bytes_per_sec);
+    if remaining < PREFLIGHT_SECS {
+        return Err(format!(
+            "Not enough disk space: {} MB free holds about {} s of recording",
+            free_bytes / (1024 * 1024),
+            remaining
+        ));
+    }
+    Ok(())
+}
+
+/// Classify the free space left during a recording
+pub fn assess(free_bytes: u64, bytes_per_sec: u64) -> DiskStatus {
+    let remaining = remaining_secs(free_bytes, bytes_per_sec);
+    if remaining < STOP_SECS {
+        DiskStatus::Critical
+    } else if remaining < WARNING_SECS {
+        DiskStatus::Low {
+            remaining_secs: remaining,
+        }
+    } else {
+        DiskStatus::Ok
+    }
+}
+
+/// Directory the recording's files are written to
+pub fn output_dir(config: &CaptureConfig) -> PathBuf {
+    let path = match &config.segments {
+        Some(segments) => PathBuf::from(segments.template_for(&config.output_path)),
+        None => PathBuf::from(&config.output_path),
+    };
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Bytes available to unprivileged users on the filesystem holding `path`
+#[cfg(target_os = "linux")]
+pub fn free_space_bytes(path: &Path) -> std::io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is NUL-terminated and stat is a valid out-pointer
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Free space is not queried on other platforms
+#[cfg(not(target_os = "linux"))]
+pub fn free_space_bytes(_path: &Path) -> std::io::Result<u64> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Free space query not implemented for this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AudioConfig, SegmentConfig};
+
+    const MB: u64 = 1024 * 1024;
+
+    fn video_config() -> CaptureConfig {
+        CaptureConfig {
+            output_path: "/videos/rec.mp4".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_estimate_scales_with_resolution_and_fps() {
+        let config = video_config();
+        let full_hd = estimate_bytes_per_sec(&config, 1920, 1080);
+        assert_eq!(full_hd, 777_600);
+        assert_eq!(estimate_bytes_per_sec(&config, 3840, 2160), full_hd * 4);
+
+        let half_rate = CaptureConfig { fps: 15, ..config };
+        assert_eq!(estimate_bytes_per_sec(&half_rate, 1920, 1080), full_hd / 2);
+    }
+
+    #[test]
+    fn test_estimate_for_selection_defaults_missing_dimensions() {
+        let config = video_config();
+        let sized = SelectionResult {
+            node_id: 1,
+            stream_fd: None,
+            width: Some(3840),
+            height: Some(2160),
+        };
+        let unsized_selection = SelectionResult {
+            width: None,
+            height: None,
+            ..sized.clone()
+        };
+
+        assert_eq!(
+            estimate_for_selection(&config, Some(&sized)),
+            estimate_bytes_per_sec(&config, 3840, 2160)
+        );
+        assert_eq!(
+            estimate_for_selection(&config, Some(&unsized_selection)),
+            estimate_bytes_per_sec(&config, 1920, 1080)
+        );
+        assert_eq!(estimate_for_selection(&config, None), 0);
+    }
+
+    #[test]
+    fn test_estimate_audio_only() {
+        let mut config = CaptureConfig {
+            mode: CaptureMode::AudioOnly,
+            container: ContainerFormat::Ogg,
+            audio: AudioConfig {
+                mic: true,
+                system: false,
+            },
+            ..Default::default()
+        };
+        // Video dimensions are ignored for audio-only recordings
+        assert_eq!(estimate_bytes_per_sec(&config, 1920, 1080), 24_000);
+
+        config.container = ContainerFormat::Wav;
+        assert_eq!(estimate_bytes_per_sec(&config, 0, 0), 192_000);
+    }
+
+    #[test]
+    fn test_preflight_requires_a_minute_beyond_reserve() {
+        let rate = MB;
+        assert!(preflight(RESERVE_BYTES + 60 * MB, rate).is_ok());
+        let err = preflight(RESERVE_BYTES + 59 * MB, rate).unwrap_err();
+        assert!(err.contains("59 s"), "{}", err);
+        assert!(preflight(0, rate).is_err());
+    }
+
+    #[test]
+    fn test_assess_thresholds() {
+        let rate = MB;
+        assert_eq!(assess(RESERVE_BYTES + 600 * MB, rate), DiskStatus::Ok);
+        assert_eq!(
+            assess(RESERVE_BYTES + 120 * MB, rate),
+            DiskStatus::Low {
+                remaining_secs: 120
+            }
+        );
+        assert_eq!(assess(RESERVE_BYTES + 5 * MB, rate), DiskStatus::Critical);
+        assert_eq!(assess(RESERVE_BYTES / 2, rate), DiskStatus::Critical);
+    }
+
+    #[test]
+    fn test_remaining_secs_with_zero_rate() {
+        assert_eq!(remaining_secs(RESERVE_BYTES + 10, 0), 10);
+    }
+
+    #[test]
+    fn test_output_dir() {
+        assert_eq!(output_dir(&video_config()), PathBuf::from("/videos"));
+
+        let relative = CaptureConfig {
+            output_path: "rec.mp4".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(output_dir(&relative), PathBuf::from("."));
+
+        let segmented = CaptureConfig {
+            segments: Some(SegmentConfig {
+                max_duration_secs: Some(60),
+                filename_template: Some("/archive/part-{index}.mp4".to_string()),
+                ..Default::default()
+            }),
+            ..video_config()
+        };
+        assert_eq!(output_dir(&segmented), PathBuf::from("/archive"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_free_space_of_temp_dir() {
+        assert!(free_space_bytes(&std::env::temp_dir()).unwrap() > 0);
+        assert!(free_space_bytes(Path::new("/does/not/exist")).is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::capture::SelectionResult;
+use crate::capture::{AvDrift, RecordingResult, RecordingSegment, SelectionResult};
+use crate::config::ScreenshotFormat;
+use crate::recovery::RecoveredRecording;
+use crate::schedule::ScheduledCapture;
 use crate::state::{CaptureError, CaptureState};
 use serde::{Deserialize, Serialize};
 
@@ -47,6 +50,8 @@ pub struct ScreenshotCompleteEvent {
     pub path: String,
     pub width: u32,
     pub height: u32,
+    pub format: ScreenshotFormat,
+    pub byte_size: u64,
 }
 
 /// Event emitted when recording starts
@@ -55,6 +60,20 @@ pub struct RecordingStartedEvent {
     pub output_path: String,
 }
 
+/// Why a recording ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// Stopped from the UI
+    User,
+    /// `max_duration_secs` or `max_file_size_bytes` was reached
+    Limit,
+    /// Stopped because recording could not continue (e.g. disk almost full)
+    Error,
+    /// The portal session was closed from outside the app
+    SessionClosed,
+}
+
 /// Event emitted when recording stops and finalization completes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingStoppedEvent {
@@ -62,6 +81,147 @@ pub struct RecordingStoppedEvent {
     pub duration_ms: u64,
     pub width: u32,
     pub height: u32,
+    pub reason: StopReason,
+}
+
+/// Event emitted each time a segmented recording closes a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentClosedEvent {
+    pub segment: RecordingSegment,
+}
+
+/// Event emitted about once a second while recording audio and video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvDriftEvent {
+    pub drift: AvDrift,
+}
+
+/// Event emitted once per recording when free disk space runs low
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSpaceLowEvent {
+    pub output_path: String,
+    pub free_bytes: u64,
+    /// Estimated recording time left before the recording is stopped
+    pub remaining_secs: u64,
+}
+
+/// Event emitted when the replay buffer has been written to a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySavedEvent {
+    pub path: String,
+    pub duration_ms: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Event emitted once per second while a delayed capture counts down
+///
+/// The last tick has `remaining_secs` 0, right before the capture happens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountdownEvent {
+    pub remaining_secs: u32,
+    pub total_secs: u32,
+}
+
+/// Event emitted when the scheduler starts a scheduled capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledCaptureStartedEvent {
+    pub capture: ScheduledCapture,
+}
+
+/// Event emitted after each timelapse frame is written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelapseFrameEvent {
+    /// 1-based frame number
+    pub index: u32,
+    pub frame_count: u32,
+    pub path: String,
+}
+
+/// Event emitted when a timelapse ends and its frames are finalized
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelapseCompleteEvent {
+    pub frames_dir: String,
+    /// Frames actually captured
+    pub frames: u32,
+    /// The encoded video, when `encode_video` was set and encoding succeeded
+    pub video: Option<RecordingResult>,
+    pub reason: StopReason,
+}
+
+/// Why a capture session opened with `open_capture_session` ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionCloseReason {
+    /// Closed with `close_capture_session`
+    User,
+    /// The portal ended the session (e.g. sharing stopped from the desktop)
+    Portal,
+}
+
+/// Event emitted when an open capture session ends
+///
+/// A capture that was using the session has already been stopped; a
+/// recording reports `StopReason::SessionClosed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionClosedEvent {
+    pub reason: SessionCloseReason,
+}
+
+/// Event emitted at startup after recordings interrupted by a crash were recovered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingsRecoveredEvent {
+    pub recordings: Vec<RecoveredRecording>,
+}
+
+/// An event published by `service::CaptureService`
+///
+/// Serializes as the wrapped payload, so a sink can forward it under
+/// `name()` as-is.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum CaptureEvent {
+    StateChanged(StateChangedEvent),
+    Error(ErrorEvent),
+    SelectionComplete(SelectionCompleteEvent),
+    RecordingStarted(RecordingStartedEvent),
+    RecordingStopped(RecordingStoppedEvent),
+    ScreenshotComplete(ScreenshotCompleteEvent),
+    Countdown(CountdownEvent),
+    ReplaySaved(ReplaySavedEvent),
+    TimelapseFrame(TimelapseFrameEvent),
+    TimelapseComplete(TimelapseCompleteEvent),
+    SessionClosed(SessionClosedEvent),
+    SegmentClosed(SegmentClosedEvent),
+    AvDrift(AvDriftEvent),
+    DiskSpaceLow(DiskSpaceLowEvent),
+    /// Boxed: a whole `CaptureConfig` dwarfs the other payloads
+    ScheduledCaptureStarted(Box<ScheduledCaptureStartedEvent>),
+    RecordingsRecovered(RecordingsRecoveredEvent),
+}
+
+impl CaptureEvent {
+    /// The `event_names` constant this event is emitted under
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StateChanged(_) => event_names::STATE_CHANGED,
+            Self::Error(_) => event_names::ERROR,
+            Self::SelectionComplete(_) => event_names::SELECTION_COMPLETE,
+            Self::RecordingStarted(_) => event_names::RECORDING_STARTED,
+            Self::RecordingStopped(_) => event_names::RECORDING_STOPPED,
+            Self::ScreenshotComplete(_) => event_names::SCREENSHOT_COMPLETE,
+            Self::Countdown(_) => event_names::COUNTDOWN,
+            Self::ReplaySaved(_) => event_names::REPLAY_SAVED,
+            Self::TimelapseFrame(_) => event_names::TIMELAPSE_FRAME,
+            Self::TimelapseComplete(_) => event_names::TIMELAPSE_COMPLETE,
+            Self::SessionClosed(_) => event_names::SESSION_CLOSED,
+            Self::SegmentClosed(_) => event_names::SEGMENT_CLOSED,
+            Self::AvDrift(_) => event_names::AV_DRIFT,
+            Self::DiskSpaceLow(_) => event_names::DISK_SPACE_LOW,
+            Self::ScheduledCaptureStarted(_) => event_names::SCHEDULED_CAPTURE_STARTED,
+            Self::RecordingsRecovered(_) => event_names::RECORDINGS_RECOVERED,
+        }
+    }
 }
 
 /// Event names for Tauri event system
@@ -74,4 +234,14 @@ pub mod event_names {
     pub const SCREENSHOT_COMPLETE: &str = "capture:screenshot_complete";
     pub const RECORDING_STARTED: &str = "capture:recording_started";
     pub const RECORDING_STOPPED: &str = "capture:recording_stopped";
+    pub const RECORDINGS_RECOVERED: &str = "capture:recordings_recovered";
+    pub const SEGMENT_CLOSED: &str = "capture:segment_closed";
+    pub const AV_DRIFT: &str = "capture:av_drift";
+    pub const REPLAY_SAVED: &str = "capture:replay_saved";
+    pub const DISK_SPACE_LOW: &str = "capture:disk_space_low";
+    pub const COUNTDOWN: &str = "capture:countdown";
+    pub const SCHEDULED_CAPTURE_STARTED: &str = "capture:scheduled_capture_started";
+    pub const TIMELAPSE_FRAME: &str = "capture:timelapse_frame";
+    pub const TIMELAPSE_COMPLETE: &str = "capture:timelapse_complete";
+    pub const SESSION_CLOSED: &str = "capture:session_closed";
 }
@@ -0,0 +1,19 @@
+use tauri::{AppHandle, Runtime};
+
+use crate::config::CaptureConfig;
+use crate::ipc::settings::capture_config;
+use crate::state::CaptureState;
+use crate::AppState;
+
+/// Start an audio-only recording (see `CaptureService::start_audio_recording`)
+///
+/// Without `config`, records with the default profile.
+#[tauri::command]
+pub(crate) async fn start_audio_recording<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: Option<CaptureConfig>,
+) -> Result<CaptureState, String> {
+    let config = capture_config(&state, config)?;
+    state.service.start_audio_recording(&app, config).await
+}
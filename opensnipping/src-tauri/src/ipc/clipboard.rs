@@ -0,0 +1,116 @@
+use tauri::{AppHandle, Runtime};
+
+use crate::AppState;
+
+/// Copy the last screenshot to the clipboard (see
+/// `CaptureService::copy_screenshot`)
+///
+/// With `with_file_uri`, the saved file is offered as a `text/uri-list`
+/// too, so file managers paste the file itself.
+#[tauri::command]
+pub(crate) async fn copy_screenshot<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    with_file_uri: Option<bool>,
+) -> Result<(), String> {
+    state
+        .service
+        .copy_screenshot(&app, with_file_uri.unwrap_or(false))
+        .await
+}
+
+/// Copy screenshots to the desktop clipboard instead of the headless one
+#[cfg(target_os = "linux")]
+pub(crate) fn install_clipboard<R: Runtime>(app: &AppHandle<R>) {
+    use tauri::Manager;
+
+    let clipboard = std::sync::Arc::new(gtk_clipboard::GtkClipboard::new(app.clone()));
+    app.state::<AppState>().service.set_clipboard(clipboard);
+}
+
+/// The GTK clipboard Tauri's windows already use
+///
+/// GDK talks to the X11 selection or the Wayland data device, so one
+/// implementation covers both. GTK is only touched on the main thread.
+#[cfg(target_os = "linux")]
+mod gtk_clipboard {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use gtk::{gdk, TargetEntry, TargetFlags};
+    use tauri::{AppHandle, Runtime};
+
+    use crate::capture::CaptureBackendError;
+    use crate::screenshot::{Clipboard, ClipboardContent, PNG_MIME, URI_LIST_MIME};
+
+    /// How long to wait for the main thread to take the content
+    const MAIN_THREAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// `info` values telling `set_with_data` which target is asked for
+    const PNG_TARGET: u32 = 0;
+    const URI_LIST_TARGET: u32 = 1;
+
+    pub(super) struct GtkClipboard<R: Runtime> {
+        app: AppHandle<R>,
+    }
+
+    impl<R: Runtime> GtkClipboard<R> {
+        pub(super) fn new(app: AppHandle<R>) -> Self {
+            Self { app }
+        }
+    }
+
+    impl<R: Runtime> Clipboard for GtkClipboard<R> {
+        /// Blocks until the main thread owns the clipboard; call it off the
+        /// main thread
+        fn set_content(&self, content: ClipboardContent) -> Result<(), CaptureBackendError> {
+            let (tx, rx) = mpsc::channel();
+            self.app
+                .run_on_main_thread(move || {
+                    let _ = tx.send(offer(content));
+                })
+                .map_err(|e| {
+                    CaptureBackendError::Internal(format!("Failed to reach the main thread: {}", e))
+                })?;
+            rx.recv_timeout(MAIN_THREAD_TIMEOUT).unwrap_or_else(|_| {
+                Err(CaptureBackendError::Internal(
+                    "Timed out waiting for the clipboard".to_string(),
+                ))
+            })
+        }
+    }
+
+    /// Take ownership of the clipboard, serving `content` on request
+    fn offer(content: ClipboardContent) -> Result<(), CaptureBackendError> {
+        let mut targets = vec![TargetEntry::new(
+            PNG_MIME,
+            TargetFlags::OTHER_APP,
+            PNG_TARGET,
+        )];
+        if content.uri_list.is_some() {
+            targets.push(TargetEntry::new(
+                URI_LIST_MIME,
+                TargetFlags::OTHER_APP,
+                URI_LIST_TARGET,
+            ));
+        }
+
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        let owned = clipboard.set_with_data(&targets, move |_, selection, info| match info {
+            URI_LIST_TARGET => {
+                if let Some(uri_list) = &content.uri_list {
+                    selection.set(&selection.target(), 8, uri_list.as_bytes());
+                }
+            }
+            _ => selection.set(&selection.target(), 8, &content.png),
+        });
+        if !owned {
+            return Err(CaptureBackendError::Internal(
+                "Failed to take the clipboard".to_string(),
+            ));
+        }
+        // Let a clipboard manager keep the image after the app quits
+        clipboard.set_can_store(&targets);
+        Ok(())
+    }
+}
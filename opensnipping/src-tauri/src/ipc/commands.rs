@@ -1,16 +1,13 @@
-use tauri::{AppHandle, Emitter};
-use tracing::info;
+use tauri::{AppHandle, Runtime};
 
-use crate::capture::{self, CaptureBackend, RecordingResult, ScreenshotResult};
-use crate::config::CaptureConfig;
-use crate::events::{
-    event_names, RecordingStartedEvent, RecordingStoppedEvent, ScreenshotCompleteEvent,
-    SelectionCompleteEvent,
-};
-use crate::ipc::emit::{emit_error, emit_state_change};
-use crate::ipc::errors::backend_error_to_capture_error;
-use crate::state::{CaptureError, CaptureState, ErrorCode};
-use crate::{generate_screenshot_temp_path, AppState};
+use crate::capture::ScreenshotResult;
+use crate::config::{CaptureConfig, ScreenshotConfig, ScreenshotFormat};
+use crate::events::StopReason;
+use crate::ipc::settings::capture_config;
+use crate::screenshot::{available_formats, Annotations, Redaction};
+use crate::service::SessionInfo;
+use crate::state::CaptureState;
+use crate::AppState;
 
 #[tauri::command]
 pub(crate) fn ping() -> String {
@@ -19,525 +16,152 @@ pub(crate) fn ping() -> String {
 
 #[tauri::command]
 pub(crate) fn get_state(state: tauri::State<AppState>) -> CaptureState {
-    state.state_machine.lock().unwrap().state()
+    state.service.state()
 }
 
+/// Current state with its session payload and recent transitions
+///
+/// Lets the UI rebuild itself after a window reload.
 #[tauri::command]
-pub(crate) async fn start_capture(
-    app: AppHandle,
-    state: tauri::State<'_, AppState>,
-    config: CaptureConfig,
-) -> Result<CaptureState, String> {
-    // Validate config
-    if let Err(err) = config.validate() {
-        let mut sm = state.state_machine.lock().unwrap();
-        let error = CaptureError {
-            code: ErrorCode::InvalidConfig,
-            message: format!("{}: {}", err.field, err.message),
-        };
-        sm.set_error(error.clone());
-        emit_error(&app, &error);
-        return Err(error.message);
-    }
-
-    // Store config
-    let stored_config = config.clone();
-    *state.config.lock().unwrap() = Some(stored_config);
-
-    // Transition to Selecting
-    let _selecting_state = {
-        let mut sm = state.state_machine.lock().unwrap();
-        let previous = sm.state();
-        match sm.start_selecting() {
-            Ok(new_state) => {
-                emit_state_change(&app, previous, new_state);
-                new_state
-            }
-            Err(e) => return Err(e.message),
-        }
-    };
-
-    info!("Starting portal selection...");
-
-    // Now call the portal (this shows the picker dialog)
-    // Use the concrete Linux backend so we can store it with the session
-    #[cfg(target_os = "linux")]
-    let backend = capture::linux::LinuxCaptureBackend::new();
-    #[cfg(not(target_os = "linux"))]
-    let backend = capture::get_backend();
-    
-    let selection_result = backend.request_selection(&config).await;
-
-    match selection_result {
-        Ok(selection) => {
-            info!("Portal selection successful: node_id={}", selection.node_id);
-
-            // Store selection result
-            *state.selection.lock().unwrap() = Some(selection.clone());
-
-            // IMPORTANT: Start recording immediately while PipeWire stream is valid
-            // Don't wait for a separate start_recording_video call
-            #[cfg(target_os = "linux")]
-            let recording_success = {
-                eprintln!("[DEBUG] start_capture: Starting recording immediately after selection...");
-                match backend.start_recording(&selection, &config).await {
-                    Ok(()) => {
-                        info!("Recording started immediately: {}", config.output_path);
-                        
-                        // Emit recording started event
-                        let _ = app.emit(
-                            event_names::RECORDING_STARTED,
-                            RecordingStartedEvent {
-                                output_path: config.output_path.clone(),
-                            },
-                        );
-
-                        // Store backend for pause/resume/stop operations
-                        let mut backend_lock = state.backend.lock().await;
-                        *backend_lock = Some(backend);
-                        
-                        true
-                    }
-                    Err(e) => {
-                        eprintln!("[DEBUG] start_capture: Failed to start recording: {:?}", e);
-                        let error = backend_error_to_capture_error(&e);
-                        emit_error(&app, &error);
-                        false
-                    }
-                }
-            };
-
-            #[cfg(not(target_os = "linux"))]
-            let recording_success = true; // Non-Linux doesn't start recording here
-
-            // Emit selection complete event
-            let _ = app.emit(
-                event_names::SELECTION_COMPLETE,
-                SelectionCompleteEvent { selection },
-            );
-
-            // Only transition to Recording if recording actually started
-            if recording_success {
-                let mut sm = state.state_machine.lock().unwrap();
-                let previous = sm.state();
-                match sm.begin_recording() {
-                    Ok(new_state) => {
-                        emit_state_change(&app, previous, new_state);
-                        Ok(new_state)
-                    }
-                    Err(e) => Err(e.message),
-                }
-            } else {
-                // Recording failed - transition to Error state
-                let mut sm = state.state_machine.lock().unwrap();
-                let error = CaptureError {
-                    code: ErrorCode::PipelineError,
-                    message: "Failed to start recording pipeline".to_string(),
-                };
-                sm.set_error(error.clone());
-                emit_error(&app, &error);
-                Err(error.message)
-            }
-        }
-        Err(backend_err) => {
-            info!("Portal selection failed: {:?}", backend_err);
-
-            let error = backend_error_to_capture_error(&backend_err);
-
-            // Transition to Error
-            let mut sm = state.state_machine.lock().unwrap();
-            sm.set_error(error.clone());
-            emit_error(&app, &error);
-
-            // Clear config since we failed
-            *state.config.lock().unwrap() = None;
-
-            Err(error.message)
-        }
-    }
+pub(crate) fn get_session_info(state: tauri::State<AppState>) -> SessionInfo {
+    state.service.session_info()
 }
 
+/// Pick a source and start recording it (see `CaptureService::start_capture`)
+///
+/// Without `config`, records with the default profile.
 #[tauri::command]
-pub(crate) fn cancel_capture(
-    app: AppHandle,
-    state: tauri::State<AppState>,
+pub(crate) async fn start_capture<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: Option<CaptureConfig>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.cancel_selection() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            *state.config.lock().unwrap() = None;
-            *state.selection.lock().unwrap() = None;
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
+    let config = capture_config(&state, config)?;
+    state.service.start_capture(&app, config).await
 }
 
 #[tauri::command]
-pub(crate) fn begin_recording(
-    app: AppHandle,
+pub(crate) fn cancel_capture<R: Runtime>(
+    app: AppHandle<R>,
     state: tauri::State<AppState>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.begin_recording() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
+    state.service.cancel_capture(&app)
 }
 
+/// Start recording the selection `start_capture` left in Selecting
+///
+/// The state only moves to Recording once the pipeline is running.
 #[tauri::command]
-pub(crate) fn pause_recording(
-    app: AppHandle,
-    state: tauri::State<AppState>,
+pub(crate) async fn begin_recording<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.pause() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
+    state.service.start_recording(&app).await
 }
 
+/// Pause the pipeline, then move to Paused; stays Recording if it fails
 #[tauri::command]
-pub(crate) fn resume_recording(
-    app: AppHandle,
-    state: tauri::State<AppState>,
+pub(crate) async fn pause_recording<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.resume() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
-}
-
-/// Pause the GStreamer recording pipeline
-#[tauri::command]
-#[cfg(target_os = "linux")]
-pub(crate) async fn pause_recording_video(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    info!("Pausing video recording...");
-
-    let backend_lock = state.backend.lock().await;
-    let backend = backend_lock
-        .as_ref()
-        .ok_or_else(|| "No recording in progress".to_string())?;
-
-    backend
-        .pause_recording()
-        .await
-        .map_err(|e| format!("Failed to pause recording: {}", e))
+    state.service.pause_recording(&app).await
 }
 
-/// Resume the GStreamer recording pipeline
+/// Resume the pipeline, then move to Recording; stays Paused if it fails
 #[tauri::command]
-#[cfg(target_os = "linux")]
-pub(crate) async fn resume_recording_video(
+pub(crate) async fn resume_recording<R: Runtime>(
+    app: AppHandle<R>,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    info!("Resuming video recording...");
-
-    let backend_lock = state.backend.lock().await;
-    let backend = backend_lock
-        .as_ref()
-        .ok_or_else(|| "No recording in progress".to_string())?;
-
-    backend
-        .resume_recording()
-        .await
-        .map_err(|e| format!("Failed to resume recording: {}", e))
-}
-
-/// Stub for non-Linux platforms
-#[tauri::command]
-#[cfg(not(target_os = "linux"))]
-pub(crate) async fn pause_recording_video(
-    _state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    Err("Recording not implemented for this platform".to_string())
-}
-
-/// Stub for non-Linux platforms
-#[tauri::command]
-#[cfg(not(target_os = "linux"))]
-pub(crate) async fn resume_recording_video(
-    _state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    Err("Recording not implemented for this platform".to_string())
+) -> Result<CaptureState, String> {
+    state.service.resume_recording(&app).await
 }
 
+/// Stop and finalize the recording, walking Finalizing → Idle
+///
+/// If the pipeline fails to stop, the state stays Recording or Paused.
 #[tauri::command]
-pub(crate) fn stop_recording(
-    app: AppHandle,
-    state: tauri::State<AppState>,
+pub(crate) async fn stop_recording<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.stop() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
+    state.service.stop_recording(&app, StopReason::User).await?;
+    Ok(state.service.state())
 }
 
 #[tauri::command]
-pub(crate) fn finalize_complete(
-    app: AppHandle,
+pub(crate) fn finalize_complete<R: Runtime>(
+    app: AppHandle<R>,
     state: tauri::State<AppState>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.finalize_complete() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            *state.config.lock().unwrap() = None;
-            *state.selection.lock().unwrap() = None;
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
+    state.service.finalize_complete(&app)
 }
 
 #[tauri::command]
-pub(crate) fn reset_error(
-    app: AppHandle,
+pub(crate) fn reset_error<R: Runtime>(
+    app: AppHandle<R>,
     state: tauri::State<AppState>,
 ) -> Result<CaptureState, String> {
-    let mut sm = state.state_machine.lock().unwrap();
-    let previous = sm.state();
-    match sm.reset() {
-        Ok(new_state) => {
-            emit_state_change(&app, previous, new_state);
-            Ok(new_state)
-        }
-        Err(e) => Err(e.message),
-    }
+    state.service.reset_error(&app)
 }
 
-/// Take a screenshot: request portal selection, capture frame, emit event
+/// Take a screenshot (see `CaptureService::take_screenshot`)
+///
+/// With `delay_secs`, counts down (`capture:countdown`) between the picker
+/// and the frame grab. Without `config`, uses the default profile.
 #[tauri::command]
-pub(crate) async fn take_screenshot(
-    app: AppHandle,
-    _state: tauri::State<'_, AppState>,
-    config: CaptureConfig,
+pub(crate) async fn take_screenshot<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: Option<CaptureConfig>,
+    delay_secs: Option<u32>,
 ) -> Result<ScreenshotResult, String> {
-    // Validate config
-    if let Err(err) = config.validate() {
-        let error = CaptureError {
-            code: ErrorCode::InvalidConfig,
-            message: format!("{}: {}", err.field, err.message),
-        };
-        emit_error(&app, &error);
-        return Err(error.message);
-    }
-
-    info!("Starting screenshot portal selection...");
-
-    // Request selection via portal
-    let backend = capture::get_backend();
-    let selection_result = backend.request_selection(&config).await;
-
-    let selection = match selection_result {
-        Ok(sel) => {
-            info!("Screenshot selection successful: node_id={}", sel.node_id);
-            sel
-        }
-        Err(backend_err) => {
-            info!("Screenshot selection failed: {:?}", backend_err);
-            let error = backend_error_to_capture_error(&backend_err);
-            emit_error(&app, &error);
-            return Err(error.message);
-        }
-    };
-
-    // Generate unique output path
-    let output_path = generate_screenshot_temp_path();
-
-    info!("Capturing screenshot to {:?}...", output_path);
-
-    // Capture the screenshot
-    let screenshot_result = backend.capture_screenshot(&selection, &output_path).await;
-
-    match screenshot_result {
-        Ok(screenshot) => {
-            info!(
-                "Screenshot captured: {}x{} at {}",
-                screenshot.width, screenshot.height, screenshot.path
-            );
-
-            // Emit screenshot complete event
-            let _ = app.emit(
-                event_names::SCREENSHOT_COMPLETE,
-                ScreenshotCompleteEvent {
-                    path: screenshot.path.clone(),
-                    width: screenshot.width,
-                    height: screenshot.height,
-                },
-            );
-
-            Ok(screenshot)
-        }
-        Err(backend_err) => {
-            info!("Screenshot capture failed: {:?}", backend_err);
-            let error = backend_error_to_capture_error(&backend_err);
-            emit_error(&app, &error);
-            Err(error.message)
-        }
-    }
+    let config = capture_config(&state, config)?;
+    state
+        .service
+        .take_screenshot(&app, config, delay_secs)
+        .await
 }
 
-/// Start video recording with the current selection
+/// Draw `annotations` onto the last screenshot and save the result (see
+/// `CaptureService::export_annotated_screenshot`)
+///
+/// Without `screenshot`, the export is a PNG.
 #[tauri::command]
-#[cfg(target_os = "linux")]
-pub(crate) async fn start_recording_video(
-    app: AppHandle,
+pub(crate) async fn export_annotated_screenshot<R: Runtime>(
+    app: AppHandle<R>,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    eprintln!("[DEBUG] start_recording_video: Starting video recording...");
-
-    // Get stored config and selection
-    let config = state
-        .config
-        .lock()
-        .unwrap()
-        .clone()
-        .ok_or_else(|| "No capture config set. Call start_capture first.".to_string())?;
-    eprintln!("[DEBUG] start_recording_video: Got config, output_path={}", config.output_path);
-
-    let selection = state
-        .selection
-        .lock()
-        .unwrap()
-        .clone()
-        .ok_or_else(|| "No selection available. Call start_capture first.".to_string())?;
-    eprintln!("[DEBUG] start_recording_video: Got selection, node_id={}", selection.node_id);
-
-    // Get the stored backend (which holds the portal session)
-    eprintln!("[DEBUG] start_recording_video: Acquiring backend lock...");
-    let backend_lock = state.backend.lock().await;
-    eprintln!("[DEBUG] start_recording_video: Backend lock acquired, checking if backend exists...");
-    let backend = backend_lock
-        .as_ref()
-        .ok_or_else(|| "No backend available. Call start_capture first.".to_string())?;
-    eprintln!("[DEBUG] start_recording_video: Backend found, calling start_recording...");
-
-    // Start recording using the stored backend
-    match backend.start_recording(&selection, &config).await {
-        Ok(()) => {
-            eprintln!("[DEBUG] start_recording_video: Recording started successfully: {}", config.output_path);
-
-            // Emit recording started event
-            let _ = app.emit(
-                event_names::RECORDING_STARTED,
-                RecordingStartedEvent {
-                    output_path: config.output_path.clone(),
-                },
-            );
-
-            Ok(())
-        }
-        Err(backend_err) => {
-            eprintln!("[DEBUG] start_recording_video: Recording start failed: {:?}", backend_err);
-            let error = backend_error_to_capture_error(&backend_err);
-            emit_error(&app, &error);
-            Err(error.message)
-        }
-    }
+    annotations: Annotations,
+    screenshot: Option<ScreenshotConfig>,
+) -> Result<ScreenshotResult, String> {
+    state
+        .service
+        .export_annotated_screenshot(&app, annotations, screenshot.unwrap_or_default())
+        .await
 }
 
-/// Stop video recording and finalize the output file
+/// Blur, pixelate or fill areas of the last screenshot and save the result
+/// (see `CaptureService::redact_screenshot`)
+///
+/// Without `screenshot`, the redacted copy is a PNG. It replaces the last
+/// screenshot for later copies and exports.
 #[tauri::command]
-#[cfg(target_os = "linux")]
-pub(crate) async fn stop_recording_video(
-    app: AppHandle,
+pub(crate) async fn redact_screenshot<R: Runtime>(
+    app: AppHandle<R>,
     state: tauri::State<'_, AppState>,
-) -> Result<RecordingResult, String> {
-    info!("Stopping video recording...");
-
-    // Take backend from storage
-    let backend = {
-        let mut backend_lock = state.backend.lock().await;
-        backend_lock
-            .take()
-            .ok_or_else(|| "No recording in progress".to_string())?
-    };
-
-    // Stop recording
-    match backend.stop_recording().await {
-        Ok(result) => {
-            info!(
-                "Recording stopped: {} ({} ms)",
-                result.path, result.duration_ms
-            );
-
-            // Transition state to Finalizing then Idle
-            {
-                let mut sm = state.state_machine.lock().unwrap();
-                let previous = sm.state();
-                if let Ok(finalizing) = sm.stop() {
-                    emit_state_change(&app, previous, finalizing);
-                    let previous_finalizing = finalizing;
-                    if let Ok(idle) = sm.finalize_complete() {
-                        emit_state_change(&app, previous_finalizing, idle);
-                    }
-                }
-            }
-
-            // Clear config and selection
-            *state.config.lock().unwrap() = None;
-            *state.selection.lock().unwrap() = None;
-
-            // Emit recording stopped event
-            let _ = app.emit(
-                event_names::RECORDING_STOPPED,
-                RecordingStoppedEvent {
-                    path: result.path.clone(),
-                    duration_ms: result.duration_ms,
-                    width: result.width,
-                    height: result.height,
-                },
-            );
-
-            Ok(result)
-        }
-        Err(backend_err) => {
-            info!("Recording stop failed: {:?}", backend_err);
-            let error = backend_error_to_capture_error(&backend_err);
-            emit_error(&app, &error);
-            Err(error.message)
-        }
-    }
-}
-
-/// Stub for non-Linux platforms
-#[tauri::command]
-#[cfg(not(target_os = "linux"))]
-pub(crate) async fn start_recording_video(
-    _app: AppHandle,
-    _state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    Err("Recording not implemented for this platform".to_string())
+    redactions: Vec<Redaction>,
+    screenshot: Option<ScreenshotConfig>,
+) -> Result<ScreenshotResult, String> {
+    state
+        .service
+        .redact_screenshot(&app, redactions, screenshot.unwrap_or_default())
+        .await
 }
 
-/// Stub for non-Linux platforms
+/// Screenshot formats this system can encode
+///
+/// Lossy WebP is only listed when GStreamer's `webpenc` is installed.
 #[tauri::command]
-#[cfg(not(target_os = "linux"))]
-pub(crate) async fn stop_recording_video(
-    _app: AppHandle,
-    _state: tauri::State<'_, AppState>,
-) -> Result<RecordingResult, String> {
-    Err("Recording not implemented for this platform".to_string())
+pub(crate) fn get_screenshot_formats() -> Vec<ScreenshotFormat> {
+    available_formats()
 }
@@ -1,23 +1,11 @@
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Runtime};
 
-use crate::events::{event_names, ErrorEvent, StateChangedEvent};
-use crate::state::{CaptureError, CaptureState};
+use crate::events::CaptureEvent;
+use crate::service::EventSink;
 
-pub(crate) fn emit_state_change(app: &AppHandle, previous: CaptureState, current: CaptureState) {
-    let _ = app.emit(
-        event_names::STATE_CHANGED,
-        StateChangedEvent {
-            state: current,
-            previous,
-        },
-    );
-}
-
-pub(crate) fn emit_error(app: &AppHandle, error: &CaptureError) {
-    let _ = app.emit(
-        event_names::ERROR,
-        ErrorEvent {
-            error: error.clone(),
-        },
-    );
+/// `CaptureService` events go to the webview under their `event_names`
+impl<R: Runtime> EventSink for AppHandle<R> {
+    fn publish(&self, event: CaptureEvent) {
+        let _ = self.emit(event.name(), event);
+    }
 }
@@ -1,3 +1,15 @@
+pub(crate) mod audio;
+pub(crate) mod clipboard;
 pub(crate) mod commands;
 pub(crate) mod emit;
-pub(crate) mod errors;
+pub(crate) mod profiles;
+pub(crate) mod recording;
+pub(crate) mod recovery;
+pub(crate) mod replay;
+pub(crate) mod schedule;
+pub(crate) mod session;
+pub(crate) mod settings;
+pub(crate) mod timelapse;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,125 @@
+use tauri::{AppHandle, Runtime};
+use tracing::info;
+
+use crate::config::CaptureMode;
+use crate::ipc::timelapse::begin_timelapse;
+use crate::settings::{Profile, ProfileExport, Settings};
+use crate::state::CaptureState;
+use crate::AppState;
+
+// Each change below is saved right away and answered with the settings as
+// stored.
+
+/// Every saved profile, in the order they were created
+#[tauri::command]
+pub(crate) fn list_profiles(state: tauri::State<AppState>) -> Result<Vec<Profile>, String> {
+    Ok(state.settings.load()?.profiles)
+}
+
+/// The profile called `name`, ignoring case
+#[tauri::command]
+pub(crate) fn get_profile(state: tauri::State<AppState>, name: String) -> Result<Profile, String> {
+    state
+        .settings
+        .load()?
+        .profile(&name)
+        .cloned()
+        .ok_or_else(|| format!("No profile named {:?}", name))
+}
+
+/// Start a capture with the profile called `name`
+///
+/// Starts whatever the profile's mode records: a video capture (with the
+/// picker), an audio-only recording, a replay buffer or a timelapse. A
+/// profile without encoder preferences uses the default profile's.
+#[tauri::command]
+pub(crate) async fn start_profile_capture<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    name: String,
+) -> Result<CaptureState, String> {
+    let config = state.settings.load()?.profile_config(&name)?;
+    info!("Starting {:?} capture from profile {:?}", config.mode, name);
+    match config.mode {
+        CaptureMode::Video => state.service.start_capture(&app, config).await,
+        CaptureMode::AudioOnly => state.service.start_audio_recording(&app, config).await,
+        CaptureMode::ReplayBuffer => state.service.start_replay_buffer(&app, config).await,
+        CaptureMode::Timelapse => begin_timelapse(&app, &state, config).await,
+    }
+}
+
+/// Add a new named profile
+#[tauri::command]
+pub(crate) fn create_profile(
+    state: tauri::State<AppState>,
+    profile: Profile,
+) -> Result<Settings, String> {
+    let (settings, ()) = state
+        .settings
+        .update(|settings| settings.create_profile(profile))?;
+    Ok(settings)
+}
+
+/// Replace the profile called `name`, possibly renaming it
+#[tauri::command]
+pub(crate) fn update_profile(
+    state: tauri::State<AppState>,
+    name: String,
+    profile: Profile,
+) -> Result<Settings, String> {
+    let (settings, ()) = state
+        .settings
+        .update(|settings| settings.update_profile(&name, profile))?;
+    Ok(settings)
+}
+
+/// Remove a profile other than the default one
+#[tauri::command]
+pub(crate) fn delete_profile(
+    state: tauri::State<AppState>,
+    name: String,
+) -> Result<Settings, String> {
+    let (settings, _) = state
+        .settings
+        .update(|settings| settings.delete_profile(&name))?;
+    Ok(settings)
+}
+
+/// Make `name` the profile new captures start from
+#[tauri::command]
+pub(crate) fn set_default_profile(
+    state: tauri::State<AppState>,
+    name: String,
+) -> Result<Settings, String> {
+    let (settings, ()) = state
+        .settings
+        .update(|settings| settings.set_default_profile(&name))?;
+    Ok(settings)
+}
+
+/// The profiles called `names` (all of them when empty) as JSON to share
+#[tauri::command]
+pub(crate) fn export_profiles(
+    state: tauri::State<AppState>,
+    names: Vec<String>,
+) -> Result<String, String> {
+    state.settings.load()?.export_profiles(&names)?.to_json()
+}
+
+/// Add the profiles of an exported JSON file
+///
+/// Taken names fail the import unless `replace` is set; nothing is
+/// imported when any profile is invalid.
+#[tauri::command]
+pub(crate) fn import_profiles(
+    state: tauri::State<AppState>,
+    json: String,
+    replace: bool,
+) -> Result<Settings, String> {
+    let export = ProfileExport::from_json(&json)?;
+    let (settings, names) = state
+        .settings
+        .update(|settings| settings.import_profiles(export, replace))?;
+    info!("Imported profiles: {}", names.join(", "));
+    Ok(settings)
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::info;
+
+use crate::capture::{AvDrift, RecordingResult, RecordingSegment};
+use crate::events::{AvDriftEvent, CaptureEvent, StopReason};
+use crate::service::{check_delay, run_countdown, EventSink};
+use crate::state::CaptureState;
+use crate::AppState;
+
+/// Run the service's scheduler and recording monitors for the lifetime of
+/// the app
+pub(crate) fn spawn_supervisor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        app.state::<AppState>().service.supervise(&app).await;
+    });
+}
+
+/// Pass the files a segmented recording closes to the service
+pub(crate) fn forward_segment_events<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let app = app.clone();
+    state
+        .service
+        .backend()
+        .on_segment_closed(Arc::new(move |segment: &RecordingSegment| {
+            app.state::<AppState>()
+                .service
+                .segment_closed(&app, segment)
+        }));
+}
+
+/// Emit `capture:av_drift` about once a second while recording audio and video
+pub(crate) fn forward_av_drift<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let app = app.clone();
+    state
+        .service
+        .backend()
+        .on_av_drift(Arc::new(move |drift: &AvDrift| {
+            app.publish(CaptureEvent::AvDrift(AvDriftEvent {
+                drift: drift.clone(),
+            }))
+        }));
+}
+
+/// Pause the recording pipeline (same as `pause_recording`)
+#[tauri::command]
+pub(crate) async fn pause_recording_video<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    info!("Pausing video recording...");
+    state.service.pause_recording(&app).await.map(|_| ())
+}
+
+/// Resume the recording pipeline (same as `resume_recording`)
+#[tauri::command]
+pub(crate) async fn resume_recording_video<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    info!("Resuming video recording...");
+    state.service.resume_recording(&app).await.map(|_| ())
+}
+
+/// Start video recording with the current selection
+///
+/// With `delay_secs`, counts down (`capture:countdown`) before recording.
+/// Outside Selecting the call fails right away, without a countdown.
+#[tauri::command]
+pub(crate) async fn start_recording_video<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    delay_secs: Option<u32>,
+) -> Result<(), String> {
+    let current = state.service.state();
+    if current != CaptureState::Selecting {
+        return Err(format!("Cannot start recording while {:?}", current));
+    }
+    let delay_secs = check_delay(&app, delay_secs)?;
+    run_countdown(&app, delay_secs).await;
+
+    state.service.start_recording(&app).await.map(|_| ())
+}
+
+/// Stop video recording and finalize the output file
+#[tauri::command]
+pub(crate) async fn stop_recording_video<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RecordingResult, String> {
+    info!("Stopping video recording...");
+    state.service.stop_recording(&app, StopReason::User).await
+}
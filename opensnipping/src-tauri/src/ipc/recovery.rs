@@ -0,0 +1,25 @@
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::recovery::RecoveredRecording;
+use crate::AppState;
+
+/// Finalize recordings left behind by a previous run, off the main thread
+pub(crate) fn recover_interrupted_recordings<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<AppState>()
+            .service
+            .recover_interrupted_recordings(&app)
+    });
+}
+
+/// Recordings recovered at startup after a crash
+///
+/// Recovery starts during setup, before the webview listens for
+/// `capture:recordings_recovered`, so the webview asks for the result once
+/// it has subscribed.
+#[tauri::command]
+pub(crate) fn get_recovered_recordings(
+    state: tauri::State<'_, AppState>,
+) -> Vec<RecoveredRecording> {
+    state.service.recovered_recordings()
+}
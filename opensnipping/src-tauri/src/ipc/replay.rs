@@ -0,0 +1,43 @@
+use tauri::{AppHandle, Runtime};
+
+use crate::capture::RecordingResult;
+use crate::config::CaptureConfig;
+use crate::ipc::settings::capture_config;
+use crate::state::CaptureState;
+use crate::AppState;
+
+/// Pick a source and start keeping its last `replay_buffer_secs` in memory
+/// (see `CaptureService::start_replay_buffer`)
+///
+/// Without `config`, buffers with the default profile.
+#[tauri::command]
+pub(crate) async fn start_replay_buffer<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: Option<CaptureConfig>,
+) -> Result<CaptureState, String> {
+    let config = capture_config(&state, config)?;
+    state.service.start_replay_buffer(&app, config).await
+}
+
+/// Write the buffered replay to `output_path`; buffering continues afterwards
+///
+/// Without a path, each save gets a new file named from the buffer's
+/// `naming`.
+#[tauri::command]
+pub(crate) async fn save_replay<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    output_path: Option<String>,
+) -> Result<RecordingResult, String> {
+    state.service.save_replay(&app, output_path).await
+}
+
+/// Stop the replay buffer and discard anything not yet saved (Buffering → Idle)
+#[tauri::command]
+pub(crate) async fn stop_replay_buffer<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<CaptureState, String> {
+    state.service.stop_replay_buffer(&app).await
+}
@@ -0,0 +1,40 @@
+use tauri::{AppHandle, Runtime};
+
+use crate::config::CaptureConfig;
+use crate::ipc::settings::capture_config;
+use crate::schedule::ScheduledCapture;
+use crate::AppState;
+
+/// Record `config` from `start_at_ms` (Unix epoch ms) for `duration_secs`
+///
+/// The service's scheduler runs in the Rust process, so the capture starts
+/// even if the window is hidden (see `CaptureService::schedule_capture`).
+/// Without `config`, the default profile is scheduled as it is now.
+#[tauri::command]
+pub(crate) fn schedule_capture<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<AppState>,
+    config: Option<CaptureConfig>,
+    start_at_ms: u64,
+    duration_secs: u64,
+) -> Result<ScheduledCapture, String> {
+    let config = capture_config(&state, config)?;
+    state
+        .service
+        .schedule_capture(&app, config, start_at_ms, duration_secs)
+}
+
+/// Cancel a scheduled capture that has not started yet
+#[tauri::command]
+pub(crate) fn cancel_scheduled_capture(
+    state: tauri::State<AppState>,
+    id: String,
+) -> Result<(), String> {
+    state.service.cancel_scheduled_capture(&id)
+}
+
+/// Scheduled captures that have not started yet, soonest first
+#[tauri::command]
+pub(crate) fn list_scheduled_captures(state: tauri::State<AppState>) -> Vec<ScheduledCapture> {
+    state.service.scheduled_captures()
+}
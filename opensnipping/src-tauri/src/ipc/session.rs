@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::capture::SelectionResult;
+use crate::config::CaptureConfig;
+use crate::ipc::settings::capture_config;
+use crate::AppState;
+
+/// Pick a source once and keep its portal session for later captures
+///
+/// The state stays Idle. Until `close_capture_session`, or until the portal
+/// closes the session, `take_screenshot`, `start_capture`,
+/// `start_replay_buffer` and `start_timelapse` reuse this selection instead
+/// of showing the picker again. `config` (the default profile's when
+/// omitted) decides the source type and cursor for the whole session.
+#[tauri::command]
+pub(crate) async fn open_capture_session<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: Option<CaptureConfig>,
+) -> Result<SelectionResult, String> {
+    let config = capture_config(&state, config)?;
+    state.service.open_capture_session(&app, config).await
+}
+
+/// Close the session opened with `open_capture_session`
+///
+/// Only allowed while nothing is capturing from it (Idle or Error).
+#[tauri::command]
+pub(crate) async fn close_capture_session<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.service.close_capture_session(&app).await
+}
+
+/// Let the service stop what read the stream when the portal closes the
+/// backend's session from its side
+pub(crate) fn forward_session_closed<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let app = app.clone();
+    state.service.backend().on_session_closed(Arc::new(move || {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            app.state::<AppState>()
+                .service
+                .portal_session_closed(&app)
+                .await
+        });
+    }));
+}
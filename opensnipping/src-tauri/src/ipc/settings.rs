@@ -0,0 +1,44 @@
+use tracing::{info, warn};
+
+use crate::config::CaptureConfig;
+use crate::settings::Settings;
+use crate::AppState;
+
+/// `config` completed from the saved default profile (see
+/// `Settings::capture_config`)
+///
+/// Capture commands take an optional config and resolve it here. Settings
+/// that cannot be loaded leave a given config as it is.
+pub(crate) fn capture_config(
+    state: &AppState,
+    config: Option<CaptureConfig>,
+) -> Result<CaptureConfig, String> {
+    match state.settings.load() {
+        Ok(settings) => Ok(settings.capture_config(config)),
+        Err(e) => {
+            warn!("Capturing without saved defaults: {}", e);
+            config.ok_or(e)
+        }
+    }
+}
+
+/// Saved settings, or the defaults when none are saved yet
+///
+/// Fails when the file cannot be read, is invalid or comes from a newer
+/// build; the UI should then fall back to `Settings` defaults without
+/// saving over the file.
+#[tauri::command]
+pub(crate) fn get_settings(state: tauri::State<AppState>) -> Result<Settings, String> {
+    state.settings.load()
+}
+
+/// Validate and save `settings`, returning them as stored
+#[tauri::command]
+pub(crate) fn save_settings(
+    state: tauri::State<AppState>,
+    settings: Settings,
+) -> Result<Settings, String> {
+    let saved = state.settings.save(&settings)?;
+    info!("Settings saved to {}", state.settings.path().display());
+    Ok(saved)
+}
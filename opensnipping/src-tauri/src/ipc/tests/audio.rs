@@ -0,0 +1,82 @@
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::ipc::audio::start_audio_recording;
+use crate::ipc::recording::stop_recording_video;
+
+fn audio_config() -> CaptureConfig {
+    CaptureConfig {
+        audio: AudioConfig {
+            system: true,
+            mic: true,
+        },
+        container: ContainerFormat::Ogg,
+        output_path: String::new(),
+        mode: CaptureMode::AudioOnly,
+        ..test_config()
+    }
+}
+
+/// Recordings the app has journaled as in progress
+fn journaled(app: &App<MockRuntime>) -> usize {
+    std::fs::read_dir(app.state::<AppState>().service.journal().dir())
+        .map(|entries| entries.count())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_start_audio_recording_journals_until_stopped() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_audio_recording(
+        app.handle().clone(),
+        app.state(),
+        Some(audio_config()),
+    ));
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    assert!(backend.is_audio_only());
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(journaled(&app), 1);
+
+    let stopped = block_on(stop_recording_video(app.handle().clone(), app.state())).unwrap();
+
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(journaled(&app), 0);
+    let _ = std::fs::remove_file(&stopped.path);
+}
+
+#[test]
+fn test_start_audio_recording_rejects_video_mode() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_audio_recording(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert_eq!(journaled(&app), 0);
+}
+
+#[test]
+fn test_start_audio_recording_failure_stays_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend.set_should_succeed(false);
+    let app = mock_app(&backend);
+
+    let result = block_on(start_audio_recording(
+        app.handle().clone(),
+        app.state(),
+        Some(audio_config()),
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(journaled(&app), 0);
+}
@@ -0,0 +1,68 @@
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::ipc::clipboard::copy_screenshot;
+use crate::ipc::commands::take_screenshot;
+use crate::screenshot::{file_uri, HeadlessClipboard};
+
+#[test]
+fn test_copy_screenshot_copies_last_screenshot() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let clipboard = Arc::new(HeadlessClipboard::default());
+    app.state::<AppState>()
+        .service
+        .set_clipboard(clipboard.clone());
+
+    let before = block_on(copy_screenshot(app.handle().clone(), app.state(), None));
+    assert!(before.is_err());
+
+    let screenshot = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ))
+    .unwrap();
+    let result = block_on(copy_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(true),
+    ));
+
+    assert_eq!(result, Ok(()));
+    let content = clipboard.content().unwrap();
+    assert!(content.png.starts_with(b"\x89PNG"));
+    let uri = file_uri(std::path::Path::new(&screenshot.path));
+    assert_eq!(content.uri_list, Some(format!("{}\r\n", uri)));
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[test]
+fn test_copy_screenshot_without_file_uri_copies_png_only() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let clipboard = Arc::new(HeadlessClipboard::default());
+    app.state::<AppState>()
+        .service
+        .set_clipboard(clipboard.clone());
+    let screenshot = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ))
+    .unwrap();
+
+    let result = block_on(copy_screenshot(app.handle().clone(), app.state(), None));
+
+    assert_eq!(result, Ok(()));
+    let content = clipboard.content().unwrap();
+    assert!(content.png.starts_with(b"\x89PNG"));
+    assert_eq!(content.uri_list, None);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
@@ -0,0 +1,375 @@
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::capture::fake::FakeOperation;
+use crate::capture::SelectionResult;
+use crate::config::ScreenshotFormat;
+use crate::ipc::commands::{
+    begin_recording, cancel_capture, export_annotated_screenshot, finalize_complete,
+    get_screenshot_formats, get_session_info, get_state, pause_recording, redact_screenshot,
+    reset_error, resume_recording, start_capture, stop_recording, take_screenshot,
+};
+
+#[test]
+fn test_start_capture_records_with_fake_backend() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    assert_eq!(get_state(app.state()), CaptureState::Recording);
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(backend.start_recording_count(), 1);
+    assert!(backend.is_recording());
+}
+
+#[test]
+fn test_get_session_info_reports_recording_session() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let config = test_config();
+    block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(config.clone()),
+    ))
+    .unwrap();
+
+    let info = get_session_info(app.state());
+
+    assert_eq!(info.current.state(), CaptureState::Recording);
+    assert!(info.current.session_id().is_some());
+    assert_eq!(
+        info.current.config().map(|c| c.output_path.as_str()),
+        Some(config.output_path.as_str())
+    );
+    assert_eq!(info.history.len(), 2);
+}
+
+#[test]
+fn test_start_capture_permission_denied_moves_to_error() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Error);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert!(app.state::<AppState>().service.config().is_none());
+}
+
+#[test]
+fn test_start_capture_rejects_invalid_config() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let config = CaptureConfig {
+        fps: 0,
+        ..test_config()
+    };
+
+    let result = block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(config),
+    ));
+
+    assert!(result.unwrap_err().starts_with("fps"));
+    assert_eq!(current_state(&app), CaptureState::Error);
+    assert_eq!(backend.selection_count(), 0);
+}
+
+#[test]
+fn test_start_capture_rejects_other_modes() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let config = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+
+    let result = block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(config),
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(backend.selection_count(), 0);
+}
+
+#[test]
+fn test_cancel_capture_from_selecting_returns_to_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    app.state::<AppState>()
+        .service
+        .select_for_test(test_config(), None);
+
+    let result = cancel_capture(app.handle().clone(), app.state());
+
+    assert_eq!(result, Ok(CaptureState::Idle));
+    assert!(app.state::<AppState>().service.config().is_none());
+}
+
+#[test]
+fn test_cancel_capture_rejected_while_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ))
+    .unwrap();
+
+    assert!(cancel_capture(app.handle().clone(), app.state()).is_err());
+    assert_eq!(current_state(&app), CaptureState::Recording);
+}
+
+#[test]
+fn test_pause_resume_stop_finalize_walk_back_to_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ))
+    .unwrap();
+
+    let handle = app.handle().clone();
+    assert_eq!(
+        block_on(pause_recording(handle.clone(), app.state())),
+        Ok(CaptureState::Paused)
+    );
+    assert!(backend.is_paused());
+    assert_eq!(
+        block_on(resume_recording(handle.clone(), app.state())),
+        Ok(CaptureState::Recording)
+    );
+    assert_eq!(
+        block_on(stop_recording(handle.clone(), app.state())),
+        Ok(CaptureState::Idle)
+    );
+    assert!(!backend.is_recording());
+    // Stopping already finalized; the frontend's finalize is a no-op
+    assert_eq!(
+        finalize_complete(handle, app.state()),
+        Ok(CaptureState::Idle)
+    );
+    assert!(app.state::<AppState>().service.config().is_none());
+    assert!(app.state::<AppState>().service.selection().is_none());
+}
+
+#[test]
+fn test_pause_rejected_while_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    assert!(block_on(pause_recording(app.handle().clone(), app.state())).is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(backend.pause_recording_count(), 0);
+}
+
+#[test]
+fn test_begin_recording_pipeline_failure_returns_to_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend.fail_operation(FakeOperation::StartRecording);
+    let app = mock_app(&backend);
+    let selection = SelectionResult {
+        node_id: 42,
+        stream_fd: None,
+        width: Some(1920),
+        height: Some(1080),
+    };
+    app.state::<AppState>()
+        .service
+        .select_for_test(test_config(), Some(selection));
+
+    let result = block_on(begin_recording(app.handle().clone(), app.state()));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert!(!backend.is_recording());
+}
+
+#[test]
+fn test_pause_failure_keeps_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ))
+    .unwrap();
+    backend.fail_operation(FakeOperation::PauseRecording);
+
+    assert!(block_on(pause_recording(app.handle().clone(), app.state())).is_err());
+    assert_eq!(current_state(&app), CaptureState::Recording);
+}
+
+#[test]
+fn test_reset_error_returns_to_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let app = mock_app(&backend);
+    let _ = block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+    assert_eq!(current_state(&app), CaptureState::Error);
+
+    let result = reset_error(app.handle().clone(), app.state());
+
+    assert_eq!(result, Ok(CaptureState::Idle));
+}
+
+#[test]
+fn test_take_screenshot_with_fake_backend() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ));
+
+    let screenshot = result.unwrap();
+    assert_eq!((screenshot.width, screenshot.height), (1920, 1080));
+    assert!(std::path::Path::new(&screenshot.path).exists());
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(current_state(&app), CaptureState::Idle);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[test]
+fn test_export_annotated_screenshot_from_json_strokes() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let screenshot = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ))
+    .unwrap();
+    let annotations = serde_json::from_str(
+        r##"{"strokes":[{"points":[{"x":10,"y":10},{"x":90,"y":10}],"color":"#ff0000","width":6}]}"##,
+    )
+    .unwrap();
+
+    let exported = block_on(export_annotated_screenshot(
+        app.handle().clone(),
+        app.state(),
+        annotations,
+        None,
+    ))
+    .unwrap();
+
+    assert_eq!(exported.format, ScreenshotFormat::Png);
+    assert_eq!(
+        (exported.width, exported.height),
+        (screenshot.width, screenshot.height)
+    );
+    let decoded = image::open(&exported.path).unwrap().into_rgba8();
+    assert_eq!(decoded.get_pixel(50, 10).0, [255, 0, 0, 255]);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+    let _ = std::fs::remove_file(&exported.path);
+}
+
+#[test]
+fn test_redact_screenshot_from_json_areas() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let screenshot = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ))
+    .unwrap();
+    let redactions = serde_json::from_str(
+        r##"[{"rect":{"x":0,"y":0,"width":64,"height":32},"style":"fill","color":"#ffffff"}]"##,
+    )
+    .unwrap();
+
+    let redacted = block_on(redact_screenshot(
+        app.handle().clone(),
+        app.state(),
+        redactions,
+        None,
+    ))
+    .unwrap();
+
+    let decoded = image::open(&redacted.path).unwrap().into_rgba8();
+    assert_eq!(decoded.get_pixel(5, 5).0, [255, 255, 255, 255]);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+    let _ = std::fs::remove_file(&redacted.path);
+}
+
+#[test]
+fn test_get_screenshot_formats_lists_builtin_encoders() {
+    let formats = get_screenshot_formats();
+
+    assert_eq!(formats.first(), Some(&ScreenshotFormat::Png));
+    assert!(formats.contains(&ScreenshotFormat::WebpLossless));
+}
+
+#[test]
+fn test_take_screenshot_permission_denied() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let app = mock_app(&backend);
+
+    let result = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+}
+
+#[test]
+fn test_take_screenshot_rejected_while_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ))
+    .unwrap();
+
+    let result = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(current_state(&app), CaptureState::Recording);
+}
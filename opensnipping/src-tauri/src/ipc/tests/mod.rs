@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::{App, Manager};
+
+use crate::capture::fake::FakeCaptureBackend;
+use crate::config::{AudioConfig, CaptureConfig, CaptureMode, CaptureSource, ContainerFormat};
+use crate::naming::temp_naming;
+use crate::recovery::RecordingJournal;
+use crate::service::CaptureService;
+use crate::settings::SettingsStore;
+use crate::state::CaptureState;
+use crate::AppState;
+
+/// A mock Tauri app whose `AppState` captures through `backend`, with its
+/// journal and settings in a fresh temp directory
+///
+/// The fake is cloned, so `backend` keeps reporting the calls made by the
+/// commands under test.
+pub(super) fn mock_app(backend: &FakeCaptureBackend) -> App<MockRuntime> {
+    let data_dir =
+        std::env::temp_dir().join(format!("opensnipping-ipc-test-{}", uuid::Uuid::new_v4()));
+    mock_builder()
+        .manage(AppState {
+            service: CaptureService::new(
+                Arc::new(backend.clone()),
+                RecordingJournal::new(data_dir.join("recordings")),
+            ),
+            settings: SettingsStore::new(data_dir.join("settings.json")),
+        })
+        .build(mock_context(noop_assets()))
+        .expect("failed to build mock app")
+}
+
+pub(super) fn current_state(app: &App<MockRuntime>) -> CaptureState {
+    app.state::<AppState>().service.state()
+}
+
+pub(super) fn test_config() -> CaptureConfig {
+    CaptureConfig::for_test("opensnipping-ipc-test")
+}
+
+mod audio;
+mod clipboard;
+mod commands;
+mod recording;
+mod recovery;
+mod replay;
+mod schedule;
+mod session;
+mod settings;
+mod timelapse;
@@ -0,0 +1,24 @@
+use std::time::{Duration, Instant};
+
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::ipc::recording::start_recording_video;
+
+#[test]
+fn test_start_recording_video_outside_selecting_skips_countdown() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let started = Instant::now();
+
+    let result = block_on(start_recording_video(
+        app.handle().clone(),
+        app.state(),
+        Some(3),
+    ));
+
+    assert!(result.unwrap_err().contains("Idle"));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(backend.start_recording_count(), 0);
+}
@@ -0,0 +1,48 @@
+use super::*;
+use crate::ipc::recovery::get_recovered_recordings;
+use crate::recovery::{JournalEntry, RecoveredRecording, RecoveryOutcome};
+
+/// One above the highest pid Linux can hand out, so never a live process
+const DEAD_PID: u32 = 4_194_305;
+
+#[test]
+fn test_no_recovered_recordings_before_recovery() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+
+    assert!(get_recovered_recordings(app.state()).is_empty());
+}
+
+#[test]
+fn test_recovered_recordings_are_served_after_setup() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    let state = app.state::<AppState>();
+    let output = std::env::temp_dir().join(format!("partial-{}.ogg", uuid::Uuid::new_v4()));
+    std::fs::write(&output, b"partial").unwrap();
+    let output_path = output.to_string_lossy().to_string();
+    state
+        .service
+        .journal()
+        .begin(&JournalEntry {
+            output_path: output_path.clone(),
+            container: ContainerFormat::Ogg,
+            crash_safe: false,
+            pid: DEAD_PID,
+            pid_start_time: None,
+            segmented: false,
+            closed_segments: Vec::new(),
+        })
+        .unwrap();
+
+    state.service.recover_interrupted_recordings(app.handle());
+
+    assert_eq!(
+        get_recovered_recordings(app.state()),
+        vec![RecoveredRecording {
+            path: output_path,
+            outcome: RecoveryOutcome::AlreadyPlayable,
+        }]
+    );
+    assert!(state.service.journal().orphans().unwrap().is_empty());
+    let _ = std::fs::remove_file(output);
+    let _ = std::fs::remove_dir_all(state.service.journal().dir());
+}
@@ -0,0 +1,93 @@
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::ipc::replay::{save_replay, start_replay_buffer, stop_replay_buffer};
+
+fn replay_config() -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    }
+}
+
+#[test]
+fn test_replay_buffer_start_save_stop() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let started = block_on(start_replay_buffer(
+        app.handle().clone(),
+        app.state(),
+        Some(replay_config()),
+    ));
+    assert_eq!(started, Ok(CaptureState::Buffering));
+    assert!(backend.is_buffering());
+
+    let saved = block_on(save_replay(
+        app.handle().clone(),
+        app.state(),
+        Some("/tmp/replay.mp4".to_string()),
+    ));
+    assert_eq!(saved.unwrap().path, "/tmp/replay.mp4");
+    assert_eq!(backend.save_replay_count(), 1);
+    assert_eq!(current_state(&app), CaptureState::Buffering);
+
+    let stopped = block_on(stop_replay_buffer(app.handle().clone(), app.state()));
+    assert_eq!(stopped, Ok(CaptureState::Idle));
+    assert!(!backend.is_buffering());
+}
+
+#[test]
+fn test_save_replay_without_path_names_the_file() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    block_on(start_replay_buffer(
+        app.handle().clone(),
+        app.state(),
+        Some(replay_config()),
+    ))
+    .unwrap();
+
+    let saved = block_on(save_replay(app.handle().clone(), app.state(), None)).unwrap();
+
+    assert!(saved.path.ends_with(".mp4"), "{}", saved.path);
+    assert_eq!(current_state(&app), CaptureState::Buffering);
+}
+
+#[test]
+fn test_replay_buffer_start_failure_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_replay_buffer(
+        app.handle().clone(),
+        app.state(),
+        Some(replay_config()),
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert!(!backend.is_buffering());
+}
+
+#[test]
+fn test_save_replay_rejected_while_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(save_replay(app.handle().clone(), app.state(), None));
+
+    assert!(result.is_err());
+    assert_eq!(backend.save_replay_count(), 0);
+}
+
+#[test]
+fn test_stop_replay_buffer_rejected_while_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(stop_replay_buffer(app.handle().clone(), app.state()));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+}
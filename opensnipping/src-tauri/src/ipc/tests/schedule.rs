@@ -0,0 +1,72 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::*;
+use crate::ipc::schedule::{cancel_scheduled_capture, list_scheduled_captures, schedule_capture};
+
+/// Unix epoch ms `secs` from now
+fn in_secs(secs: u64) -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    now.as_millis() as u64 + secs * 1000
+}
+
+#[test]
+fn test_schedule_list_and_cancel() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+
+    let later = schedule_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        in_secs(600),
+        30,
+    )
+    .unwrap();
+    let sooner = schedule_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        in_secs(60),
+        30,
+    )
+    .unwrap();
+
+    assert_eq!(later.config.max_duration_secs, Some(30));
+    assert_eq!(
+        list_scheduled_captures(app.state()),
+        vec![sooner.clone(), later.clone()]
+    );
+    assert_eq!(
+        cancel_scheduled_capture(app.state(), sooner.id.clone()),
+        Ok(())
+    );
+    assert!(cancel_scheduled_capture(app.state(), sooner.id).is_err());
+    assert_eq!(list_scheduled_captures(app.state()), vec![later]);
+}
+
+#[test]
+fn test_schedule_capture_rejects_replay_buffers_and_past_starts() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    let replay = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+
+    let err = schedule_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(replay),
+        in_secs(60),
+        30,
+    )
+    .unwrap_err();
+    assert!(err.starts_with("mode"), "{}", err);
+    let past = schedule_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        1,
+        30,
+    );
+    assert!(past.is_err());
+    assert!(list_scheduled_captures(app.state()).is_empty());
+}
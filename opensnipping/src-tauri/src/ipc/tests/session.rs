@@ -0,0 +1,94 @@
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::ipc::commands::take_screenshot;
+use crate::ipc::replay::start_replay_buffer;
+use crate::ipc::session::{close_capture_session, open_capture_session};
+
+fn session_is_open(app: &App<MockRuntime>) -> bool {
+    app.state::<AppState>().service.session_is_open()
+}
+
+#[test]
+fn test_open_session_is_reused_until_closed() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let opened = block_on(open_capture_session(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+    assert!(opened.is_ok());
+    assert!(session_is_open(&app));
+    assert_eq!(current_state(&app), CaptureState::Idle);
+
+    let screenshot = block_on(take_screenshot(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+        None,
+    ))
+    .unwrap();
+    assert_eq!(backend.selection_count(), 1);
+
+    let closed = block_on(close_capture_session(app.handle().clone(), app.state()));
+    assert_eq!(closed, Ok(()));
+    assert!(!session_is_open(&app));
+    assert!(app.state::<AppState>().service.selection().is_none());
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[test]
+fn test_open_session_failure_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let app = mock_app(&backend);
+
+    let result = block_on(open_capture_session(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+
+    assert!(result.is_err());
+    assert!(!session_is_open(&app));
+    assert_eq!(current_state(&app), CaptureState::Idle);
+}
+
+#[test]
+fn test_close_session_without_session_is_rejected() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(close_capture_session(app.handle().clone(), app.state()));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_close_session_rejected_while_buffering() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    block_on(open_capture_session(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ))
+    .unwrap();
+    let replay = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+    block_on(start_replay_buffer(
+        app.handle().clone(),
+        app.state(),
+        Some(replay),
+    ))
+    .unwrap();
+
+    let result = block_on(close_capture_session(app.handle().clone(), app.state()));
+
+    assert!(result.is_err());
+    assert!(session_is_open(&app));
+    assert!(backend.is_buffering());
+}
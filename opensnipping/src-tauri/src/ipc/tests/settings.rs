@@ -0,0 +1,241 @@
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::config::{EncoderConfig, EncoderPreset, VideoEncoder};
+use crate::ipc::commands::{get_session_info, start_capture};
+use crate::ipc::profiles::{
+    create_profile, delete_profile, export_profiles, get_profile, import_profiles, list_profiles,
+    set_default_profile, start_profile_capture, update_profile,
+};
+use crate::ipc::settings::{get_settings, save_settings};
+use crate::settings::{Profile, Settings, DEFAULT_PROFILE_NAME};
+
+fn remove_settings(app: &App<MockRuntime>) {
+    let dir = app
+        .state::<AppState>()
+        .settings
+        .path()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+fn bug_report() -> Profile {
+    Profile::new(
+        "Bug report",
+        CaptureConfig {
+            source: CaptureSource::Region,
+            ..Default::default()
+        },
+    )
+}
+
+/// Default settings whose default profile records at 24 fps with x264
+fn save_encoder_defaults(app: &App<MockRuntime>) -> Settings {
+    let mut settings = Settings::default();
+    let config = &mut settings.profiles[0].config;
+    config.fps = 24;
+    config.naming = temp_naming();
+    config.encoder = Some(EncoderConfig {
+        encoder: VideoEncoder::X264,
+        bitrate_kbps: Some(4000),
+        preset: EncoderPreset::Fast,
+    });
+    save_settings(app.state(), settings).unwrap()
+}
+
+fn recording_config(app: &App<MockRuntime>) -> CaptureConfig {
+    get_session_info(app.state())
+        .current
+        .config()
+        .cloned()
+        .unwrap()
+}
+
+#[test]
+fn test_get_settings_defaults_before_any_save() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+
+    assert_eq!(get_settings(app.state()), Ok(Settings::default()));
+}
+
+#[test]
+fn test_save_settings_persists_for_the_next_get() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    let mut settings = Settings::default();
+    settings.profiles[0].config.fps = 24;
+    settings.hotkeys.toggle_recording = Some("Ctrl+Alt+R".to_string());
+
+    let saved = save_settings(app.state(), settings.clone()).unwrap();
+
+    assert_eq!(saved, settings);
+    assert_eq!(get_settings(app.state()), Ok(settings));
+    remove_settings(&app);
+}
+
+#[test]
+fn test_save_settings_rejects_invalid_config() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    let mut settings = Settings::default();
+    settings.profiles[0].config.fps = 0;
+
+    let err = save_settings(app.state(), settings).unwrap_err();
+
+    assert!(err.starts_with("profiles.Default.fps:"), "{}", err);
+    assert_eq!(get_settings(app.state()), Ok(Settings::default()));
+}
+
+#[test]
+fn test_profile_commands_save_each_change() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+
+    create_profile(app.state(), bug_report()).unwrap();
+    let mut renamed = bug_report();
+    renamed.name = "Bugs".to_string();
+    update_profile(app.state(), "Bug report".to_string(), renamed.clone()).unwrap();
+    let settings = set_default_profile(app.state(), "Bugs".to_string()).unwrap();
+
+    assert_eq!(settings.default_profile, "Bugs");
+    assert_eq!(get_settings(app.state()), Ok(settings));
+
+    assert!(delete_profile(app.state(), "Bugs".to_string()).is_err());
+    let settings = delete_profile(app.state(), DEFAULT_PROFILE_NAME.to_string()).unwrap();
+    assert_eq!(settings.profiles, [renamed]);
+    remove_settings(&app);
+}
+
+#[test]
+fn test_rejected_profile_changes_are_not_saved() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    create_profile(app.state(), bug_report()).unwrap();
+    let saved = get_settings(app.state()).unwrap();
+
+    let err = create_profile(app.state(), bug_report()).unwrap_err();
+    assert!(err.contains("already exists"), "{}", err);
+    let mut clash = bug_report();
+    clash.name = DEFAULT_PROFILE_NAME.to_string();
+    assert!(update_profile(app.state(), "Bug report".to_string(), clash).is_err());
+    assert!(set_default_profile(app.state(), "Missing".to_string()).is_err());
+    assert!(delete_profile(app.state(), "Missing".to_string()).is_err());
+
+    assert_eq!(get_settings(app.state()), Ok(saved));
+    remove_settings(&app);
+}
+
+#[test]
+fn test_export_and_import_between_installs() {
+    let team = mock_app(&FakeCaptureBackend::succeeding());
+    create_profile(team.state(), bug_report()).unwrap();
+    let json = export_profiles(team.state(), vec!["Bug report".to_string()]).unwrap();
+
+    let mine = mock_app(&FakeCaptureBackend::succeeding());
+    let settings = import_profiles(mine.state(), json.clone(), false).unwrap();
+    assert_eq!(settings.profile("Bug report"), Some(&bug_report()));
+
+    let err = import_profiles(mine.state(), json.clone(), false).unwrap_err();
+    assert!(err.contains("already exist"), "{}", err);
+    assert!(import_profiles(mine.state(), json, true).is_ok());
+    assert!(import_profiles(mine.state(), "{}".to_string(), true).is_err());
+    remove_settings(&team);
+    remove_settings(&mine);
+}
+
+#[test]
+fn test_start_capture_without_config_uses_the_default_profile() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    let settings = save_encoder_defaults(&app);
+
+    block_on(start_capture(app.handle().clone(), app.state(), None)).unwrap();
+
+    let config = recording_config(&app);
+    assert_eq!(config.fps, 24);
+    assert_eq!(config.encoder, settings.profiles[0].config.encoder);
+    remove_settings(&app);
+}
+
+#[test]
+fn test_config_without_encoder_takes_the_default_profiles() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    let settings = save_encoder_defaults(&app);
+
+    block_on(start_capture(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ))
+    .unwrap();
+
+    let config = recording_config(&app);
+    assert_eq!(config.fps, test_config().fps);
+    assert_eq!(config.encoder, settings.profiles[0].config.encoder);
+    remove_settings(&app);
+}
+
+#[test]
+fn test_list_and_get_profiles() {
+    let app = mock_app(&FakeCaptureBackend::succeeding());
+    create_profile(app.state(), bug_report()).unwrap();
+
+    let profiles = list_profiles(app.state()).unwrap();
+    let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, [DEFAULT_PROFILE_NAME, "Bug report"]);
+
+    assert_eq!(
+        get_profile(app.state(), "bug REPORT".to_string()),
+        Ok(bug_report())
+    );
+    let err = get_profile(app.state(), "Missing".to_string()).unwrap_err();
+    assert!(err.contains("No profile named"), "{}", err);
+    remove_settings(&app);
+}
+
+#[test]
+fn test_start_profile_capture_starts_the_profiles_mode() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let settings = save_encoder_defaults(&app);
+    let podcast = Profile::new(
+        "Podcast",
+        CaptureConfig {
+            mode: CaptureMode::AudioOnly,
+            container: ContainerFormat::Ogg,
+            audio: AudioConfig {
+                system: false,
+                mic: true,
+            },
+            naming: temp_naming(),
+            ..Default::default()
+        },
+    );
+    create_profile(app.state(), podcast).unwrap();
+
+    let result = block_on(start_profile_capture(
+        app.handle().clone(),
+        app.state(),
+        "podcast".to_string(),
+    ));
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    assert!(backend.is_audio_only());
+    let config = recording_config(&app);
+    assert_eq!(config.mode, CaptureMode::AudioOnly);
+    assert_eq!(config.encoder, settings.profiles[0].config.encoder);
+    remove_settings(&app);
+}
+
+#[test]
+fn test_start_profile_capture_rejects_unknown_profiles() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_profile_capture(
+        app.handle().clone(),
+        app.state(),
+        "Missing".to_string(),
+    ));
+
+    assert!(result.unwrap_err().contains("No profile named"));
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    assert_eq!(backend.selection_count(), 0);
+}
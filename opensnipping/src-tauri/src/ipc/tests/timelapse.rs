@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tauri::async_runtime::block_on;
+
+use super::*;
+use crate::capture::timelapse::frame_path;
+use crate::config::TimelapseConfig;
+use crate::ipc::commands::{pause_recording, resume_recording};
+use crate::ipc::timelapse::{start_timelapse, stop_timelapse};
+
+fn timelapse_config(frame_count: u32) -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::Timelapse,
+        timelapse: Some(TimelapseConfig {
+            interval_secs: 60,
+            frame_count,
+            encode_video: false,
+            frames_dir: None,
+        }),
+        ..test_config()
+    }
+}
+
+fn frames_dir(config: &CaptureConfig) -> PathBuf {
+    config
+        .timelapse
+        .as_ref()
+        .unwrap()
+        .frames_dir_for(&config.output_path)
+}
+
+/// Wait for the spawned capture loop to settle back to Idle
+fn wait_for_idle(app: &App<MockRuntime>) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while current_state(app) != CaptureState::Idle {
+        assert!(Instant::now() < deadline, "timelapse never finished");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn test_start_timelapse_captures_in_the_background() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let config = timelapse_config(1);
+
+    let result = block_on(start_timelapse(
+        app.handle().clone(),
+        app.state(),
+        Some(config.clone()),
+    ));
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    wait_for_idle(&app);
+    assert!(frame_path(&frames_dir(&config), 1).is_file());
+    assert_eq!(backend.selection_count(), 1);
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[test]
+fn test_pause_resume_and_stop_timelapse() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+    let config = timelapse_config(5);
+    block_on(start_timelapse(
+        app.handle().clone(),
+        app.state(),
+        Some(config.clone()),
+    ))
+    .unwrap();
+
+    block_on(pause_recording(app.handle().clone(), app.state())).unwrap();
+    assert_eq!(current_state(&app), CaptureState::Paused);
+    block_on(resume_recording(app.handle().clone(), app.state())).unwrap();
+    assert_eq!(backend.pause_recording_count(), 0);
+
+    let stopped = stop_timelapse(app.handle().clone(), app.state());
+
+    assert_eq!(stopped, Ok(CaptureState::Finalizing));
+    wait_for_idle(&app);
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[test]
+fn test_start_timelapse_failure_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let app = mock_app(&backend);
+    let config = timelapse_config(1);
+
+    let result = block_on(start_timelapse(
+        app.handle().clone(),
+        app.state(),
+        Some(config.clone()),
+    ));
+
+    assert!(result.is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[test]
+fn test_start_timelapse_rejects_other_modes() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    let result = block_on(start_timelapse(
+        app.handle().clone(),
+        app.state(),
+        Some(test_config()),
+    ));
+
+    assert!(result.unwrap_err().starts_with("mode"));
+    assert_eq!(backend.selection_count(), 0);
+}
+
+#[test]
+fn test_stop_timelapse_without_timelapse_is_rejected() {
+    let backend = FakeCaptureBackend::succeeding();
+    let app = mock_app(&backend);
+
+    assert!(stop_timelapse(app.handle().clone(), app.state()).is_err());
+    assert_eq!(current_state(&app), CaptureState::Idle);
+}
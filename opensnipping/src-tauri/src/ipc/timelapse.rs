@@ -0,0 +1,50 @@
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::config::CaptureConfig;
+use crate::ipc::settings::capture_config;
+use crate::state::CaptureState;
+use crate::AppState;
+
+/// Pick a source and grab a frame every `interval_secs` until `frame_count`
+///
+/// Goes Idle → Recording once the picker succeeds and keeps the portal
+/// session for every frame; an open capture session is used instead of the
+/// picker. `pause_recording` / `resume_recording` pause the schedule and
+/// `stop_timelapse` ends it early; either way the frames (and the video,
+/// with `encode_video`) are reported by `capture:timelapse_complete`.
+/// Without `config`, uses the default profile.
+#[tauri::command]
+pub(crate) async fn start_timelapse<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: Option<CaptureConfig>,
+) -> Result<CaptureState, String> {
+    let config = capture_config(&state, config)?;
+    begin_timelapse(&app, &state, config).await
+}
+
+/// Start a timelapse of `config` and run its capture loop in the background
+pub(crate) async fn begin_timelapse<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    config: CaptureConfig,
+) -> Result<CaptureState, String> {
+    let run = state.service.start_timelapse(app, config).await?;
+    let loop_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = loop_app.state::<AppState>();
+        state.service.run_timelapse(&loop_app, run).await;
+    });
+    Ok(CaptureState::Recording)
+}
+
+/// End a timelapse early (Recording/Paused → Finalizing)
+///
+/// The capture loop then encodes what it has and returns to Idle.
+#[tauri::command]
+pub(crate) fn stop_timelapse<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<AppState>,
+) -> Result<CaptureState, String> {
+    state.service.stop_timelapse(&app)
+}
@@ -1,51 +1,69 @@
 pub mod capture;
+pub mod cli;
 pub mod config;
+pub mod disk;
 pub mod events;
+pub mod limits;
+pub mod naming;
+pub mod recovery;
+pub mod schedule;
+pub mod screenshot;
+pub mod service;
+pub mod settings;
 pub mod state;
 
 mod ipc;
 
-use std::path::PathBuf;
-use std::sync::Mutex;
-
-use capture::SelectionResult;
-use config::CaptureConfig;
+use capture::SharedBackend;
+use ipc::audio::start_audio_recording;
+use ipc::clipboard::copy_screenshot;
 use ipc::commands::{
-    begin_recording, cancel_capture, finalize_complete, get_state, pause_recording,
-    pause_recording_video, ping, reset_error, resume_recording, resume_recording_video,
-    start_capture, start_recording_video, stop_recording, stop_recording_video, take_screenshot,
+    begin_recording, cancel_capture, export_annotated_screenshot, finalize_complete,
+    get_screenshot_formats, get_session_info, get_state, pause_recording, ping, redact_screenshot,
+    reset_error, resume_recording, start_capture, stop_recording, take_screenshot,
 };
-use state::StateMachine;
-
-/// Generate a unique temporary file path for screenshots.
-/// Returns a path in /tmp with format: opensnipping-{uuid}.png
-pub fn generate_screenshot_temp_path() -> PathBuf {
-    PathBuf::from(format!("/tmp/opensnipping-{}.png", uuid::Uuid::new_v4()))
-}
+use ipc::profiles::{
+    create_profile, delete_profile, export_profiles, get_profile, import_profiles, list_profiles,
+    set_default_profile, start_profile_capture, update_profile,
+};
+use ipc::recording::{
+    pause_recording_video, resume_recording_video, start_recording_video, stop_recording_video,
+};
+use ipc::recovery::get_recovered_recordings;
+use ipc::replay::{save_replay, start_replay_buffer, stop_replay_buffer};
+use ipc::schedule::{cancel_scheduled_capture, list_scheduled_captures, schedule_capture};
+use ipc::session::{close_capture_session, open_capture_session};
+use ipc::settings::{get_settings, save_settings};
+use ipc::timelapse::{start_timelapse, stop_timelapse};
+use recovery::RecordingJournal;
+use service::CaptureService;
+use settings::SettingsStore;
 
 /// Application state managed by Tauri
 pub struct AppState {
-    pub state_machine: Mutex<StateMachine>,
-    pub config: Mutex<Option<CaptureConfig>>,
-    /// Result from portal selection (PipeWire node info)
-    pub selection: Mutex<Option<SelectionResult>>,
-    /// Active backend instance for recording
-    #[cfg(target_os = "linux")]
-    pub backend: tokio::sync::Mutex<Option<capture::linux::LinuxCaptureBackend>>,
+    /// Capture orchestration: state machine, backend, config, selection,
+    /// recording journal and scheduled captures
+    pub service: CaptureService,
+    /// Saved defaults and shortcuts, read and written on request
+    pub settings: SettingsStore,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
+impl AppState {
+    /// State that captures through `backend`; tests inject a fake one
+    pub fn with_backend(backend: SharedBackend) -> Self {
         Self {
-            state_machine: Mutex::new(StateMachine::new()),
-            config: Mutex::new(None),
-            selection: Mutex::new(None),
-            #[cfg(target_os = "linux")]
-            backend: tokio::sync::Mutex::new(None),
+            service: CaptureService::new(backend, RecordingJournal::default()),
+            settings: SettingsStore::default(),
         }
     }
 }
 
+impl Default for AppState {
+    fn default() -> Self {
+        Self::with_backend(capture::default_backend())
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let mut builder = tauri::Builder::default()
@@ -58,9 +76,20 @@ pub fn run() {
     }
 
     builder
+        .setup(|app| {
+            ipc::recovery::recover_interrupted_recordings(app.handle().clone());
+            ipc::recording::spawn_supervisor(app.handle().clone());
+            ipc::recording::forward_segment_events(app.handle());
+            ipc::recording::forward_av_drift(app.handle());
+            ipc::session::forward_session_closed(app.handle());
+            #[cfg(target_os = "linux")]
+            ipc::clipboard::install_clipboard(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             ping,
             get_state,
+            get_session_info,
             start_capture,
             cancel_capture,
             begin_recording,
@@ -70,10 +99,37 @@ pub fn run() {
             finalize_complete,
             reset_error,
             take_screenshot,
+            get_screenshot_formats,
+            copy_screenshot,
+            export_annotated_screenshot,
+            redact_screenshot,
             start_recording_video,
             stop_recording_video,
             pause_recording_video,
             resume_recording_video,
+            start_audio_recording,
+            get_recovered_recordings,
+            start_replay_buffer,
+            save_replay,
+            stop_replay_buffer,
+            schedule_capture,
+            cancel_scheduled_capture,
+            list_scheduled_captures,
+            start_timelapse,
+            stop_timelapse,
+            open_capture_session,
+            close_capture_session,
+            get_settings,
+            save_settings,
+            list_profiles,
+            get_profile,
+            start_profile_capture,
+            create_profile,
+            update_profile,
+            delete_profile,
+            set_default_profile,
+            export_profiles,
+            import_profiles,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -82,57 +138,9 @@ pub fn run() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashSet;
 
     #[test]
     fn test_ping_returns_pong() {
         assert_eq!(ping(), "Pong from Rust!");
     }
-
-    #[test]
-    fn test_screenshot_temp_path_is_in_tmp_dir() {
-        let path = generate_screenshot_temp_path();
-        assert!(
-            path.starts_with("/tmp"),
-            "Path should be in /tmp directory: {:?}",
-            path
-        );
-    }
-
-    #[test]
-    fn test_screenshot_temp_path_has_correct_prefix() {
-        let path = generate_screenshot_temp_path();
-        let filename = path.file_name().unwrap().to_str().unwrap();
-        assert!(
-            filename.starts_with("opensnipping-"),
-            "Filename should start with 'opensnipping-': {}",
-            filename
-        );
-    }
-
-    #[test]
-    fn test_screenshot_temp_path_has_png_extension() {
-        let path = generate_screenshot_temp_path();
-        assert_eq!(
-            path.extension().and_then(|e| e.to_str()),
-            Some("png"),
-            "Path should have .png extension: {:?}",
-            path
-        );
-    }
-
-    #[test]
-    fn test_screenshot_temp_path_is_unique() {
-        // Generate multiple paths and ensure they are all unique
-        let mut paths = HashSet::new();
-        for _ in 0..100 {
-            let path = generate_screenshot_temp_path();
-            assert!(
-                paths.insert(path.clone()),
-                "Generated path should be unique: {:?}",
-                path
-            );
-        }
-        assert_eq!(paths.len(), 100);
-    }
 }
@@ -0,0 +1,120 @@
+use crate::config::CaptureConfig;
+use std::time::Duration;
+
+/// How often limits are checked while recording
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Which configured limit ended a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitReached {
+    Duration,
+    FileSize,
+}
+
+/// Tracks a recording against its `max_duration_secs` / `max_file_size_bytes`
+///
+/// Only time spent in the Recording state counts towards the duration, so
+/// pausing does not use up the limit.
+#[derive(Debug, Clone)]
+pub struct LimitTracker {
+    max_duration: Option<Duration>,
+    max_file_size_bytes: Option<u64>,
+    recorded: Duration,
+}
+
+impl LimitTracker {
+    /// Tracker for `config`, or None when it sets no limits
+    pub fn for_config(config: &CaptureConfig) -> Option<Self> {
+        if config.max_duration_secs.is_none() && config.max_file_size_bytes.is_none() {
+            return None;
+        }
+        Some(Self {
+            max_duration: config.max_duration_secs.map(Duration::from_secs),
+            max_file_size_bytes: config.max_file_size_bytes,
+            recorded: Duration::ZERO,
+        })
+    }
+
+    /// Account for `elapsed` wall time, which only counts while recording
+    pub fn advance(&mut self, elapsed: Duration, recording: bool) {
+        if recording {
+            self.recorded += elapsed;
+        }
+    }
+
+    /// Time recorded so far, excluding pauses
+    pub fn recorded(&self) -> Duration {
+        self.recorded
+    }
+
+    /// The first limit reached, given the bytes recorded so far (all
+    /// segments together)
+    ///
+    /// The muxer writes its index after the stop, so the finished file can
+    /// end up slightly above `max_file_size_bytes`.
+    pub fn check(&self, file_bytes: u64) -> Option<LimitReached> {
+        if self.max_duration.is_some_and(|max| self.recorded >= max) {
+            return Some(LimitReached::Duration);
+        }
+        if self
+            .max_file_size_bytes
+            .is_some_and(|max| file_bytes >= max)
+        {
+            return Some(LimitReached::FileSize);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limited(max_duration_secs: Option<u64>, max_file_size_bytes: Option<u64>) -> LimitTracker {
+        LimitTracker::for_config(&CaptureConfig {
+            output_path: "/tmp/limited.mp4".to_string(),
+            max_duration_secs,
+            max_file_size_bytes,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_no_tracker_without_limits() {
+        assert!(LimitTracker::for_config(&CaptureConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_duration_limit() {
+        let mut tracker = limited(Some(10), None);
+        tracker.advance(Duration::from_secs(9), true);
+        assert_eq!(tracker.check(u64::MAX), None);
+
+        tracker.advance(Duration::from_secs(1), true);
+        assert_eq!(tracker.check(0), Some(LimitReached::Duration));
+    }
+
+    #[test]
+    fn test_paused_time_does_not_count() {
+        let mut tracker = limited(Some(10), None);
+        tracker.advance(Duration::from_secs(5), true);
+        tracker.advance(Duration::from_secs(60), false);
+        assert_eq!(tracker.recorded(), Duration::from_secs(5));
+        assert_eq!(tracker.check(0), None);
+    }
+
+    #[test]
+    fn test_file_size_limit() {
+        let tracker = limited(None, Some(1_000_000));
+        assert_eq!(tracker.check(999_999), None);
+        assert_eq!(tracker.check(1_000_000), Some(LimitReached::FileSize));
+    }
+
+    #[test]
+    fn test_duration_reported_before_size() {
+        let mut tracker = limited(Some(1), Some(10));
+        tracker.advance(Duration::from_secs(1), true);
+        assert_eq!(tracker.check(10), Some(LimitReached::Duration));
+    }
+}
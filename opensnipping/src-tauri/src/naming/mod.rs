@@ -0,0 +1,173 @@
+// Output file naming for screenshots and recordings
+//
+// A capture without an explicit path is saved in its kind's directory (the
+// configured one, else XDG Pictures or Videos) under a name rendered from
+// a filename template. Missing directories are created on the way, and a
+// name that is taken gets the next `{counter}` or a `-2` suffix.
+
+mod template;
+mod time;
+
+#[cfg(test)]
+mod tests;
+
+pub use template::{NameValues, Template, TOKENS};
+pub use time::{now_ms, CalendarTime};
+
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::config::{CaptureConfig, CaptureSource, OutputNaming};
+use crate::state::{CaptureError, ErrorCode};
+
+pub const DEFAULT_SCREENSHOT_TEMPLATE: &str = "Screenshot_{date}_{time}.{ext}";
+pub const DEFAULT_RECORDING_TEMPLATE: &str = "Recording_{date}_{time}.{ext}";
+
+/// Names tried before giving up on finding a free one
+const MAX_ATTEMPTS: u32 = 10_000;
+
+/// What a file is named for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputKind {
+    Screenshot,
+    Recording,
+}
+
+impl OutputKind {
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            Self::Screenshot => DEFAULT_SCREENSHOT_TEMPLATE,
+            Self::Recording => DEFAULT_RECORDING_TEMPLATE,
+        }
+    }
+
+    /// XDG Pictures or Videos directory; the home directory, then the temp
+    /// directory, where that is not set up
+    pub fn default_directory(&self) -> PathBuf {
+        let xdg = match self {
+            Self::Screenshot => dirs::picture_dir(),
+            Self::Recording => dirs::video_dir(),
+        };
+        xdg.or_else(dirs::home_dir)
+            .unwrap_or_else(std::env::temp_dir)
+    }
+}
+
+/// Directory `naming` saves captures of `kind` to
+pub fn directory(naming: &OutputNaming, kind: OutputKind) -> PathBuf {
+    match naming.directory.as_deref() {
+        Some(directory) => match (directory.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(relative), Some(home)) => home.join(relative),
+            _ => PathBuf::from(directory),
+        },
+        None => kind.default_directory(),
+    }
+}
+
+/// Name a new file of `kind` as `naming` asks, at the current local time
+///
+/// Screenshot names are reserved by creating the (empty) file, since
+/// several screenshots can be saved within a second. Recording names are
+/// only checked: their pipelines create the file, or the segment files
+/// derived from it.
+pub fn new_output_path(
+    naming: &OutputNaming,
+    kind: OutputKind,
+    source: CaptureSource,
+    extension: &str,
+) -> Result<PathBuf, String> {
+    let template = Template::parse(
+        naming
+            .template
+            .as_deref()
+            .unwrap_or(kind.default_template()),
+    )?;
+    let values = NameValues {
+        time: CalendarTime::now(),
+        source: source.name(),
+        extension,
+    };
+    free_path(&directory(naming, kind), &template, &values, kind)
+}
+
+/// `config` with an empty `output_path` named from `config.naming`
+pub fn with_recording_path(mut config: CaptureConfig) -> Result<CaptureConfig, CaptureError> {
+    if config.output_path.is_empty() {
+        let path = new_output_path(
+            &config.naming,
+            OutputKind::Recording,
+            config.source,
+            config.container.extension(),
+        )
+        .map_err(|message| CaptureError {
+            code: ErrorCode::IoError,
+            message,
+        })?;
+        config.output_path = path.to_string_lossy().to_string();
+    }
+    Ok(config)
+}
+
+/// First free path for `template` in `directory`, creating the
+/// directories it needs (and the file itself for screenshots)
+pub fn free_path(
+    directory: &Path,
+    template: &Template,
+    values: &NameValues,
+    kind: OutputKind,
+) -> Result<PathBuf, String> {
+    for attempt in 1..=MAX_ATTEMPTS {
+        let name = if template.has_counter() {
+            template.render(values, attempt)
+        } else {
+            with_suffix(&template.render(values, 1), attempt)
+        };
+        let path = directory.join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        match kind {
+            OutputKind::Recording if path.exists() => continue,
+            OutputKind::Recording => return Ok(path),
+            OutputKind::Screenshot => {
+                match OpenOptions::new().write(true).create_new(true).open(&path) {
+                    Ok(_) => return Ok(path),
+                    Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                    Err(e) => return Err(format!("Failed to create {}: {}", path.display(), e)),
+                }
+            }
+        }
+    }
+    Err(format!(
+        "No free file name in {} after {} attempts",
+        directory.display(),
+        MAX_ATTEMPTS
+    ))
+}
+
+/// Naming that keeps test captures in the temp directory
+#[cfg(test)]
+pub(crate) fn temp_naming() -> OutputNaming {
+    OutputNaming {
+        directory: Some(std::env::temp_dir().to_string_lossy().to_string()),
+        template: Some("opensnipping-test {date} {time}.{ext}".to_string()),
+    }
+}
+
+/// `shot.png` → `shot-2.png` from the second attempt on
+fn with_suffix(name: &str, attempt: u32) -> String {
+    if attempt == 1 {
+        return name.to_string();
+    }
+    let name_start = name.rfind('/').map_or(0, |i| i + 1);
+    match name[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let (stem, extension) = name.split_at(name_start + dot);
+            format!("{}-{}{}", stem, attempt, extension)
+        }
+        _ => format!("{}-{}", name, attempt),
+    }
+}
@@ -0,0 +1,122 @@
+use super::CalendarTime;
+
+/// Tokens a filename template may use, written `{name}`
+pub const TOKENS: [&str; 5] = ["date", "time", "source", "counter", "ext"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// `2024-05-01`
+    Date,
+    /// `12-30-00`
+    Time,
+    /// `screen`, `monitor`, `window` or `region`
+    Source,
+    /// First free number from 1
+    Counter,
+    /// Format or container extension, without the dot
+    Ext,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Token(Token),
+}
+
+/// What the tokens of a template stand for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NameValues<'a> {
+    pub time: CalendarTime,
+    pub source: &'a str,
+    pub extension: &'a str,
+}
+
+/// A parsed filename template, such as `Screenshot {date} {time}.{ext}`
+///
+/// `/` starts a subdirectory of the save directory. A template without
+/// `{ext}` gets `.{ext}` appended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        if template.trim().is_empty() {
+            return Err("Filename template cannot be empty".to_string());
+        }
+        if template.starts_with('/') || template.ends_with('/') {
+            return Err(format!(
+                "Filename template {:?} must be a file name relative to the save directory",
+                template
+            ));
+        }
+        if template
+            .split('/')
+            .any(|part| part.is_empty() || part == "..")
+        {
+            return Err(format!(
+                "Filename template {:?} must not contain empty or .. directories",
+                template
+            ));
+        }
+
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(Part::Text(rest[..open].to_string()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .map(|close| open + close)
+                .ok_or_else(|| format!("Unclosed {{ in filename template {:?}", template))?;
+            let token = match &rest[open + 1..close] {
+                "date" => Token::Date,
+                "time" => Token::Time,
+                "source" => Token::Source,
+                "counter" => Token::Counter,
+                "ext" => Token::Ext,
+                other => {
+                    return Err(format!(
+                        "Unknown token {{{}}} in filename template; use {}",
+                        other,
+                        TOKENS.map(|token| format!("{{{}}}", token)).join(", ")
+                    ))
+                }
+            };
+            parts.push(Part::Token(token));
+            rest = &rest[close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+
+        if !parts.contains(&Part::Token(Token::Ext)) {
+            parts.push(Part::Text(".".to_string()));
+            parts.push(Part::Token(Token::Ext));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Whether collisions are resolved through `{counter}` rather than a
+    /// `-2` suffix
+    pub fn has_counter(&self) -> bool {
+        self.parts.contains(&Part::Token(Token::Counter))
+    }
+
+    /// The relative path for `values` and `counter`
+    pub fn render(&self, values: &NameValues, counter: u32) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Token(Token::Date) => values.time.date(),
+                Part::Token(Token::Time) => values.time.time_for_filename(),
+                Part::Token(Token::Source) => values.source.to_string(),
+                Part::Token(Token::Counter) => counter.to_string(),
+                Part::Token(Token::Ext) => values.extension.to_string(),
+            })
+            .collect()
+    }
+}
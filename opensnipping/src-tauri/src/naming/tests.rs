@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+
+use super::*;
+
+/// 2024-05-01T12:30:00Z
+const MAY_DAY_MS: u64 = 1_714_566_600_000;
+
+fn values() -> NameValues<'static> {
+    NameValues {
+        time: CalendarTime::utc(MAY_DAY_MS),
+        source: "window",
+        extension: "png",
+    }
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("opensnipping-naming-test-{}", uuid::Uuid::new_v4()))
+}
+
+fn render(template: &str) -> String {
+    Template::parse(template).unwrap().render(&values(), 3)
+}
+
+#[test]
+fn test_calendar_time_from_epoch() {
+    assert_eq!(
+        CalendarTime::utc(MAY_DAY_MS),
+        CalendarTime {
+            year: 2024,
+            month: 5,
+            day: 1,
+            hour: 12,
+            minute: 30,
+            second: 0,
+        }
+    );
+    assert_eq!(CalendarTime::utc(0).date(), "1970-01-01");
+    // Leap day, and the last second of a year
+    assert_eq!(CalendarTime::utc(951_782_400_000).date(), "2000-02-29");
+    let new_year_eve = CalendarTime::utc(1_704_067_199_999);
+    assert_eq!(new_year_eve.date(), "2023-12-31");
+    assert_eq!(new_year_eve.time_for_filename(), "23-59-59");
+}
+
+#[test]
+fn test_render_replaces_tokens() {
+    assert_eq!(
+        render("Screenshot {date} {time}.{ext}"),
+        "Screenshot 2024-05-01 12-30-00.png"
+    );
+    assert_eq!(render("{source}-{counter}.{ext}"), "window-3.png");
+    assert_eq!(render("{date}/{source}.{ext}"), "2024-05-01/window.png");
+}
+
+#[test]
+fn test_template_without_ext_gets_one() {
+    assert_eq!(render("shot {counter}"), "shot 3.png");
+}
+
+#[test]
+fn test_default_templates_parse_without_spaces() {
+    for kind in [OutputKind::Screenshot, OutputKind::Recording] {
+        assert!(Template::parse(kind.default_template()).is_ok());
+        assert!(!kind.default_template().contains(' '));
+    }
+}
+
+#[test]
+fn test_parse_rejects_bad_templates() {
+    for template in [
+        "",
+        "  ",
+        "/tmp/{date}",
+        "{date}/",
+        "a//b",
+        "../{date}",
+        "{date}/../b",
+        "shot {date",
+        "shot {size}",
+    ] {
+        assert!(
+            Template::parse(template).is_err(),
+            "{:?} should be rejected",
+            template
+        );
+    }
+    let err = Template::parse("{size}").unwrap_err();
+    assert!(err.contains("{counter}"), "lists the tokens: {}", err);
+}
+
+#[test]
+fn test_directory_expands_home() {
+    let naming = OutputNaming {
+        directory: Some("~/Captures".to_string()),
+        template: None,
+    };
+    if let Some(home) = dirs::home_dir() {
+        assert_eq!(
+            directory(&naming, OutputKind::Screenshot),
+            home.join("Captures")
+        );
+    }
+
+    let naming = OutputNaming::default();
+    assert_eq!(
+        directory(&naming, OutputKind::Recording),
+        OutputKind::Recording.default_directory()
+    );
+}
+
+#[test]
+fn test_free_path_creates_missing_directories() {
+    let root = temp_dir();
+    let template = Template::parse("{date}/{source}.{ext}").unwrap();
+
+    let path = free_path(&root, &template, &values(), OutputKind::Screenshot).unwrap();
+
+    assert_eq!(path, root.join("2024-05-01").join("window.png"));
+    assert!(path.is_file(), "screenshot name is reserved");
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_free_path_suffixes_taken_names() {
+    let root = temp_dir();
+    let template = Template::parse("shot.{ext}").unwrap();
+
+    let paths: Vec<_> = (0..3)
+        .map(|_| free_path(&root, &template, &values(), OutputKind::Screenshot).unwrap())
+        .collect();
+
+    assert_eq!(
+        paths,
+        [
+            root.join("shot.png"),
+            root.join("shot-2.png"),
+            root.join("shot-3.png")
+        ]
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_free_path_counts_up_with_counter() {
+    let root = temp_dir();
+    let template = Template::parse("shot-{counter}").unwrap();
+
+    let first = free_path(&root, &template, &values(), OutputKind::Screenshot).unwrap();
+    let second = free_path(&root, &template, &values(), OutputKind::Screenshot).unwrap();
+
+    assert_eq!(first, root.join("shot-1.png"));
+    assert_eq!(second, root.join("shot-2.png"));
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_recording_names_are_checked_not_created() {
+    let root = temp_dir();
+    let template = Template::parse("rec").unwrap();
+
+    let path = free_path(&root, &template, &values(), OutputKind::Recording).unwrap();
+    assert_eq!(path, root.join("rec.png"));
+    assert!(!path.exists(), "the pipeline creates recordings");
+
+    std::fs::write(&path, b"taken").unwrap();
+    let next = free_path(&root, &template, &values(), OutputKind::Recording).unwrap();
+    assert_eq!(next, root.join("rec-2.png"));
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_with_recording_path_fills_empty_path() {
+    let root = temp_dir();
+    let config = CaptureConfig {
+        naming: OutputNaming {
+            directory: Some(root.to_string_lossy().to_string()),
+            template: Some("{source} {counter}".to_string()),
+        },
+        ..Default::default()
+    };
+
+    let named = with_recording_path(config.clone()).unwrap();
+    assert_eq!(PathBuf::from(&named.output_path), root.join("screen 1.mp4"));
+
+    let explicit = CaptureConfig {
+        output_path: "/videos/rec.mp4".to_string(),
+        ..config
+    };
+    assert_eq!(
+        with_recording_path(explicit).unwrap().output_path,
+        "/videos/rec.mp4"
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_suffix_goes_before_extension() {
+    assert_eq!(with_suffix("shot.png", 1), "shot.png");
+    assert_eq!(with_suffix("shot.png", 2), "shot-2.png");
+    assert_eq!(with_suffix("a.b/shot", 2), "a.b/shot-2");
+    assert_eq!(with_suffix("dir/.hidden", 3), "dir/.hidden-3");
+}
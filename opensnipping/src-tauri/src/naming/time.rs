@@ -0,0 +1,95 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Calendar date and time of day, to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl CalendarTime {
+    /// UTC time of `epoch_ms` (Unix epoch milliseconds)
+    pub fn utc(epoch_ms: u64) -> Self {
+        Self::from_epoch_secs((epoch_ms / 1000) as i64)
+    }
+
+    /// Local time of `epoch_ms`; UTC where the local offset is unknown
+    pub fn local(epoch_ms: u64) -> Self {
+        let secs = (epoch_ms / 1000) as i64;
+        Self::from_epoch_secs(secs + local_offset_secs(secs))
+    }
+
+    /// Local time now
+    pub fn now() -> Self {
+        Self::local(now_ms())
+    }
+
+    fn from_epoch_secs(secs: i64) -> Self {
+        let (days, of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+        // Days to a civil date (Howard Hinnant's algorithm, era from 0000-03-01)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month: month as u32,
+            day: day as u32,
+            hour: (of_day / 3600) as u32,
+            minute: (of_day % 3600 / 60) as u32,
+            second: (of_day % 60) as u32,
+        }
+    }
+
+    /// `2024-05-01`
+    pub fn date(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// `12-30-00`; colons are not allowed in file names everywhere
+    pub fn time_for_filename(&self) -> String {
+        format!("{:02}-{:02}-{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+/// Unix epoch milliseconds now
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Offset of local time from UTC at `secs`, from the C library's time zone
+#[cfg(target_os = "linux")]
+fn local_offset_secs(secs: i64) -> i64 {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    // SAFETY: time and tm are valid pointers for the duration of the call
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+    tm.tm_gmtoff as i64
+}
+
+/// Local time is UTC on other platforms
+#[cfg(not(target_os = "linux"))]
+fn local_offset_secs(_secs: i64) -> i64 {
+    0
+}
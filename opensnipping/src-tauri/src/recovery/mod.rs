@@ -0,0 +1,288 @@
+use crate::capture::CaptureBackendError;
+use crate::config::{CaptureConfig, ContainerFormat, SegmentConfig};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// A recording that was started but not yet finalized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Path of the file being recorded, or the `{index}` template of a
+    /// segmented recording's files
+    pub output_path: String,
+    /// Container the file is being written in
+    pub container: ContainerFormat,
+    /// Whether the muxer was writing a crash-safe (fragmented/streamable) file
+    pub crash_safe: bool,
+    /// Process that owns the recording
+    pub pid: u32,
+    /// Start time of that process, in clock ticks after boot, to tell it
+    /// apart from a later process reusing the pid (absent off Linux and in
+    /// older entries)
+    #[serde(default)]
+    pub pid_start_time: Option<u64>,
+    /// Whether `output_path` is a segment template
+    #[serde(default)]
+    pub segmented: bool,
+    /// Segments the muxer has already finished, which need no recovery
+    #[serde(default)]
+    pub closed_segments: Vec<String>,
+}
+
+impl JournalEntry {
+    /// Entry for a recording of `config` owned by this process
+    pub fn for_config(config: &CaptureConfig) -> Self {
+        Self {
+            output_path: config.recording_path(),
+            container: config.container,
+            crash_safe: config.crash_safe,
+            pid: std::process::id(),
+            pid_start_time: process_start_time(std::process::id()),
+            segmented: config.segments.is_some(),
+            closed_segments: Vec::new(),
+        }
+    }
+
+    /// Files the recording wrote: `output_path`, or every segment up to the
+    /// first one missing
+    ///
+    /// A segmented recording that wrote nothing yields its template, which
+    /// recovery reports as missing.
+    fn files(&self) -> Vec<String> {
+        if !self.segmented {
+            return vec![self.output_path.clone()];
+        }
+        let segments: Vec<String> = (0..)
+            .map(|index| SegmentConfig::segment_path(&self.output_path, index))
+            .take_while(|path| Path::new(path).exists())
+            .collect();
+        if segments.is_empty() {
+            return vec![self.output_path.clone()];
+        }
+        segments
+    }
+}
+
+/// What happened to an orphaned recording during recovery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RecoveryOutcome {
+    /// Remuxed into a finished file at the same path
+    Remuxed,
+    /// The partial file was already playable as-is
+    AlreadyPlayable,
+    /// The output file no longer exists
+    Missing,
+    /// Remuxing failed; the partial file was left untouched
+    Failed { message: String },
+}
+
+/// Result of recovering one orphaned recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveredRecording {
+    pub path: String,
+    pub outcome: RecoveryOutcome,
+}
+
+/// On-disk journal of in-progress recordings
+///
+/// Each recording gets a small JSON file while it is running. Entries left
+/// behind by a process that is no longer alive are orphans: the app crashed
+/// or was killed before the muxer could finalize the file.
+#[derive(Debug, Clone)]
+pub struct RecordingJournal {
+    dir: PathBuf,
+}
+
+impl Default for RecordingJournal {
+    fn default() -> Self {
+        Self::new(Self::default_dir())
+    }
+}
+
+impl RecordingJournal {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$XDG_STATE_HOME/opensnipping/recordings`, or the temp dir if unset
+    pub fn default_dir() -> PathBuf {
+        dirs::state_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("opensnipping")
+            .join("recordings")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record that `entry.output_path` is being written
+    pub fn begin(&self, entry: &JournalEntry) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.json", uuid::Uuid::new_v4()));
+        write_entry(&path, entry)?;
+        Ok(path)
+    }
+
+    /// Record that the segmented recording `output_path` has finished the
+    /// segment file `segment_path`
+    pub fn segment_closed(&self, output_path: &str, segment_path: &str) -> std::io::Result<()> {
+        for (journal_path, mut entry) in self.entries()? {
+            if entry.output_path == output_path {
+                entry.closed_segments.push(segment_path.to_string());
+                write_entry(&journal_path, &entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the entries for a recording that finalized successfully
+    pub fn complete(&self, output_path: &str) -> std::io::Result<()> {
+        for (journal_path, entry) in self.entries()? {
+            if entry.output_path == output_path {
+                std::fs::remove_file(journal_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Entries whose owning process is no longer running
+    pub fn orphans(&self) -> std::io::Result<Vec<(PathBuf, JournalEntry)>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|(_, entry)| !process_is_alive(entry.pid, entry.pid_start_time))
+            .collect())
+    }
+
+    /// Every readable entry, with the path of its journal file
+    pub fn entries(&self) -> std::io::Result<Vec<(PathBuf, JournalEntry)>> {
+        let dir = match std::fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut entries = Vec::new();
+        for item in dir {
+            let path = item?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<JournalEntry>(&bytes).ok())
+            {
+                Some(entry) => entries.push((path, entry)),
+                None => warn!("Ignoring unreadable journal entry {:?}", path),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+fn write_entry(path: &Path, entry: &JournalEntry) -> std::io::Result<()> {
+    let json = serde_json::to_vec_pretty(entry)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(path, json)
+}
+
+/// Whether the process that had this pid (and start time) is still running
+fn process_is_alive(pid: u32, start_time: Option<u64>) -> bool {
+    match process_start_time(pid) {
+        // A pid reused by a later process has a later start time
+        Some(current) => start_time.is_none_or(|started| started == current),
+        // Without /proc only this process is known to be alive
+        None => pid == std::process::id(),
+    }
+}
+
+/// Start time of a running process, from field 22 of `/proc/<pid>/stat`
+fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name in field 2 may contain spaces and parentheses
+    let after_name = &stat[stat.rfind(')')? + 1..];
+    after_name.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Whether a partial file in this container plays without remuxing
+fn playable_without_remux(entry: &JournalEntry) -> bool {
+    // OGG pages are self-delimiting, so a truncated file is still valid
+    entry.container == ContainerFormat::Ogg
+}
+
+/// Whether a partial file can be remuxed at all
+fn recoverable(entry: &JournalEntry) -> bool {
+    // A plain MP4 only gets its moov atom on EOS; fragmented MP4 has one up front
+    let is_mp4 = matches!(entry.container, ContainerFormat::Mp4 | ContainerFormat::M4a);
+    entry.crash_safe || !is_mp4
+}
+
+/// Finalize every orphaned recording in the journal
+///
+/// `remux` rewrites a partial file into a finished one at the same path.
+/// Every file is reported on its own: each segment of a segmented
+/// recording is checked and remuxed, except those the muxer had already
+/// finished. Journal entries are removed whatever the outcome, so a file
+/// that cannot be recovered is only attempted once.
+pub fn recover_orphans<F>(journal: &RecordingJournal, remux: F) -> Vec<RecoveredRecording>
+where
+    F: Fn(&Path, ContainerFormat) -> Result<(), CaptureBackendError>,
+{
+    let orphans = match journal.orphans() {
+        Ok(orphans) => orphans,
+        Err(e) => {
+            warn!(
+                "Failed to read recording journal {:?}: {}",
+                journal.dir(),
+                e
+            );
+            return Vec::new();
+        }
+    };
+
+    let mut recovered = Vec::new();
+    for (journal_path, entry) in orphans {
+        for path in entry.files() {
+            let outcome = if entry.closed_segments.contains(&path) {
+                RecoveryOutcome::AlreadyPlayable
+            } else {
+                recover_file(&entry, Path::new(&path), &remux)
+            };
+            info!("Recovered orphaned recording {}: {:?}", path, outcome);
+            recovered.push(RecoveredRecording { path, outcome });
+        }
+
+        if let Err(e) = std::fs::remove_file(&journal_path) {
+            warn!("Failed to remove journal entry {:?}: {}", journal_path, e);
+        }
+    }
+    recovered
+}
+
+/// Finalize one file an orphaned recording wrote
+fn recover_file<F>(entry: &JournalEntry, path: &Path, remux: &F) -> RecoveryOutcome
+where
+    F: Fn(&Path, ContainerFormat) -> Result<(), CaptureBackendError>,
+{
+    if !path.exists() {
+        RecoveryOutcome::Missing
+    } else if playable_without_remux(entry) {
+        RecoveryOutcome::AlreadyPlayable
+    } else if !recoverable(entry) {
+        RecoveryOutcome::Failed {
+            message: "MP4 recorded without crash-safe mode has no index to recover".to_string(),
+        }
+    } else {
+        match remux(path, entry.container) {
+            Ok(()) => RecoveryOutcome::Remuxed,
+            Err(e) => RecoveryOutcome::Failed {
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,327 @@
+use super::*;
+use std::cell::RefCell;
+
+/// One above the highest pid Linux can hand out, so never a live process
+const DEAD_PID: u32 = 4_194_305;
+
+fn temp_journal() -> RecordingJournal {
+    RecordingJournal::new(
+        std::env::temp_dir().join(format!("opensnipping-journal-{}", uuid::Uuid::new_v4())),
+    )
+}
+
+fn temp_recording(ext: &str) -> String {
+    let path = std::env::temp_dir().join(format!("partial-{}.{}", uuid::Uuid::new_v4(), ext));
+    std::fs::write(&path, b"partial").unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn entry(output_path: &str, container: ContainerFormat, pid: u32) -> JournalEntry {
+    JournalEntry {
+        output_path: output_path.to_string(),
+        container,
+        crash_safe: true,
+        pid,
+        pid_start_time: None,
+        segmented: false,
+        closed_segments: Vec::new(),
+    }
+}
+
+/// Entry for a segmented recording in a fresh temp directory that wrote
+/// `written` segments
+fn segmented_entry(written: u32) -> JournalEntry {
+    let dir = std::env::temp_dir().join(format!("opensnipping-segments-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("long-{index}.mkv").to_string_lossy().to_string();
+    for index in 0..written {
+        std::fs::write(SegmentConfig::segment_path(&template, index), b"partial").unwrap();
+    }
+    JournalEntry {
+        segmented: true,
+        ..entry(&template, ContainerFormat::Mkv, DEAD_PID)
+    }
+}
+
+#[test]
+fn test_entry_for_config_is_owned_by_this_process() {
+    let config = CaptureConfig {
+        output_path: "/tmp/rec.mkv".to_string(),
+        container: ContainerFormat::Mkv,
+        crash_safe: true,
+        ..Default::default()
+    };
+    let entry = JournalEntry::for_config(&config);
+    assert_eq!(entry.output_path, "/tmp/rec.mkv");
+    assert_eq!(entry.container, ContainerFormat::Mkv);
+    assert!(entry.crash_safe);
+    assert_eq!(entry.pid, std::process::id());
+    #[cfg(target_os = "linux")]
+    assert!(entry.pid_start_time.is_some());
+}
+
+#[test]
+fn test_empty_journal_has_no_orphans() {
+    let journal = temp_journal();
+    assert!(journal.orphans().unwrap().is_empty());
+}
+
+#[test]
+fn test_live_recording_is_not_orphaned() {
+    let journal = temp_journal();
+    journal
+        .begin(&entry(
+            "/tmp/live.mp4",
+            ContainerFormat::Mp4,
+            std::process::id(),
+        ))
+        .unwrap();
+
+    assert!(journal.orphans().unwrap().is_empty());
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_dead_process_recording_is_orphaned() {
+    let journal = temp_journal();
+    let dead = entry("/tmp/crashed.mp4", ContainerFormat::Mp4, DEAD_PID);
+    journal.begin(&dead).unwrap();
+
+    let orphans = journal.orphans().unwrap();
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].1, dead);
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_reused_pid_recording_is_orphaned() {
+    let journal = temp_journal();
+    let own = JournalEntry::for_config(&CaptureConfig {
+        output_path: "/tmp/own.mkv".to_string(),
+        ..Default::default()
+    });
+    let previous_run = JournalEntry {
+        output_path: "/tmp/previous-run.mkv".to_string(),
+        pid_start_time: own.pid_start_time.map(|started| started - 1),
+        ..own.clone()
+    };
+    journal.begin(&own).unwrap();
+    journal.begin(&previous_run).unwrap();
+
+    let orphans = journal.orphans().unwrap();
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].1, previous_run);
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_entry_without_start_time_uses_pid_alone() {
+    let journal = temp_journal();
+    let legacy = format!(
+        r#"{{"output_path":"/tmp/legacy.mp4","container":"mp4","crash_safe":true,"pid":{}}}"#,
+        std::process::id()
+    );
+    std::fs::create_dir_all(journal.dir()).unwrap();
+    std::fs::write(journal.dir().join("legacy.json"), legacy).unwrap();
+
+    assert!(journal.orphans().unwrap().is_empty());
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_complete_removes_entry() {
+    let journal = temp_journal();
+    journal
+        .begin(&entry("/tmp/done.mkv", ContainerFormat::Mkv, DEAD_PID))
+        .unwrap();
+    journal
+        .begin(&entry("/tmp/other.mkv", ContainerFormat::Mkv, DEAD_PID))
+        .unwrap();
+
+    journal.complete("/tmp/done.mkv").unwrap();
+
+    let orphans = journal.orphans().unwrap();
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].1.output_path, "/tmp/other.mkv");
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_recover_remuxes_orphans_and_clears_journal() {
+    let journal = temp_journal();
+    let path = temp_recording("mp4");
+    journal
+        .begin(&entry(&path, ContainerFormat::Mp4, DEAD_PID))
+        .unwrap();
+
+    let calls = RefCell::new(Vec::new());
+    let recovered = recover_orphans(&journal, |p, container| {
+        calls.borrow_mut().push((p.to_path_buf(), container));
+        Ok(())
+    });
+
+    assert_eq!(
+        recovered,
+        vec![RecoveredRecording {
+            path: path.clone(),
+            outcome: RecoveryOutcome::Remuxed,
+        }]
+    );
+    assert_eq!(
+        calls.into_inner(),
+        vec![(PathBuf::from(&path), ContainerFormat::Mp4)]
+    );
+    assert!(journal.orphans().unwrap().is_empty());
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_recover_reports_remux_failure_once() {
+    let journal = temp_journal();
+    let path = temp_recording("mkv");
+    journal
+        .begin(&entry(&path, ContainerFormat::Mkv, DEAD_PID))
+        .unwrap();
+
+    let recovered = recover_orphans(&journal, |_, _| {
+        Err(CaptureBackendError::Internal("no moov".to_string()))
+    });
+    assert!(matches!(
+        recovered[0].outcome,
+        RecoveryOutcome::Failed { .. }
+    ));
+
+    // The partial file is kept, but not retried on the next startup
+    assert!(Path::new(&path).exists());
+    assert!(recover_orphans(&journal, |_, _| Ok(())).is_empty());
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_recover_does_not_remux_plain_mp4() {
+    let journal = temp_journal();
+    let path = temp_recording("mp4");
+    journal
+        .begin(&JournalEntry {
+            crash_safe: false,
+            ..entry(&path, ContainerFormat::Mp4, DEAD_PID)
+        })
+        .unwrap();
+
+    let recovered = recover_orphans(&journal, |_, _| panic!("should not remux"));
+    assert!(matches!(
+        recovered[0].outcome,
+        RecoveryOutcome::Failed { .. }
+    ));
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_recover_skips_missing_and_ogg_files() {
+    let journal = temp_journal();
+    let ogg = temp_recording("ogg");
+    journal
+        .begin(&entry(&ogg, ContainerFormat::Ogg, DEAD_PID))
+        .unwrap();
+    journal
+        .begin(&entry(
+            "/nonexistent/gone.mp4",
+            ContainerFormat::Mp4,
+            DEAD_PID,
+        ))
+        .unwrap();
+
+    let mut recovered = recover_orphans(&journal, |_, _| panic!("should not remux"));
+    recovered.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(recovered[0].outcome, RecoveryOutcome::Missing);
+    assert_eq!(recovered[1].outcome, RecoveryOutcome::AlreadyPlayable);
+
+    let _ = std::fs::remove_file(&ogg);
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_segmented_entry_journals_the_template() {
+    let config = CaptureConfig {
+        output_path: "/tmp/long.mkv".to_string(),
+        segments: Some(SegmentConfig {
+            max_duration_secs: Some(600),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let entry = JournalEntry::for_config(&config);
+
+    assert_eq!(entry.output_path, "/tmp/long-{index}.mkv");
+    assert!(entry.segmented);
+}
+
+#[test]
+fn test_recover_checks_every_segment() {
+    let journal = temp_journal();
+    let entry = segmented_entry(3);
+    let first = SegmentConfig::segment_path(&entry.output_path, 0);
+    journal.begin(&entry).unwrap();
+    journal.segment_closed(&entry.output_path, &first).unwrap();
+
+    let remuxed = RefCell::new(Vec::new());
+    let recovered = recover_orphans(&journal, |path, _| {
+        remuxed
+            .borrow_mut()
+            .push(path.to_string_lossy().to_string());
+        Ok(())
+    });
+
+    let segments: Vec<String> = (0..3)
+        .map(|index| SegmentConfig::segment_path(&entry.output_path, index))
+        .collect();
+    assert_eq!(
+        recovered,
+        vec![
+            RecoveredRecording {
+                path: segments[0].clone(),
+                outcome: RecoveryOutcome::AlreadyPlayable,
+            },
+            RecoveredRecording {
+                path: segments[1].clone(),
+                outcome: RecoveryOutcome::Remuxed,
+            },
+            RecoveredRecording {
+                path: segments[2].clone(),
+                outcome: RecoveryOutcome::Remuxed,
+            },
+        ]
+    );
+    assert_eq!(remuxed.into_inner(), segments[1..]);
+    assert!(journal.orphans().unwrap().is_empty());
+    let _ = std::fs::remove_dir_all(Path::new(&segments[0]).parent().unwrap());
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
+
+#[test]
+fn test_segmented_recording_without_segments_is_missing() {
+    let journal = temp_journal();
+    let entry = segmented_entry(0);
+    journal.begin(&entry).unwrap();
+
+    let recovered = recover_orphans(&journal, |_, _| panic!("should not remux"));
+
+    assert_eq!(
+        recovered,
+        vec![RecoveredRecording {
+            path: entry.output_path.clone(),
+            outcome: RecoveryOutcome::Missing,
+        }]
+    );
+    let _ = std::fs::remove_dir_all(Path::new(&entry.output_path).parent().unwrap());
+    let _ = std::fs::remove_dir_all(journal.dir());
+}
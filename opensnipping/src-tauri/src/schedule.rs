@@ -0,0 +1,223 @@
+use crate::config::CaptureConfig;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest countdown accepted by `take_screenshot` / `start_recording_video`
+pub const MAX_DELAY_SECS: u32 = 600;
+
+/// Upper bound on how long the scheduler sleeps between checks
+///
+/// Keeps scheduled captures on time when the wall clock jumps (suspend,
+/// NTP adjustments) instead of trusting one long sleep.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Source of wall-clock time, swapped out in tests
+pub trait Clock: Send + Sync {
+    /// Milliseconds since the Unix epoch
+    fn now_ms(&self) -> u64;
+}
+
+/// The real system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// A recording that starts at `start_at_ms` and stops after `duration_secs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledCapture {
+    pub id: String,
+    /// Milliseconds since the Unix epoch
+    pub start_at_ms: u64,
+    pub duration_secs: u64,
+    /// Config the recording starts with; its `max_duration_secs` is the duration
+    pub config: CaptureConfig,
+}
+
+/// Check a countdown requested for a screenshot or recording
+pub fn validate_delay(delay_secs: u32) -> Result<(), String> {
+    if delay_secs > MAX_DELAY_SECS {
+        return Err(format!("Delay must be at most {} seconds", MAX_DELAY_SECS));
+    }
+    Ok(())
+}
+
+/// Pending scheduled captures, ordered by start time
+///
+/// Pure bookkeeping: `CaptureService::supervise` polls `take_due` and starts
+/// the captures.
+#[derive(Debug, Default)]
+pub struct Scheduler<C: Clock = SystemClock> {
+    clock: C,
+    pending: Vec<ScheduledCapture>,
+}
+
+impl<C: Clock> Scheduler<C> {
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Schedule `config` to record from `start_at_ms` for `duration_secs`
+    pub fn schedule(
+        &mut self,
+        mut config: CaptureConfig,
+        start_at_ms: u64,
+        duration_secs: u64,
+    ) -> Result<ScheduledCapture, String> {
+        if start_at_ms <= self.clock.now_ms() {
+            return Err("Scheduled start time is in the past".to_string());
+        }
+        if duration_secs == 0 {
+            return Err("Scheduled duration must be at least one second".to_string());
+        }
+
+        config.max_duration_secs = Some(duration_secs);
+        let capture = ScheduledCapture {
+            id: uuid::Uuid::new_v4().to_string(),
+            start_at_ms,
+            duration_secs,
+            config,
+        };
+        let index = self
+            .pending
+            .partition_point(|pending| pending.start_at_ms <= start_at_ms);
+        self.pending.insert(index, capture.clone());
+        Ok(capture)
+    }
+
+    /// Remove a pending capture; false if it is unknown or already started
+    pub fn cancel(&mut self, id: &str) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|pending| pending.id != id);
+        self.pending.len() != before
+    }
+
+    /// Captures still waiting to start, soonest first
+    pub fn pending(&self) -> &[ScheduledCapture] {
+        &self.pending
+    }
+
+    /// Remove and return every capture whose start time has passed
+    pub fn take_due(&mut self) -> Vec<ScheduledCapture> {
+        let now = self.clock.now_ms();
+        let due = self
+            .pending
+            .partition_point(|pending| pending.start_at_ms <= now);
+        self.pending.drain(..due).collect()
+    }
+
+    /// How long until the next capture is due, capped at `POLL_INTERVAL`
+    pub fn next_wake(&self) -> Duration {
+        let now = self.clock.now_ms();
+        self.pending
+            .first()
+            .map(|next| Duration::from_millis(next.start_at_ms.saturating_sub(now)))
+            .map_or(POLL_INTERVAL, |until| until.min(POLL_INTERVAL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, Default)]
+    struct ManualClock(Arc<AtomicU64>);
+
+    impl ManualClock {
+        fn advance(&self, ms: u64) {
+            self.0.fetch_add(ms, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now_ms(&self) -> u64 {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    fn scheduler() -> (Scheduler<ManualClock>, ManualClock) {
+        let clock = ManualClock::default();
+        clock.advance(1_000_000);
+        (Scheduler::with_clock(clock.clone()), clock)
+    }
+
+    fn config() -> CaptureConfig {
+        CaptureConfig {
+            output_path: "/tmp/scheduled.mp4".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_schedule_sets_duration_limit() {
+        let (mut scheduler, _) = scheduler();
+        let capture = scheduler.schedule(config(), 1_060_000, 300).unwrap();
+        assert_eq!(capture.config.max_duration_secs, Some(300));
+        assert_eq!(scheduler.pending(), &[capture]);
+    }
+
+    #[test]
+    fn test_schedule_rejects_past_start_and_zero_duration() {
+        let (mut scheduler, _) = scheduler();
+        assert!(scheduler.schedule(config(), 1_000_000, 60).is_err());
+        assert!(scheduler.schedule(config(), 2_000_000, 0).is_err());
+        assert!(scheduler.pending().is_empty());
+    }
+
+    #[test]
+    fn test_take_due_follows_the_clock() {
+        let (mut scheduler, clock) = scheduler();
+        let late = scheduler.schedule(config(), 1_020_000, 60).unwrap();
+        let early = scheduler.schedule(config(), 1_010_000, 60).unwrap();
+        assert!(scheduler.take_due().is_empty());
+
+        clock.advance(10_000);
+        assert_eq!(scheduler.take_due(), vec![early]);
+
+        clock.advance(60_000);
+        assert_eq!(scheduler.take_due(), vec![late]);
+        assert!(scheduler.pending().is_empty());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let (mut scheduler, clock) = scheduler();
+        let capture = scheduler.schedule(config(), 1_005_000, 60).unwrap();
+        assert!(scheduler.cancel(&capture.id));
+        assert!(!scheduler.cancel(&capture.id));
+
+        clock.advance(10_000);
+        assert!(scheduler.take_due().is_empty());
+    }
+
+    #[test]
+    fn test_next_wake_is_capped() {
+        let (mut scheduler, clock) = scheduler();
+        assert_eq!(scheduler.next_wake(), POLL_INTERVAL);
+
+        scheduler.schedule(config(), 1_000_300, 60).unwrap();
+        assert_eq!(scheduler.next_wake(), Duration::from_millis(300));
+
+        clock.advance(500);
+        assert_eq!(scheduler.next_wake(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_validate_delay() {
+        assert!(validate_delay(0).is_ok());
+        assert!(validate_delay(MAX_DELAY_SECS).is_ok());
+        assert!(validate_delay(MAX_DELAY_SECS + 1).is_err());
+    }
+}
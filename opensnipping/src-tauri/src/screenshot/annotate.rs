@@ -0,0 +1,258 @@
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::CaptureBackendError;
+use crate::config::ScreenshotConfig;
+
+use super::{save_screenshot, Screenshot};
+
+/// Widest pen stroke accepted, in image pixels
+pub const MAX_STROKE_WIDTH: f32 = 200.0;
+
+/// Pen color, written `#rrggbb` or `#rrggbbaa` like the web canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StrokeColor(pub [u8; 4]);
+
+impl Default for StrokeColor {
+    fn default() -> Self {
+        Self([255, 0, 0, 255])
+    }
+}
+
+impl TryFrom<String> for StrokeColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("Invalid stroke color {:?}; use #rrggbb or #rrggbbaa", value);
+        let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut rgba = [255; 4];
+        for (channel, pair) in rgba.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            *channel = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(rgba))
+    }
+}
+
+impl From<StrokeColor> for String {
+    fn from(color: StrokeColor) -> Self {
+        let [r, g, b, a] = color.0;
+        if a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        }
+    }
+}
+
+/// Position in image pixels, from the top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// One pen stroke, drawn with round caps and joins
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stroke {
+    pub points: Vec<Point>,
+    pub color: StrokeColor,
+    /// Pen width in image pixels
+    pub width: f32,
+}
+
+/// Strokes drawn on a screenshot, with an undo stack
+///
+/// Only `strokes` is rendered; `undone` keeps what `undo` took back until
+/// the next new stroke.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Annotations {
+    pub strokes: Vec<Stroke>,
+    /// Strokes taken back by `undo`, most recent last
+    pub undone: Vec<Stroke>,
+}
+
+impl Annotations {
+    /// Add a stroke; it can no longer be followed by `redo`
+    pub fn push(&mut self, stroke: Stroke) {
+        self.strokes.push(stroke);
+        self.undone.clear();
+    }
+
+    /// Take back the last stroke; false when there is none
+    pub fn undo(&mut self) -> bool {
+        match self.strokes.pop() {
+            Some(stroke) => {
+                self.undone.push(stroke);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restore the last undone stroke; false when there is none
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(stroke) => {
+                self.strokes.push(stroke);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every stroke, including the undo history
+    pub fn clear(&mut self) {
+        self.strokes.clear();
+        self.undone.clear();
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for stroke in &self.strokes {
+            if !(stroke.width > 0.0 && stroke.width <= MAX_STROKE_WIDTH) {
+                return Err(format!(
+                    "Stroke width must be above 0 and at most {}",
+                    MAX_STROKE_WIDTH
+                ));
+            }
+            if stroke
+                .points
+                .iter()
+                .any(|point| !point.x.is_finite() || !point.y.is_finite())
+            {
+                return Err("Stroke points must be finite".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Flatten `annotations` onto a copy of `image` at its native resolution
+pub fn render_annotations(image: &RgbaImage, annotations: &Annotations) -> RgbaImage {
+    let mut rendered = image.clone();
+    for stroke in &annotations.strokes {
+        draw_stroke(&mut rendered, stroke);
+    }
+    rendered
+}
+
+/// Render `annotations` onto `screenshot` and save the result as `settings`
+/// asks
+pub fn export_annotated(
+    screenshot: &Screenshot,
+    annotations: &Annotations,
+    settings: &ScreenshotConfig,
+) -> Result<Screenshot, CaptureBackendError> {
+    save_screenshot(
+        render_annotations(&screenshot.image, annotations),
+        screenshot.info,
+        settings,
+    )
+}
+
+/// Draw one stroke, blending each pixel once so overlapping segments of a
+/// translucent stroke do not darken
+fn draw_stroke(image: &mut RgbaImage, stroke: &Stroke) {
+    let Some(first) = stroke.points.first() else {
+        return;
+    };
+    let radius = stroke.width / 2.0;
+    let reach = radius + 1.0;
+
+    // Pixels the stroke can touch, clamped to the image
+    let (min, max) = stroke
+        .points
+        .iter()
+        .fold((*first, *first), |(min, max), p| {
+            (
+                Point {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Point {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        });
+    let clamp = |value: f32, limit: u32| value.clamp(0.0, limit as f32) as u32;
+    let (x0, y0) = (
+        clamp((min.x - reach).floor(), image.width()),
+        clamp((min.y - reach).floor(), image.height()),
+    );
+    let (x1, y1) = (
+        clamp((max.x + reach).ceil(), image.width()),
+        clamp((max.y + reach).ceil(), image.height()),
+    );
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    // Coverage of each pixel in the box, 0 to 1 with a one-pixel soft edge
+    let box_width = (x1 - x0) as usize;
+    let mut coverage = vec![0.0f32; box_width * (y1 - y0) as usize];
+    let segments: Vec<(Point, Point)> = if stroke.points.len() == 1 {
+        vec![(*first, *first)]
+    } else {
+        stroke.points.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    for (a, b) in segments {
+        let sx0 = clamp((a.x.min(b.x) - reach).floor(), image.width()).max(x0);
+        let sx1 = clamp((a.x.max(b.x) + reach).ceil(), image.width()).min(x1);
+        let sy0 = clamp((a.y.min(b.y) - reach).floor(), image.height()).max(y0);
+        let sy1 = clamp((a.y.max(b.y) + reach).ceil(), image.height()).min(y1);
+        for y in sy0..sy1 {
+            for x in sx0..sx1 {
+                let center = Point {
+                    x: x as f32 + 0.5,
+                    y: y as f32 + 0.5,
+                };
+                let amount = (radius + 0.5 - distance_to_segment(center, a, b)).clamp(0.0, 1.0);
+                let cell = &mut coverage[(y - y0) as usize * box_width + (x - x0) as usize];
+                *cell = cell.max(amount);
+            }
+        }
+    }
+
+    for (i, amount) in coverage.into_iter().enumerate() {
+        if amount > 0.0 {
+            let x = x0 + (i % box_width) as u32;
+            let y = y0 + (i / box_width) as u32;
+            blend(image.get_pixel_mut(x, y), stroke.color, amount);
+        }
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq == 0.0 {
+        0.0
+    } else {
+        (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_sq).clamp(0.0, 1.0)
+    };
+    let (cx, cy) = (a.x + t * dx, a.y + t * dy);
+    ((p.x - cx).powi(2) + (p.y - cy).powi(2)).sqrt()
+}
+
+/// Source-over blend of `color` at `amount` coverage onto `pixel`
+fn blend(pixel: &mut Rgba<u8>, color: StrokeColor, amount: f32) {
+    let src_alpha = color.0[3] as f32 / 255.0 * amount;
+    let dst_alpha = pixel.0[3] as f32 / 255.0;
+    let out_alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+    if out_alpha == 0.0 {
+        return;
+    }
+    for channel in 0..3 {
+        let src = color.0[channel] as f32;
+        let dst = pixel.0[channel] as f32;
+        let out = (src * src_alpha + dst * dst_alpha * (1.0 - src_alpha)) / out_alpha;
+        pixel.0[channel] = out.round() as u8;
+    }
+    pixel.0[3] = (out_alpha * 255.0).round() as u8;
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use image::codecs::png::PngEncoder;
+use image::RgbaImage;
+
+use crate::capture::CaptureBackendError;
+
+/// MIME type of the PNG image data
+pub const PNG_MIME: &str = "image/png";
+/// MIME type of the saved file's URI
+pub const URI_LIST_MIME: &str = "text/uri-list";
+
+/// What a copy puts on the clipboard, one entry per MIME type
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardContent {
+    /// `image/png` data
+    pub png: Vec<u8>,
+    /// `text/uri-list` of the saved file, if it is offered too
+    pub uri_list: Option<String>,
+}
+
+impl ClipboardContent {
+    /// PNG of `image`, plus a URI list of `file` when given
+    ///
+    /// Always PNG whatever the screenshot format, since that is what
+    /// applications accept when pasting images.
+    pub fn new(image: &RgbaImage, file: Option<&Path>) -> Result<Self, CaptureBackendError> {
+        let mut png = Vec::new();
+        image
+            .write_with_encoder(PngEncoder::new(&mut png))
+            .map_err(|e| {
+                CaptureBackendError::Internal(format!("Failed to encode clipboard PNG: {}", e))
+            })?;
+        Ok(Self {
+            png,
+            uri_list: file.map(uri_list),
+        })
+    }
+}
+
+/// Where copied screenshots go
+///
+/// The app uses the desktop clipboard; tests and headless front ends use
+/// `HeadlessClipboard`.
+pub trait Clipboard: Send + Sync {
+    /// Replace the clipboard's content, offering every entry of `content`
+    fn set_content(&self, content: ClipboardContent) -> Result<(), CaptureBackendError>;
+}
+
+/// Shared clipboard handle held by the service
+pub type SharedClipboard = Arc<dyn Clipboard>;
+
+/// Clipboard stand-in that keeps the last content in memory
+#[derive(Debug, Default)]
+pub struct HeadlessClipboard {
+    content: Mutex<Option<ClipboardContent>>,
+}
+
+impl HeadlessClipboard {
+    /// Content of the last copy
+    pub fn content(&self) -> Option<ClipboardContent> {
+        self.content.lock().unwrap().clone()
+    }
+}
+
+impl Clipboard for HeadlessClipboard {
+    fn set_content(&self, content: ClipboardContent) -> Result<(), CaptureBackendError> {
+        *self.content.lock().unwrap() = Some(content);
+        Ok(())
+    }
+}
+
+/// `file://` URI of an absolute `path`, percent-encoding reserved bytes
+pub fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// `text/uri-list` body for one file (RFC 2483 lines end in CRLF)
+fn uri_list(path: &Path) -> String {
+    format!("{}\r\n", file_uri(path))
+}
@@ -0,0 +1,114 @@
+use image::{imageops, GenericImageView, Rgba, RgbaImage};
+
+use crate::config::{AutoCropConfig, AutoCropMode, CaptureSource};
+
+use super::Rect;
+
+/// Trim `image` as `settings` asks for a capture of `source`
+///
+/// Returns the frame untouched when auto-crop is off for this source or
+/// nothing but border was found.
+pub fn apply_auto_crop(
+    image: RgbaImage,
+    source: CaptureSource,
+    settings: &AutoCropConfig,
+) -> RgbaImage {
+    let applies = match settings.mode {
+        AutoCropMode::Off => false,
+        AutoCropMode::Windows => source == CaptureSource::Window,
+        AutoCropMode::Always => true,
+    };
+    if applies {
+        auto_crop(&image, settings)
+    } else {
+        image
+    }
+}
+
+/// Trim transparent margins and solid borders from `image`, then pad the
+/// content by `settings.padding` on every side
+pub fn auto_crop(image: &RgbaImage, settings: &AutoCropConfig) -> RgbaImage {
+    let Some(bounds) = content_bounds(image, settings.tolerance) else {
+        return image.clone();
+    };
+    let content = image
+        .view(bounds.x, bounds.y, bounds.width, bounds.height)
+        .to_image();
+    if settings.padding == 0 && settings.background.is_none() {
+        return content;
+    }
+
+    let padding = settings.padding;
+    let background = settings.background.map_or([0; 4], |color| color.0);
+    let mut padded = RgbaImage::from_pixel(
+        bounds.width + 2 * padding,
+        bounds.height + 2 * padding,
+        Rgba(background),
+    );
+    // Blends translucent corners of the content over the background
+    imageops::overlay(&mut padded, &content, padding.into(), padding.into());
+    padded
+}
+
+/// Area inside the image's transparent margins and solid borders, or
+/// `None` when the whole image is border
+///
+/// Translucent pixels (soft window shadows, rounded corners) are trimmed
+/// first; then rows and columns matching the first opaque color of the
+/// remaining area's top edge within `tolerance`.
+pub fn content_bounds(image: &RgbaImage, tolerance: u8) -> Option<Rect> {
+    let full = Rect {
+        x: 0,
+        y: 0,
+        width: image.width(),
+        height: image.height(),
+    };
+    let translucent = |pixel: &Rgba<u8>| pixel.0[3] < u8::MAX;
+    let opaque = trim(image, full, translucent)?;
+
+    // The top edge has an opaque pixel, or it would have been trimmed
+    let border = (opaque.x..opaque.x + opaque.width)
+        .map(|x| *image.get_pixel(x, opaque.y))
+        .find(|pixel| !translucent(pixel))?;
+    let solid = trim(image, opaque, |pixel| {
+        translucent(pixel)
+            || pixel
+                .0
+                .iter()
+                .zip(border.0)
+                .all(|(channel, border)| channel.abs_diff(border) <= tolerance)
+    });
+    // A uniform window (a blank page) is content, not border
+    Some(solid.unwrap_or(opaque))
+}
+
+/// Shrink `bounds` from each side while a whole edge row or column is
+/// border
+fn trim(image: &RgbaImage, bounds: Rect, is_border: impl Fn(&Rgba<u8>) -> bool) -> Option<Rect> {
+    let row =
+        |y: u32, left: u32, right: u32| (left..right).all(|x| is_border(image.get_pixel(x, y)));
+    let column =
+        |x: u32, top: u32, bottom: u32| (top..bottom).all(|y| is_border(image.get_pixel(x, y)));
+
+    let (mut left, mut right) = (bounds.x, bounds.x + bounds.width);
+    let (mut top, mut bottom) = (bounds.y, bounds.y + bounds.height);
+    while top < bottom && row(top, left, right) {
+        top += 1;
+    }
+    while bottom > top && row(bottom - 1, left, right) {
+        bottom -= 1;
+    }
+    while left < right && column(left, top, bottom) {
+        left += 1;
+    }
+    while right > left && column(right - 1, top, bottom) {
+        right -= 1;
+    }
+
+    (left < right && top < bottom).then(|| Rect {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    })
+}
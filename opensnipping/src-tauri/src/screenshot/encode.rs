@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageEncoder, ImageError, ImageResult, RgbaImage};
+use tracing::debug;
+
+use crate::capture::{CaptureBackendError, ScreenshotResult};
+use crate::config::{ScreenshotConfig, ScreenshotFormat};
+use crate::naming::{self, OutputKind};
+
+use super::metadata::EmbeddedMetadata;
+use super::{CaptureInfo, Screenshot};
+
+/// AVIF encoder speed (1 slowest and smallest, 10 fastest); a screenshot
+/// should be ready within a second or two
+const AVIF_SPEED: u8 = 8;
+
+/// Whether this build can write `format`
+///
+/// Lossy WebP needs GStreamer's `webpenc`; every other format is built in.
+pub fn is_format_available(format: ScreenshotFormat) -> bool {
+    match format {
+        ScreenshotFormat::Webp => lossy_webp_available(),
+        _ => true,
+    }
+}
+
+/// Formats this build can write, in `ScreenshotFormat::ALL` order
+pub fn available_formats() -> Vec<ScreenshotFormat> {
+    ScreenshotFormat::ALL
+        .into_iter()
+        .filter(|format| is_format_available(*format))
+        .collect()
+}
+
+/// Encode `image` to `output_path` as configured, returning the file size
+///
+/// The file carries no metadata; `save_screenshot` adds what
+/// `settings.metadata` allows about the capture.
+pub fn save_image(
+    image: &RgbaImage,
+    settings: &ScreenshotConfig,
+    output_path: &Path,
+) -> Result<u64, CaptureBackendError> {
+    write_image(image, settings, &EmbeddedMetadata::default(), output_path)
+}
+
+fn write_image(
+    image: &RgbaImage,
+    settings: &ScreenshotConfig,
+    metadata: &EmbeddedMetadata,
+    output_path: &Path,
+) -> Result<u64, CaptureBackendError> {
+    match settings.format {
+        ScreenshotFormat::Png => {
+            let compression = match settings.png_compression {
+                0 => CompressionType::Uncompressed,
+                level => CompressionType::Level(level),
+            };
+            write_file(output_path, |w| {
+                let mut png = Vec::new();
+                image.write_with_encoder(PngEncoder::new_with_quality(
+                    &mut png,
+                    compression,
+                    FilterType::Adaptive,
+                ))?;
+                w.write_all(&metadata.add_to_png(png))
+                    .map_err(ImageError::IoError)
+            })
+        }
+        ScreenshotFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = DynamicImage::ImageRgba8(image.clone()).into_rgb8();
+            write_file(output_path, |w| {
+                let mut encoder = JpegEncoder::new_with_quality(w, settings.quality);
+                let exif = metadata.exif();
+                if !exif.is_empty() {
+                    encoder
+                        .set_exif_metadata(exif)
+                        .map_err(ImageError::Unsupported)?;
+                }
+                rgb.write_with_encoder(encoder)
+            })
+        }
+        ScreenshotFormat::Webp => encode_lossy_webp(image, settings.quality, output_path),
+        ScreenshotFormat::WebpLossless => write_file(output_path, |w| {
+            image.write_with_encoder(WebPEncoder::new_lossless(w))
+        }),
+        ScreenshotFormat::Avif => write_file(output_path, |w| {
+            image.write_with_encoder(AvifEncoder::new_with_speed_quality(
+                w,
+                AVIF_SPEED,
+                settings.quality,
+            ))
+        }),
+    }?;
+
+    std::fs::metadata(output_path)
+        .map(|metadata| metadata.len())
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to read screenshot: {}", e)))
+}
+
+/// Save a grabbed frame as configured, keeping it in memory
+///
+/// The file is named as `settings.naming` asks and gets the capture
+/// details `settings.metadata` allows.
+pub fn save_screenshot(
+    image: RgbaImage,
+    info: CaptureInfo,
+    settings: &ScreenshotConfig,
+) -> Result<Screenshot, CaptureBackendError> {
+    let output_path = naming::new_output_path(
+        &settings.naming,
+        OutputKind::Screenshot,
+        info.source,
+        settings.format.extension(),
+    )
+    .map_err(CaptureBackendError::Internal)?;
+    debug!(
+        "Encoding {}x{} screenshot as {:?} to {:?}",
+        image.width(),
+        image.height(),
+        settings.format,
+        output_path
+    );
+    let metadata = EmbeddedMetadata::new(settings.metadata, &info);
+    let byte_size = write_image(&image, settings, &metadata, &output_path).inspect_err(|_| {
+        // Don't leave the reserved name behind as an empty file
+        let _ = std::fs::remove_file(&output_path);
+    })?;
+
+    let saved = ScreenshotResult {
+        path: output_path.to_string_lossy().to_string(),
+        width: image.width(),
+        height: image.height(),
+        format: settings.format,
+        byte_size,
+    };
+    Ok(Screenshot { image, info, saved })
+}
+
+/// Create `output_path` and run an `image` encoder over it
+fn write_file(
+    output_path: &Path,
+    encode: impl FnOnce(&mut BufWriter<File>) -> ImageResult<()>,
+) -> Result<(), CaptureBackendError> {
+    let file = File::create(output_path).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to create {:?}: {}", output_path, e))
+    })?;
+    let mut writer = BufWriter::new(file);
+    encode(&mut writer).map_err(|e| {
+        CaptureBackendError::Internal(format!("Failed to encode screenshot: {}", e))
+    })?;
+    writer
+        .flush()
+        .map_err(|e| CaptureBackendError::Internal(format!("Failed to write screenshot: {}", e)))
+}
+
+#[cfg(target_os = "linux")]
+fn lossy_webp_available() -> bool {
+    crate::capture::linux::webp_encoder_available()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn lossy_webp_available() -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+fn encode_lossy_webp(
+    image: &RgbaImage,
+    quality: u8,
+    output_path: &Path,
+) -> Result<(), CaptureBackendError> {
+    crate::capture::linux::encode_webp(image, quality, output_path)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn encode_lossy_webp(
+    _image: &RgbaImage,
+    _quality: u8,
+    _output_path: &Path,
+) -> Result<(), CaptureBackendError> {
+    Err(CaptureBackendError::NotSupported(
+        "Lossy WebP is not available on this platform; use webp_lossless".to_string(),
+    ))
+}
@@ -0,0 +1,175 @@
+use crate::config::{CaptureSource, MetadataPolicy};
+use crate::naming::{now_ms, CalendarTime};
+
+/// Value of the PNG `Software` keyword and the EXIF `Software` tag
+pub const SOFTWARE: &str = concat!("OpenSnipping ", env!("CARGO_PKG_VERSION"));
+
+/// PNG keyword holding the capture source
+pub const SOURCE_KEYWORD: &str = "Capture Source";
+
+const PNG_SIGNATURE_LEN: usize = 8;
+/// Length, type, 13 data bytes and CRC
+const PNG_IHDR_LEN: usize = 25;
+
+const EXIF_ASCII: u16 = 2;
+const EXIF_IMAGE_DESCRIPTION: u16 = 0x010E;
+const EXIF_SOFTWARE: u16 = 0x0131;
+const EXIF_DATE_TIME: u16 = 0x0132;
+
+/// When and from what a screenshot was grabbed
+///
+/// Kept with the frame so edits of a screenshot carry the original
+/// capture's details, not the time of the edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureInfo {
+    /// Unix epoch milliseconds
+    pub captured_at_ms: u64,
+    pub source: CaptureSource,
+}
+
+impl CaptureInfo {
+    pub fn now(source: CaptureSource) -> Self {
+        Self {
+            captured_at_ms: now_ms(),
+            source,
+        }
+    }
+}
+
+/// The capture details a policy lets into a file; empty when stripped
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct EmbeddedMetadata {
+    captured_at: Option<CalendarTime>,
+    source: Option<&'static str>,
+    software: bool,
+}
+
+impl EmbeddedMetadata {
+    pub(super) fn new(policy: MetadataPolicy, info: &CaptureInfo) -> Self {
+        let MetadataPolicy::Include(fields) = policy else {
+            return Self::default();
+        };
+        Self {
+            captured_at: fields
+                .capture_time
+                .then(|| CalendarTime::utc(info.captured_at_ms)),
+            source: fields.source.then(|| info.source.name()),
+            software: fields.app_version,
+        }
+    }
+
+    /// `(keyword, text)` pairs for PNG `tEXt` chunks
+    fn png_text(&self) -> Vec<(&'static str, String)> {
+        let mut text = Vec::new();
+        if let Some(time) = &self.captured_at {
+            text.push(("Creation Time", iso8601(time)));
+        }
+        if let Some(source) = self.source {
+            text.push((SOURCE_KEYWORD, source.to_string()));
+        }
+        if self.software {
+            text.push(("Software", SOFTWARE.to_string()));
+        }
+        text
+    }
+
+    /// TIFF-structured EXIF block (little endian, IFD0 only) for a JPEG
+    /// APP1 segment; empty when there is nothing to write
+    pub(super) fn exif(&self) -> Vec<u8> {
+        // IFD0 tags must be in ascending order
+        let mut entries = Vec::new();
+        if let Some(source) = self.source {
+            entries.push((EXIF_IMAGE_DESCRIPTION, format!("Captured from {}", source)));
+        }
+        if self.software {
+            entries.push((EXIF_SOFTWARE, SOFTWARE.to_string()));
+        }
+        if let Some(time) = &self.captured_at {
+            entries.push((EXIF_DATE_TIME, exif_date_time(time)));
+        }
+        if entries.is_empty() {
+            return Vec::new();
+        }
+
+        let header_len = 8;
+        let ifd_len = 2 + entries.len() * 12 + 4;
+        let mut data_offset = (header_len + ifd_len) as u32;
+        let mut exif = b"II\x2A\x00".to_vec();
+        exif.extend_from_slice(&(header_len as u32).to_le_bytes());
+        exif.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        let mut data = Vec::new();
+        for (tag, value) in &entries {
+            let mut bytes = value.clone().into_bytes();
+            bytes.push(0);
+            exif.extend_from_slice(&tag.to_le_bytes());
+            exif.extend_from_slice(&EXIF_ASCII.to_le_bytes());
+            exif.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            if bytes.len() <= 4 {
+                bytes.resize(4, 0);
+                exif.extend_from_slice(&bytes);
+            } else {
+                exif.extend_from_slice(&data_offset.to_le_bytes());
+                data_offset += bytes.len() as u32;
+                data.extend_from_slice(&bytes);
+            }
+        }
+        // No next IFD
+        exif.extend_from_slice(&0u32.to_le_bytes());
+        exif.extend_from_slice(&data);
+        exif
+    }
+
+    /// Insert `tEXt` chunks into an encoded PNG, right after its header
+    pub(super) fn add_to_png(&self, png: Vec<u8>) -> Vec<u8> {
+        let text = self.png_text();
+        if text.is_empty() {
+            return png;
+        }
+        let split = PNG_SIGNATURE_LEN + PNG_IHDR_LEN;
+        let mut with_text = png[..split].to_vec();
+        for (keyword, value) in text {
+            let mut data = keyword.as_bytes().to_vec();
+            data.push(0);
+            data.extend(latin1(&value));
+            with_text.extend_from_slice(&png_chunk(b"tEXt", &data));
+        }
+        with_text.extend_from_slice(&png[split..]);
+        with_text
+    }
+}
+
+/// `tEXt` is Latin-1; anything outside it becomes `?`
+fn latin1(text: &str) -> impl Iterator<Item = u8> + '_ {
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+}
+
+fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    chunk.extend_from_slice(&crc.finalize().to_be_bytes());
+    chunk
+}
+
+/// `2024-05-01T12:30:00Z`
+fn iso8601(time: &CalendarTime) -> String {
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        time.date(),
+        time.hour,
+        time.minute,
+        time.second
+    )
+}
+
+/// `2024:05:01 12:30:00`, the EXIF date format
+fn exif_date_time(time: &CalendarTime) -> String {
+    format!(
+        "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+        time.year, time.month, time.day, time.hour, time.minute, time.second
+    )
+}
@@ -0,0 +1,49 @@
+// Screenshot post-processing, independent of the capture backend
+//
+// Backends hand over each grabbed frame as an `RgbaImage`; this module
+// encodes it in the format, quality and compression of the capture's
+// `ScreenshotConfig` and keeps the decoded frame for later edits and for
+// copying to the clipboard. Annotations are flattened onto that frame here
+// too, so exports do not depend on the webview's canvas, and areas are
+// redacted (blurred, pixelated or filled) before a screenshot is shared.
+// Window shadows and solid borders can be trimmed before saving, and
+// capture details go into the file only as far as the metadata policy
+// allows.
+
+mod annotate;
+mod clipboard;
+mod crop;
+mod encode;
+mod metadata;
+mod redact;
+
+#[cfg(test)]
+mod tests;
+
+pub use annotate::{
+    export_annotated, render_annotations, Annotations, Point, Stroke, StrokeColor, MAX_STROKE_WIDTH,
+};
+pub use clipboard::{
+    file_uri, Clipboard, ClipboardContent, HeadlessClipboard, SharedClipboard, PNG_MIME,
+    URI_LIST_MIME,
+};
+pub use crop::{apply_auto_crop, auto_crop, content_bounds};
+pub use encode::{available_formats, is_format_available, save_image, save_screenshot};
+pub use metadata::{CaptureInfo, SOFTWARE, SOURCE_KEYWORD};
+pub use redact::{redact, Rect, Redaction, RedactionStyle, MAX_REDACTION_BLOCK};
+
+use image::RgbaImage;
+
+use crate::capture::ScreenshotResult;
+
+/// A grabbed frame together with the file it was saved to
+///
+/// Crop, annotate and copy operations read `image` instead of decoding
+/// the file again.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    pub image: RgbaImage,
+    /// When and from what the frame was grabbed, for file metadata
+    pub info: CaptureInfo,
+    pub saved: ScreenshotResult,
+}
@@ -0,0 +1,176 @@
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use super::StrokeColor;
+
+/// Largest blur radius or pixelate block, in image pixels
+pub const MAX_REDACTION_BLOCK: u32 = 256;
+
+/// Area of a screenshot, in image pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Part of the rectangle inside a `width`x`height` image, if any
+    fn clip(&self, width: u32, height: u32) -> Option<Rect> {
+        let x1 = self.x.saturating_add(self.width).min(width);
+        let y1 = self.y.saturating_add(self.height).min(height);
+        (self.x < x1 && self.y < y1).then(|| Rect {
+            x: self.x,
+            y: self.y,
+            width: x1 - self.x,
+            height: y1 - self.y,
+        })
+    }
+}
+
+/// How a redacted area is hidden
+///
+/// Blur and pixelate both reduce the area to averages of
+/// `radius`/`block_size`-pixel blocks first, so nothing finer than a block
+/// survives and the original cannot be recovered by deconvolution.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "style", rename_all = "snake_case")]
+pub enum RedactionStyle {
+    /// Smooth gradient between block averages
+    Blur { radius: u32 },
+    /// Flat blocks of the average color
+    Pixelate { block_size: u32 },
+    /// One solid color
+    Fill { color: StrokeColor },
+}
+
+/// One area to hide
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Redaction {
+    pub rect: Rect,
+    #[serde(flatten)]
+    pub style: RedactionStyle,
+}
+
+impl Redaction {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rect.width == 0 || self.rect.height == 0 {
+            return Err("Redaction areas must not be empty".to_string());
+        }
+        let block = match self.style {
+            RedactionStyle::Blur { radius } => radius,
+            RedactionStyle::Pixelate { block_size } => block_size,
+            RedactionStyle::Fill { .. } => return Ok(()),
+        };
+        if !(2..=MAX_REDACTION_BLOCK).contains(&block) {
+            return Err(format!(
+                "Blur radius and pixelate block size must be between 2 and {}",
+                MAX_REDACTION_BLOCK
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hide every area of `redactions` on a copy of `image`
+///
+/// Areas are clipped to the image; each one only reads its own pixels,
+/// so nothing outside it leaks in or changes.
+pub fn redact(image: &RgbaImage, redactions: &[Redaction]) -> RgbaImage {
+    let mut redacted = image.clone();
+    for redaction in redactions {
+        let Some(rect) = redaction.rect.clip(image.width(), image.height()) else {
+            continue;
+        };
+        match redaction.style {
+            RedactionStyle::Fill { color } => {
+                for (x, y) in pixels(rect) {
+                    redacted.put_pixel(x, y, Rgba(color.0));
+                }
+            }
+            RedactionStyle::Pixelate { block_size } => {
+                let blocks = BlockMeans::new(&redacted, rect, block_size);
+                for (x, y) in pixels(rect) {
+                    let mean = blocks.at((x - rect.x) / block_size, (y - rect.y) / block_size);
+                    redacted.put_pixel(x, y, to_pixel(mean));
+                }
+            }
+            RedactionStyle::Blur { radius } => {
+                let blocks = BlockMeans::new(&redacted, rect, radius);
+                for (x, y) in pixels(rect) {
+                    let mean = blocks.interpolate(x - rect.x, y - rect.y);
+                    redacted.put_pixel(x, y, to_pixel(mean));
+                }
+            }
+        }
+    }
+    redacted
+}
+
+fn pixels(rect: Rect) -> impl Iterator<Item = (u32, u32)> {
+    (rect.y..rect.y + rect.height)
+        .flat_map(move |y| (rect.x..rect.x + rect.width).map(move |x| (x, y)))
+}
+
+fn to_pixel(mean: [f32; 4]) -> Rgba<u8> {
+    Rgba(mean.map(|channel| channel.round().clamp(0.0, 255.0) as u8))
+}
+
+/// Average color of each `size`-pixel block of an area, blocks aligned to
+/// the area's top-left corner (edge blocks may be smaller)
+struct BlockMeans {
+    size: u32,
+    columns: u32,
+    rows: u32,
+    means: Vec<[f32; 4]>,
+}
+
+impl BlockMeans {
+    fn new(image: &RgbaImage, rect: Rect, size: u32) -> Self {
+        let columns = rect.width.div_ceil(size);
+        let rows = rect.height.div_ceil(size);
+        let mut sums = vec![[0.0f32; 4]; (columns * rows) as usize];
+        let mut counts = vec![0u32; sums.len()];
+        for (x, y) in pixels(rect) {
+            let index = (((y - rect.y) / size) * columns + (x - rect.x) / size) as usize;
+            for (sum, channel) in sums[index].iter_mut().zip(image.get_pixel(x, y).0) {
+                *sum += channel as f32;
+            }
+            counts[index] += 1;
+        }
+        let means = sums
+            .into_iter()
+            .zip(counts)
+            .map(|(sum, count)| sum.map(|channel| channel / count as f32))
+            .collect();
+        Self {
+            size,
+            columns,
+            rows,
+            means,
+        }
+    }
+
+    fn at(&self, column: u32, row: u32) -> [f32; 4] {
+        self.means[(row.min(self.rows - 1) * self.columns + column.min(self.columns - 1)) as usize]
+    }
+
+    /// Bilinear blend of the block means around area pixel (`x`, `y`),
+    /// treating each mean as sitting at its block's center
+    fn interpolate(&self, x: u32, y: u32) -> [f32; 4] {
+        let half = self.size as f32 / 2.0;
+        let fx = ((x as f32 + 0.5 - half) / self.size as f32).max(0.0);
+        let fy = ((y as f32 + 0.5 - half) / self.size as f32).max(0.0);
+        let (column, row) = (fx.floor() as u32, fy.floor() as u32);
+        let (tx, ty) = (fx.fract(), fy.fract());
+
+        let top = lerp(self.at(column, row), self.at(column + 1, row), tx);
+        let bottom = lerp(self.at(column, row + 1), self.at(column + 1, row + 1), tx);
+        lerp(top, bottom, ty)
+    }
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
@@ -0,0 +1,161 @@
+use std::path::Path;
+
+use super::*;
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const RED: StrokeColor = StrokeColor([255, 0, 0, 255]);
+
+fn white(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, WHITE)
+}
+
+fn stroke(points: &[(f32, f32)], color: StrokeColor, width: f32) -> Stroke {
+    Stroke {
+        points: points.iter().map(|&(x, y)| Point { x, y }).collect(),
+        color,
+        width,
+    }
+}
+
+#[test]
+fn test_annotations_round_trip_as_json() {
+    let mut annotations = Annotations::default();
+    annotations.push(stroke(&[(1.0, 2.0), (3.5, 4.0)], RED, 3.0));
+    annotations.push(stroke(&[(0.0, 0.0)], StrokeColor([0, 0, 255, 128]), 1.0));
+    annotations.undo();
+
+    let json = serde_json::to_value(&annotations).unwrap();
+    assert_eq!(json["strokes"][0]["color"], "#ff0000");
+    assert_eq!(json["strokes"][0]["points"][1]["x"], 3.5);
+    assert_eq!(json["undone"][0]["color"], "#0000ff80");
+    let parsed: Annotations = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, annotations);
+
+    // The frontend may send strokes alone
+    let strokes_only: Annotations = serde_json::from_str(
+        r##"{"strokes":[{"points":[{"x":1,"y":1}],"color":"#00FF00","width":2}]}"##,
+    )
+    .unwrap();
+    assert_eq!(strokes_only.strokes[0].color, StrokeColor([0, 255, 0, 255]));
+    assert!(strokes_only.undone.is_empty());
+}
+
+#[test]
+fn test_invalid_stroke_colors_are_rejected() {
+    for color in ["red", "#ff00", "#gg0000", "ff0000", "#ff0000ff00"] {
+        let json = format!(r#"{{"points":[],"color":"{}","width":2}}"#, color);
+        assert!(
+            serde_json::from_str::<Stroke>(&json).is_err(),
+            "{} should be rejected",
+            color
+        );
+    }
+}
+
+#[test]
+fn test_undo_redo_and_clear() {
+    let mut annotations = Annotations::default();
+    assert!(!annotations.undo());
+
+    annotations.push(stroke(&[(0.0, 0.0)], RED, 1.0));
+    annotations.push(stroke(&[(1.0, 1.0)], RED, 1.0));
+    assert!(annotations.undo());
+    assert_eq!(annotations.strokes.len(), 1);
+    assert!(annotations.redo());
+    assert_eq!(annotations.strokes.len(), 2);
+    assert!(!annotations.redo());
+
+    // A new stroke drops the redo history
+    annotations.undo();
+    annotations.push(stroke(&[(2.0, 2.0)], RED, 1.0));
+    assert!(!annotations.redo());
+
+    annotations.clear();
+    assert_eq!(annotations, Annotations::default());
+}
+
+#[test]
+fn test_validate_rejects_bad_width_and_points() {
+    for bad in [
+        stroke(&[(0.0, 0.0)], RED, 0.0),
+        stroke(&[(0.0, 0.0)], RED, MAX_STROKE_WIDTH + 1.0),
+        stroke(&[(f32::NAN, 0.0)], RED, 2.0),
+    ] {
+        let mut annotations = Annotations::default();
+        annotations.push(bad);
+        assert!(annotations.validate().is_err());
+    }
+    assert!(Annotations::default().validate().is_ok());
+}
+
+#[test]
+fn test_render_draws_stroke_at_native_resolution() {
+    let image = white(40, 20);
+    let mut annotations = Annotations::default();
+    annotations.push(stroke(&[(5.0, 10.0), (35.0, 10.0)], RED, 4.0));
+
+    let rendered = render_annotations(&image, &annotations);
+
+    assert_eq!(rendered.dimensions(), (40, 20));
+    // On the line, past its ends, and well off it
+    assert_eq!(*rendered.get_pixel(20, 9), Rgba([255, 0, 0, 255]));
+    assert_eq!(*rendered.get_pixel(20, 10), Rgba([255, 0, 0, 255]));
+    assert_eq!(*rendered.get_pixel(1, 10), WHITE);
+    assert_eq!(*rendered.get_pixel(38, 10), WHITE);
+    assert_eq!(*rendered.get_pixel(20, 2), WHITE);
+    // The source frame is untouched
+    assert_eq!(image, white(40, 20));
+}
+
+#[test]
+fn test_render_blends_translucent_stroke_once() {
+    let image = white(20, 20);
+    let mut annotations = Annotations::default();
+    // Doubles back over itself; overlapping segments must not darken
+    annotations.push(stroke(
+        &[(2.0, 10.0), (18.0, 10.0), (2.0, 10.0)],
+        StrokeColor([0, 0, 0, 128]),
+        6.0,
+    ));
+
+    let rendered = render_annotations(&image, &annotations);
+
+    let pixel = rendered.get_pixel(10, 10);
+    assert_eq!(pixel.0[3], 255);
+    assert!((126..=128).contains(&pixel.0[0]), "got {:?}", pixel);
+}
+
+#[test]
+fn test_render_single_point_and_off_image_strokes() {
+    let image = white(10, 10);
+    let mut annotations = Annotations::default();
+    annotations.push(stroke(&[(5.0, 5.0)], RED, 4.0));
+    annotations.push(stroke(&[(-50.0, -50.0), (-40.0, -40.0)], RED, 4.0));
+
+    let rendered = render_annotations(&image, &annotations);
+
+    assert_eq!(*rendered.get_pixel(5, 5), Rgba([255, 0, 0, 255]));
+    assert_eq!(*rendered.get_pixel(0, 0), WHITE);
+}
+
+#[test]
+fn test_export_annotated_saves_rendered_image() {
+    let screenshot = save_screenshot(
+        white(16, 16),
+        capture_info(),
+        &settings(ScreenshotFormat::Png),
+    )
+    .unwrap();
+    let mut annotations = Annotations::default();
+    annotations.push(stroke(&[(0.0, 8.0), (16.0, 8.0)], RED, 2.0));
+
+    let exported =
+        export_annotated(&screenshot, &annotations, &settings(ScreenshotFormat::Png)).unwrap();
+
+    assert_ne!(exported.saved.path, screenshot.saved.path);
+    let decoded = image::open(&exported.saved.path).unwrap().into_rgba8();
+    assert_eq!(decoded, exported.image);
+    assert_eq!(*decoded.get_pixel(8, 8), Rgba([255, 0, 0, 255]));
+    let _ = std::fs::remove_file(Path::new(&screenshot.saved.path));
+    let _ = std::fs::remove_file(Path::new(&exported.saved.path));
+}
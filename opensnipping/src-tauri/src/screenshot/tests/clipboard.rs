@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use super::*;
+
+#[test]
+fn test_clipboard_content_is_png_with_optional_uri_list() {
+    let image = gradient(16, 12);
+
+    let image_only = ClipboardContent::new(&image, None).unwrap();
+    let decoded = image::load_from_memory(&image_only.png)
+        .unwrap()
+        .into_rgba8();
+    assert_eq!(decoded, image);
+    assert_eq!(image_only.uri_list, None);
+
+    let with_file = ClipboardContent::new(&image, Some(Path::new("/tmp/My Shot #1.jpg"))).unwrap();
+    assert_eq!(
+        with_file.uri_list.as_deref(),
+        Some("file:///tmp/My%20Shot%20%231.jpg\r\n")
+    );
+}
+
+#[test]
+fn test_headless_clipboard_keeps_last_content() {
+    let clipboard = HeadlessClipboard::default();
+    assert_eq!(clipboard.content(), None);
+
+    for size in [4, 8] {
+        let content = ClipboardContent::new(&gradient(size, size), None).unwrap();
+        clipboard.set_content(content).unwrap();
+    }
+
+    let png = clipboard.content().unwrap().png;
+    let decoded = image::load_from_memory(&png).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (8, 8));
+}
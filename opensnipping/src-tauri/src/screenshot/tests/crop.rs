@@ -0,0 +1,147 @@
+use image::imageops;
+
+use super::*;
+use crate::config::{AutoCropConfig, AutoCropMode, CaptureSource};
+
+const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+const FRAME: Rgba<u8> = Rgba([51, 102, 153, 255]);
+
+fn area(x: u32, y: u32, width: u32, height: u32) -> Rect {
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Fixture: a window with rounded corners at `window`, casting a soft
+/// shadow that is deeper below than above, on a transparent canvas
+fn window_with_shadow(width: u32, height: u32, window: Rect) -> RgbaImage {
+    const RADIUS: f32 = 4.0;
+    let content = gradient(window.width, window.height);
+    RgbaImage::from_fn(width, height, |x, y| {
+        let (left, top) = (window.x as f32, window.y as f32);
+        let (right, bottom) = (left + window.width as f32, top + window.height as f32);
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+
+        // Distance outside the rounded window, 0 inside
+        let dx = (left + RADIUS - px).max(px - (right - RADIUS)).max(0.0);
+        let dy = (top + RADIUS - py).max(py - (bottom - RADIUS)).max(0.0);
+        let outside = (dx.hypot(dy) - RADIUS).max(0.0);
+        if outside == 0.0 {
+            return *content.get_pixel(x - window.x, y - window.y);
+        }
+        // Shadow reaches 6 pixels up and 12 down, fading out
+        let reach = if py > bottom { 12.0 } else { 6.0 };
+        let alpha = (1.0 - outside / reach).max(0.0) * 90.0;
+        Rgba([0, 0, 0, alpha as u8])
+    })
+}
+
+/// Fixture: `gradient` content inside a `thickness`-pixel solid frame
+/// whose color wobbles by up to `noise` per channel
+fn framed(width: u32, height: u32, thickness: u32, noise: u8) -> RgbaImage {
+    let content = gradient(width, height);
+    RgbaImage::from_fn(width, height, |x, y| {
+        let edge =
+            x < thickness || y < thickness || x >= width - thickness || y >= height - thickness;
+        if !edge {
+            return *content.get_pixel(x, y);
+        }
+        let wobble = ((x + y) % 2) as u8 * noise;
+        Rgba([FRAME[0] + wobble, FRAME[1], FRAME[2] - wobble, 255])
+    })
+}
+
+fn crop(mode: AutoCropMode) -> AutoCropConfig {
+    AutoCropConfig {
+        mode,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_window_shadow_and_rounded_corners_are_trimmed() {
+    let window = area(10, 8, 40, 30);
+    let image = window_with_shadow(64, 56, window);
+
+    assert_eq!(content_bounds(&image, 0), Some(window));
+
+    let cropped = auto_crop(&image, &AutoCropConfig::default());
+    assert_eq!(cropped.dimensions(), (40, 30));
+    assert_eq!(cropped.get_pixel(20, 15), image.get_pixel(30, 23));
+}
+
+#[test]
+fn test_solid_border_is_trimmed_within_tolerance() {
+    let image = framed(40, 30, 3, 6);
+
+    assert_eq!(content_bounds(&image, 8), Some(area(3, 3, 34, 24)));
+    // Below the noise, the wobbling frame is not uniform enough to trim
+    assert_eq!(content_bounds(&image, 2), Some(area(0, 0, 40, 30)));
+}
+
+#[test]
+fn test_shadow_and_frame_are_trimmed_together() {
+    let framed_window = framed(30, 20, 2, 0);
+    let mut image = RgbaImage::from_pixel(50, 40, CLEAR);
+    imageops::overlay(&mut image, &framed_window, 8, 12);
+
+    assert_eq!(content_bounds(&image, 0), Some(area(10, 14, 26, 16)));
+}
+
+#[test]
+fn test_padding_uses_background_color() {
+    let image = window_with_shadow(64, 56, area(10, 8, 40, 30));
+    let settings = AutoCropConfig {
+        padding: 5,
+        background: Some(StrokeColor([255, 255, 255, 255])),
+        ..Default::default()
+    };
+
+    let padded = auto_crop(&image, &settings);
+
+    assert_eq!(padded.dimensions(), (50, 40));
+    assert_eq!(padded.get_pixel(0, 0).0, [255, 255, 255, 255]);
+    assert_eq!(padded.get_pixel(49, 39).0, [255, 255, 255, 255]);
+    // Content sits inside the padding, unchanged
+    assert_eq!(padded.get_pixel(25, 20), image.get_pixel(30, 23));
+    // Rounded corners blend onto the background instead of staying clear
+    assert_eq!(padded.get_pixel(5, 5).0[3], 255);
+}
+
+#[test]
+fn test_padding_without_background_is_transparent() {
+    let settings = AutoCropConfig {
+        padding: 3,
+        ..Default::default()
+    };
+
+    let padded = auto_crop(&framed(20, 20, 2, 0), &settings);
+
+    assert_eq!(padded.dimensions(), (22, 22));
+    assert_eq!(*padded.get_pixel(1, 1), CLEAR);
+}
+
+#[test]
+fn test_images_without_content_are_kept() {
+    let blank = RgbaImage::from_pixel(20, 10, Rgba([255, 255, 255, 255]));
+    let clear = RgbaImage::from_pixel(20, 10, CLEAR);
+
+    // A blank page is content; an empty canvas has nothing to keep
+    assert_eq!(content_bounds(&blank, 8), Some(area(0, 0, 20, 10)));
+    assert_eq!(content_bounds(&clear, 8), None);
+    assert_eq!(auto_crop(&clear, &AutoCropConfig::default()), clear);
+}
+
+#[test]
+fn test_auto_crop_mode_selects_sources() {
+    let image = framed(40, 30, 3, 0);
+    let size = |mode, source| apply_auto_crop(image.clone(), source, &crop(mode)).dimensions();
+
+    assert_eq!(size(AutoCropMode::Off, CaptureSource::Window), (40, 30));
+    assert_eq!(size(AutoCropMode::Windows, CaptureSource::Window), (34, 24));
+    assert_eq!(size(AutoCropMode::Windows, CaptureSource::Region), (40, 30));
+    assert_eq!(size(AutoCropMode::Always, CaptureSource::Screen), (34, 24));
+}
@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use super::*;
+
+/// Encode `image` and return its file's bytes
+fn encoded(image: &RgbaImage, settings: &ScreenshotConfig) -> Vec<u8> {
+    let path = temp_path(settings.format.extension());
+    let byte_size = save_image(image, settings, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(byte_size, bytes.len() as u64);
+    bytes
+}
+
+#[test]
+fn test_png_round_trips_at_every_compression_level() {
+    let image = gradient(64, 48);
+    let sizes: Vec<_> = [0, 1, 9]
+        .into_iter()
+        .map(|png_compression| {
+            let bytes = encoded(
+                &image,
+                &ScreenshotConfig {
+                    png_compression,
+                    ..settings(ScreenshotFormat::Png)
+                },
+            );
+            let decoded = image::load_from_memory(&bytes).unwrap().into_rgba8();
+            assert_eq!(decoded, image);
+            bytes.len()
+        })
+        .collect();
+
+    // Stored is largest; level 9 is never larger than level 1
+    assert!(sizes[0] > sizes[1]);
+    assert!(sizes[2] <= sizes[1]);
+}
+
+#[test]
+fn test_jpeg_quality_changes_size() {
+    let image = gradient(64, 48);
+    let low = encoded(
+        &image,
+        &ScreenshotConfig {
+            quality: 10,
+            ..settings(ScreenshotFormat::Jpeg)
+        },
+    );
+    let high = encoded(
+        &image,
+        &ScreenshotConfig {
+            quality: 100,
+            ..settings(ScreenshotFormat::Jpeg)
+        },
+    );
+
+    assert_eq!(&low[..3], &[0xFF, 0xD8, 0xFF]);
+    assert!(low.len() < high.len());
+    let decoded = image::load_from_memory(&high).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (64, 48));
+}
+
+#[test]
+fn test_lossless_webp_round_trips() {
+    let image = gradient(32, 24);
+    let bytes = encoded(&image, &settings(ScreenshotFormat::WebpLossless));
+
+    assert_eq!(&bytes[..4], b"RIFF");
+    assert_eq!(&bytes[8..12], b"WEBP");
+    let decoded = image::load_from_memory(&bytes).unwrap().into_rgba8();
+    assert_eq!(decoded, image);
+}
+
+#[test]
+fn test_avif_writes_avif_brand() {
+    let bytes = encoded(&gradient(32, 24), &settings(ScreenshotFormat::Avif));
+
+    assert_eq!(&bytes[4..12], b"ftypavif");
+}
+
+#[test]
+fn test_builtin_formats_are_available() {
+    let formats = available_formats();
+
+    for format in [
+        ScreenshotFormat::Png,
+        ScreenshotFormat::Jpeg,
+        ScreenshotFormat::WebpLossless,
+        ScreenshotFormat::Avif,
+    ] {
+        assert!(formats.contains(&format), "{:?} should be built in", format);
+    }
+    assert_eq!(
+        formats.contains(&ScreenshotFormat::Webp),
+        is_format_available(ScreenshotFormat::Webp)
+    );
+}
+
+#[test]
+fn test_save_screenshot_keeps_frame_in_memory() {
+    let image = gradient(40, 30);
+
+    let screenshot = save_screenshot(
+        image.clone(),
+        capture_info(),
+        &settings(ScreenshotFormat::Jpeg),
+    )
+    .unwrap();
+
+    let saved = &screenshot.saved;
+    let path = Path::new(&saved.path);
+    assert_eq!(path.extension().and_then(|e| e.to_str()), Some("jpg"));
+    assert_eq!((saved.width, saved.height), (40, 30));
+    assert_eq!(saved.format, ScreenshotFormat::Jpeg);
+    assert_eq!(saved.byte_size, std::fs::metadata(path).unwrap().len());
+    assert_eq!(screenshot.image, image);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_save_image_fails_for_missing_directory() {
+    let path = std::env::temp_dir()
+        .join(format!("opensnipping-missing-{}", uuid::Uuid::new_v4()))
+        .join("shot.png");
+
+    assert!(save_image(&gradient(4, 4), &ScreenshotConfig::default(), &path).is_err());
+}
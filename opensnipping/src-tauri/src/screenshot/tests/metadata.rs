@@ -0,0 +1,209 @@
+use super::*;
+use crate::config::{MetadataFields, MetadataPolicy};
+use crate::screenshot::metadata::EmbeddedMetadata;
+
+const METADATA_CHUNKS: [&str; 5] = ["tEXt", "iTXt", "zTXt", "eXIf", "tIME"];
+
+/// `(type, data)` of every chunk of a PNG file
+fn png_chunks(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+    let mut chunks = Vec::new();
+    let mut rest = &bytes[8..];
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind = String::from_utf8(rest[4..8].to_vec()).unwrap();
+        chunks.push((kind, rest[8..8 + len].to_vec()));
+        rest = &rest[12 + len..];
+    }
+    chunks
+}
+
+/// `(keyword, text)` of every `tEXt` chunk
+fn png_text(bytes: &[u8]) -> Vec<(String, String)> {
+    png_chunks(bytes)
+        .into_iter()
+        .filter(|(kind, _)| kind == "tEXt")
+        .map(|(_, data)| {
+            let text = String::from_utf8(data).unwrap();
+            let (keyword, value) = text.split_once('\0').unwrap();
+            (keyword.to_string(), value.to_string())
+        })
+        .collect()
+}
+
+/// `(marker, payload)` of the JPEG segments before the image data
+fn jpeg_segments(bytes: &[u8]) -> Vec<(u8, Vec<u8>)> {
+    assert_eq!(&bytes[..2], &[0xFF, 0xD8]);
+    let mut segments = Vec::new();
+    let mut rest = &bytes[2..];
+    // Stop at start of scan
+    while rest[1] != 0xDA {
+        let len = u16::from_be_bytes([rest[2], rest[3]]) as usize;
+        segments.push((rest[1], rest[4..2 + len].to_vec()));
+        rest = &rest[2 + len..];
+    }
+    segments
+}
+
+/// ASCII values of the IFD0 entries of a little-endian EXIF block
+fn exif_strings(exif: &[u8]) -> Vec<(u16, String)> {
+    assert_eq!(&exif[..4], b"II\x2A\x00");
+    let u16_at = |at: usize| u16::from_le_bytes([exif[at], exif[at + 1]]);
+    let u32_at = |at: usize| u32::from_le_bytes(exif[at..at + 4].try_into().unwrap()) as usize;
+    let ifd = u32_at(4);
+    (0..u16_at(ifd) as usize)
+        .map(|index| {
+            let entry = ifd + 2 + index * 12;
+            assert_eq!(u16_at(entry + 2), 2, "ASCII type");
+            let len = u32_at(entry + 4);
+            let at = if len <= 4 {
+                entry + 8
+            } else {
+                u32_at(entry + 8)
+            };
+            let value = &exif[at..at + len - 1];
+            (u16_at(entry), String::from_utf8(value.to_vec()).unwrap())
+        })
+        .collect()
+}
+
+fn with_metadata(format: ScreenshotFormat, metadata: MetadataPolicy) -> ScreenshotConfig {
+    ScreenshotConfig {
+        metadata,
+        ..settings(format)
+    }
+}
+
+fn include_all() -> MetadataPolicy {
+    MetadataPolicy::Include(MetadataFields::default())
+}
+
+/// Save through the screenshot path and return the file's bytes
+fn saved_bytes(settings: &ScreenshotConfig) -> Vec<u8> {
+    let screenshot = save_screenshot(gradient(24, 16), capture_info(), settings).unwrap();
+    let bytes = std::fs::read(&screenshot.saved.path).unwrap();
+    let _ = std::fs::remove_file(&screenshot.saved.path);
+    assert_eq!(screenshot.saved.byte_size, bytes.len() as u64);
+    bytes
+}
+
+#[test]
+fn test_strip_writes_no_png_metadata() {
+    let bytes = saved_bytes(&with_metadata(ScreenshotFormat::Png, MetadataPolicy::Strip));
+
+    let kinds: Vec<_> = png_chunks(&bytes)
+        .into_iter()
+        .map(|(kind, _)| kind)
+        .collect();
+    assert_eq!(kinds.first().map(String::as_str), Some("IHDR"));
+    assert_eq!(kinds.last().map(String::as_str), Some("IEND"));
+    assert!(
+        kinds
+            .iter()
+            .all(|kind| !METADATA_CHUNKS.contains(&kind.as_str())),
+        "{:?}",
+        kinds
+    );
+}
+
+#[test]
+fn test_include_writes_png_text_chunks() {
+    let bytes = saved_bytes(&with_metadata(ScreenshotFormat::Png, include_all()));
+
+    assert_eq!(
+        png_text(&bytes),
+        [
+            (
+                "Creation Time".to_string(),
+                "2024-05-01T12:30:00Z".to_string()
+            ),
+            (SOURCE_KEYWORD.to_string(), "window".to_string()),
+            ("Software".to_string(), SOFTWARE.to_string()),
+        ]
+    );
+    // Chunk CRCs are checked on decode
+    let decoded = image::load_from_memory(&bytes).unwrap().into_rgba8();
+    assert_eq!(decoded, gradient(24, 16));
+}
+
+#[test]
+fn test_include_writes_only_chosen_fields() {
+    let policy = MetadataPolicy::Include(MetadataFields {
+        capture_time: false,
+        app_version: false,
+        ..Default::default()
+    });
+
+    let bytes = saved_bytes(&with_metadata(ScreenshotFormat::Png, policy));
+
+    assert_eq!(
+        png_text(&bytes),
+        [(SOURCE_KEYWORD.to_string(), "window".to_string())]
+    );
+}
+
+#[test]
+fn test_save_image_writes_no_metadata() {
+    let path = temp_path("png");
+    let settings = with_metadata(ScreenshotFormat::Png, include_all());
+
+    save_image(&gradient(8, 8), &settings, &path).unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(png_text(&bytes).is_empty());
+}
+
+#[test]
+fn test_exif_block_holds_chosen_fields() {
+    let metadata = EmbeddedMetadata::new(include_all(), &capture_info());
+
+    assert_eq!(
+        exif_strings(&metadata.exif()),
+        [
+            (0x010E, "Captured from window".to_string()),
+            (0x0131, SOFTWARE.to_string()),
+            (0x0132, "2024:05:01 12:30:00".to_string()),
+        ]
+    );
+    let stripped = EmbeddedMetadata::new(MetadataPolicy::Strip, &capture_info());
+    assert!(stripped.exif().is_empty());
+}
+
+#[test]
+fn test_jpeg_exif_follows_policy() {
+    let exif_segments = |policy| {
+        let bytes = saved_bytes(&with_metadata(ScreenshotFormat::Jpeg, policy));
+        image::load_from_memory(&bytes).unwrap();
+        jpeg_segments(&bytes)
+            .into_iter()
+            .filter(|(marker, _)| *marker == 0xE1)
+            .map(|(_, payload)| payload)
+            .collect::<Vec<_>>()
+    };
+
+    assert!(exif_segments(MetadataPolicy::Strip).is_empty());
+
+    let included = exif_segments(include_all());
+    assert_eq!(included.len(), 1);
+    assert_eq!(&included[0][..6], b"Exif\0\0");
+    let strings = exif_strings(&included[0][6..]);
+    assert!(strings.contains(&(0x0132, "2024:05:01 12:30:00".to_string())));
+}
+
+#[test]
+fn test_edits_keep_the_original_capture_details() {
+    let settings = with_metadata(ScreenshotFormat::Png, include_all());
+    let screenshot = save_screenshot(gradient(16, 16), capture_info(), &settings).unwrap();
+
+    let exported = export_annotated(&screenshot, &Annotations::default(), &settings).unwrap();
+
+    let bytes = std::fs::read(&exported.saved.path).unwrap();
+    assert_eq!(exported.info, capture_info());
+    assert!(png_text(&bytes).contains(&(
+        "Creation Time".to_string(),
+        "2024-05-01T12:30:00Z".to_string()
+    )));
+    let _ = std::fs::remove_file(&screenshot.saved.path);
+    let _ = std::fs::remove_file(&exported.saved.path);
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use image::{Rgba, RgbaImage};
+
+use super::*;
+use crate::config::{CaptureSource, ScreenshotConfig, ScreenshotFormat};
+use crate::naming::temp_naming;
+
+/// Gradient with some detail, so compression levels differ in size
+pub(super) fn gradient(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        Rgba([(x * 4) as u8, (y * 4) as u8, ((x ^ y) * 8) as u8, 255])
+    })
+}
+
+pub(super) fn temp_path(extension: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "opensnipping-encode-test-{}.{}",
+        uuid::Uuid::new_v4(),
+        extension
+    ))
+}
+
+pub(super) fn settings(format: ScreenshotFormat) -> ScreenshotConfig {
+    ScreenshotConfig {
+        format,
+        naming: temp_naming(),
+        ..Default::default()
+    }
+}
+
+/// Window capture at 2024-05-01T12:30:00Z
+pub(super) fn capture_info() -> CaptureInfo {
+    CaptureInfo {
+        captured_at_ms: 1_714_566_600_000,
+        source: CaptureSource::Window,
+    }
+}
+
+mod annotate;
+mod clipboard;
+mod crop;
+mod encode;
+mod metadata;
+mod redact;
@@ -0,0 +1,205 @@
+use super::*;
+
+const BLOCK: u32 = 4;
+
+fn area(x: u32, y: u32, width: u32, height: u32) -> Rect {
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// `image` with the pixels of every `BLOCK`-sized block of `rect` mirrored,
+/// so each block keeps its average but no pixel stays in place
+fn shuffled_blocks(image: &RgbaImage, rect: Rect) -> RgbaImage {
+    let mut shuffled = image.clone();
+    for y in rect.y..rect.y + rect.height {
+        for x in rect.x..rect.x + rect.width {
+            let block_x = rect.x + (x - rect.x) / BLOCK * BLOCK;
+            let mirrored = block_x + BLOCK - 1 - (x - block_x);
+            shuffled.put_pixel(x, y, *image.get_pixel(mirrored, y));
+        }
+    }
+    shuffled
+}
+
+/// Pixels of `rect`, row by row
+fn inside(image: &RgbaImage, rect: Rect) -> Vec<Rgba<u8>> {
+    (rect.y..rect.y + rect.height)
+        .flat_map(|y| (rect.x..rect.x + rect.width).map(move |x| *image.get_pixel(x, y)))
+        .collect()
+}
+
+fn assert_outside_unchanged(original: &RgbaImage, redacted: &RgbaImage, rect: Rect) {
+    for (x, y, pixel) in original.enumerate_pixels() {
+        let inside = (rect.x..rect.x + rect.width).contains(&x)
+            && (rect.y..rect.y + rect.height).contains(&y);
+        if !inside {
+            assert_eq!(redacted.get_pixel(x, y), pixel, "({}, {}) changed", x, y);
+        }
+    }
+}
+
+#[test]
+fn test_redaction_json_shape() {
+    let redaction: Redaction = serde_json::from_str(
+        r#"{"rect":{"x":1,"y":2,"width":3,"height":4},"style":"pixelate","block_size":8}"#,
+    )
+    .unwrap();
+    assert_eq!(redaction.rect, area(1, 2, 3, 4));
+    assert_eq!(redaction.style, RedactionStyle::Pixelate { block_size: 8 });
+
+    let fill = Redaction {
+        rect: area(0, 0, 1, 1),
+        style: RedactionStyle::Fill {
+            color: StrokeColor([0, 0, 0, 255]),
+        },
+    };
+    let json = serde_json::to_value(fill).unwrap();
+    assert_eq!(json["style"], "fill");
+    assert_eq!(json["color"], "#000000");
+}
+
+#[test]
+fn test_validate_rejects_empty_areas_and_tiny_blocks() {
+    let invalid = [
+        Redaction {
+            rect: area(0, 0, 0, 5),
+            style: RedactionStyle::Pixelate { block_size: 8 },
+        },
+        Redaction {
+            rect: area(0, 0, 5, 5),
+            style: RedactionStyle::Blur { radius: 1 },
+        },
+        Redaction {
+            rect: area(0, 0, 5, 5),
+            style: RedactionStyle::Pixelate {
+                block_size: MAX_REDACTION_BLOCK + 1,
+            },
+        },
+    ];
+    for redaction in invalid {
+        assert!(redaction.validate().is_err(), "{:?}", redaction);
+    }
+}
+
+#[test]
+fn test_fill_replaces_every_pixel() {
+    let image = gradient(32, 32);
+    let rect = area(4, 6, 10, 12);
+    let color = StrokeColor([12, 34, 56, 255]);
+    let redactions = [Redaction {
+        rect,
+        style: RedactionStyle::Fill { color },
+    }];
+
+    let redacted = redact(&image, &redactions);
+
+    assert!(inside(&redacted, rect)
+        .iter()
+        .all(|pixel| pixel.0 == color.0));
+    assert_outside_unchanged(&image, &redacted, rect);
+}
+
+#[test]
+fn test_pixelate_keeps_only_block_averages() {
+    let image = gradient(32, 32);
+    let rect = area(8, 4, 16, 12);
+    let redactions = [Redaction {
+        rect,
+        style: RedactionStyle::Pixelate { block_size: BLOCK },
+    }];
+
+    let redacted = redact(&image, &redactions);
+
+    // Each block is one flat color
+    for y in rect.y..rect.y + rect.height {
+        for x in rect.x..rect.x + rect.width {
+            let corner_x = rect.x + (x - rect.x) / BLOCK * BLOCK;
+            let corner_y = rect.y + (y - rect.y) / BLOCK * BLOCK;
+            assert_eq!(
+                redacted.get_pixel(x, y),
+                redacted.get_pixel(corner_x, corner_y)
+            );
+        }
+    }
+    // Different content with the same block averages redacts identically,
+    // so the original pixels cannot be told apart
+    let other = shuffled_blocks(&image, rect);
+    assert_ne!(inside(&other, rect), inside(&image, rect));
+    assert_eq!(
+        inside(&redact(&other, &redactions), rect),
+        inside(&redacted, rect)
+    );
+    assert_outside_unchanged(&image, &redacted, rect);
+}
+
+#[test]
+fn test_blur_is_not_reversible() {
+    let image = gradient(32, 32);
+    let rect = area(4, 4, 24, 20);
+    let redactions = [Redaction {
+        rect,
+        style: RedactionStyle::Blur { radius: BLOCK },
+    }];
+
+    let redacted = redact(&image, &redactions);
+
+    assert_ne!(inside(&redacted, rect), inside(&image, rect));
+    // Only block averages survive: content that shares them cannot be
+    // told apart, so no deconvolution brings the original back
+    let other = shuffled_blocks(&image, rect);
+    assert_ne!(inside(&other, rect), inside(&image, rect));
+    assert_eq!(
+        inside(&redact(&other, &redactions), rect),
+        inside(&redacted, rect)
+    );
+    assert_outside_unchanged(&image, &redacted, rect);
+}
+
+#[test]
+fn test_blur_is_smooth_across_blocks() {
+    // Sharp black/white columns become a gradual ramp
+    let image = RgbaImage::from_fn(16, 4, |x, _| {
+        if x < 8 {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    });
+    let redactions = [Redaction {
+        rect: area(0, 0, 16, 4),
+        style: RedactionStyle::Blur { radius: BLOCK },
+    }];
+
+    let redacted = redact(&image, &redactions);
+
+    let row: Vec<u8> = (0..16).map(|x| redacted.get_pixel(x, 1).0[0]).collect();
+    assert!(row.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", row);
+    assert!(row[7] > 0 && row[8] < 255, "{:?}", row);
+}
+
+#[test]
+fn test_areas_are_clipped_to_the_image() {
+    let image = gradient(10, 10);
+    let redactions = [
+        Redaction {
+            rect: area(6, 6, 100, 100),
+            style: RedactionStyle::Pixelate { block_size: BLOCK },
+        },
+        Redaction {
+            rect: area(50, 50, 4, 4),
+            style: RedactionStyle::Fill {
+                color: StrokeColor::default(),
+            },
+        },
+    ];
+
+    let redacted = redact(&image, &redactions);
+
+    assert_eq!(redacted.dimensions(), (10, 10));
+    assert_outside_unchanged(&image, &redacted, area(6, 6, 4, 4));
+    assert_eq!(redacted.get_pixel(9, 9), redacted.get_pixel(6, 6));
+}
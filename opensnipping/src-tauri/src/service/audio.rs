@@ -0,0 +1,62 @@
+use tracing::info;
+
+use super::{preflight_disk, CaptureService, EventSink};
+use crate::config::{CaptureConfig, CaptureMode};
+use crate::events::{CaptureEvent, RecordingStartedEvent};
+use crate::naming;
+use crate::state::{CaptureError, CaptureState, ErrorCode};
+
+impl CaptureService {
+    /// Start an audio-only recording (mic and/or system audio)
+    ///
+    /// Skips the portal picker and goes straight from Idle to Recording once
+    /// the disk check passes and the pipeline runs; a failed start leaves the
+    /// state Idle. Pause, resume and stop are the same as for video
+    /// recordings. An open capture session stays open (and unused) meanwhile.
+    pub async fn start_audio_recording(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+    ) -> Result<CaptureState, String> {
+        if let Some(error) = validate_audio_config(&config) {
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+        let config = match naming::with_recording_path(config) {
+            Ok(config) => config,
+            Err(error) => {
+                events.publish_error(&error);
+                return Err(error.message);
+            }
+        };
+
+        info!("Starting audio-only recording to {}", config.output_path);
+        let start = async {
+            preflight_disk(&config, None)?;
+            self.backend.start_audio_recording(&config).await
+        };
+        self.transition_with(events, |sm| sm.begin_audio_recording(config.clone()), start)
+            .await?;
+
+        *self.config.lock().unwrap() = Some(config.clone());
+        self.journal_started(&config);
+        events.publish(CaptureEvent::RecordingStarted(RecordingStartedEvent {
+            output_path: config.output_path,
+        }));
+        Ok(CaptureState::Recording)
+    }
+}
+
+fn validate_audio_config(config: &CaptureConfig) -> Option<CaptureError> {
+    if config.mode != CaptureMode::AudioOnly {
+        return Some(CaptureError {
+            code: ErrorCode::InvalidConfig,
+            message: "mode: start_audio_recording requires audio-only mode".to_string(),
+        });
+    }
+
+    config.validate().err().map(|err| CaptureError {
+        code: ErrorCode::InvalidConfig,
+        message: format!("{}: {}", err.field, err.message),
+    })
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use tracing::info;
+
+use super::screenshot::check_format;
+use super::{backend_error_to_capture_error, CaptureService, EventSink};
+use crate::capture::{CaptureBackendError, ScreenshotResult};
+use crate::config::ScreenshotConfig;
+use crate::screenshot::{
+    export_annotated, redact, save_screenshot, Annotations, ClipboardContent, Redaction, Screenshot,
+};
+use crate::state::{CaptureError, ErrorCode};
+
+impl CaptureService {
+    /// Flatten `annotations` onto the last screenshot at its native
+    /// resolution and save the result as `settings` asks
+    ///
+    /// The last screenshot stays as captured, so it can be exported again
+    /// with other annotations. With `copy_to_clipboard`, the export is
+    /// copied too.
+    pub async fn export_annotated_screenshot(
+        &self,
+        events: &dyn EventSink,
+        annotations: Annotations,
+        settings: ScreenshotConfig,
+    ) -> Result<ScreenshotResult, String> {
+        let exported = self
+            .edit_last_screenshot(
+                events,
+                annotations.validate(),
+                &settings,
+                move |shot, settings| export_annotated(shot, &annotations, settings),
+            )
+            .await?;
+        info!("Annotated screenshot saved to {}", exported.saved.path);
+
+        self.copy_if_configured(events, &exported, &settings).await;
+        Ok(exported.saved.clone())
+    }
+
+    /// Hide `redactions` on the last screenshot and save the result as
+    /// `settings` asks
+    ///
+    /// The redacted screenshot replaces the last one, so later copies and
+    /// annotated exports cannot reveal the hidden areas. With
+    /// `copy_to_clipboard`, it is copied too.
+    pub async fn redact_screenshot(
+        &self,
+        events: &dyn EventSink,
+        redactions: Vec<Redaction>,
+        settings: ScreenshotConfig,
+    ) -> Result<ScreenshotResult, String> {
+        let valid = redactions.iter().try_for_each(Redaction::validate);
+        let redacted = self
+            .edit_last_screenshot(events, valid, &settings, move |shot, settings| {
+                save_screenshot(redact(&shot.image, &redactions), shot.info, settings)
+            })
+            .await?;
+        info!("Redacted screenshot saved to {}", redacted.saved.path);
+        *self.last_screenshot.lock().unwrap() = Some(redacted.clone());
+
+        self.copy_if_configured(events, &redacted, &settings).await;
+        Ok(redacted.saved.clone())
+    }
+
+    /// Copy the last screenshot to the clipboard as PNG, optionally with
+    /// its saved file as a `text/uri-list`
+    pub async fn copy_screenshot(
+        &self,
+        events: &dyn EventSink,
+        with_file_uri: bool,
+    ) -> Result<(), String> {
+        let screenshot = self
+            .last_screenshot()
+            .ok_or_else(|| "No screenshot to copy".to_string())?;
+        self.copy_to_clipboard(events, screenshot, with_file_uri)
+            .await
+    }
+
+    /// Copy `screenshot`'s image (and file, with `with_file_uri`) to the
+    /// clipboard, encoding off the async runtime
+    pub async fn copy_to_clipboard(
+        &self,
+        events: &dyn EventSink,
+        screenshot: Arc<Screenshot>,
+        with_file_uri: bool,
+    ) -> Result<(), String> {
+        let clipboard = self.clipboard.lock().unwrap().clone();
+        let copied = tokio::task::spawn_blocking(move || {
+            let file = with_file_uri.then(|| Path::new(&screenshot.saved.path));
+            ClipboardContent::new(&screenshot.image, file)
+                .and_then(|content| clipboard.set_content(content))
+        })
+        .await
+        .unwrap_or_else(|e| {
+            Err(CaptureBackendError::Internal(format!(
+                "Clipboard copy failed: {}",
+                e
+            )))
+        });
+
+        match copied {
+            Ok(()) => {
+                info!("Screenshot copied to the clipboard");
+                Ok(())
+            }
+            Err(backend_err) => {
+                info!("Clipboard copy failed: {:?}", backend_err);
+                let error = backend_error_to_capture_error(&backend_err);
+                events.publish_error(&error);
+                Err(error.message)
+            }
+        }
+    }
+
+    /// Copy `screenshot` when `settings` asks for it; the file is saved
+    /// either way, so a failed copy only reports an error
+    pub(super) async fn copy_if_configured(
+        &self,
+        events: &dyn EventSink,
+        screenshot: &Arc<Screenshot>,
+        settings: &ScreenshotConfig,
+    ) {
+        if settings.copy_to_clipboard {
+            let _ = self
+                .copy_to_clipboard(events, screenshot.clone(), settings.copy_file_uri)
+                .await;
+        }
+    }
+
+    /// Run `edit` on the last screenshot off the async runtime, once
+    /// `settings` and the edit itself (`valid`) check out
+    async fn edit_last_screenshot(
+        &self,
+        events: &dyn EventSink,
+        valid: Result<(), String>,
+        settings: &ScreenshotConfig,
+        edit: impl FnOnce(&Screenshot, &ScreenshotConfig) -> Result<Screenshot, CaptureBackendError>
+            + Send
+            + 'static,
+    ) -> Result<Arc<Screenshot>, String> {
+        if let Err(message) = settings.validate().and(valid) {
+            let error = CaptureError {
+                code: ErrorCode::InvalidConfig,
+                message,
+            };
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+        check_format(events, settings)?;
+        let screenshot = self
+            .last_screenshot()
+            .ok_or_else(|| "No screenshot to edit".to_string())?;
+
+        let settings = settings.clone();
+        let edited = tokio::task::spawn_blocking(move || edit(&screenshot, &settings))
+            .await
+            .unwrap_or_else(|e| {
+                Err(CaptureBackendError::Internal(format!(
+                    "Screenshot edit failed: {}",
+                    e
+                )))
+            });
+        match edited {
+            Ok(edited) => Ok(Arc::new(edited)),
+            Err(backend_err) => {
+                info!("Screenshot edit failed: {:?}", backend_err);
+                let error = backend_error_to_capture_error(&backend_err);
+                events.publish_error(&error);
+                Err(error.message)
+            }
+        }
+    }
+}
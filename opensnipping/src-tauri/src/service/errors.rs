@@ -1,7 +1,8 @@
 use crate::capture::CaptureBackendError;
 use crate::state::{CaptureError, ErrorCode};
 
-pub(crate) fn backend_error_to_capture_error(err: &CaptureBackendError) -> CaptureError {
+/// Map a backend failure to the error reported to front ends
+pub fn backend_error_to_capture_error(err: &CaptureBackendError) -> CaptureError {
     match err {
         CaptureBackendError::PermissionDenied(msg) => CaptureError {
             code: ErrorCode::PermissionDenied,
@@ -19,6 +20,10 @@ pub(crate) fn backend_error_to_capture_error(err: &CaptureBackendError) -> Captu
             code: ErrorCode::Unknown,
             message: msg.clone(),
         },
+        CaptureBackendError::DiskFull(msg) => CaptureError {
+            code: ErrorCode::IoError,
+            message: msg.clone(),
+        },
         CaptureBackendError::Internal(msg) => CaptureError {
             code: ErrorCode::Unknown,
             message: msg.clone(),
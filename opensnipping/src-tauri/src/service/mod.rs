@@ -0,0 +1,298 @@
+// Capture orchestration, independent of Tauri
+//
+// `CaptureService` owns the state machine, the capture backend, the config,
+// the source selection, the recording journal and the scheduled captures.
+// Front ends (the Tauri commands, a CLI, tests) drive it through its async
+// methods, run its `supervise` loop in the background and receive its events
+// through an `EventSink`.
+
+mod audio;
+mod edit;
+mod errors;
+mod recording;
+mod recovery;
+mod replay;
+mod schedule;
+mod screenshot;
+mod session;
+mod sink;
+mod supervisor;
+mod timelapse;
+
+#[cfg(test)]
+mod tests;
+
+pub use errors::backend_error_to_capture_error;
+pub use recording::preflight_disk;
+pub use sink::{check_delay, run_countdown, EventSink};
+pub use timelapse::TimelapseRun;
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use image::RgbaImage;
+
+use crate::capture::{CaptureBackendError, SelectionResult, SharedBackend};
+use crate::config::CaptureConfig;
+use crate::recovery::{RecordingJournal, RecoveredRecording};
+use crate::schedule::Scheduler;
+use crate::screenshot::{HeadlessClipboard, Screenshot, SharedClipboard};
+use crate::state::{
+    CaptureError, CaptureState, StateDetail, StateMachine, TransitionError, TransitionRecord,
+};
+
+/// Snapshot of the capture in progress, for a UI rebuilding after a reload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionInfo {
+    /// Current state with its session, config and timestamps
+    pub current: StateDetail,
+    /// State a reserved transition moves to once its backend operation
+    /// succeeds
+    pub pending: Option<CaptureState>,
+    /// Last transitions, oldest first
+    pub history: Vec<TransitionRecord>,
+    /// Whether `open_capture_session` is holding a portal session
+    pub session_open: bool,
+}
+
+/// Runs captures on one backend and tracks them in a `StateMachine`
+pub struct CaptureService {
+    state_machine: Mutex<StateMachine>,
+    config: Mutex<Option<CaptureConfig>>,
+    /// Result from portal selection (PipeWire node info)
+    selection: Mutex<Option<SelectionResult>>,
+    backend: SharedBackend,
+    /// Whether an open capture session keeps `selection` and the backend's
+    /// portal session for later captures
+    session_open: Mutex<bool>,
+    /// Whether the capture in progress opened the backend's portal session
+    /// itself, and so has to close it when it ends
+    owns_portal_session: Mutex<bool>,
+    /// Last screenshot taken, still decoded
+    last_screenshot: Mutex<Option<Arc<Screenshot>>>,
+    /// Where copied screenshots go; headless until the app installs the
+    /// desktop clipboard
+    clipboard: Mutex<SharedClipboard>,
+    /// Journal of in-progress recordings, used to recover them after a crash
+    journal: RecordingJournal,
+    /// Recordings recovered from the journal at startup
+    recovered: Mutex<Vec<RecoveredRecording>>,
+    /// Captures scheduled to start later
+    scheduler: Mutex<Scheduler>,
+}
+
+impl CaptureService {
+    /// Service capturing through `backend` and journaling recordings in
+    /// `journal`
+    pub fn new(backend: SharedBackend, journal: RecordingJournal) -> Self {
+        Self {
+            state_machine: Mutex::new(StateMachine::new()),
+            config: Mutex::new(None),
+            selection: Mutex::new(None),
+            backend,
+            session_open: Mutex::new(false),
+            owns_portal_session: Mutex::new(false),
+            last_screenshot: Mutex::new(None),
+            clipboard: Mutex::new(Arc::new(HeadlessClipboard::default())),
+            journal,
+            recovered: Mutex::new(Vec::new()),
+            scheduler: Mutex::new(Scheduler::default()),
+        }
+    }
+
+    pub fn backend(&self) -> &SharedBackend {
+        &self.backend
+    }
+
+    /// Copy screenshots to `clipboard` from now on
+    pub fn set_clipboard(&self, clipboard: SharedClipboard) {
+        *self.clipboard.lock().unwrap() = clipboard;
+    }
+
+    pub fn state(&self) -> CaptureState {
+        self.state_machine.lock().unwrap().state()
+    }
+
+    /// Current state, its payload and the recent transitions
+    pub fn session_info(&self) -> SessionInfo {
+        let sm = self.state_machine.lock().unwrap();
+        SessionInfo {
+            current: sm.detail().clone(),
+            pending: sm.pending(),
+            history: sm.history().records().cloned().collect(),
+            session_open: self.session_is_open(),
+        }
+    }
+
+    /// Config of the capture in progress
+    pub fn config(&self) -> Option<CaptureConfig> {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Source picked for the capture in progress or the open session
+    pub fn selection(&self) -> Option<SelectionResult> {
+        self.selection.lock().unwrap().clone()
+    }
+
+    /// Last screenshot taken, for crop, annotate and copy operations
+    pub fn last_screenshot(&self) -> Option<Arc<Screenshot>> {
+        self.last_screenshot.lock().unwrap().clone()
+    }
+
+    /// Apply `step` to the state machine and publish the change
+    pub fn transition(
+        &self,
+        events: &dyn EventSink,
+        step: impl FnOnce(&mut StateMachine) -> Result<CaptureState, TransitionError>,
+    ) -> Result<CaptureState, String> {
+        let mut sm = self.state_machine.lock().unwrap();
+        let previous = sm.state();
+        let current = step(&mut sm).map_err(|e| e.message)?;
+        events.publish_state_change(previous, current);
+        Ok(current)
+    }
+
+    /// Reserve the transition `step` makes until `settle` is called
+    ///
+    /// Other commands cannot move the state machine meanwhile.
+    pub fn reserve(
+        &self,
+        step: impl FnOnce(&mut StateMachine) -> Result<CaptureState, TransitionError>,
+    ) -> Result<CaptureState, String> {
+        self.state_machine
+            .lock()
+            .unwrap()
+            .begin_pending(step)
+            .map_err(|e| e.message)
+    }
+
+    /// Commit the reserved transition if `outcome` succeeded
+    ///
+    /// On failure the reservation is rolled back, leaving the state where it
+    /// was, and the backend error is published.
+    pub fn settle<T>(
+        &self,
+        events: &dyn EventSink,
+        outcome: Result<T, CaptureBackendError>,
+    ) -> Result<T, String> {
+        let mut sm = self.state_machine.lock().unwrap();
+        match outcome {
+            Ok(value) => {
+                let previous = sm.state();
+                let current = sm.commit().map_err(|e| e.message)?;
+                events.publish_state_change(previous, current);
+                Ok(value)
+            }
+            Err(backend_err) => {
+                sm.rollback();
+                let error = backend_error_to_capture_error(&backend_err);
+                events.publish_error(&error);
+                Err(error.message)
+            }
+        }
+    }
+
+    /// Move as `step` does only once `operation` succeeds
+    ///
+    /// See `reserve` and `settle`.
+    pub async fn transition_with<T>(
+        &self,
+        events: &dyn EventSink,
+        step: impl FnOnce(&mut StateMachine) -> Result<CaptureState, TransitionError>,
+        operation: impl Future<Output = Result<T, CaptureBackendError>>,
+    ) -> Result<T, String> {
+        self.reserve(step)?;
+        self.settle(events, operation.await)
+    }
+
+    /// Move to Error, publish `error` and return its message
+    pub fn fail(&self, events: &dyn EventSink, error: CaptureError) -> String {
+        self.state_machine.lock().unwrap().set_error(error.clone());
+        events.publish_error(&error);
+        error.message
+    }
+
+    /// Cancel source selection (Selecting → Idle)
+    pub fn cancel_capture(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        let new_state = self.transition(events, StateMachine::cancel_selection)?;
+        self.clear_capture();
+        Ok(new_state)
+    }
+
+    /// Finalizing → Idle, forgetting the finished capture
+    pub fn finalize_complete(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        let new_state = self.transition(events, StateMachine::finalize_complete)?;
+        self.clear_capture();
+        Ok(new_state)
+    }
+
+    /// Error → Idle
+    pub fn reset_error(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        self.transition(events, StateMachine::reset)
+    }
+
+    /// Whether `open_capture_session` is holding a session
+    pub fn session_is_open(&self) -> bool {
+        *self.session_open.lock().unwrap()
+    }
+
+    /// Forget the finished capture's config, and its selection unless a
+    /// session keeps it for the next capture
+    pub fn clear_capture(&self) {
+        *self.config.lock().unwrap() = None;
+        if !self.session_is_open() {
+            *self.selection.lock().unwrap() = None;
+        }
+    }
+
+    /// The open session's selection, reused by a new capture instead of the
+    /// picker
+    ///
+    /// Returns `None` without a session; call `release_session` when the
+    /// capture ends.
+    pub fn session_selection(&self) -> Option<SelectionResult> {
+        if !self.session_is_open() {
+            return None;
+        }
+        self.selection()
+    }
+
+    /// The open session's selection, or else a new one from the picker
+    ///
+    /// A picked source's portal session belongs to the capture; call
+    /// `release_session` when it ends.
+    pub async fn select_source(
+        &self,
+        config: &CaptureConfig,
+    ) -> Result<SelectionResult, CaptureBackendError> {
+        if let Some(selection) = self.session_selection() {
+            return Ok(selection);
+        }
+        let selection = self.backend.request_selection(config).await?;
+        *self.owns_portal_session.lock().unwrap() = true;
+        Ok(selection)
+    }
+
+    /// End a finished capture's portal session if the capture owns it
+    ///
+    /// Ownership is taken under its lock, so the session is closed once
+    /// however many callers release it.
+    pub async fn release_session(&self) {
+        let owned = std::mem::take(&mut *self.owns_portal_session.lock().unwrap());
+        if owned {
+            self.backend.close_session().await;
+        }
+    }
+
+    /// Grab a frame from the open session's stream
+    pub async fn session_screenshot(&self) -> Result<RgbaImage, CaptureBackendError> {
+        match self.selection() {
+            Some(selection) => self.backend.capture_screenshot(&selection).await,
+            None => Err(CaptureBackendError::Internal(
+                "Capture session has no stream".to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use tracing::{debug, info, warn};
+
+use super::{backend_error_to_capture_error, CaptureService, EventSink};
+use crate::capture::{CaptureBackendError, RecordingResult, SelectionResult};
+use crate::config::{CaptureConfig, CaptureMode};
+use crate::disk;
+use crate::events::{
+    CaptureEvent, RecordingStartedEvent, RecordingStoppedEvent, SelectionCompleteEvent, StopReason,
+};
+use crate::naming;
+use crate::state::{CaptureError, CaptureState, ErrorCode, StateMachine};
+
+impl CaptureService {
+    /// Pick a source and start recording it (Idle → Selecting → Recording)
+    ///
+    /// Recording starts right after the picker, while the PipeWire stream is
+    /// still valid. An open capture session is used instead of the picker.
+    /// Only video mode goes through here; other modes have their own start.
+    pub async fn start_capture(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+    ) -> Result<CaptureState, String> {
+        if let Err(err) = config.validate() {
+            let error = CaptureError {
+                code: ErrorCode::InvalidConfig,
+                message: format!("{}: {}", err.field, err.message),
+            };
+            return Err(self.fail(events, error));
+        }
+
+        let wrong_command = match config.mode {
+            CaptureMode::Video => None,
+            CaptureMode::AudioOnly => Some("start_audio_recording for audio-only capture"),
+            CaptureMode::ReplayBuffer => Some("start_replay_buffer for replay buffer mode"),
+            CaptureMode::Timelapse => Some("start_timelapse for timelapse mode"),
+        };
+        if let Some(command) = wrong_command {
+            let error = CaptureError {
+                code: ErrorCode::InvalidConfig,
+                message: format!("mode: use {}", command),
+            };
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+        let config = match naming::with_recording_path(config) {
+            Ok(config) => config,
+            Err(error) => return Err(self.fail(events, error)),
+        };
+
+        *self.config.lock().unwrap() = Some(config.clone());
+        self.transition(events, |sm| sm.start_selecting(config.clone()))?;
+
+        info!("Starting portal selection...");
+        let selection = match self.select_source(&config).await {
+            Ok(selection) => selection,
+            Err(backend_err) => {
+                info!("Portal selection failed: {:?}", backend_err);
+                let message = self.fail(events, backend_error_to_capture_error(&backend_err));
+                *self.config.lock().unwrap() = None;
+                return Err(message);
+            }
+        };
+        info!("Portal selection successful: node_id={}", selection.node_id);
+        *self.selection.lock().unwrap() = Some(selection.clone());
+
+        events.publish(CaptureEvent::SelectionComplete(SelectionCompleteEvent {
+            selection,
+        }));
+
+        self.start_recording(events).await
+    }
+
+    /// Start recording the stored selection (Selecting → Recording)
+    ///
+    /// The state machine only moves once the disk check passes and the
+    /// backend pipeline is running. If either fails, its error is published
+    /// once, the portal session is released and the capture is cancelled
+    /// (back to Idle); outside Selecting the call is rejected without
+    /// touching anything.
+    pub async fn start_recording(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        let config = self
+            .config()
+            .ok_or_else(|| "No capture config set. Call start_capture first.".to_string())?;
+        let selection = self
+            .selection()
+            .ok_or_else(|| "No selection available. Call start_capture first.".to_string())?;
+
+        self.reserve(StateMachine::begin_recording)?;
+        debug!("Starting recording to {}", config.output_path);
+        let start = async {
+            preflight_disk(&config, Some(&selection))?;
+            self.backend.start_recording(&selection, &config).await
+        };
+        match self.settle(events, start.await) {
+            Ok(()) => {
+                info!("Recording started: {}", config.output_path);
+                self.journal_started(&config);
+                events.publish(CaptureEvent::RecordingStarted(RecordingStartedEvent {
+                    output_path: config.output_path.clone(),
+                }));
+                Ok(CaptureState::Recording)
+            }
+            Err(message) => {
+                // settle has already published the error
+                info!("Failed to start recording: {}", message);
+                self.release_session().await;
+                let _ = self.cancel_capture(events);
+                Err(message)
+            }
+        }
+    }
+
+    /// Pause the recording pipeline (Recording → Paused)
+    ///
+    /// Stays Recording when the backend fails to pause. A timelapse has no
+    /// pipeline; its capture loop just skips frames while Paused.
+    pub async fn pause_recording(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        if self.timelapse_running() {
+            return self.transition(events, StateMachine::pause);
+        }
+        self.transition_with(events, StateMachine::pause, self.backend.pause_recording())
+            .await?;
+        Ok(CaptureState::Paused)
+    }
+
+    /// Resume the recording pipeline (Paused → Recording)
+    ///
+    /// Stays Paused when the backend fails to resume. A timelapse resumes
+    /// its schedule without a backend call.
+    pub async fn resume_recording(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        if self.timelapse_running() {
+            return self.transition(events, StateMachine::resume);
+        }
+        self.transition_with(
+            events,
+            StateMachine::resume,
+            self.backend.resume_recording(),
+        )
+        .await?;
+        Ok(CaptureState::Recording)
+    }
+
+    /// Stop and finalize the recording (Recording/Paused → Finalizing → Idle)
+    ///
+    /// Shared by the stop commands and the automatic stops (limits, low disk
+    /// space, portal closed). Publishes `capture:recording_stopped` with
+    /// `reason` and drops the recording's journal entry. When the backend
+    /// fails to stop, the state rolls back to Recording or Paused and the
+    /// portal session is kept, so the stop can be retried.
+    pub async fn stop_recording(
+        &self,
+        events: &dyn EventSink,
+        reason: StopReason,
+    ) -> Result<RecordingResult, String> {
+        let config = self.config();
+        let mut result = self
+            .transition_with(events, StateMachine::stop, self.backend.stop_recording())
+            .await?;
+        // Backends time the whole recording; report only the active part
+        let paused_ms = self.state_machine.lock().unwrap().detail().paused_ms();
+        result.duration_ms = result.duration_ms.saturating_sub(paused_ms.unwrap_or(0));
+        info!(
+            "Recording stopped: {} ({} ms, {:?})",
+            result.path, result.duration_ms, reason
+        );
+        if let Some(config) = config {
+            self.journal_finished(&config);
+        }
+
+        self.release_session().await;
+        self.finalize_complete(events)?;
+        events.publish(CaptureEvent::RecordingStopped(RecordingStoppedEvent {
+            path: result.path.clone(),
+            duration_ms: result.duration_ms,
+            width: result.width,
+            height: result.height,
+            reason,
+        }));
+        Ok(result)
+    }
+}
+
+/// Refuse to start a recording the disk cannot hold for a minute
+///
+/// Fails with `CaptureBackendError::DiskFull`. When free space cannot be
+/// queried the recording is allowed to start.
+pub fn preflight_disk(
+    config: &CaptureConfig,
+    selection: Option<&SelectionResult>,
+) -> Result<(), CaptureBackendError> {
+    let bytes_per_sec = disk::estimate_for_selection(config, selection);
+
+    let dir = disk::output_dir(config);
+    match disk::free_space_bytes(&dir) {
+        Ok(free_bytes) => {
+            disk::preflight(free_bytes, bytes_per_sec).map_err(CaptureBackendError::DiskFull)
+        }
+        Err(e) => {
+            warn!("Could not check free space in {:?}: {}", dir, e);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use super::{CaptureService, EventSink};
+use crate::capture::{CaptureBackendError, RecordingSegment};
+use crate::config::{CaptureConfig, ContainerFormat};
+use crate::events::{CaptureEvent, RecordingsRecoveredEvent, SegmentClosedEvent};
+use crate::recovery::{recover_orphans, JournalEntry, RecordingJournal, RecoveredRecording};
+
+impl CaptureService {
+    /// Journal of the recordings in progress
+    pub fn journal(&self) -> &RecordingJournal {
+        &self.journal
+    }
+
+    /// Finalize the recordings a previous run left in the journal
+    ///
+    /// Blocks while remuxing, so front ends call it off their main thread.
+    /// The result is kept for `recovered_recordings` and, when at least one
+    /// orphan was found, published as `capture:recordings_recovered`.
+    pub fn recover_interrupted_recordings(&self, events: &dyn EventSink) {
+        let recovered = recover_orphans(&self.journal, remux);
+        if recovered.is_empty() {
+            return;
+        }
+        info!("Recovered {} interrupted recording(s)", recovered.len());
+        self.recovered
+            .lock()
+            .unwrap()
+            .extend(recovered.iter().cloned());
+        events.publish(CaptureEvent::RecordingsRecovered(
+            RecordingsRecoveredEvent {
+                recordings: recovered,
+            },
+        ));
+    }
+
+    /// Recordings recovered at startup after a crash
+    pub fn recovered_recordings(&self) -> Vec<RecoveredRecording> {
+        self.recovered.lock().unwrap().clone()
+    }
+
+    /// Journal and publish a file a segmented recording has finished, so
+    /// recovery leaves it alone
+    ///
+    /// Front ends call this from the backend's `on_segment_closed` callback.
+    pub fn segment_closed(&self, events: &dyn EventSink, segment: &RecordingSegment) {
+        if let Some(config) = self.config() {
+            let recording_path = config.recording_path();
+            if let Err(e) = self.journal.segment_closed(&recording_path, &segment.path) {
+                warn!("Failed to journal segment {}: {}", segment.path, e);
+            }
+        }
+        events.publish(CaptureEvent::SegmentClosed(SegmentClosedEvent {
+            segment: segment.clone(),
+        }));
+    }
+
+    /// Journal a recording that just started so a crash can be recovered
+    pub(super) fn journal_started(&self, config: &CaptureConfig) {
+        if let Err(e) = self.journal.begin(&JournalEntry::for_config(config)) {
+            warn!("Failed to journal recording {}: {}", config.output_path, e);
+        }
+    }
+
+    /// Drop the journal entry of a recording of `config` that finalized
+    /// normally
+    pub(super) fn journal_finished(&self, config: &CaptureConfig) {
+        let recording_path = config.recording_path();
+        if let Err(e) = self.journal.complete(&recording_path) {
+            warn!("Failed to clear journal for {}: {}", recording_path, e);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn remux(path: &Path, container: ContainerFormat) -> Result<(), CaptureBackendError> {
+    crate::capture::linux::remux_recording(path, container)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn remux(_path: &Path, _container: ContainerFormat) -> Result<(), CaptureBackendError> {
+    Err(CaptureBackendError::NotSupported(
+        "Recording recovery not implemented for this platform".to_string(),
+    ))
+}
@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use tracing::info;
+
+use super::{backend_error_to_capture_error, CaptureService, EventSink};
+use crate::capture::RecordingResult;
+use crate::config::{CaptureConfig, CaptureMode};
+use crate::events::{CaptureEvent, ReplaySavedEvent, SelectionCompleteEvent};
+use crate::naming::{self, OutputKind};
+use crate::state::{CaptureError, CaptureState, ErrorCode, StateMachine};
+
+impl CaptureService {
+    /// Pick a source and start keeping its last `replay_buffer_secs` in memory
+    ///
+    /// Goes Idle → Buffering once the picker and the buffer pipeline have
+    /// both succeeded; on failure the state stays Idle. An open capture
+    /// session is used instead of the picker. Nothing is written to disk
+    /// until `save_replay`.
+    pub async fn start_replay_buffer(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+    ) -> Result<CaptureState, String> {
+        if let Some(error) = validate_replay_config(&config) {
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+
+        info!("Starting replay buffer...");
+        let start = async {
+            let selection = self.select_source(&config).await?;
+            self.backend
+                .start_replay_buffer(&selection, &config)
+                .await?;
+            Ok(selection)
+        };
+        let step = |sm: &mut StateMachine| {
+            sm.start_selecting(config.clone())?;
+            sm.begin_replay_buffer()
+        };
+        let selection = match self.transition_with(events, step, start).await {
+            Ok(selection) => selection,
+            Err(message) => {
+                info!("Replay buffer start failed: {}", message);
+                self.release_session().await;
+                return Err(message);
+            }
+        };
+
+        *self.selection.lock().unwrap() = Some(selection.clone());
+        *self.config.lock().unwrap() = Some(config);
+        events.publish(CaptureEvent::SelectionComplete(SelectionCompleteEvent {
+            selection,
+        }));
+        Ok(CaptureState::Buffering)
+    }
+
+    /// Write the buffered replay to `output_path`; buffering continues
+    ///
+    /// Without a path, each save gets a new file named from the buffer's
+    /// `naming`. A failed save leaves the buffer running, so the state stays
+    /// Buffering either way.
+    pub async fn save_replay(
+        &self,
+        events: &dyn EventSink,
+        output_path: Option<String>,
+    ) -> Result<RecordingResult, String> {
+        if self.state() != CaptureState::Buffering {
+            return Err("Replay buffer is not running".to_string());
+        }
+        let output_path = match output_path.filter(|path| !path.is_empty()) {
+            Some(path) => path,
+            None => {
+                let config = self.config().unwrap_or_default();
+                let named = naming::new_output_path(
+                    &config.naming,
+                    OutputKind::Recording,
+                    config.source,
+                    config.container.extension(),
+                );
+                match named {
+                    Ok(path) => path.to_string_lossy().to_string(),
+                    Err(message) => {
+                        let error = CaptureError {
+                            code: ErrorCode::IoError,
+                            message,
+                        };
+                        events.publish_error(&error);
+                        return Err(error.message);
+                    }
+                }
+            }
+        };
+
+        match self.backend.save_replay(Path::new(&output_path)).await {
+            Ok(result) => {
+                info!("Replay saved: {} ({} ms)", result.path, result.duration_ms);
+                events.publish(CaptureEvent::ReplaySaved(ReplaySavedEvent {
+                    path: result.path.clone(),
+                    duration_ms: result.duration_ms,
+                    width: result.width,
+                    height: result.height,
+                }));
+                Ok(result)
+            }
+            Err(backend_err) => {
+                let error = backend_error_to_capture_error(&backend_err);
+                events.publish_error(&error);
+                Err(error.message)
+            }
+        }
+    }
+
+    /// Stop the replay buffer and discard anything not yet saved
+    /// (Buffering → Idle)
+    ///
+    /// When the backend fails to stop, the state stays Buffering and the
+    /// error is published, so the stop can be retried.
+    pub async fn stop_replay_buffer(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        self.transition_with(
+            events,
+            StateMachine::stop_replay_buffer,
+            self.backend.stop_replay_buffer(),
+        )
+        .await?;
+
+        self.release_session().await;
+        self.clear_capture();
+        Ok(CaptureState::Idle)
+    }
+}
+
+fn validate_replay_config(config: &CaptureConfig) -> Option<CaptureError> {
+    if config.mode != CaptureMode::ReplayBuffer {
+        return Some(CaptureError {
+            code: ErrorCode::InvalidConfig,
+            message: "mode: start_replay_buffer requires replay buffer mode".to_string(),
+        });
+    }
+
+    config.validate().err().map(|err| CaptureError {
+        code: ErrorCode::InvalidConfig,
+        message: format!("{}: {}", err.field, err.message),
+    })
+}
@@ -0,0 +1,113 @@
+use tracing::info;
+
+use super::{CaptureService, EventSink};
+use crate::config::{CaptureConfig, CaptureMode};
+use crate::events::{CaptureEvent, ScheduledCaptureStartedEvent};
+use crate::schedule::ScheduledCapture;
+use crate::state::{CaptureError, CaptureState, ErrorCode};
+
+impl CaptureService {
+    /// Record `config` from `start_at_ms` (Unix epoch ms) for `duration_secs`
+    ///
+    /// Only video and audio-only captures can be scheduled. `supervise`
+    /// starts them as they come due; video captures still show the portal
+    /// picker then, unless a capture session is open.
+    pub fn schedule_capture(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+        start_at_ms: u64,
+        duration_secs: u64,
+    ) -> Result<ScheduledCapture, String> {
+        let invalid = |message: String| {
+            let error = CaptureError {
+                code: ErrorCode::InvalidConfig,
+                message,
+            };
+            events.publish_error(&error);
+            error.message
+        };
+
+        if let Err(err) = config.validate() {
+            return Err(invalid(format!("{}: {}", err.field, err.message)));
+        }
+        if !matches!(config.mode, CaptureMode::Video | CaptureMode::AudioOnly) {
+            return Err(invalid(
+                "mode: only video and audio-only captures can be scheduled".to_string(),
+            ));
+        }
+
+        let capture = self
+            .scheduler
+            .lock()
+            .unwrap()
+            .schedule(config, start_at_ms, duration_secs)
+            .map_err(invalid)?;
+        info!(
+            "Scheduled capture {} at {} for {} s",
+            capture.id, capture.start_at_ms, capture.duration_secs
+        );
+        Ok(capture)
+    }
+
+    /// Cancel a scheduled capture that has not started yet
+    pub fn cancel_scheduled_capture(&self, id: &str) -> Result<(), String> {
+        if self.scheduler.lock().unwrap().cancel(id) {
+            Ok(())
+        } else {
+            Err(format!("No pending scheduled capture {}", id))
+        }
+    }
+
+    /// Scheduled captures that have not started yet, soonest first
+    pub fn scheduled_captures(&self) -> Vec<ScheduledCapture> {
+        self.scheduler.lock().unwrap().pending().to_vec()
+    }
+
+    /// Start scheduled captures as they come due; never returns
+    pub(super) async fn run_scheduler(&self, events: &dyn EventSink) {
+        loop {
+            let wake = self.scheduler.lock().unwrap().next_wake();
+            tokio::time::sleep(wake).await;
+
+            let due = self.scheduler.lock().unwrap().take_due();
+            for capture in due {
+                self.start_scheduled(events, capture).await;
+            }
+        }
+    }
+
+    /// Start one due capture; `supervise` stops it after its duration
+    ///
+    /// Skipped with an error while another capture is in progress. Failed
+    /// starts are published by the start itself.
+    pub async fn start_scheduled(&self, events: &dyn EventSink, capture: ScheduledCapture) {
+        if self.state() != CaptureState::Idle {
+            events.publish_error(&CaptureError {
+                code: ErrorCode::Unknown,
+                message: format!(
+                    "Scheduled capture skipped: another capture is in progress ({})",
+                    capture.config.output_path
+                ),
+            });
+            return;
+        }
+
+        info!("Starting scheduled capture {}", capture.id);
+        events.publish(CaptureEvent::ScheduledCaptureStarted(Box::new(
+            ScheduledCaptureStartedEvent {
+                capture: capture.clone(),
+            },
+        )));
+        let started = match capture.config.mode {
+            CaptureMode::AudioOnly => self.start_audio_recording(events, capture.config).await,
+            _ => self.start_capture(events, capture.config).await,
+        };
+        if let Err(message) = started {
+            info!(
+                "Scheduled capture {} failed to start: {}",
+                capture.id, message
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use image::RgbaImage;
+use tracing::info;
+
+use super::{
+    backend_error_to_capture_error, check_delay, run_countdown, CaptureService, EventSink,
+};
+use crate::capture::{CaptureBackendError, ScreenshotResult};
+use crate::config::{CaptureConfig, CaptureMode, ScreenshotConfig};
+use crate::events::{CaptureEvent, ScreenshotCompleteEvent};
+use crate::screenshot::{apply_auto_crop, is_format_available, save_screenshot, CaptureInfo};
+use crate::state::{CaptureError, CaptureState, ErrorCode};
+
+impl CaptureService {
+    /// Take a screenshot: pick a source, grab one frame, publish the result
+    ///
+    /// While a video recording runs (or is paused), the frame comes from the
+    /// recording's own stream and the recording is not interrupted. With an
+    /// open capture session the picker is skipped and the session's stream
+    /// is used. Otherwise the picker is only shown while Idle or Error, since
+    /// a new selection replaces the stream a running capture reads from, and
+    /// Selecting is reserved until the frame is grabbed.
+    /// With `delay_secs`, counts down between the picker and the frame grab
+    /// so menus and tooltips can be opened first. The frame arrives in memory,
+    /// is saved as `config.screenshot` asks and stays available through
+    /// `last_screenshot`; with `copy_to_clipboard` it is copied too.
+    pub async fn take_screenshot(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+        delay_secs: Option<u32>,
+    ) -> Result<ScreenshotResult, String> {
+        if let Err(err) = config.validate() {
+            let error = CaptureError {
+                code: ErrorCode::InvalidConfig,
+                message: format!("{}: {}", err.field, err.message),
+            };
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+        check_format(events, &config.screenshot)?;
+        let delay_secs = check_delay(events, delay_secs)?;
+
+        let frame = if self.recording_video() {
+            run_countdown(events, delay_secs).await;
+            info!("Capturing screenshot from the running recording...");
+            self.backend.capture_recording_frame().await
+        } else if self.session_is_open() {
+            run_countdown(events, delay_secs).await;
+            info!("Capturing screenshot from capture session...");
+            self.session_screenshot().await
+        } else {
+            let current = self.state();
+            if !matches!(current, CaptureState::Idle | CaptureState::Error) {
+                return Err(format!(
+                    "Cannot take a screenshot while {:?} without an open capture session",
+                    current
+                ));
+            }
+
+            // Hold Selecting until the frame is in, so no capture opens a
+            // picker of its own meanwhile
+            self.reserve(|sm| {
+                if sm.state() == CaptureState::Error {
+                    sm.reset()?;
+                }
+                sm.start_selecting(config.clone())
+            })?;
+            let grab = async {
+                info!("Starting screenshot portal selection...");
+                let selection = match self.select_source(&config).await {
+                    Ok(selection) => {
+                        info!(
+                            "Screenshot selection successful: node_id={}",
+                            selection.node_id
+                        );
+                        selection
+                    }
+                    Err(backend_err) => {
+                        info!("Screenshot selection failed: {:?}", backend_err);
+                        let error = backend_error_to_capture_error(&backend_err);
+                        events.publish_error(&error);
+                        return Err(error.message);
+                    }
+                };
+
+                run_countdown(events, delay_secs).await;
+
+                info!("Capturing screenshot...");
+                let frame = self.backend.capture_screenshot(&selection).await;
+                self.release_session().await;
+                Ok(frame)
+            };
+            let grabbed = grab.await;
+            self.state_machine.lock().unwrap().rollback();
+            grabbed?
+        };
+
+        let info = CaptureInfo::now(config.source);
+        let screenshot_result = self.keep_screenshot(frame, info, &config.screenshot).await;
+        let screenshot = report_screenshot(events, screenshot_result)?;
+
+        if let Some(kept) = self.last_screenshot() {
+            self.copy_if_configured(events, &kept, &config.screenshot)
+                .await;
+        }
+        Ok(screenshot)
+    }
+
+    /// Trim and save a grabbed frame as `settings` asks, off the async
+    /// runtime, and keep it as the last screenshot
+    async fn keep_screenshot(
+        &self,
+        frame: Result<RgbaImage, CaptureBackendError>,
+        info: CaptureInfo,
+        settings: &ScreenshotConfig,
+    ) -> Result<ScreenshotResult, CaptureBackendError> {
+        let frame = frame?;
+        let settings = settings.clone();
+        let screenshot = tokio::task::spawn_blocking(move || {
+            let frame = apply_auto_crop(frame, info.source, &settings.auto_crop);
+            save_screenshot(frame, info, &settings)
+        })
+        .await
+        .unwrap_or_else(|e| {
+            Err(CaptureBackendError::Internal(format!(
+                "Screenshot encoder failed: {}",
+                e
+            )))
+        })?;
+        let saved = screenshot.saved.clone();
+        *self.last_screenshot.lock().unwrap() = Some(Arc::new(screenshot));
+        Ok(saved)
+    }
+
+    /// Whether a video recording is running or paused, so screenshots can
+    /// be taken from its stream
+    fn recording_video(&self) -> bool {
+        let sm = self.state_machine.lock().unwrap();
+        matches!(sm.state(), CaptureState::Recording | CaptureState::Paused)
+            && sm
+                .detail()
+                .config()
+                .is_some_and(|config| config.mode == CaptureMode::Video)
+    }
+}
+
+/// Report `ErrorCode::Unknown` when this system cannot write `settings.format`
+pub(super) fn check_format(
+    events: &dyn EventSink,
+    settings: &ScreenshotConfig,
+) -> Result<(), String> {
+    if is_format_available(settings.format) {
+        return Ok(());
+    }
+    let unsupported = CaptureBackendError::NotSupported(format!(
+        "Screenshot format {:?} is not available on this system",
+        settings.format
+    ));
+    let error = backend_error_to_capture_error(&unsupported);
+    events.publish_error(&error);
+    Err(error.message)
+}
+
+/// Publish the screenshot or the error for a finished grab
+fn report_screenshot(
+    events: &dyn EventSink,
+    screenshot_result: Result<ScreenshotResult, CaptureBackendError>,
+) -> Result<ScreenshotResult, String> {
+    match screenshot_result {
+        Ok(screenshot) => {
+            info!(
+                "Screenshot captured: {}x{} {:?} ({} bytes) at {}",
+                screenshot.width,
+                screenshot.height,
+                screenshot.format,
+                screenshot.byte_size,
+                screenshot.path
+            );
+            events.publish(CaptureEvent::ScreenshotComplete(ScreenshotCompleteEvent {
+                path: screenshot.path.clone(),
+                width: screenshot.width,
+                height: screenshot.height,
+                format: screenshot.format,
+                byte_size: screenshot.byte_size,
+            }));
+            Ok(screenshot)
+        }
+        Err(backend_err) => {
+            info!("Screenshot capture failed: {:?}", backend_err);
+            let error = backend_error_to_capture_error(&backend_err);
+            events.publish_error(&error);
+            Err(error.message)
+        }
+    }
+}
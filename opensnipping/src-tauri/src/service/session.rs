@@ -0,0 +1,147 @@
+use tracing::info;
+
+use super::{backend_error_to_capture_error, CaptureService, EventSink};
+use crate::capture::SelectionResult;
+use crate::config::{CaptureConfig, CaptureMode};
+use crate::events::{
+    CaptureEvent, SelectionCompleteEvent, SessionCloseReason, SessionClosedEvent, StopReason,
+};
+use crate::state::{CaptureError, CaptureState, ErrorCode};
+
+impl CaptureService {
+    /// Pick a source once and keep its portal session for later captures
+    ///
+    /// Selecting is reserved while the picker is up, so no capture can start
+    /// meanwhile; holding a session is not a capture, so the state stays
+    /// Idle whether the picker succeeds or fails.
+    pub async fn open_capture_session(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+    ) -> Result<SelectionResult, String> {
+        if let Err(err) = config.validate() {
+            let error = CaptureError {
+                code: ErrorCode::InvalidConfig,
+                message: format!("{}: {}", err.field, err.message),
+            };
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+        if self.session_is_open() {
+            return Err("A capture session is already open".to_string());
+        }
+
+        self.reserve(|sm| sm.start_selecting(config.clone()))?;
+        info!("Opening capture session...");
+        let selected = self.backend.request_selection(&config).await;
+        self.state_machine.lock().unwrap().rollback();
+
+        let selection = selected.map_err(|backend_err| {
+            info!("Capture session selection failed: {:?}", backend_err);
+            let error = backend_error_to_capture_error(&backend_err);
+            events.publish_error(&error);
+            error.message
+        })?;
+        info!("Capture session open: node_id={}", selection.node_id);
+        *self.selection.lock().unwrap() = Some(selection.clone());
+        *self.session_open.lock().unwrap() = true;
+        events.publish(CaptureEvent::SelectionComplete(SelectionCompleteEvent {
+            selection: selection.clone(),
+        }));
+        Ok(selection)
+    }
+
+    /// Close the session opened with `open_capture_session`
+    ///
+    /// Only allowed while nothing is capturing from it (Idle or Error).
+    pub async fn close_capture_session(&self, events: &dyn EventSink) -> Result<(), String> {
+        if !self.session_is_open() {
+            return Err("No capture session is open".to_string());
+        }
+        let current = self.state();
+        if !matches!(current, CaptureState::Idle | CaptureState::Error) {
+            return Err(format!(
+                "Cannot close the capture session while {:?}; stop the capture first",
+                current
+            ));
+        }
+
+        self.forget_session();
+        *self.selection.lock().unwrap() = None;
+        self.release_session().await;
+        info!("Capture session closed");
+        events.publish(CaptureEvent::SessionClosed(SessionClosedEvent {
+            reason: SessionCloseReason::User,
+        }));
+        Ok(())
+    }
+
+    /// Stop reusing the open session's selection for new captures
+    ///
+    /// For when the portal has ended the session from its side; a capture
+    /// still reading the stream has to be stopped by the caller. Returns
+    /// whether a capture session was open.
+    ///
+    /// The portal session passes to the capture in progress, or to whoever
+    /// calls `release_session` next when there is none.
+    pub fn forget_session(&self) -> bool {
+        let mut open = self.session_open.lock().unwrap();
+        if *open {
+            *self.owns_portal_session.lock().unwrap() = true;
+        }
+        std::mem::replace(&mut *open, false)
+    }
+
+    /// Stop whatever was reading the stream the portal closed and settle
+    /// back to Idle
+    ///
+    /// Front ends call this from the backend's `on_session_closed` callback.
+    /// Recordings walk Finalizing → Idle with `StopReason::SessionClosed`, a
+    /// replay buffer goes straight to Idle and a timelapse loop notices the
+    /// missing session and finalizes its frames.
+    pub async fn portal_session_closed(&self, events: &dyn EventSink) {
+        let was_open = self.forget_session();
+        let current = self.state();
+        let mode = self.config().map(|config| config.mode);
+        info!("Portal session closed while {:?}", current);
+
+        match current {
+            CaptureState::Recording | CaptureState::Paused
+                if mode == Some(CaptureMode::Timelapse) =>
+            {
+                self.release_session().await;
+            }
+            // Audio-only recordings do not read the stream; the session is
+            // closed when they stop
+            CaptureState::Recording | CaptureState::Paused
+                if mode == Some(CaptureMode::AudioOnly) => {}
+            CaptureState::Recording | CaptureState::Paused => {
+                // The stream is gone, so a failed stop cannot be retried
+                if let Err(message) = self.stop_recording(events, StopReason::SessionClosed).await {
+                    let error = CaptureError {
+                        code: ErrorCode::PipelineError,
+                        message: format!("Recording lost when the portal closed: {}", message),
+                    };
+                    self.fail(events, error);
+                }
+            }
+            CaptureState::Buffering => {
+                if let Err(message) = self.stop_replay_buffer(events).await {
+                    info!("Stopping replay buffer failed: {}", message);
+                }
+            }
+            CaptureState::Idle | CaptureState::Error if was_open => {
+                self.release_session().await;
+                self.clear_capture();
+            }
+            // Selecting fails on its own; Finalizing is already ending
+            _ => {}
+        }
+
+        if was_open {
+            events.publish(CaptureEvent::SessionClosed(SessionClosedEvent {
+                reason: SessionCloseReason::Portal,
+            }));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crate::events::{CaptureEvent, CountdownEvent, ErrorEvent, StateChangedEvent};
+use crate::schedule;
+use crate::state::{CaptureError, CaptureState, ErrorCode};
+
+/// Where `CaptureService` publishes its events
+///
+/// The Tauri app forwards them to the webview; tests and other front ends
+/// can collect or print them.
+pub trait EventSink: Send + Sync {
+    /// Deliver one event
+    fn publish(&self, event: CaptureEvent);
+
+    fn publish_state_change(&self, previous: CaptureState, current: CaptureState) {
+        self.publish(CaptureEvent::StateChanged(StateChangedEvent {
+            state: current,
+            previous,
+        }));
+    }
+
+    fn publish_error(&self, error: &CaptureError) {
+        self.publish(CaptureEvent::Error(ErrorEvent {
+            error: error.clone(),
+        }));
+    }
+
+    fn publish_countdown(&self, remaining_secs: u32, total_secs: u32) {
+        self.publish(CaptureEvent::Countdown(CountdownEvent {
+            remaining_secs,
+            total_secs,
+        }));
+    }
+}
+
+/// Publish a countdown tick once per second for `delay_secs`, then return
+///
+/// Ticks count down from `delay_secs` to 0; a zero delay returns at once.
+pub async fn run_countdown(events: &dyn EventSink, delay_secs: u32) {
+    if delay_secs == 0 {
+        return;
+    }
+    for remaining in (1..=delay_secs).rev() {
+        events.publish_countdown(remaining, delay_secs);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    events.publish_countdown(0, delay_secs);
+}
+
+/// Check an optional countdown, reporting `ErrorCode::InvalidConfig` on failure
+pub fn check_delay(events: &dyn EventSink, delay_secs: Option<u32>) -> Result<u32, String> {
+    let delay_secs = delay_secs.unwrap_or(0);
+    schedule::validate_delay(delay_secs).map_err(|message| {
+        let error = CaptureError {
+            code: ErrorCode::InvalidConfig,
+            message: format!("delay_secs: {}", message),
+        };
+        events.publish_error(&error);
+        error.message
+    })?;
+    Ok(delay_secs)
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tracing::{error, info, warn};
+
+use super::{CaptureService, EventSink};
+use crate::config::{CaptureConfig, CaptureMode};
+use crate::disk::{self, DiskStatus};
+use crate::events::{CaptureEvent, DiskSpaceLowEvent, StopReason};
+use crate::limits::{self, LimitTracker};
+use crate::state::{CaptureError, CaptureState, ErrorCode};
+
+/// The video or audio-only recording `watch_recordings` is following
+struct RecordingWatch {
+    output_path: String,
+    dir: PathBuf,
+    bytes_per_sec: u64,
+    limits: Option<LimitTracker>,
+    /// Whether `capture:disk_space_low` was already published
+    warned: bool,
+    last_tick: Instant,
+    last_disk_check: Instant,
+}
+
+impl RecordingWatch {
+    fn new(config: &CaptureConfig, bytes_per_sec: u64) -> Self {
+        Self {
+            output_path: config.output_path.clone(),
+            dir: disk::output_dir(config),
+            bytes_per_sec,
+            limits: LimitTracker::for_config(config),
+            warned: false,
+            last_tick: Instant::now(),
+            last_disk_check: Instant::now(),
+        }
+    }
+}
+
+impl CaptureService {
+    /// Run the scheduler and the recording monitors; never returns
+    ///
+    /// Front ends run this once in the background for the lifetime of the
+    /// app, whatever command started the capture.
+    pub async fn supervise(&self, events: &dyn EventSink) {
+        tokio::join!(self.run_scheduler(events), self.watch_recordings(events));
+    }
+
+    /// Stop recordings at their limits or before the disk fills
+    ///
+    /// Follows whichever video or audio-only recording is running. At
+    /// `max_duration_secs` (not counting pauses) or `max_file_size_bytes`
+    /// it stops with `StopReason::Limit`. It publishes
+    /// `capture:disk_space_low` once per recording when the disk gets low,
+    /// and stops with `StopReason::Error` and an `ErrorCode::IoError` before
+    /// the disk fills.
+    pub(super) async fn watch_recordings(&self, events: &dyn EventSink) {
+        let mut watch = None;
+        loop {
+            tokio::time::sleep(limits::POLL_INTERVAL).await;
+            self.check_recording(events, &mut watch).await;
+        }
+    }
+
+    async fn check_recording(&self, events: &dyn EventSink, watch: &mut Option<RecordingWatch>) {
+        let Some(config) = self.watched_config() else {
+            *watch = None;
+            return;
+        };
+        let watch = match watch {
+            Some(current) if current.output_path == config.output_path => current,
+            _ => {
+                let selection = self.selection();
+                let bytes_per_sec = disk::estimate_for_selection(&config, selection.as_ref());
+                watch.insert(RecordingWatch::new(&config, bytes_per_sec))
+            }
+        };
+
+        let recording = self.state() == CaptureState::Recording;
+        if let Some(tracker) = &mut watch.limits {
+            tracker.advance(watch.last_tick.elapsed(), recording);
+        }
+        watch.last_tick = Instant::now();
+        if self.check_limits(events, watch).await {
+            return;
+        }
+
+        if watch.last_disk_check.elapsed() >= Duration::from_secs(disk::POLL_INTERVAL_SECS) {
+            watch.last_disk_check = Instant::now();
+            self.check_disk_space(events, watch).await;
+        }
+    }
+
+    /// Config of the running or paused video or audio-only recording
+    fn watched_config(&self) -> Option<CaptureConfig> {
+        if !matches!(self.state(), CaptureState::Recording | CaptureState::Paused) {
+            return None;
+        }
+        self.config()
+            .filter(|config| matches!(config.mode, CaptureMode::Video | CaptureMode::AudioOnly))
+    }
+
+    /// Stop the recording if it reached a limit; returns whether it did
+    async fn check_limits(&self, events: &dyn EventSink, watch: &RecordingWatch) -> bool {
+        let Some(tracker) = &watch.limits else {
+            return false;
+        };
+        // Segmented recordings never write `config.output_path` itself
+        let file_bytes = self.backend.recorded_bytes().await.unwrap_or(0);
+        let Some(limit) = tracker.check(file_bytes) else {
+            return false;
+        };
+
+        info!(
+            "{:?} limit reached after {:?}, stopping recording {}",
+            limit,
+            tracker.recorded(),
+            watch.output_path
+        );
+        // stop_recording reports a failed stop through capture:error
+        if let Err(message) = self.stop_recording(events, StopReason::Limit).await {
+            error!("Failed to stop recording at its limit: {}", message);
+        }
+        true
+    }
+
+    async fn check_disk_space(&self, events: &dyn EventSink, watch: &mut RecordingWatch) {
+        let Ok(free_bytes) = disk::free_space_bytes(&watch.dir) else {
+            return;
+        };
+        match disk::assess(free_bytes, watch.bytes_per_sec) {
+            DiskStatus::Ok => {}
+            DiskStatus::Low { remaining_secs } => {
+                if !watch.warned {
+                    warn!(
+                        "Low disk space: {} bytes free, about {} s of recording left",
+                        free_bytes, remaining_secs
+                    );
+                    events.publish(CaptureEvent::DiskSpaceLow(DiskSpaceLowEvent {
+                        output_path: watch.output_path.clone(),
+                        free_bytes,
+                        remaining_secs,
+                    }));
+                    watch.warned = true;
+                }
+            }
+            DiskStatus::Critical => {
+                info!("Disk almost full, stopping recording {}", watch.output_path);
+                let message = match self.stop_recording(events, StopReason::Error).await {
+                    Ok(result) => format!(
+                        "Disk almost full: recording stopped and saved to {}",
+                        result.path
+                    ),
+                    Err(message) => format!("Disk almost full: {}", message),
+                };
+                events.publish_error(&CaptureError {
+                    code: ErrorCode::IoError,
+                    message,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+#[tokio::test]
+async fn test_audio_recording_starts_without_picker() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service
+        .start_audio_recording(&sink, audio_only_config())
+        .await;
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    assert!(backend.is_audio_only());
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(
+        sink.names(),
+        vec![event_names::STATE_CHANGED, event_names::RECORDING_STARTED]
+    );
+    let output_path = service.config().unwrap().output_path;
+    assert!(output_path.ends_with(".ogg"), "{}", output_path);
+}
+
+#[tokio::test]
+async fn test_audio_recording_stops_like_video() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .start_audio_recording(&sink, audio_only_config())
+        .await
+        .unwrap();
+
+    let result = service.stop_recording(&sink, StopReason::User).await;
+
+    assert_eq!(result.map(|result| result.width), Ok(0));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(!backend.is_recording());
+}
+
+#[tokio::test]
+async fn test_audio_recording_rejects_video_config() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_audio_recording(&sink, test_config()).await;
+
+    assert!(result.unwrap_err().starts_with("mode:"));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_audio_recording_failure_stays_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend.set_should_succeed(false);
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service
+        .start_audio_recording(&sink, audio_only_config())
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(service.config().is_none());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
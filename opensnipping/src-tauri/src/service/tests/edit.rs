@@ -0,0 +1,155 @@
+use super::*;
+
+#[tokio::test]
+async fn test_copy_screenshot_needs_a_screenshot() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let clipboard = Arc::new(HeadlessClipboard::default());
+    service.set_clipboard(clipboard.clone());
+    let sink = RecordingSink::default();
+
+    assert!(service.copy_screenshot(&sink, false).await.is_err());
+    assert_eq!(clipboard.content(), None);
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+    assert_eq!(clipboard.content(), None);
+    service.copy_screenshot(&sink, false).await.unwrap();
+    assert_eq!(clipboard.content().unwrap().uri_list, None);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_export_annotated_screenshot_keeps_original() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let mut annotations = Annotations::default();
+    annotations.push(Stroke {
+        points: vec![Point { x: 0.0, y: 0.0 }, Point { x: 100.0, y: 100.0 }],
+        color: StrokeColor([255, 0, 0, 255]),
+        width: 8.0,
+    });
+
+    let missing = service
+        .export_annotated_screenshot(&sink, annotations.clone(), test_config().screenshot)
+        .await;
+    assert!(missing.is_err());
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+    let exported = service
+        .export_annotated_screenshot(&sink, annotations, test_config().screenshot)
+        .await
+        .unwrap();
+
+    assert_ne!(exported.path, screenshot.path);
+    assert_eq!(
+        (exported.width, exported.height),
+        (screenshot.width, screenshot.height)
+    );
+    let decoded = image::open(&exported.path).unwrap().into_rgba8();
+    assert_eq!(decoded.get_pixel(50, 50).0, [255, 0, 0, 255]);
+    let last = service.last_screenshot().unwrap();
+    assert_eq!(last.saved.path, screenshot.path);
+    assert_ne!(last.image.get_pixel(50, 50).0, [255, 0, 0, 255]);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+    let _ = std::fs::remove_file(&exported.path);
+}
+
+#[tokio::test]
+async fn test_export_annotated_screenshot_rejects_invalid_strokes() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let mut annotations = Annotations::default();
+    annotations.push(Stroke {
+        points: vec![Point { x: 0.0, y: 0.0 }],
+        color: StrokeColor::default(),
+        width: 0.0,
+    });
+
+    let result = service
+        .export_annotated_screenshot(&sink, annotations, test_config().screenshot)
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_redact_screenshot_replaces_last_screenshot() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let clipboard = Arc::new(HeadlessClipboard::default());
+    service.set_clipboard(clipboard.clone());
+    let sink = RecordingSink::default();
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+    let redactions = vec![Redaction {
+        rect: Rect {
+            x: 10,
+            y: 10,
+            width: 20,
+            height: 20,
+        },
+        style: RedactionStyle::Fill {
+            color: StrokeColor([0, 0, 0, 255]),
+        },
+    }];
+
+    let redacted = service
+        .redact_screenshot(&sink, redactions, test_config().screenshot)
+        .await
+        .unwrap();
+
+    assert_ne!(redacted.path, screenshot.path);
+    let decoded = image::open(&redacted.path).unwrap().into_rgba8();
+    assert_eq!(decoded.get_pixel(15, 15).0, [0, 0, 0, 255]);
+    assert_ne!(decoded.get_pixel(40, 40).0, [0, 0, 0, 255]);
+    // Later copies see the redacted frame
+    let last = service.last_screenshot().unwrap();
+    assert_eq!(last.saved.path, redacted.path);
+    service.copy_screenshot(&sink, false).await.unwrap();
+    let copied = image::load_from_memory(&clipboard.content().unwrap().png)
+        .unwrap()
+        .into_rgba8();
+    assert_eq!(copied.get_pixel(15, 15).0, [0, 0, 0, 255]);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+    let _ = std::fs::remove_file(&redacted.path);
+}
+
+#[tokio::test]
+async fn test_redact_screenshot_rejects_invalid_areas() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let redactions = vec![Redaction {
+        rect: Rect {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+        },
+        style: RedactionStyle::Blur { radius: 0 },
+    }];
+
+    let result = service
+        .redact_screenshot(&sink, redactions, test_config().screenshot)
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
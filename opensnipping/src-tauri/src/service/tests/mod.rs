@@ -0,0 +1,156 @@
+use std::sync::{Arc, Mutex};
+
+use super::*;
+use crate::capture::fake::{FakeCaptureBackend, FakeOperation};
+use crate::config::{
+    AudioConfig, AutoCropConfig, AutoCropMode, CaptureMode, CaptureSource, ContainerFormat,
+    ScreenshotConfig, ScreenshotFormat,
+};
+use crate::events::{event_names, CaptureEvent, StopReason};
+use crate::recovery::RecordingJournal;
+use crate::screenshot::{
+    file_uri, Annotations, HeadlessClipboard, Point, Rect, Redaction, RedactionStyle, Stroke,
+    StrokeColor,
+};
+use crate::state::ErrorCode;
+
+/// Keeps every published event for assertions
+#[derive(Default)]
+pub(super) struct RecordingSink {
+    events: Mutex<Vec<CaptureEvent>>,
+}
+
+impl EventSink for RecordingSink {
+    fn publish(&self, event: CaptureEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl RecordingSink {
+    /// Forget the events published so far
+    pub(super) fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+
+    pub(super) fn names(&self) -> Vec<&'static str> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(CaptureEvent::name)
+            .collect()
+    }
+
+    /// Code of every published error
+    pub(super) fn error_codes(&self) -> Vec<ErrorCode> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                CaptureEvent::Error(error) => Some(error.error.code.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Reason of every published `RecordingStopped`
+    pub(super) fn stop_reasons(&self) -> Vec<StopReason> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                CaptureEvent::RecordingStopped(stopped) => Some(stopped.reason),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// `(previous, current)` of every published state change
+    pub(super) fn state_changes(&self) -> Vec<(CaptureState, CaptureState)> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                CaptureEvent::StateChanged(change) => Some((change.previous, change.state)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// A service capturing through a clone of `backend`, so `backend` keeps
+/// reporting the calls the service makes, with its journal in a fresh temp
+/// directory
+pub(super) fn service(backend: &FakeCaptureBackend) -> CaptureService {
+    let journal_dir = std::env::temp_dir().join(format!(
+        "opensnipping-service-journal-{}",
+        uuid::Uuid::new_v4()
+    ));
+    CaptureService::new(
+        Arc::new(backend.clone()),
+        RecordingJournal::new(journal_dir),
+    )
+}
+
+/// Mark `service` as holding an open capture session on `backend`'s source
+pub(super) fn open_session(service: &CaptureService) {
+    *service.selection.lock().unwrap() = Some(SelectionResult {
+        node_id: 7,
+        stream_fd: None,
+        width: Some(64),
+        height: Some(48),
+    });
+    *service.session_open.lock().unwrap() = true;
+}
+
+impl CaptureService {
+    /// Move to Selecting with `config`, as if the picker were still up, and
+    /// store `selection` as its result
+    ///
+    /// For tests outside this module, which can't reach the fields.
+    pub(crate) fn select_for_test(
+        &self,
+        config: CaptureConfig,
+        selection: Option<SelectionResult>,
+    ) {
+        self.state_machine
+            .lock()
+            .unwrap()
+            .start_selecting(config.clone())
+            .unwrap();
+        *self.config.lock().unwrap() = Some(config);
+        *self.selection.lock().unwrap() = selection;
+    }
+}
+
+pub(super) fn test_config() -> CaptureConfig {
+    CaptureConfig::for_test("opensnipping-service-test")
+}
+
+/// `test_config()` recording mic and system audio to an Ogg file
+pub(super) fn audio_only_config() -> CaptureConfig {
+    CaptureConfig {
+        audio: AudioConfig {
+            system: true,
+            mic: true,
+        },
+        container: ContainerFormat::Ogg,
+        output_path: String::new(),
+        mode: CaptureMode::AudioOnly,
+        ..test_config()
+    }
+}
+
+mod audio;
+mod edit;
+mod recording;
+mod replay;
+mod schedule;
+mod screenshot;
+mod session;
+mod supervisor;
+mod timelapse;
+mod transitions;
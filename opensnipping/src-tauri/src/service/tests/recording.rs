@@ -0,0 +1,301 @@
+use super::*;
+use crate::capture::RecordingSegment;
+use crate::config::SegmentConfig;
+
+#[tokio::test]
+async fn test_start_capture_selects_and_records() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_capture(&sink, test_config()).await;
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(backend.start_recording_count(), 1);
+    assert!(service.config().is_some());
+    assert_eq!(service.selection().map(|s| s.node_id), Some(42));
+    assert_eq!(
+        sink.names(),
+        vec![
+            event_names::STATE_CHANGED,
+            event_names::SELECTION_COMPLETE,
+            event_names::STATE_CHANGED,
+            event_names::RECORDING_STARTED,
+        ]
+    );
+    assert_eq!(
+        sink.state_changes(),
+        vec![
+            (CaptureState::Idle, CaptureState::Selecting),
+            (CaptureState::Selecting, CaptureState::Recording),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_start_capture_permission_denied_moves_to_error() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_capture(&sink, test_config()).await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Error);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert!(service.config().is_none());
+    assert_eq!(
+        sink.names(),
+        vec![event_names::STATE_CHANGED, event_names::ERROR]
+    );
+}
+
+#[tokio::test]
+async fn test_start_capture_invalid_config_moves_to_error() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        fps: 0,
+        ..test_config()
+    };
+
+    let result = service.start_capture(&sink, config).await;
+
+    assert!(result.unwrap_err().starts_with("fps"));
+    assert_eq!(service.state(), CaptureState::Error);
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_start_capture_rejects_other_modes_without_state_change() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+
+    let result = service.start_capture(&sink, config).await;
+
+    assert!(result.unwrap_err().contains("start_replay_buffer"));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_start_capture_reuses_open_session() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+
+    let result = service.start_capture(&sink, test_config()).await;
+
+    assert_eq!(result, Ok(CaptureState::Recording));
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(backend.start_recording_count(), 1);
+    assert_eq!(service.selection().map(|s| s.node_id), Some(7));
+}
+
+#[tokio::test]
+async fn test_recording_controls_walk_back_to_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = test_config();
+    service.start_capture(&sink, config.clone()).await.unwrap();
+
+    assert_eq!(
+        service.pause_recording(&sink).await,
+        Ok(CaptureState::Paused)
+    );
+    assert_eq!(
+        service.resume_recording(&sink).await,
+        Ok(CaptureState::Recording)
+    );
+    let result = service.stop_recording(&sink, StopReason::User).await;
+
+    assert_eq!(result.map(|r| r.path), Ok(config.output_path));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(!backend.is_recording());
+    assert!(service.config().is_none());
+    assert!(service.selection().is_none());
+    assert_eq!(
+        sink.state_changes()[2..],
+        [
+            (CaptureState::Recording, CaptureState::Paused),
+            (CaptureState::Paused, CaptureState::Recording),
+            (CaptureState::Recording, CaptureState::Finalizing),
+            (CaptureState::Finalizing, CaptureState::Idle),
+        ]
+    );
+    assert_eq!(sink.names().last(), Some(&event_names::RECORDING_STOPPED));
+}
+
+#[tokio::test]
+async fn test_stopped_duration_leaves_out_pauses() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service.start_capture(&sink, test_config()).await.unwrap();
+
+    service.pause_recording(&sink).await.unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    service.resume_recording(&sink).await.unwrap();
+    let result = service.stop_recording(&sink, StopReason::User).await;
+
+    assert!(result.unwrap().duration_ms < 200);
+    let stopped = sink
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|event| match event {
+            CaptureEvent::RecordingStopped(stopped) => Some(stopped.duration_ms),
+            _ => None,
+        });
+    assert!(stopped.unwrap() < 200);
+}
+
+#[tokio::test]
+async fn test_recording_is_journaled_until_stopped() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = test_config();
+    service.start_capture(&sink, config.clone()).await.unwrap();
+
+    let journaled = service.journal().entries().unwrap();
+    assert_eq!(journaled.len(), 1);
+    assert_eq!(journaled[0].1.output_path, config.output_path);
+
+    service
+        .stop_recording(&sink, StopReason::User)
+        .await
+        .unwrap();
+
+    assert!(service.journal().entries().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_segmented_recording_journals_its_segments() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        container: ContainerFormat::Mkv,
+        segments: Some(SegmentConfig {
+            max_duration_secs: Some(600),
+            ..Default::default()
+        }),
+        ..test_config()
+    };
+    service.start_capture(&sink, config.clone()).await.unwrap();
+    let template = config.recording_path();
+    let segment = RecordingSegment {
+        index: 0,
+        path: SegmentConfig::segment_path(&template, 0),
+        duration_ms: 600_000,
+    };
+
+    service.segment_closed(&sink, &segment);
+
+    let journaled = service.journal().entries().unwrap();
+    assert_eq!(journaled[0].1.output_path, template);
+    assert_eq!(journaled[0].1.closed_segments, vec![segment.path]);
+    assert_eq!(sink.names().last(), Some(&event_names::SEGMENT_CLOSED));
+    let stopped = service.stop_recording(&sink, StopReason::User).await;
+
+    assert_eq!(stopped.unwrap().path, template);
+    assert!(service.journal().entries().unwrap().is_empty());
+}
+
+#[test]
+fn test_finalize_keeps_open_session_selection() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+    {
+        let mut sm = service.state_machine.lock().unwrap();
+        sm.start_selecting(test_config()).unwrap();
+        sm.begin_recording().unwrap();
+        sm.stop().unwrap();
+    }
+
+    assert_eq!(service.finalize_complete(&sink), Ok(CaptureState::Idle));
+    assert!(service.selection().is_some());
+}
+
+#[tokio::test]
+async fn test_invalid_transition_publishes_nothing() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    assert!(service.pause_recording(&sink).await.is_err());
+    assert!(service
+        .stop_recording(&sink, StopReason::User)
+        .await
+        .is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(backend.pause_recording_count(), 0);
+    assert_eq!(backend.stop_recording_count(), 0);
+    assert!(sink.names().is_empty());
+}
+
+#[test]
+fn test_cancel_capture_forgets_config() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .state_machine
+        .lock()
+        .unwrap()
+        .start_selecting(test_config())
+        .unwrap();
+    *service.config.lock().unwrap() = Some(test_config());
+
+    assert_eq!(service.cancel_capture(&sink), Ok(CaptureState::Idle));
+    assert!(service.config().is_none());
+}
+
+#[tokio::test]
+async fn test_reset_error_returns_to_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let _ = service.start_capture(&sink, test_config()).await;
+
+    assert_eq!(service.reset_error(&sink), Ok(CaptureState::Idle));
+    assert_eq!(
+        sink.state_changes().last(),
+        Some(&(CaptureState::Error, CaptureState::Idle))
+    );
+}
+
+#[tokio::test]
+async fn test_session_info_describes_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = test_config();
+    service.start_capture(&sink, config.clone()).await.unwrap();
+
+    let info = service.session_info();
+
+    assert_eq!(info.current.state(), CaptureState::Recording);
+    assert_eq!(info.current.config(), Some(&config));
+    assert!(info.current.started_at_ms().is_some());
+    assert_eq!(info.pending, None);
+    assert!(!info.session_open);
+    let steps: Vec<_> = info.history.iter().map(|r| (r.from, r.to)).collect();
+    assert_eq!(steps, sink.state_changes());
+}
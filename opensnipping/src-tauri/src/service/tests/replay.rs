@@ -0,0 +1,152 @@
+use super::*;
+
+fn replay_config() -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    }
+}
+
+#[tokio::test]
+async fn test_replay_buffer_starts_in_one_transition() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_replay_buffer(&sink, replay_config()).await;
+
+    assert_eq!(result, Ok(CaptureState::Buffering));
+    assert!(backend.is_buffering());
+    assert_eq!(
+        sink.state_changes(),
+        vec![(CaptureState::Idle, CaptureState::Buffering)]
+    );
+    assert_eq!(
+        sink.names(),
+        vec![event_names::STATE_CHANGED, event_names::SELECTION_COMPLETE]
+    );
+    assert!(service.selection().is_some());
+}
+
+#[tokio::test]
+async fn test_replay_buffer_start_failure_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_replay_buffer(&sink, replay_config()).await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+    assert!(service.config().is_none());
+    assert!(service.selection().is_none());
+}
+
+#[tokio::test]
+async fn test_replay_buffer_rejects_other_modes() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_replay_buffer(&sink, test_config()).await;
+
+    assert!(result.unwrap_err().starts_with("mode"));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_replay_buffer_reuses_open_session() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+
+    let result = service.start_replay_buffer(&sink, replay_config()).await;
+
+    assert_eq!(result, Ok(CaptureState::Buffering));
+    assert_eq!(backend.selection_count(), 0);
+}
+
+#[tokio::test]
+async fn test_save_replay_keeps_buffering() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .start_replay_buffer(&sink, replay_config())
+        .await
+        .unwrap();
+    sink.clear();
+
+    let result = service
+        .save_replay(&sink, Some("/tmp/replay.mp4".to_string()))
+        .await;
+
+    assert_eq!(result.unwrap().path, "/tmp/replay.mp4");
+    assert_eq!(service.state(), CaptureState::Buffering);
+    assert_eq!(sink.names(), vec![event_names::REPLAY_SAVED]);
+}
+
+#[tokio::test]
+async fn test_save_replay_requires_buffering() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.save_replay(&sink, None).await;
+
+    assert!(result.is_err());
+    assert_eq!(backend.save_replay_count(), 0);
+    assert!(sink.names().is_empty());
+}
+
+#[tokio::test]
+async fn test_stop_replay_buffer_returns_to_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .start_replay_buffer(&sink, replay_config())
+        .await
+        .unwrap();
+    sink.clear();
+
+    let result = service.stop_replay_buffer(&sink).await;
+
+    assert_eq!(result, Ok(CaptureState::Idle));
+    assert!(!backend.is_buffering());
+    assert_eq!(
+        sink.state_changes(),
+        vec![(CaptureState::Buffering, CaptureState::Idle)]
+    );
+    assert!(service.config().is_none());
+}
+
+#[tokio::test]
+async fn test_stop_replay_buffer_failure_stays_buffering() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .start_replay_buffer(&sink, replay_config())
+        .await
+        .unwrap();
+    sink.clear();
+    backend.fail_operation(FakeOperation::StopReplayBuffer);
+
+    let result = service.stop_replay_buffer(&sink).await;
+
+    assert!(result.unwrap_err().contains("StopReplayBuffer"));
+    assert_eq!(service.state(), CaptureState::Buffering);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+
+    // The stop can be retried once the backend recovers
+    backend.restore_operation(FakeOperation::StopReplayBuffer);
+    assert_eq!(
+        service.stop_replay_buffer(&sink).await,
+        Ok(CaptureState::Idle)
+    );
+}
@@ -0,0 +1,70 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::*;
+use crate::schedule::ScheduledCapture;
+
+fn due_capture(config: CaptureConfig) -> ScheduledCapture {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    ScheduledCapture {
+        id: "due".to_string(),
+        start_at_ms: now.as_millis() as u64,
+        duration_secs: 60,
+        config,
+    }
+}
+
+#[tokio::test]
+async fn test_due_capture_starts_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    service
+        .start_scheduled(&sink, due_capture(audio_only_config()))
+        .await;
+
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert!(backend.is_audio_only());
+    assert_eq!(sink.names()[0], event_names::SCHEDULED_CAPTURE_STARTED);
+    let stopped = service.stop_recording(&sink, StopReason::User).await;
+    let _ = std::fs::remove_file(stopped.unwrap().path);
+}
+
+#[tokio::test]
+async fn test_due_capture_skipped_while_busy() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let replay = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+    service.start_replay_buffer(&sink, replay).await.unwrap();
+    sink.clear();
+
+    service
+        .start_scheduled(&sink, due_capture(audio_only_config()))
+        .await;
+
+    assert_eq!(service.state(), CaptureState::Buffering);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert_eq!(sink.error_codes(), vec![ErrorCode::Unknown]);
+}
+
+#[tokio::test]
+async fn test_schedule_capture_rejects_replay_buffers() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let replay = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+    let start_at_ms = due_capture(replay.clone()).start_at_ms + 60_000;
+
+    let result = service.schedule_capture(&sink, replay, start_at_ms, 30);
+
+    assert!(result.unwrap_err().starts_with("mode"));
+    assert_eq!(sink.error_codes(), vec![ErrorCode::InvalidConfig]);
+    assert!(service.scheduled_captures().is_empty());
+}
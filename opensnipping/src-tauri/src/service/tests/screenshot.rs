@@ -0,0 +1,337 @@
+use super::*;
+use crate::schedule::MAX_DELAY_SECS;
+
+#[tokio::test]
+async fn test_take_screenshot_picks_source_and_publishes_result() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+
+    assert_eq!((screenshot.width, screenshot.height), (1920, 1080));
+    assert!(std::path::Path::new(&screenshot.path).exists());
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(sink.names(), vec![event_names::SCREENSHOT_COMPLETE]);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_uses_open_session() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+
+    assert_eq!((screenshot.width, screenshot.height), (64, 48));
+    assert_eq!(backend.selection_count(), 0);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_encodes_configured_format() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        screenshot: ScreenshotConfig {
+            format: ScreenshotFormat::Jpeg,
+            quality: 80,
+            ..test_config().screenshot
+        },
+        ..test_config()
+    };
+
+    let screenshot = service.take_screenshot(&sink, config, None).await.unwrap();
+
+    assert!(screenshot.path.ends_with(".jpg"));
+    assert_eq!(screenshot.format, ScreenshotFormat::Jpeg);
+    assert_eq!(
+        screenshot.byte_size,
+        std::fs::metadata(&screenshot.path).unwrap().len()
+    );
+    match sink.events.lock().unwrap().last() {
+        Some(CaptureEvent::ScreenshotComplete(event)) => {
+            assert_eq!(event.format, ScreenshotFormat::Jpeg);
+            assert_eq!(event.byte_size, screenshot.byte_size);
+        }
+        other => panic!("Expected a screenshot event, got {:?}", other),
+    }
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_keeps_last_frame_in_memory() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    assert!(service.last_screenshot().is_none());
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+
+    let last = service.last_screenshot().expect("screenshot kept");
+    assert_eq!(last.saved.path, screenshot.path);
+    assert_eq!(
+        last.image.dimensions(),
+        (screenshot.width, screenshot.height)
+    );
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_copies_to_clipboard_when_configured() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let clipboard = Arc::new(HeadlessClipboard::default());
+    service.set_clipboard(clipboard.clone());
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        screenshot: ScreenshotConfig {
+            copy_to_clipboard: true,
+            copy_file_uri: true,
+            ..test_config().screenshot
+        },
+        ..test_config()
+    };
+
+    let screenshot = service.take_screenshot(&sink, config, None).await.unwrap();
+
+    let content = clipboard.content().expect("screenshot copied");
+    let copied = image::load_from_memory(&content.png).unwrap();
+    assert_eq!(
+        (copied.width(), copied.height()),
+        (screenshot.width, screenshot.height)
+    );
+    assert_eq!(
+        content.uri_list,
+        Some(format!(
+            "{}\r\n",
+            file_uri(std::path::Path::new(&screenshot.path))
+        ))
+    );
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_auto_crops_window_captures() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = |source| CaptureConfig {
+        source,
+        screenshot: ScreenshotConfig {
+            auto_crop: AutoCropConfig {
+                mode: AutoCropMode::Windows,
+                padding: 10,
+                background: Some(StrokeColor([255, 255, 255, 255])),
+                ..Default::default()
+            },
+            ..test_config().screenshot
+        },
+        ..test_config()
+    };
+
+    let window = service
+        .take_screenshot(&sink, config(CaptureSource::Window), None)
+        .await
+        .unwrap();
+    let screen = service
+        .take_screenshot(&sink, config(CaptureSource::Screen), None)
+        .await
+        .unwrap();
+
+    // The fake frame is one flat color, so it is all content and only
+    // gains the padding
+    assert_eq!((window.width, window.height), (1940, 1100));
+    assert_eq!((screen.width, screen.height), (1920, 1080));
+
+    // Cleanup
+    let _ = std::fs::remove_file(&window.path);
+    let _ = std::fs::remove_file(&screen.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_permission_denied_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.take_screenshot(&sink, test_config(), None).await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_capture_cannot_start_while_the_screenshot_picker_is_up() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    // The countdown keeps the screenshot between its picker and its grab
+    let screenshot = service.take_screenshot(&sink, test_config(), Some(1));
+    let start = async {
+        tokio::task::yield_now().await;
+        let pending = service.session_info().pending;
+        (pending, service.start_capture(&sink, test_config()).await)
+    };
+    let (screenshot, (pending, started)) = tokio::join!(screenshot, start);
+
+    assert_eq!(pending, Some(CaptureState::Selecting));
+    assert!(started.is_err());
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(service.session_info().pending, None);
+    let _ = std::fs::remove_file(screenshot.unwrap().path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_after_an_error_stays_in_error() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    assert!(service.start_capture(&sink, test_config()).await.is_err());
+    backend.set_should_succeed(true);
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(service.state(), CaptureState::Error);
+    assert_eq!(service.session_info().pending, None);
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_from_running_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service.start_capture(&sink, test_config()).await.unwrap();
+    sink.clear();
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+
+    assert_eq!((screenshot.width, screenshot.height), (1920, 1080));
+    assert_eq!(backend.selection_count(), 1);
+    assert_eq!(backend.recording_frame_count(), 1);
+    assert!(backend.is_recording());
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert_eq!(sink.names(), vec![event_names::SCREENSHOT_COMPLETE]);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_while_paused() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service.start_capture(&sink, test_config()).await.unwrap();
+    service.pause_recording(&sink).await.unwrap();
+
+    let screenshot = service
+        .take_screenshot(&sink, test_config(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(backend.recording_frame_count(), 1);
+    assert_eq!(service.state(), CaptureState::Paused);
+
+    // Cleanup
+    let _ = std::fs::remove_file(&screenshot.path);
+}
+
+#[tokio::test]
+async fn test_recording_frame_failure_keeps_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service.start_capture(&sink, test_config()).await.unwrap();
+    sink.clear();
+    backend.fail_operation(FakeOperation::CaptureRecordingFrame);
+
+    let result = service.take_screenshot(&sink, test_config(), None).await;
+
+    assert!(result.is_err());
+    assert!(backend.is_recording());
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_rejected_during_audio_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let audio_config = CaptureConfig {
+        mode: CaptureMode::AudioOnly,
+        ..test_config()
+    };
+    service
+        .state_machine
+        .lock()
+        .unwrap()
+        .begin_audio_recording(audio_config)
+        .unwrap();
+
+    let result = service.take_screenshot(&sink, test_config(), None).await;
+
+    assert!(result.is_err());
+    assert_eq!(backend.recording_frame_count(), 0);
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(service.state(), CaptureState::Recording);
+}
+
+#[tokio::test]
+async fn test_take_screenshot_rejects_long_delay() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service
+        .take_screenshot(&sink, test_config(), Some(MAX_DELAY_SECS + 1))
+        .await;
+
+    assert!(result.unwrap_err().starts_with("delay_secs"));
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_zero_countdown_publishes_nothing() {
+    let sink = RecordingSink::default();
+
+    run_countdown(&sink, 0).await;
+
+    assert!(sink.names().is_empty());
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+#[tokio::test]
+async fn test_open_capture_session_stays_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.open_capture_session(&sink, test_config()).await;
+
+    let selection = result.unwrap();
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(service.session_is_open());
+    assert_eq!(
+        service.selection().map(|s| s.node_id),
+        Some(selection.node_id)
+    );
+    assert_eq!(sink.names(), vec![event_names::SELECTION_COMPLETE]);
+    assert!(service.session_info().pending.is_none());
+}
+
+#[tokio::test]
+async fn test_open_capture_session_failure_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.open_capture_session(&sink, test_config()).await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(!service.session_is_open());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_open_capture_session_twice_is_rejected() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .open_capture_session(&sink, test_config())
+        .await
+        .unwrap();
+
+    let result = service.open_capture_session(&sink, test_config()).await;
+
+    assert!(result.is_err());
+    assert_eq!(backend.selection_count(), 1);
+}
+
+#[tokio::test]
+async fn test_close_capture_session_forgets_selection() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+
+    let result = service.close_capture_session(&sink).await;
+
+    assert_eq!(result, Ok(()));
+    assert!(!service.session_is_open());
+    assert!(service.selection().is_none());
+    assert_eq!(backend.close_session_count(), 1);
+    assert_eq!(sink.names(), vec![event_names::SESSION_CLOSED]);
+}
+
+#[tokio::test]
+async fn test_close_capture_session_while_recording_is_rejected() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+    service.start_capture(&sink, test_config()).await.unwrap();
+
+    let result = service.close_capture_session(&sink).await;
+
+    assert!(result.is_err());
+    assert!(service.session_is_open());
+    assert!(backend.is_recording());
+}
+
+#[tokio::test]
+async fn test_close_capture_session_without_session_is_rejected() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    assert!(service.close_capture_session(&sink).await.is_err());
+    assert!(sink.names().is_empty());
+}
+
+#[test]
+fn test_forget_session_reports_whether_one_was_open() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+
+    assert!(service.forget_session());
+    assert!(!service.forget_session());
+    assert!(!service.session_is_open());
+}
+
+#[tokio::test]
+async fn test_portal_closing_session_stops_replay_buffer() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .open_capture_session(&sink, test_config())
+        .await
+        .unwrap();
+    let replay = CaptureConfig {
+        mode: CaptureMode::ReplayBuffer,
+        ..test_config()
+    };
+    service.start_replay_buffer(&sink, replay).await.unwrap();
+    sink.clear();
+
+    service.portal_session_closed(&sink).await;
+
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(!backend.is_buffering());
+    assert!(!service.session_is_open());
+    assert!(service.selection().is_none());
+    assert_eq!(sink.names().last(), Some(&event_names::SESSION_CLOSED));
+}
+
+#[tokio::test]
+async fn test_portal_closing_session_stops_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .open_capture_session(&sink, test_config())
+        .await
+        .unwrap();
+    service.start_capture(&sink, test_config()).await.unwrap();
+
+    service.portal_session_closed(&sink).await;
+
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(!backend.is_recording());
+    assert_eq!(sink.stop_reasons(), vec![StopReason::SessionClosed]);
+    assert!(service.journal().entries().unwrap().is_empty());
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use super::*;
+
+/// Run the recording monitors until `service` is back to Idle
+async fn watch_until_idle(service: &CaptureService, sink: &RecordingSink) {
+    let idle = async {
+        while service.state() != CaptureState::Idle {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+    tokio::select! {
+        _ = service.watch_recordings(sink) => {}
+        _ = tokio::time::timeout(Duration::from_secs(5), idle) => {}
+    }
+}
+
+#[tokio::test]
+async fn test_recording_stops_at_its_duration_limit() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        max_duration_secs: Some(1),
+        ..audio_only_config()
+    };
+    service.start_audio_recording(&sink, config).await.unwrap();
+
+    watch_until_idle(&service, &sink).await;
+
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(sink.stop_reasons(), vec![StopReason::Limit]);
+    assert!(service.journal().entries().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_paused_time_does_not_count_towards_the_limit() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = CaptureConfig {
+        max_duration_secs: Some(1),
+        ..audio_only_config()
+    };
+    service.start_audio_recording(&sink, config).await.unwrap();
+    service.pause_recording(&sink).await.unwrap();
+
+    let watched =
+        tokio::time::timeout(Duration::from_millis(1500), service.watch_recordings(&sink));
+
+    assert!(watched.await.is_err());
+    assert_eq!(service.state(), CaptureState::Paused);
+    assert!(sink.stop_reasons().is_empty());
+}
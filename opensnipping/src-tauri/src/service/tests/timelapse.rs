@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use super::*;
+use crate::config::TimelapseConfig;
+use crate::events::TimelapseCompleteEvent;
+
+fn timelapse_config(frame_count: u32, encode_video: bool) -> CaptureConfig {
+    CaptureConfig {
+        mode: CaptureMode::Timelapse,
+        timelapse: Some(TimelapseConfig {
+            interval_secs: 60,
+            frame_count,
+            encode_video,
+            frames_dir: None,
+        }),
+        ..test_config()
+    }
+}
+
+fn frames_dir(config: &CaptureConfig) -> std::path::PathBuf {
+    config
+        .timelapse
+        .as_ref()
+        .unwrap()
+        .frames_dir_for(&config.output_path)
+}
+
+/// The `TimelapseComplete` payload `sink` received
+fn completion(sink: &RecordingSink) -> TimelapseCompleteEvent {
+    sink.events
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|event| match event {
+            CaptureEvent::TimelapseComplete(complete) => Some(complete.clone()),
+            _ => None,
+        })
+        .expect("no timelapse_complete event")
+}
+
+#[tokio::test]
+async fn test_timelapse_starts_in_one_transition() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = timelapse_config(3, false);
+
+    let run = service.start_timelapse(&sink, config.clone()).await;
+
+    assert!(run.is_ok());
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert_eq!(
+        sink.state_changes(),
+        vec![(CaptureState::Idle, CaptureState::Recording)]
+    );
+    assert_eq!(
+        sink.names(),
+        vec![event_names::STATE_CHANGED, event_names::SELECTION_COMPLETE]
+    );
+    assert!(frames_dir(&config).is_dir());
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[tokio::test]
+async fn test_timelapse_runs_to_frame_count() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = timelapse_config(1, true);
+    let run = service
+        .start_timelapse(&sink, config.clone())
+        .await
+        .unwrap();
+    sink.clear();
+
+    service.run_timelapse(&sink, run).await;
+
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert!(service.config().is_none());
+    let complete = completion(&sink);
+    assert_eq!(complete.frames, 1);
+    assert_eq!(complete.reason, StopReason::Limit);
+    assert!(complete.video.is_some());
+    assert_eq!(
+        sink.state_changes(),
+        vec![
+            (CaptureState::Recording, CaptureState::Finalizing),
+            (CaptureState::Finalizing, CaptureState::Idle),
+        ]
+    );
+    assert!(sink.names().contains(&event_names::TIMELAPSE_FRAME));
+    assert!(crate::capture::timelapse::frame_path(&frames_dir(&config), 1).is_file());
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[tokio::test]
+async fn test_stop_timelapse_finalizes_captured_frames() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = timelapse_config(5, false);
+    let run = service
+        .start_timelapse(&sink, config.clone())
+        .await
+        .unwrap();
+
+    let stop = async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        service.stop_timelapse(&sink)
+    };
+    let ((), stopped) = tokio::join!(service.run_timelapse(&sink, run), stop);
+
+    assert_eq!(stopped, Ok(CaptureState::Finalizing));
+    assert_eq!(service.state(), CaptureState::Idle);
+    let complete = completion(&sink);
+    assert_eq!(complete.frames, 1);
+    assert_eq!(complete.reason, StopReason::User);
+    assert!(complete.video.is_none());
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[tokio::test]
+async fn test_timelapse_start_failure_stays_idle() {
+    let backend = FakeCaptureBackend::permission_denied();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = timelapse_config(3, false);
+
+    let result = service.start_timelapse(&sink, config.clone()).await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+    assert!(service.config().is_none());
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[tokio::test]
+async fn test_timelapse_rejects_other_modes() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_timelapse(&sink, test_config()).await;
+
+    assert!(result.unwrap_err().starts_with("mode"));
+    assert_eq!(backend.selection_count(), 0);
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_timelapse_reuses_open_session() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+    let config = timelapse_config(3, false);
+
+    let result = service.start_timelapse(&sink, config.clone()).await;
+
+    assert!(result.is_ok());
+    assert_eq!(backend.selection_count(), 0);
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[tokio::test]
+async fn test_pause_timelapse_skips_the_backend() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    let config = timelapse_config(3, false);
+    service
+        .start_timelapse(&sink, config.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        service.pause_recording(&sink).await,
+        Ok(CaptureState::Paused)
+    );
+    assert_eq!(
+        service.resume_recording(&sink).await,
+        Ok(CaptureState::Recording)
+    );
+    assert_eq!(backend.pause_recording_count(), 0);
+    assert_eq!(backend.resume_recording_count(), 0);
+    let _ = std::fs::remove_dir_all(frames_dir(&config));
+}
+
+#[test]
+fn test_stop_timelapse_without_timelapse_is_rejected() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    assert!(service.stop_timelapse(&sink).is_err());
+    assert!(sink.names().is_empty());
+}
@@ -0,0 +1,229 @@
+use super::*;
+
+/// A service recording `test_config()` on `backend`, with the start events
+/// cleared
+async fn recording(backend: &FakeCaptureBackend, sink: &RecordingSink) -> CaptureService {
+    let service = service(backend);
+    service.start_capture(sink, test_config()).await.unwrap();
+    sink.clear();
+    service
+}
+
+#[tokio::test]
+async fn test_pipeline_start_failure_never_reports_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend.fail_operation(FakeOperation::StartRecording);
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    let result = service.start_capture(&sink, test_config()).await;
+
+    assert_eq!(result, Err("Injected StartRecording failure".to_string()));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(service.state_machine.lock().unwrap().pending(), None);
+    assert_eq!(
+        sink.state_changes(),
+        vec![
+            (CaptureState::Idle, CaptureState::Selecting),
+            (CaptureState::Selecting, CaptureState::Idle),
+        ]
+    );
+    assert_eq!(sink.error_codes(), vec![ErrorCode::Unknown]);
+    assert!(!sink.names().contains(&event_names::RECORDING_STARTED));
+    assert!(service.config().is_none());
+}
+
+#[tokio::test]
+async fn test_start_failure_closes_the_picked_portal_session_once() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend.fail_operation(FakeOperation::StartRecording);
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+
+    assert!(service.start_capture(&sink, test_config()).await.is_err());
+    service.release_session().await;
+
+    assert_eq!(backend.close_session_count(), 1);
+}
+
+#[tokio::test]
+async fn test_start_failure_keeps_the_open_capture_session() {
+    let backend = FakeCaptureBackend::succeeding();
+    backend.fail_operation(FakeOperation::StartRecording);
+    let service = service(&backend);
+    open_session(&service);
+    let sink = RecordingSink::default();
+
+    assert!(service.start_capture(&sink, test_config()).await.is_err());
+
+    assert_eq!(backend.close_session_count(), 0);
+    assert!(service.session_is_open());
+    assert_eq!(service.selection().map(|s| s.node_id), Some(7));
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_start_recording_preflight_failure_returns_to_idle() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    // No disk holds a minute of a stream this large
+    let selection = SelectionResult {
+        node_id: 42,
+        stream_fd: None,
+        width: Some(u32::MAX),
+        height: Some(u32::MAX),
+    };
+    service.select_for_test(test_config(), Some(selection));
+
+    let result = service.start_recording(&sink).await;
+
+    assert!(result.unwrap_err().starts_with("Not enough disk space"));
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(sink.error_codes(), vec![ErrorCode::IoError]);
+    assert_eq!(
+        sink.state_changes(),
+        vec![(CaptureState::Selecting, CaptureState::Idle)]
+    );
+    assert_eq!(backend.start_recording_count(), 0);
+}
+
+#[tokio::test]
+async fn test_start_recording_without_selection_keeps_state() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    let sink = RecordingSink::default();
+    service
+        .state_machine
+        .lock()
+        .unwrap()
+        .start_selecting(test_config())
+        .unwrap();
+    *service.config.lock().unwrap() = Some(test_config());
+
+    assert!(service.start_recording(&sink).await.is_err());
+    assert_eq!(service.state(), CaptureState::Selecting);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert!(sink.names().is_empty());
+}
+
+#[tokio::test]
+async fn test_start_recording_rejected_outside_selecting() {
+    let backend = FakeCaptureBackend::succeeding();
+    let service = service(&backend);
+    open_session(&service);
+    *service.config.lock().unwrap() = Some(test_config());
+    let sink = RecordingSink::default();
+
+    let result = service.start_recording(&sink).await;
+
+    assert!(result.is_err());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(backend.start_recording_count(), 0);
+    assert!(service.selection().is_some());
+    assert!(sink.names().is_empty());
+}
+
+#[tokio::test]
+async fn test_pause_failure_stays_recording() {
+    let backend = FakeCaptureBackend::succeeding();
+    let sink = RecordingSink::default();
+    let service = recording(&backend, &sink).await;
+    backend.fail_operation(FakeOperation::PauseRecording);
+
+    assert!(service.pause_recording(&sink).await.is_err());
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert!(!backend.is_paused());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+
+    backend.restore_operation(FakeOperation::PauseRecording);
+    assert_eq!(
+        service.pause_recording(&sink).await,
+        Ok(CaptureState::Paused)
+    );
+    assert!(backend.is_paused());
+}
+
+#[tokio::test]
+async fn test_resume_failure_stays_paused() {
+    let backend = FakeCaptureBackend::succeeding();
+    let sink = RecordingSink::default();
+    let service = recording(&backend, &sink).await;
+    service.pause_recording(&sink).await.unwrap();
+    sink.clear();
+    backend.fail_operation(FakeOperation::ResumeRecording);
+
+    assert!(service.resume_recording(&sink).await.is_err());
+    assert_eq!(service.state(), CaptureState::Paused);
+    assert!(backend.is_paused());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+}
+
+#[tokio::test]
+async fn test_stop_failure_rolls_back_and_can_retry() {
+    let backend = FakeCaptureBackend::succeeding();
+    let sink = RecordingSink::default();
+    let service = recording(&backend, &sink).await;
+    backend.fail_operation(FakeOperation::StopRecording);
+
+    assert!(service
+        .stop_recording(&sink, StopReason::User)
+        .await
+        .is_err());
+    assert_eq!(service.state(), CaptureState::Recording);
+    assert!(service.config().is_some());
+    assert!(service.selection().is_some());
+    assert_eq!(sink.names(), vec![event_names::ERROR]);
+
+    backend.restore_operation(FakeOperation::StopRecording);
+    assert!(service
+        .stop_recording(&sink, StopReason::User)
+        .await
+        .is_ok());
+    assert_eq!(service.state(), CaptureState::Idle);
+    assert_eq!(backend.stop_recording_count(), 2);
+}
+
+#[tokio::test]
+async fn test_stop_failure_from_paused_stays_paused() {
+    let backend = FakeCaptureBackend::succeeding();
+    let sink = RecordingSink::default();
+    let service = recording(&backend, &sink).await;
+    service.pause_recording(&sink).await.unwrap();
+    backend.fail_operation(FakeOperation::StopRecording);
+
+    assert!(service
+        .stop_recording(&sink, StopReason::Limit)
+        .await
+        .is_err());
+    assert_eq!(service.state(), CaptureState::Paused);
+    assert!(!sink.names().contains(&event_names::RECORDING_STOPPED));
+}
+
+#[tokio::test]
+async fn test_pending_transition_rejects_other_commands() {
+    let backend = FakeCaptureBackend::succeeding();
+    let sink = RecordingSink::default();
+    let service = recording(&backend, &sink).await;
+    service
+        .state_machine
+        .lock()
+        .unwrap()
+        .begin_pending(StateMachine::stop)
+        .unwrap();
+
+    assert!(service.pause_recording(&sink).await.is_err());
+    assert!(service
+        .stop_recording(&sink, StopReason::User)
+        .await
+        .is_err());
+    assert_eq!(backend.pause_recording_count(), 0);
+    assert_eq!(backend.stop_recording_count(), 0);
+    assert!(sink.names().is_empty());
+
+    service.state_machine.lock().unwrap().rollback();
+    assert_eq!(
+        service.pause_recording(&sink).await,
+        Ok(CaptureState::Paused)
+    );
+}
@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use super::{backend_error_to_capture_error, CaptureService, EventSink};
+use crate::capture::timelapse::{frame_path, TimelapseSchedule};
+use crate::capture::SelectionResult;
+use crate::config::{CaptureConfig, CaptureMode, ScreenshotConfig, TimelapseConfig};
+use crate::events::{
+    CaptureEvent, SelectionCompleteEvent, StopReason, TimelapseCompleteEvent, TimelapseFrameEvent,
+};
+use crate::naming;
+use crate::screenshot::save_image;
+use crate::state::{CaptureError, CaptureState, ErrorCode, StateMachine};
+
+/// How often the capture loop checks the state machine and the schedule
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A started timelapse, for `run_timelapse` to capture
+#[derive(Debug, Clone)]
+pub struct TimelapseRun {
+    config: CaptureConfig,
+    timelapse: TimelapseConfig,
+    selection: SelectionResult,
+    frames_dir: PathBuf,
+}
+
+impl CaptureService {
+    /// Pick a source for a timelapse (Idle → Recording)
+    ///
+    /// The state only moves once the picker succeeds; an open capture
+    /// session is used instead of the picker. Frames are captured by
+    /// `run_timelapse` with the returned run.
+    pub async fn start_timelapse(
+        &self,
+        events: &dyn EventSink,
+        config: CaptureConfig,
+    ) -> Result<TimelapseRun, String> {
+        let timelapse = match validate_timelapse_config(&config) {
+            Ok(timelapse) => timelapse,
+            Err(error) => {
+                events.publish_error(&error);
+                return Err(error.message);
+            }
+        };
+        let config = match naming::with_recording_path(config) {
+            Ok(config) => config,
+            Err(error) => {
+                events.publish_error(&error);
+                return Err(error.message);
+            }
+        };
+        let frames_dir = timelapse.frames_dir_for(&config.output_path);
+        if let Err(e) = std::fs::create_dir_all(&frames_dir) {
+            let error = CaptureError {
+                code: ErrorCode::IoError,
+                message: format!("Failed to create {}: {}", frames_dir.display(), e),
+            };
+            events.publish_error(&error);
+            return Err(error.message);
+        }
+
+        info!("Starting portal selection for timelapse...");
+        let select = self.select_source(&config);
+        let step = |sm: &mut StateMachine| {
+            sm.start_selecting(config.clone())?;
+            sm.begin_recording()
+        };
+        let selection = self.transition_with(events, step, select).await?;
+
+        *self.selection.lock().unwrap() = Some(selection.clone());
+        *self.config.lock().unwrap() = Some(config.clone());
+        events.publish(CaptureEvent::SelectionComplete(SelectionCompleteEvent {
+            selection: selection.clone(),
+        }));
+        Ok(TimelapseRun {
+            config,
+            timelapse,
+            selection,
+            frames_dir,
+        })
+    }
+
+    /// Grab due frames until the timelapse completes, is stopped or fails
+    ///
+    /// Paused time does not count towards the interval. Once the loop ends,
+    /// the frames (and the video, with `encode_video`) are finalized and
+    /// reported by `TimelapseComplete`, and the state returns to Idle.
+    pub async fn run_timelapse(&self, events: &dyn EventSink, run: TimelapseRun) {
+        let mut schedule = TimelapseSchedule::new(&run.timelapse);
+        let mut last_tick = Instant::now();
+        let reason = loop {
+            let recording = match self.state() {
+                CaptureState::Recording => true,
+                CaptureState::Paused => false,
+                CaptureState::Finalizing => break StopReason::User,
+                // Reset or failed elsewhere: nothing left to finalize
+                _ => return,
+            };
+            schedule.advance(last_tick.elapsed(), recording);
+            last_tick = Instant::now();
+
+            if !self.backend.has_session().await {
+                break StopReason::SessionClosed;
+            }
+            if let Some(index) = schedule.frame_due().filter(|_| recording) {
+                let path = frame_path(&run.frames_dir, index);
+                // The default screenshot config writes the PNGs the encoder reads
+                let grabbed = self
+                    .backend
+                    .capture_screenshot(&run.selection)
+                    .await
+                    .and_then(|frame| save_image(&frame, &ScreenshotConfig::default(), &path));
+                if let Err(backend_err) = grabbed {
+                    events.publish_error(&backend_error_to_capture_error(&backend_err));
+                    break StopReason::Error;
+                }
+                schedule.frame_captured();
+                events.publish(CaptureEvent::TimelapseFrame(TimelapseFrameEvent {
+                    index,
+                    frame_count: run.timelapse.frame_count,
+                    path: path.to_string_lossy().to_string(),
+                }));
+                if schedule.is_complete() {
+                    break StopReason::Limit;
+                }
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+
+        self.finish_timelapse(events, &run, schedule.captured(), reason)
+            .await;
+    }
+
+    /// End a timelapse early (Recording/Paused → Finalizing)
+    ///
+    /// `run_timelapse` then finalizes what it has and returns to Idle.
+    pub fn stop_timelapse(&self, events: &dyn EventSink) -> Result<CaptureState, String> {
+        if !self.timelapse_running() {
+            return Err("No timelapse in progress".to_string());
+        }
+        self.transition(events, StateMachine::stop)
+    }
+
+    /// Whether the capture in progress is a timelapse
+    pub(super) fn timelapse_running(&self) -> bool {
+        self.config()
+            .is_some_and(|config| config.mode == CaptureMode::Timelapse)
+    }
+
+    /// Walk Finalizing → Idle, encoding the frames first when requested
+    async fn finish_timelapse(
+        &self,
+        events: &dyn EventSink,
+        run: &TimelapseRun,
+        frames: u32,
+        reason: StopReason,
+    ) {
+        // stop_timelapse has already moved to Finalizing
+        if self.state() != CaptureState::Finalizing {
+            let _ = self.transition(events, StateMachine::stop);
+        }
+
+        // Encoding does not need the portal, so a closed session still encodes
+        let video = if frames > 0 && run.timelapse.encode_video {
+            match self
+                .backend
+                .encode_timelapse(&run.frames_dir, frames, &run.config)
+                .await
+            {
+                Ok(result) => Some(result),
+                Err(backend_err) => {
+                    events.publish_error(&backend_error_to_capture_error(&backend_err));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        self.release_session().await;
+        let _ = self.finalize_complete(events);
+
+        info!(
+            "Timelapse finished: {} frames in {} ({:?})",
+            frames,
+            run.frames_dir.display(),
+            reason
+        );
+        events.publish(CaptureEvent::TimelapseComplete(TimelapseCompleteEvent {
+            frames_dir: run.frames_dir.to_string_lossy().to_string(),
+            frames,
+            video,
+            reason,
+        }));
+    }
+}
+
+/// The timelapse settings of a valid timelapse `config`
+fn validate_timelapse_config(config: &CaptureConfig) -> Result<TimelapseConfig, CaptureError> {
+    let invalid = |message: String| CaptureError {
+        code: ErrorCode::InvalidConfig,
+        message,
+    };
+    if config.mode != CaptureMode::Timelapse {
+        return Err(invalid(
+            "mode: start_timelapse requires timelapse mode".to_string(),
+        ));
+    }
+    config
+        .validate()
+        .map_err(|err| invalid(format!("{}: {}", err.field, err.message)))?;
+    config
+        .timelapse
+        .clone()
+        .ok_or_else(|| invalid("timelapse: Timelapse mode requires timelapse settings".to_string()))
+}
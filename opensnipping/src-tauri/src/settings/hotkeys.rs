@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// Modifier names a shortcut may combine, as in `Ctrl+Shift+S`
+pub const MODIFIERS: [&str; 6] = ["Ctrl", "Shift", "Alt", "Super", "Meta", "CmdOrCtrl"];
+
+/// Global shortcuts, written as modifiers and a key joined by `+`
+///
+/// Unset actions have no shortcut.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Hotkeys {
+    /// Take a screenshot
+    pub screenshot: Option<String>,
+    /// Start a recording, or stop the running one
+    pub toggle_recording: Option<String>,
+    /// Pause or resume the running recording
+    pub toggle_pause: Option<String>,
+}
+
+impl Hotkeys {
+    fn bindings(&self) -> [(&'static str, Option<&str>); 3] {
+        [
+            ("screenshot", self.screenshot.as_deref()),
+            ("toggle_recording", self.toggle_recording.as_deref()),
+            ("toggle_pause", self.toggle_pause.as_deref()),
+        ]
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen: Vec<(&str, String)> = Vec::new();
+        for (action, shortcut) in self.bindings() {
+            let Some(shortcut) = shortcut else {
+                continue;
+            };
+            let normalized =
+                normalize(shortcut).map_err(|message| format!("{}: {}", action, message))?;
+            if let Some((other, _)) = seen.iter().find(|(_, taken)| *taken == normalized) {
+                return Err(format!(
+                    "{}: {:?} is already the {} shortcut",
+                    action, shortcut, other
+                ));
+            }
+            seen.push((action, normalized));
+        }
+        Ok(())
+    }
+}
+
+/// `shortcut` with sorted, case-folded modifiers, so `Shift+Ctrl+s` and
+/// `Ctrl+Shift+S` compare equal
+fn normalize(shortcut: &str) -> Result<String, String> {
+    let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(format!("Shortcut {:?} has an empty key", shortcut));
+    }
+    let (key, modifiers) = parts.split_last().expect("split yields a part");
+    if is_modifier(key) {
+        return Err(format!(
+            "Shortcut {:?} must end with a key, not a modifier",
+            shortcut
+        ));
+    }
+
+    let mut modifiers = modifiers
+        .iter()
+        .map(|modifier| {
+            MODIFIERS
+                .iter()
+                .find(|known| known.eq_ignore_ascii_case(modifier))
+                .map(|known| known.to_string())
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown modifier {:?} in {:?}; use {}",
+                        modifier,
+                        shortcut,
+                        MODIFIERS.join(", ")
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    modifiers.sort_unstable();
+    modifiers.dedup();
+    modifiers.push(key.to_string());
+    Ok(modifiers.join("+").to_ascii_uppercase())
+}
+
+fn is_modifier(part: &str) -> bool {
+    MODIFIERS
+        .iter()
+        .any(|modifier| modifier.eq_ignore_ascii_case(part))
+}
@@ -0,0 +1,58 @@
+use serde_json::{json, Map, Value};
+
+use super::DEFAULT_PROFILE_NAME;
+
+/// Schema version this build reads and writes
+pub const CURRENT_VERSION: u32 = 2;
+
+/// One upgrade step, applied to the top-level settings object
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades version `n + 1` to `n + 2`
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize - 1] = [capture_to_default_profile];
+
+/// Version 2 keeps named profiles; the single version 1 `capture` config
+/// becomes the default profile
+fn capture_to_default_profile(settings: &mut Map<String, Value>) {
+    if let Some(capture) = settings.remove("capture") {
+        settings.insert(
+            "profiles".to_string(),
+            json!([{ "name": DEFAULT_PROFILE_NAME, "config": capture }]),
+        );
+        settings.insert("default_profile".to_string(), DEFAULT_PROFILE_NAME.into());
+    }
+}
+
+/// Version a settings document was written with
+pub fn version_of(value: &Value) -> Result<u32, String> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| "Settings have no version".to_string())?;
+    u32::try_from(version)
+        .ok()
+        .filter(|version| *version >= 1)
+        .ok_or_else(|| format!("Settings version {} is not valid", version))
+}
+
+/// Upgrade a settings document to `CURRENT_VERSION`
+///
+/// Settings from a newer build are rejected rather than read with their
+/// new fields dropped.
+pub fn migrate(mut value: Value) -> Result<Value, String> {
+    let version = version_of(&value)?;
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "Settings version {} is newer than this build supports ({})",
+            version, CURRENT_VERSION
+        ));
+    }
+    let Some(settings) = value.as_object_mut() else {
+        return Err("Settings must be a JSON object".to_string());
+    };
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(settings);
+    }
+    settings.insert("version".to_string(), CURRENT_VERSION.into());
+    Ok(value)
+}
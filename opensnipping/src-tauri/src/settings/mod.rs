@@ -0,0 +1,162 @@
+// Persistent user settings
+//
+// One versioned JSON file under `$XDG_CONFIG_HOME/opensnipping` holds the
+// named capture profiles (one of them the default) and the global
+// shortcuts. Older versions are migrated on load; every load and save is
+// validated.
+
+mod hotkeys;
+mod migrate;
+mod profiles;
+
+#[cfg(test)]
+mod tests;
+
+pub use hotkeys::{Hotkeys, MODIFIERS};
+pub use migrate::{migrate, version_of, CURRENT_VERSION};
+pub use profiles::{
+    Profile, ProfileExport, DEFAULT_PROFILE_NAME, EXPORT_FORMAT, EXPORT_VERSION,
+    MAX_PROFILE_NAME_LEN,
+};
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use profiles::{default_profile_name, default_profiles};
+
+/// What the settings file stores
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    /// Schema version; always `CURRENT_VERSION` once loaded
+    pub version: u32,
+    /// Named capture setups, each with its output locations and encoder
+    /// preferences; never empty
+    #[serde(default = "default_profiles")]
+    pub profiles: Vec<Profile>,
+    /// Name of the profile new captures start from
+    #[serde(default = "default_profile_name")]
+    pub default_profile: String,
+    #[serde(default)]
+    pub hotkeys: Hotkeys,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            profiles: default_profiles(),
+            default_profile: default_profile_name(),
+            hotkeys: Hotkeys::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_profiles()?;
+        self.hotkeys
+            .validate()
+            .map_err(|message| format!("hotkeys.{}", message))
+    }
+}
+
+/// The settings file on disk
+#[derive(Debug, Clone)]
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl Default for SettingsStore {
+    fn default() -> Self {
+        Self::new(Self::default_path())
+    }
+}
+
+impl SettingsStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `$XDG_CONFIG_HOME/opensnipping/settings.json`, or the temp dir if unset
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("opensnipping")
+            .join("settings.json")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Saved settings migrated to `CURRENT_VERSION`; the defaults when
+    /// nothing is saved yet
+    pub fn load(&self) -> Result<Settings, String> {
+        let Some(value) = self.read()? else {
+            return Ok(Settings::default());
+        };
+        let settings: Settings = serde_json::from_value(migrate(value)?)
+            .map_err(|e| format!("Invalid settings in {}: {}", self.path.display(), e))?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Validate `settings` and write them as `CURRENT_VERSION`
+    ///
+    /// The file is replaced in one rename, so a crash leaves the old or the
+    /// new settings. Settings saved by a newer build are never overwritten.
+    pub fn save(&self, settings: &Settings) -> Result<Settings, String> {
+        let settings = Settings {
+            version: CURRENT_VERSION,
+            ..settings.clone()
+        };
+        settings.validate()?;
+        // An unreadable file may be replaced; that is how it gets fixed
+        if let Ok(Some(stored)) = self.read() {
+            if version_of(&stored).is_ok_and(|version| version > CURRENT_VERSION) {
+                return Err(format!(
+                    "{} was saved by a newer OpenSnipping; not overwriting it",
+                    self.path.display()
+                ));
+            }
+        }
+
+        let io_error = |e: std::io::Error| format!("Failed to save settings: {}", e);
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let json = serde_json::to_vec_pretty(&settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        let partial = self.path.with_extension("json.partial");
+        std::fs::write(&partial, json).map_err(io_error)?;
+        std::fs::rename(&partial, &self.path).map_err(io_error)?;
+        Ok(settings)
+    }
+
+    /// Load, change and save the settings, returning them as saved along
+    /// with what `change` returned
+    ///
+    /// Nothing is written when `change` fails.
+    pub fn update<T>(
+        &self,
+        change: impl FnOnce(&mut Settings) -> Result<T, String>,
+    ) -> Result<(Settings, T), String> {
+        let mut settings = self.load()?;
+        let changed = change(&mut settings)?;
+        Ok((self.save(&settings)?, changed))
+    }
+
+    /// The settings file as JSON, if there is one
+    fn read(&self) -> Result<Option<serde_json::Value>, String> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {}", self.path.display(), e)),
+        };
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| format!("{} is not valid JSON: {}", self.path.display(), e))
+    }
+}
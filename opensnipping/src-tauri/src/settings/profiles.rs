@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+
+use super::Settings;
+use crate::config::CaptureConfig;
+
+/// Name of the profile fresh settings start with
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+pub const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// `format` marker of exported profile files
+pub const EXPORT_FORMAT: &str = "opensnipping-profiles";
+
+/// Version of the export format this build writes
+pub const EXPORT_VERSION: u32 = 1;
+
+/// A named capture setup, such as "Tutorial" or "Bug report"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Unique, ignoring case
+    pub name: String,
+    /// Everything a capture needs, including its output locations (`naming`
+    /// and `screenshot.naming`)
+    pub config: CaptureConfig,
+}
+
+impl Profile {
+    pub fn new(name: impl Into<String>, config: CaptureConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name: Profile name cannot be empty".to_string());
+        }
+        if self.name.trim() != self.name {
+            return Err(format!(
+                "name: Profile name {:?} cannot start or end with spaces",
+                self.name
+            ));
+        }
+        if self.name.chars().count() > MAX_PROFILE_NAME_LEN {
+            return Err(format!(
+                "name: Profile name {:?} is longer than {} characters",
+                self.name, MAX_PROFILE_NAME_LEN
+            ));
+        }
+        self.config
+            .validate()
+            .map_err(|err| format!("{}.{}: {}", self.name, err.field, err.message))?;
+        if !self.config.output_path.is_empty() {
+            return Err(format!(
+                "{}.output_path: Profiles cannot fix an output file; set naming",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Profiles shared between installs as a JSON file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileExport {
+    /// Always `EXPORT_FORMAT`
+    pub format: String,
+    pub version: u32,
+    pub profiles: Vec<Profile>,
+}
+
+impl ProfileExport {
+    pub fn new(profiles: Vec<Profile>) -> Self {
+        Self {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            profiles,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to export profiles: {}", e))
+    }
+
+    /// Parse an export; its profiles are validated on import
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let export: Self =
+            serde_json::from_str(json).map_err(|e| format!("Not a profile export: {}", e))?;
+        if export.format != EXPORT_FORMAT {
+            return Err(format!(
+                "Not a profile export: format is {:?}, not {:?}",
+                export.format, EXPORT_FORMAT
+            ));
+        }
+        if export.version > EXPORT_VERSION {
+            return Err(format!(
+                "Profile export version {} is newer than this build supports ({})",
+                export.version, EXPORT_VERSION
+            ));
+        }
+        if export.profiles.is_empty() {
+            return Err("Profile export contains no profiles".to_string());
+        }
+        Ok(export)
+    }
+}
+
+pub(super) fn default_profiles() -> Vec<Profile> {
+    vec![Profile::new(DEFAULT_PROFILE_NAME, CaptureConfig::default())]
+}
+
+pub(super) fn default_profile_name() -> String {
+    DEFAULT_PROFILE_NAME.to_string()
+}
+
+impl Settings {
+    /// The profile called `name`, ignoring case
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| same_name(&profile.name, name))
+    }
+
+    /// `config` completed from the default profile
+    ///
+    /// Without a `config` the default profile's own is used; a `config`
+    /// without encoder preferences takes the default profile's.
+    pub fn capture_config(&self, config: Option<CaptureConfig>) -> CaptureConfig {
+        let defaults = self
+            .profile(&self.default_profile)
+            .map(|profile| &profile.config);
+        let mut config = config.or_else(|| defaults.cloned()).unwrap_or_default();
+        if config.encoder.is_none() {
+            config.encoder = defaults.and_then(|defaults| defaults.encoder);
+        }
+        config
+    }
+
+    /// Config to capture with the profile called `name`, completed by
+    /// `capture_config`
+    pub fn profile_config(&self, name: &str) -> Result<CaptureConfig, String> {
+        let index = self.index_of(name)?;
+        Ok(self.capture_config(Some(self.profiles[index].config.clone())))
+    }
+
+    pub fn create_profile(&mut self, profile: Profile) -> Result<(), String> {
+        profile.validate()?;
+        if self.profile(&profile.name).is_some() {
+            return Err(format!("A profile named {:?} already exists", profile.name));
+        }
+        self.profiles.push(profile);
+        Ok(())
+    }
+
+    /// Replace the profile called `name`; `profile` may rename it
+    pub fn update_profile(&mut self, name: &str, profile: Profile) -> Result<(), String> {
+        profile.validate()?;
+        let index = self.index_of(name)?;
+        if self
+            .profiles
+            .iter()
+            .position(|other| same_name(&other.name, &profile.name))
+            .is_some_and(|other| other != index)
+        {
+            return Err(format!("A profile named {:?} already exists", profile.name));
+        }
+        if same_name(&self.default_profile, name) {
+            self.default_profile = profile.name.clone();
+        }
+        self.profiles[index] = profile;
+        Ok(())
+    }
+
+    /// Remove the profile called `name`; the default profile stays, so
+    /// there is always one left
+    pub fn delete_profile(&mut self, name: &str) -> Result<Profile, String> {
+        let index = self.index_of(name)?;
+        if same_name(&self.default_profile, name) {
+            return Err(format!(
+                "{:?} is the default profile; make another profile the default first",
+                self.profiles[index].name
+            ));
+        }
+        Ok(self.profiles.remove(index))
+    }
+
+    pub fn set_default_profile(&mut self, name: &str) -> Result<(), String> {
+        let index = self.index_of(name)?;
+        self.default_profile = self.profiles[index].name.clone();
+        Ok(())
+    }
+
+    /// The profiles called `names`, or all of them when `names` is empty
+    pub fn export_profiles(&self, names: &[String]) -> Result<ProfileExport, String> {
+        if names.is_empty() {
+            return Ok(ProfileExport::new(self.profiles.clone()));
+        }
+        names
+            .iter()
+            .map(|name| {
+                self.profile(name)
+                    .cloned()
+                    .ok_or_else(|| format!("No profile named {:?}", name))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(ProfileExport::new)
+    }
+
+    /// Add the profiles of `export`, returning their names
+    ///
+    /// A name that is already taken fails the import unless `replace` is
+    /// set, in which case that profile is overwritten. Nothing is imported
+    /// if any profile is invalid.
+    pub fn import_profiles(
+        &mut self,
+        export: ProfileExport,
+        replace: bool,
+    ) -> Result<Vec<String>, String> {
+        for (index, profile) in export.profiles.iter().enumerate() {
+            profile.validate()?;
+            if export.profiles[..index]
+                .iter()
+                .any(|other| same_name(&other.name, &profile.name))
+            {
+                return Err(format!("The export has {:?} twice", profile.name));
+            }
+        }
+        if !replace {
+            let taken: Vec<&str> = export
+                .profiles
+                .iter()
+                .filter(|profile| self.profile(&profile.name).is_some())
+                .map(|profile| profile.name.as_str())
+                .collect();
+            if !taken.is_empty() {
+                return Err(format!(
+                    "Profiles already exist: {}; import with replace to overwrite them",
+                    taken.join(", ")
+                ));
+            }
+        }
+
+        let names = export
+            .profiles
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect();
+        for profile in export.profiles {
+            match self.index_of(&profile.name) {
+                Ok(index) => {
+                    if same_name(&self.default_profile, &profile.name) {
+                        self.default_profile = profile.name.clone();
+                    }
+                    self.profiles[index] = profile;
+                }
+                Err(_) => self.profiles.push(profile),
+            }
+        }
+        Ok(names)
+    }
+
+    pub(super) fn validate_profiles(&self) -> Result<(), String> {
+        if self.profiles.is_empty() {
+            return Err("profiles: Keep at least one profile".to_string());
+        }
+        for (index, profile) in self.profiles.iter().enumerate() {
+            profile
+                .validate()
+                .map_err(|message| format!("profiles.{}", message))?;
+            if self.profiles[..index]
+                .iter()
+                .any(|other| same_name(&other.name, &profile.name))
+            {
+                return Err(format!("profiles: {:?} is there twice", profile.name));
+            }
+        }
+        if self.profile(&self.default_profile).is_none() {
+            return Err(format!(
+                "default_profile: No profile named {:?}",
+                self.default_profile
+            ));
+        }
+        Ok(())
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize, String> {
+        self.profiles
+            .iter()
+            .position(|profile| same_name(&profile.name, name))
+            .ok_or_else(|| format!("No profile named {:?}", name))
+    }
+}
+
+/// Profile names are compared ignoring case
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
@@ -0,0 +1,59 @@
+use super::*;
+use crate::config::{
+    CaptureConfig, CaptureSource, ContainerFormat, EncoderConfig, EncoderPreset, OutputNaming,
+    ScreenshotFormat, VideoEncoder,
+};
+
+fn temp_store() -> SettingsStore {
+    SettingsStore::new(
+        std::env::temp_dir()
+            .join(format!(
+                "opensnipping-settings-test-{}",
+                uuid::Uuid::new_v4()
+            ))
+            .join("settings.json"),
+    )
+}
+
+fn cleanup(store: &SettingsStore) {
+    let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+}
+
+/// Default settings with a tweaked default profile and a shortcut
+fn customized() -> Settings {
+    let mut settings = Settings::default();
+    let config = &mut settings.profiles[0].config;
+    config.fps = 60;
+    config.container = ContainerFormat::Mkv;
+    config.naming = OutputNaming {
+        directory: Some("~/Videos/Captures".to_string()),
+        template: None,
+    };
+    config.screenshot.format = ScreenshotFormat::Jpeg;
+    settings.hotkeys.screenshot = Some("Ctrl+Shift+S".to_string());
+    settings
+}
+
+/// "Bug report": 30fps region capture without audio
+fn bug_report() -> Profile {
+    Profile::new(
+        "Bug report",
+        CaptureConfig {
+            source: CaptureSource::Region,
+            ..Default::default()
+        },
+    )
+}
+
+/// "Tutorial": 60fps with the microphone
+fn tutorial() -> Profile {
+    let mut config = CaptureConfig {
+        fps: 60,
+        ..Default::default()
+    };
+    config.audio.mic = true;
+    Profile::new("Tutorial", config)
+}
+
+mod profiles;
+mod store;
@@ -0,0 +1,275 @@
+use super::*;
+
+fn with_profiles() -> Settings {
+    let mut settings = Settings::default();
+    settings.create_profile(bug_report()).unwrap();
+    settings.create_profile(tutorial()).unwrap();
+    settings
+}
+
+fn names(settings: &Settings) -> Vec<&str> {
+    settings
+        .profiles
+        .iter()
+        .map(|profile| profile.name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_default_settings_have_a_default_profile() {
+    let settings = Settings::default();
+
+    assert_eq!(names(&settings), [DEFAULT_PROFILE_NAME]);
+    assert_eq!(settings.default_profile, DEFAULT_PROFILE_NAME);
+    assert_eq!(settings.validate(), Ok(()));
+}
+
+#[test]
+fn test_create_rejects_taken_and_invalid_names() {
+    let mut settings = with_profiles();
+
+    let taken = settings
+        .create_profile(Profile::new("TUTORIAL", CaptureConfig::default()))
+        .unwrap_err();
+    assert!(taken.contains("already exists"), "{}", taken);
+
+    let too_long = "x".repeat(MAX_PROFILE_NAME_LEN + 1);
+    for name in ["", "  ", " Demo", too_long.as_str()] {
+        let profile = Profile::new(name, CaptureConfig::default());
+        assert!(
+            settings.create_profile(profile).is_err(),
+            "{:?} accepted",
+            name
+        );
+    }
+
+    let mut invalid = tutorial();
+    invalid.name = "Demo".to_string();
+    invalid.config.fps = 0;
+    let err = settings.create_profile(invalid).unwrap_err();
+    assert!(err.starts_with("Demo.fps:"), "{}", err);
+    assert_eq!(names(&settings), ["Default", "Bug report", "Tutorial"]);
+}
+
+#[test]
+fn test_profile_lookup_ignores_case() {
+    let settings = with_profiles();
+
+    assert_eq!(settings.profile("bug REPORT"), Some(&bug_report()));
+    assert_eq!(settings.profile("Demo"), None);
+}
+
+#[test]
+fn test_capture_config_falls_back_to_the_default_profile() {
+    let mut settings = with_profiles();
+    let preferences = EncoderConfig {
+        encoder: VideoEncoder::Vaapi,
+        bitrate_kbps: Some(12_000),
+        preset: EncoderPreset::Fast,
+    };
+    settings.profiles[0].config.encoder = Some(preferences);
+
+    assert_eq!(settings.capture_config(None), settings.profiles[0].config);
+
+    let given = settings.capture_config(Some(tutorial().config));
+    assert_eq!(given.fps, 60);
+    assert_eq!(given.encoder, Some(preferences));
+
+    let x264 = EncoderConfig {
+        encoder: VideoEncoder::X264,
+        ..Default::default()
+    };
+    let own = CaptureConfig {
+        encoder: Some(x264),
+        ..Default::default()
+    };
+    assert_eq!(settings.capture_config(Some(own)).encoder, Some(x264));
+}
+
+#[test]
+fn test_profile_config_completes_the_named_profile() {
+    let mut settings = with_profiles();
+    let preferences = EncoderConfig {
+        bitrate_kbps: Some(2500),
+        ..Default::default()
+    };
+    settings.profiles[0].config.encoder = Some(preferences);
+
+    let config = settings.profile_config("tutorial").unwrap();
+
+    assert_eq!(config.fps, 60);
+    assert_eq!(config.encoder, Some(preferences));
+    assert!(settings.profile_config("Demo").is_err());
+}
+
+#[test]
+fn test_update_replaces_and_renames() {
+    let mut settings = with_profiles();
+    let mut faster = bug_report();
+    faster.name = "Quick bug report".to_string();
+    faster.config.fps = 15;
+
+    settings
+        .update_profile("Bug report", faster.clone())
+        .unwrap();
+
+    assert_eq!(
+        names(&settings),
+        ["Default", "Quick bug report", "Tutorial"]
+    );
+    assert_eq!(settings.profile("Quick bug report"), Some(&faster));
+
+    let clash = settings.update_profile("Quick bug report", tutorial());
+    assert!(clash.unwrap_err().contains("already exists"));
+    assert!(settings.update_profile("Demo", tutorial()).is_err());
+}
+
+#[test]
+fn test_renaming_the_default_profile_keeps_it_default() {
+    let mut settings = with_profiles();
+
+    settings
+        .update_profile(
+            "default",
+            Profile::new("Everyday", CaptureConfig::default()),
+        )
+        .unwrap();
+
+    assert_eq!(settings.default_profile, "Everyday");
+    assert_eq!(settings.validate(), Ok(()));
+}
+
+#[test]
+fn test_delete_keeps_the_default_profile() {
+    let mut settings = with_profiles();
+
+    assert_eq!(settings.delete_profile("tutorial"), Ok(tutorial()));
+    assert!(settings.delete_profile("Tutorial").is_err());
+
+    let err = settings.delete_profile("Default").unwrap_err();
+    assert!(err.contains("default profile"), "{}", err);
+
+    settings.set_default_profile("bug report").unwrap();
+    assert_eq!(settings.default_profile, "Bug report");
+    settings.delete_profile("Default").unwrap();
+    assert_eq!(names(&settings), ["Bug report"]);
+}
+
+#[test]
+fn test_validate_checks_profiles_and_default() {
+    let mut settings = with_profiles();
+    settings.default_profile = "Demo".to_string();
+    assert!(settings
+        .validate()
+        .unwrap_err()
+        .starts_with("default_profile:"));
+
+    let mut settings = with_profiles();
+    settings
+        .profiles
+        .push(Profile::new("tutorial", CaptureConfig::default()));
+    assert!(settings.validate().unwrap_err().contains("twice"));
+
+    let mut settings = with_profiles();
+    settings.profiles.clear();
+    assert!(settings.validate().is_err());
+}
+
+#[test]
+fn test_export_then_import_shares_profiles() {
+    let team = with_profiles();
+    let json = team
+        .export_profiles(&["Tutorial".to_string(), "bug report".to_string()])
+        .unwrap()
+        .to_json()
+        .unwrap();
+
+    let mut mine = Settings::default();
+    let imported = mine
+        .import_profiles(ProfileExport::from_json(&json).unwrap(), false)
+        .unwrap();
+
+    assert_eq!(imported, ["Tutorial", "Bug report"]);
+    assert_eq!(mine.profile("Tutorial"), Some(&tutorial()));
+    assert_eq!(mine.profile("Bug report"), Some(&bug_report()));
+    assert_eq!(mine.default_profile, DEFAULT_PROFILE_NAME);
+}
+
+#[test]
+fn test_export_without_names_has_every_profile() {
+    let settings = with_profiles();
+
+    let export = settings.export_profiles(&[]).unwrap();
+
+    assert_eq!(export.format, EXPORT_FORMAT);
+    assert_eq!(export.profiles, settings.profiles);
+    assert!(settings.export_profiles(&["Demo".to_string()]).is_err());
+}
+
+#[test]
+fn test_import_clashes_need_replace() {
+    let mut settings = with_profiles();
+    let mut changed = tutorial();
+    changed.config.fps = 24;
+    let export = ProfileExport::new(vec![
+        changed.clone(),
+        Profile::new("Demo", CaptureConfig::default()),
+    ]);
+
+    let err = settings.import_profiles(export.clone(), false).unwrap_err();
+    assert!(err.contains("Tutorial"), "{}", err);
+    assert_eq!(settings, with_profiles(), "nothing imported");
+
+    settings.import_profiles(export, true).unwrap();
+    assert_eq!(settings.profile("Tutorial"), Some(&changed));
+    assert!(settings.profile("Demo").is_some());
+}
+
+#[test]
+fn test_import_is_all_or_nothing() {
+    let mut settings = Settings::default();
+    let mut invalid = tutorial();
+    invalid.config.output_path = "/tmp/fixed.mp4".to_string();
+
+    let export = ProfileExport::new(vec![bug_report(), invalid]);
+    assert!(settings.import_profiles(export, true).is_err());
+
+    let export = ProfileExport::new(vec![bug_report(), bug_report()]);
+    assert!(settings.import_profiles(export, true).is_err());
+    assert_eq!(settings, Settings::default());
+}
+
+#[test]
+fn test_from_json_rejects_other_files() {
+    let settings_json = serde_json::to_string(&Settings::default()).unwrap();
+    let mut newer = ProfileExport::new(vec![tutorial()]);
+    newer.version = EXPORT_VERSION + 1;
+    let newer_json = newer.to_json().unwrap();
+    let empty_json = ProfileExport::new(Vec::new()).to_json().unwrap();
+
+    for (json, expected) in [
+        ("[]", "Not a profile export"),
+        (settings_json.as_str(), "Not a profile export"),
+        (newer_json.as_str(), "newer"),
+        (empty_json.as_str(), "no profiles"),
+    ] {
+        let err = ProfileExport::from_json(json).unwrap_err();
+        assert!(err.contains(expected), "{}: {}", json, err);
+    }
+}
+
+#[test]
+fn test_store_update_saves_only_successful_changes() {
+    let store = temp_store();
+
+    let (saved, ()) = store
+        .update(|settings| settings.create_profile(tutorial()))
+        .unwrap();
+    assert_eq!(store.load().unwrap(), saved);
+
+    assert!(store
+        .update(|settings| settings.create_profile(tutorial()))
+        .is_err());
+    assert_eq!(store.load().unwrap(), saved);
+    cleanup(&store);
+}
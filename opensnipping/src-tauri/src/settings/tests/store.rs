@@ -0,0 +1,191 @@
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn test_default_path_is_in_config_dir() {
+    let path = SettingsStore::default_path();
+    assert!(path.ends_with("opensnipping/settings.json"), "{:?}", path);
+}
+
+#[test]
+fn test_load_without_file_gives_defaults() {
+    let store = temp_store();
+    assert_eq!(store.load().unwrap(), Settings::default());
+    assert!(!store.path().exists(), "loading writes nothing");
+}
+
+#[test]
+fn test_save_then_load_round_trips() {
+    let store = temp_store();
+
+    let saved = store.save(&customized()).unwrap();
+
+    assert_eq!(saved, customized());
+    assert_eq!(store.load().unwrap(), customized());
+    cleanup(&store);
+}
+
+#[test]
+fn test_save_stamps_current_version() {
+    let store = temp_store();
+    let settings = Settings {
+        version: 0,
+        ..customized()
+    };
+
+    assert_eq!(store.save(&settings).unwrap().version, CURRENT_VERSION);
+
+    let stored: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(store.path()).unwrap()).unwrap();
+    assert_eq!(version_of(&stored), Ok(CURRENT_VERSION));
+    cleanup(&store);
+}
+
+#[test]
+fn test_save_rejects_invalid_capture_config() {
+    let store = temp_store();
+    let mut settings = customized();
+    settings.profiles[0].config.fps = 0;
+
+    let err = store.save(&settings).unwrap_err();
+
+    assert!(err.starts_with("profiles.Default.fps:"), "{}", err);
+    assert!(!store.path().exists());
+}
+
+#[test]
+fn test_profiles_cannot_fix_an_output_path() {
+    let mut settings = customized();
+    settings.profiles[0].config.output_path = "/tmp/always-this.mp4".to_string();
+
+    let err = settings.validate().unwrap_err();
+
+    assert!(err.starts_with("profiles.Default.output_path:"), "{}", err);
+}
+
+#[test]
+fn test_load_fills_missing_fields_with_defaults() {
+    let store = temp_store();
+    std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+    std::fs::write(store.path(), json!({ "version": 1 }).to_string()).unwrap();
+
+    assert_eq!(store.load().unwrap(), Settings::default());
+    cleanup(&store);
+}
+
+#[test]
+fn test_load_rejects_invalid_files() {
+    let store = temp_store();
+    std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+    let too_fast = serde_json::to_value(CaptureConfig {
+        fps: 90,
+        ..Default::default()
+    })
+    .unwrap();
+
+    for (contents, expected) in [
+        ("{ not json".to_string(), "not valid JSON"),
+        (json!({ "capture": {} }).to_string(), "no version"),
+        (
+            json!({ "version": 1, "capture": too_fast }).to_string(),
+            "profiles.Default.fps",
+        ),
+        (
+            json!({ "version": 1, "hotkeys": { "screenshot": "Ctrl+" } }).to_string(),
+            "hotkeys.screenshot",
+        ),
+    ] {
+        std::fs::write(store.path(), &contents).unwrap();
+        let err = store.load().unwrap_err();
+        assert!(err.contains(expected), "{}: {}", contents, err);
+    }
+    cleanup(&store);
+}
+
+#[test]
+fn test_newer_settings_are_neither_read_nor_overwritten() {
+    let store = temp_store();
+    std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+    let newer = json!({ "version": CURRENT_VERSION + 1, "profiles": [] }).to_string();
+    std::fs::write(store.path(), &newer).unwrap();
+
+    assert!(store.load().unwrap_err().contains("newer"));
+    assert!(store.save(&customized()).is_err());
+    assert_eq!(std::fs::read_to_string(store.path()).unwrap(), newer);
+    cleanup(&store);
+}
+
+#[test]
+fn test_unreadable_settings_can_be_replaced() {
+    let store = temp_store();
+    std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+    std::fs::write(store.path(), "{ not json").unwrap();
+
+    store.save(&customized()).unwrap();
+
+    assert_eq!(store.load().unwrap(), customized());
+    cleanup(&store);
+}
+
+#[test]
+fn test_version_1_capture_becomes_the_default_profile() {
+    let store = temp_store();
+    std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+    let capture = customized().profiles[0].config.clone();
+    let version_1 = json!({
+        "version": 1,
+        "capture": capture,
+        "hotkeys": { "screenshot": "Ctrl+Shift+S" },
+    });
+    std::fs::write(store.path(), version_1.to_string()).unwrap();
+
+    assert_eq!(store.load().unwrap(), customized());
+    cleanup(&store);
+}
+
+#[test]
+fn test_migrate_rejects_bad_versions() {
+    assert!(migrate(json!({ "version": 0 })).is_err());
+    assert!(migrate(json!({ "version": "1" })).is_err());
+    assert!(migrate(json!({ "version": CURRENT_VERSION + 1 })).is_err());
+    assert_eq!(
+        migrate(json!({ "version": CURRENT_VERSION })).unwrap(),
+        json!({ "version": CURRENT_VERSION })
+    );
+}
+
+#[test]
+fn test_hotkeys_accept_modifier_combinations() {
+    let hotkeys = Hotkeys {
+        screenshot: Some("Ctrl+Shift+S".to_string()),
+        toggle_recording: Some("Super+R".to_string()),
+        toggle_pause: Some("F9".to_string()),
+    };
+    assert_eq!(hotkeys.validate(), Ok(()));
+}
+
+#[test]
+fn test_hotkeys_reject_malformed_shortcuts() {
+    for shortcut in ["", "Ctrl+", "+S", "Ctrl+Shift", "Hyper+S", "Ctrl++S"] {
+        let hotkeys = Hotkeys {
+            screenshot: Some(shortcut.to_string()),
+            ..Default::default()
+        };
+        assert!(hotkeys.validate().is_err(), "{:?} accepted", shortcut);
+    }
+}
+
+#[test]
+fn test_hotkeys_reject_the_same_shortcut_twice() {
+    let hotkeys = Hotkeys {
+        screenshot: Some("Ctrl+Shift+S".to_string()),
+        toggle_recording: Some("shift+ctrl+s".to_string()),
+        ..Default::default()
+    };
+
+    let err = hotkeys.validate().unwrap_err();
+
+    assert!(err.starts_with("toggle_recording:"), "{}", err);
+    assert!(err.contains("screenshot"), "{}", err);
+}
@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::{CaptureError, CaptureState};
+use crate::config::CaptureConfig;
+
+/// Transitions kept by `TransitionHistory`
+pub const HISTORY_LEN: usize = 32;
+
+/// Current state of the state machine, with what belongs to it
+///
+/// Serialized with a `state` tag, e.g.
+/// `{"state": "recording", "session_id": "…", "config": {…}, "started_at_ms": …,
+/// "paused_ms": …}`. Times are milliseconds since the Unix epoch;
+/// `paused_ms` is how long the recording has been paused so far.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum StateDetail {
+    Idle,
+    /// Picking a source for a new capture session
+    Selecting {
+        session_id: String,
+        config: CaptureConfig,
+    },
+    Recording {
+        session_id: String,
+        config: CaptureConfig,
+        started_at_ms: u64,
+        paused_ms: u64,
+    },
+    Paused {
+        session_id: String,
+        config: CaptureConfig,
+        started_at_ms: u64,
+        paused_at_ms: u64,
+        /// Earlier pauses only; the current one started at `paused_at_ms`
+        paused_ms: u64,
+    },
+    Buffering {
+        session_id: String,
+        config: CaptureConfig,
+        started_at_ms: u64,
+    },
+    Finalizing {
+        session_id: String,
+        config: CaptureConfig,
+        started_at_ms: u64,
+        paused_ms: u64,
+    },
+    /// `session_id` is the session that failed, if one was running
+    Error {
+        error: CaptureError,
+        session_id: Option<String>,
+    },
+}
+
+impl StateDetail {
+    pub fn state(&self) -> CaptureState {
+        match self {
+            StateDetail::Idle => CaptureState::Idle,
+            StateDetail::Selecting { .. } => CaptureState::Selecting,
+            StateDetail::Recording { .. } => CaptureState::Recording,
+            StateDetail::Paused { .. } => CaptureState::Paused,
+            StateDetail::Buffering { .. } => CaptureState::Buffering,
+            StateDetail::Finalizing { .. } => CaptureState::Finalizing,
+            StateDetail::Error { .. } => CaptureState::Error,
+        }
+    }
+
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            StateDetail::Idle => None,
+            StateDetail::Selecting { session_id, .. }
+            | StateDetail::Recording { session_id, .. }
+            | StateDetail::Paused { session_id, .. }
+            | StateDetail::Buffering { session_id, .. }
+            | StateDetail::Finalizing { session_id, .. } => Some(session_id),
+            StateDetail::Error { session_id, .. } => session_id.as_deref(),
+        }
+    }
+
+    pub fn config(&self) -> Option<&CaptureConfig> {
+        match self {
+            StateDetail::Selecting { config, .. }
+            | StateDetail::Recording { config, .. }
+            | StateDetail::Paused { config, .. }
+            | StateDetail::Buffering { config, .. }
+            | StateDetail::Finalizing { config, .. } => Some(config),
+            StateDetail::Idle | StateDetail::Error { .. } => None,
+        }
+    }
+
+    /// When the session started recording or buffering
+    pub fn started_at_ms(&self) -> Option<u64> {
+        match self {
+            StateDetail::Recording { started_at_ms, .. }
+            | StateDetail::Paused { started_at_ms, .. }
+            | StateDetail::Buffering { started_at_ms, .. }
+            | StateDetail::Finalizing { started_at_ms, .. } => Some(*started_at_ms),
+            _ => None,
+        }
+    }
+
+    /// Time the recording spent paused, up to now while Paused
+    pub fn paused_ms(&self) -> Option<u64> {
+        match self {
+            StateDetail::Recording { paused_ms, .. }
+            | StateDetail::Finalizing { paused_ms, .. } => Some(*paused_ms),
+            StateDetail::Paused {
+                paused_at_ms,
+                paused_ms,
+                ..
+            } => Some(paused_ms + now_ms().saturating_sub(*paused_at_ms)),
+            _ => None,
+        }
+    }
+
+    /// Detail for a new session entering `Selecting`
+    pub(super) fn selecting(config: CaptureConfig) -> Self {
+        StateDetail::Selecting {
+            session_id: new_session_id(),
+            config,
+        }
+    }
+
+    /// Detail for a new session recording without a picker (audio-only)
+    pub(super) fn recording(config: CaptureConfig) -> Self {
+        StateDetail::Recording {
+            session_id: new_session_id(),
+            config,
+            started_at_ms: now_ms(),
+            paused_ms: 0,
+        }
+    }
+
+    /// Detail for `to`, carrying this state's session over
+    ///
+    /// Only called for transitions the transition table allows. Entering
+    /// Selecting or Error needs data this state does not have, so those go
+    /// through `selecting` and `StateMachine::set_error` instead.
+    pub(super) fn advance(&self, to: CaptureState) -> Self {
+        let now = now_ms();
+        match (self, to) {
+            (_, CaptureState::Idle) => StateDetail::Idle,
+            (StateDetail::Selecting { session_id, config }, CaptureState::Recording) => {
+                StateDetail::Recording {
+                    session_id: session_id.clone(),
+                    config: config.clone(),
+                    started_at_ms: now,
+                    paused_ms: 0,
+                }
+            }
+            (StateDetail::Selecting { session_id, config }, CaptureState::Buffering) => {
+                StateDetail::Buffering {
+                    session_id: session_id.clone(),
+                    config: config.clone(),
+                    started_at_ms: now,
+                }
+            }
+            (
+                StateDetail::Recording {
+                    session_id,
+                    config,
+                    started_at_ms,
+                    paused_ms,
+                },
+                CaptureState::Paused,
+            ) => StateDetail::Paused {
+                session_id: session_id.clone(),
+                config: config.clone(),
+                started_at_ms: *started_at_ms,
+                paused_at_ms: now,
+                paused_ms: *paused_ms,
+            },
+            (
+                StateDetail::Paused {
+                    session_id,
+                    config,
+                    started_at_ms,
+                    paused_at_ms,
+                    paused_ms,
+                },
+                CaptureState::Recording,
+            ) => StateDetail::Recording {
+                session_id: session_id.clone(),
+                config: config.clone(),
+                started_at_ms: *started_at_ms,
+                paused_ms: paused_ms + now.saturating_sub(*paused_at_ms),
+            },
+            (
+                StateDetail::Recording {
+                    session_id,
+                    config,
+                    started_at_ms,
+                    ..
+                }
+                | StateDetail::Paused {
+                    session_id,
+                    config,
+                    started_at_ms,
+                    ..
+                },
+                CaptureState::Finalizing,
+            ) => StateDetail::Finalizing {
+                session_id: session_id.clone(),
+                config: config.clone(),
+                started_at_ms: *started_at_ms,
+                paused_ms: self.paused_ms().unwrap_or(0),
+            },
+            // Same state (no-op)
+            _ => self.clone(),
+        }
+    }
+}
+
+/// One applied transition
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransitionRecord {
+    pub from: CaptureState,
+    pub to: CaptureState,
+    pub at_ms: u64,
+    /// Session the transition belonged to
+    pub session_id: Option<String>,
+}
+
+/// The last `HISTORY_LEN` transitions, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct TransitionHistory {
+    records: VecDeque<TransitionRecord>,
+}
+
+impl TransitionHistory {
+    pub fn push(&mut self, record: TransitionRecord) {
+        if self.records.len() == HISTORY_LEN {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &TransitionRecord> {
+        self.records.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+pub(super) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn new_session_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
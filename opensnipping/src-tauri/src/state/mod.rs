@@ -0,0 +1,346 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::CaptureConfig;
+
+mod detail;
+
+pub use detail::{StateDetail, TransitionHistory, TransitionRecord, HISTORY_LEN};
+
+use detail::now_ms;
+
+/// Recording states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureState {
+    /// No capture in progress
+    #[default]
+    Idle,
+    /// User is selecting capture source
+    Selecting,
+    /// Recording in progress
+    Recording,
+    /// Recording paused
+    Paused,
+    /// Replay buffer running: the last N seconds are kept in memory until saved
+    Buffering,
+    /// Finalizing output file
+    Finalizing,
+    /// Error state
+    Error,
+}
+
+/// Error codes for capture errors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    PermissionDenied,
+    PortalError,
+    EncoderUnavailable,
+    PipelineError,
+    IoError,
+    InvalidConfig,
+    Unknown,
+}
+
+/// Error details
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// State transition error
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionError {
+    pub from: CaptureState,
+    pub to: CaptureState,
+    pub message: String,
+}
+
+impl std::fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid transition from {:?} to {:?}: {}",
+            self.from, self.to, self.message
+        )
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
+/// State machine for capture orchestration
+///
+/// Each state carries its session (see `StateDetail`), and applied
+/// transitions are kept in a bounded `TransitionHistory`.
+#[derive(Debug)]
+pub struct StateMachine {
+    detail: StateDetail,
+    /// Target of a reserved transition waiting on a backend operation
+    pending: Option<StateDetail>,
+    history: TransitionHistory,
+}
+
+impl Default for StateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateMachine {
+    pub fn new() -> Self {
+        Self {
+            detail: StateDetail::Idle,
+            pending: None,
+            history: TransitionHistory::default(),
+        }
+    }
+
+    pub fn state(&self) -> CaptureState {
+        self.detail.state()
+    }
+
+    /// Current state with its session, config and timestamps
+    pub fn detail(&self) -> &StateDetail {
+        &self.detail
+    }
+
+    /// Last transitions, oldest first
+    pub fn history(&self) -> &TransitionHistory {
+        &self.history
+    }
+
+    pub fn last_error(&self) -> Option<&CaptureError> {
+        match &self.detail {
+            StateDetail::Error { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// State a reserved transition moves to once it is committed
+    pub fn pending(&self) -> Option<CaptureState> {
+        self.pending.as_ref().map(StateDetail::state)
+    }
+
+    /// Reserve the transition `step` would make, without applying it
+    ///
+    /// Until `commit` or `rollback`, every other transition is rejected, so
+    /// a backend operation can run first and the state only changes once it
+    /// succeeds. Timestamps in the reserved detail are taken now.
+    pub fn begin_pending(
+        &mut self,
+        step: impl FnOnce(&mut StateMachine) -> Result<CaptureState, TransitionError>,
+    ) -> Result<CaptureState, TransitionError> {
+        let mut probe = StateMachine {
+            detail: self.detail.clone(),
+            pending: self.pending.clone(),
+            history: TransitionHistory::default(),
+        };
+        let to = step(&mut probe)?;
+        self.pending = Some(probe.detail);
+        Ok(to)
+    }
+
+    /// Apply the reserved transition
+    ///
+    /// Fails when nothing is reserved, e.g. because `set_error` replaced the
+    /// reservation while the backend operation ran.
+    pub fn commit(&mut self) -> Result<CaptureState, TransitionError> {
+        let detail = self.pending.take().ok_or_else(|| TransitionError {
+            from: self.state(),
+            to: self.state(),
+            message: "No transition is pending".to_string(),
+        })?;
+        Ok(self.enter(detail))
+    }
+
+    /// Drop the reserved transition and stay in the current state
+    pub fn rollback(&mut self) -> CaptureState {
+        self.pending = None;
+        self.state()
+    }
+
+    /// Replace the current detail, recording the change in the history
+    fn enter(&mut self, detail: StateDetail) -> CaptureState {
+        let from = self.state();
+        let to = detail.state();
+        if from != to {
+            self.history.push(TransitionRecord {
+                from,
+                to,
+                at_ms: now_ms(),
+                session_id: detail
+                    .session_id()
+                    .or_else(|| self.detail.session_id())
+                    .map(str::to_string),
+            });
+        }
+        self.detail = detail;
+        to
+    }
+
+    /// Reject transitions while another one is reserved
+    fn check_not_pending(&self, to: CaptureState) -> Result<(), TransitionError> {
+        match self.pending() {
+            Some(pending) => Err(TransitionError {
+                from: self.state(),
+                to,
+                message: format!("A transition to {:?} is in progress", pending),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Validate a state transition against the transition table
+    fn check_transition(&self, to: CaptureState) -> Result<(), TransitionError> {
+        self.check_not_pending(to)?;
+        let from = self.state();
+        let valid = match (from, to) {
+            // From Idle
+            (CaptureState::Idle, CaptureState::Selecting) => true,
+            (CaptureState::Idle, CaptureState::Error) => true,
+
+            // From Selecting
+            (CaptureState::Selecting, CaptureState::Recording) => true,
+            (CaptureState::Selecting, CaptureState::Buffering) => true,
+            (CaptureState::Selecting, CaptureState::Idle) => true, // cancelled
+            (CaptureState::Selecting, CaptureState::Error) => true,
+
+            // From Recording
+            (CaptureState::Recording, CaptureState::Paused) => true,
+            (CaptureState::Recording, CaptureState::Finalizing) => true,
+            (CaptureState::Recording, CaptureState::Error) => true,
+
+            // From Paused
+            (CaptureState::Paused, CaptureState::Recording) => true,
+            (CaptureState::Paused, CaptureState::Finalizing) => true,
+            (CaptureState::Paused, CaptureState::Error) => true,
+
+            // From Buffering (saving a replay does not leave the state)
+            (CaptureState::Buffering, CaptureState::Idle) => true,
+            (CaptureState::Buffering, CaptureState::Error) => true,
+
+            // From Finalizing
+            (CaptureState::Finalizing, CaptureState::Idle) => true,
+            (CaptureState::Finalizing, CaptureState::Error) => true,
+
+            // From Error
+            (CaptureState::Error, CaptureState::Idle) => true, // reset
+
+            // Same state is always valid (no-op)
+            (a, b) if a == b => true,
+
+            _ => false,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(TransitionError {
+                from,
+                to,
+                message: format!("Cannot transition from {:?} to {:?}", from, to),
+            })
+        }
+    }
+
+    /// Validate and perform state transition, carrying the session over
+    fn transition(&mut self, to: CaptureState) -> Result<CaptureState, TransitionError> {
+        self.check_transition(to)?;
+        let detail = self.detail.advance(to);
+        Ok(self.enter(detail))
+    }
+
+    /// Start a capture session for `config` (Idle → Selecting)
+    pub fn start_selecting(
+        &mut self,
+        config: CaptureConfig,
+    ) -> Result<CaptureState, TransitionError> {
+        self.check_transition(CaptureState::Selecting)?;
+        Ok(self.enter(StateDetail::selecting(config)))
+    }
+    /// Cancel selection (Selecting → Idle)
+    pub fn cancel_selection(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Idle)
+    }
+
+    /// Begin recording (Selecting → Recording)
+    pub fn begin_recording(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Recording)
+    }
+
+    /// Begin audio-only recording (Idle → Recording)
+    ///
+    /// Audio-only capture has no portal picker, so it skips Selecting.
+    pub fn begin_audio_recording(
+        &mut self,
+        config: CaptureConfig,
+    ) -> Result<CaptureState, TransitionError> {
+        self.check_not_pending(CaptureState::Recording)?;
+        if self.state() != CaptureState::Idle {
+            return Err(TransitionError {
+                from: self.state(),
+                to: CaptureState::Recording,
+                message: format!(
+                    "Audio-only recording can only start from Idle, not {:?}",
+                    self.state()
+                ),
+            });
+        }
+        Ok(self.enter(StateDetail::recording(config)))
+    }
+
+    /// Begin replay buffering (Selecting → Buffering)
+    pub fn begin_replay_buffer(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Buffering)
+    }
+
+    /// Stop replay buffering and discard the buffer (Buffering → Idle)
+    pub fn stop_replay_buffer(&mut self) -> Result<CaptureState, TransitionError> {
+        if self.state() != CaptureState::Buffering {
+            return Err(TransitionError {
+                from: self.state(),
+                to: CaptureState::Idle,
+                message: "Replay buffer is not running".to_string(),
+            });
+        }
+        self.transition(CaptureState::Idle)
+    }
+
+    /// Pause recording (Recording → Paused)
+    pub fn pause(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Paused)
+    }
+
+    /// Resume recording (Paused → Recording)
+    pub fn resume(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Recording)
+    }
+
+    /// Stop recording (Recording/Paused → Finalizing)
+    pub fn stop(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Finalizing)
+    }
+
+    /// Finalize complete (Finalizing → Idle)
+    pub fn finalize_complete(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Idle)
+    }
+
+    /// Set error state, dropping any reserved transition
+    ///
+    /// Keeps the failed session's id.
+    pub fn set_error(&mut self, error: CaptureError) -> CaptureState {
+        self.pending = None;
+        let session_id = self.detail.session_id().map(str::to_string);
+        self.enter(StateDetail::Error { error, session_id })
+    }
+
+    /// Reset from error (Error → Idle)
+    pub fn reset(&mut self) -> Result<CaptureState, TransitionError> {
+        self.transition(CaptureState::Idle)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,345 @@
+use super::*;
+
+#[test]
+fn test_initial_state_is_idle() {
+    let sm = StateMachine::new();
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_valid_full_recording_flow() {
+    let mut sm = StateMachine::new();
+
+    // Idle → Selecting
+    assert!(sm.start_selecting(CaptureConfig::default()).is_ok());
+    assert_eq!(sm.state(), CaptureState::Selecting);
+
+    // Selecting → Recording
+    assert!(sm.begin_recording().is_ok());
+    assert_eq!(sm.state(), CaptureState::Recording);
+
+    // Recording → Paused
+    assert!(sm.pause().is_ok());
+    assert_eq!(sm.state(), CaptureState::Paused);
+
+    // Paused → Recording
+    assert!(sm.resume().is_ok());
+    assert_eq!(sm.state(), CaptureState::Recording);
+
+    // Recording → Finalizing
+    assert!(sm.stop().is_ok());
+    assert_eq!(sm.state(), CaptureState::Finalizing);
+
+    // Finalizing → Idle
+    assert!(sm.finalize_complete().is_ok());
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_cancel_selection() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    assert!(sm.cancel_selection().is_ok());
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_invalid_transition_idle_to_recording() {
+    let mut sm = StateMachine::new();
+    let result = sm.begin_recording();
+    assert!(result.is_err());
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_replay_buffer_flow() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+
+    // Selecting → Buffering
+    assert!(sm.begin_replay_buffer().is_ok());
+    assert_eq!(sm.state(), CaptureState::Buffering);
+
+    // A buffer cannot be paused or finalized like a recording
+    assert!(sm.pause().is_err());
+    assert!(sm.stop().is_err());
+
+    // Buffering → Idle
+    assert!(sm.stop_replay_buffer().is_ok());
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_stop_replay_buffer_requires_buffering() {
+    let mut sm = StateMachine::new();
+    assert!(sm.stop_replay_buffer().is_err());
+
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    assert!(sm.stop_replay_buffer().is_err());
+    assert_eq!(sm.state(), CaptureState::Selecting);
+}
+
+#[test]
+fn test_audio_recording_flow() {
+    let mut sm = StateMachine::new();
+
+    // Idle → Recording (no selection step)
+    assert!(sm.begin_audio_recording(CaptureConfig::default()).is_ok());
+    assert_eq!(sm.state(), CaptureState::Recording);
+
+    assert!(sm.pause().is_ok());
+    assert!(sm.resume().is_ok());
+    assert!(sm.stop().is_ok());
+    assert!(sm.finalize_complete().is_ok());
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_audio_recording_requires_idle() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+
+    let result = sm.begin_audio_recording(CaptureConfig::default());
+    assert!(result.is_err());
+    assert_eq!(sm.state(), CaptureState::Selecting);
+}
+
+#[test]
+fn test_invalid_transition_idle_to_paused() {
+    let mut sm = StateMachine::new();
+    let result = sm.pause();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_invalid_transition_selecting_to_paused() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    let result = sm.pause();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_error_state_and_reset() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+
+    sm.set_error(CaptureError {
+        code: ErrorCode::PortalError,
+        message: "Portal denied access".to_string(),
+    });
+
+    assert_eq!(sm.state(), CaptureState::Error);
+    assert!(sm.last_error().is_some());
+    assert_eq!(sm.last_error().unwrap().code, ErrorCode::PortalError);
+
+    // Reset from error
+    assert!(sm.reset().is_ok());
+    assert_eq!(sm.state(), CaptureState::Idle);
+}
+
+#[test]
+fn test_stop_from_paused() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    sm.begin_recording().unwrap();
+    sm.pause().unwrap();
+
+    assert!(sm.stop().is_ok());
+    assert_eq!(sm.state(), CaptureState::Finalizing);
+}
+
+#[test]
+fn test_pending_transition_commits() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+
+    assert_eq!(
+        sm.begin_pending(StateMachine::begin_recording),
+        Ok(CaptureState::Recording)
+    );
+    assert_eq!(sm.state(), CaptureState::Selecting);
+    assert!(sm.cancel_selection().is_err());
+    assert!(sm.begin_pending(StateMachine::cancel_selection).is_err());
+
+    assert_eq!(sm.commit(), Ok(CaptureState::Recording));
+    assert_eq!(sm.state(), CaptureState::Recording);
+    assert_eq!(sm.pending(), None);
+}
+
+#[test]
+fn test_pending_transition_rolls_back() {
+    let mut sm = StateMachine::new();
+    sm.begin_pending(|sm| sm.begin_audio_recording(CaptureConfig::default()))
+        .unwrap();
+
+    assert_eq!(sm.rollback(), CaptureState::Idle);
+    assert!(sm.commit().is_err());
+    assert!(sm.begin_pending(StateMachine::pause).is_err());
+    assert_eq!(sm.pending(), None);
+}
+
+#[test]
+fn test_set_error_drops_pending_transition() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    sm.begin_pending(StateMachine::begin_recording).unwrap();
+
+    sm.set_error(CaptureError {
+        code: ErrorCode::PortalError,
+        message: "Portal closed".to_string(),
+    });
+
+    assert!(sm.commit().is_err());
+    assert_eq!(sm.state(), CaptureState::Error);
+}
+
+#[test]
+fn test_same_state_transition_is_noop() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    sm.begin_recording().unwrap();
+
+    // Calling begin_recording again should be a no-op
+    let result = sm.transition(CaptureState::Recording);
+    assert!(result.is_ok());
+    assert_eq!(sm.state(), CaptureState::Recording);
+}
+
+fn config_at(output_path: &str) -> CaptureConfig {
+    CaptureConfig {
+        output_path: output_path.to_string(),
+        ..CaptureConfig::default()
+    }
+}
+
+#[test]
+fn test_recording_detail_carries_session() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(config_at("/tmp/a.mp4")).unwrap();
+    let session_id = sm.detail().session_id().unwrap().to_string();
+
+    sm.begin_recording().unwrap();
+    let started_at_ms = sm.detail().started_at_ms().unwrap();
+    assert_eq!(sm.detail().session_id(), Some(session_id.as_str()));
+    assert_eq!(sm.detail().config(), Some(&config_at("/tmp/a.mp4")));
+
+    sm.pause().unwrap();
+    assert!(matches!(
+        sm.detail(),
+        StateDetail::Paused { paused_at_ms, .. } if *paused_at_ms >= started_at_ms
+    ));
+    sm.resume().unwrap();
+    sm.stop().unwrap();
+    assert_eq!(sm.detail().session_id(), Some(session_id.as_str()));
+    assert_eq!(sm.detail().started_at_ms(), Some(started_at_ms));
+
+    sm.finalize_complete().unwrap();
+    assert_eq!(sm.detail(), &StateDetail::Idle);
+}
+
+#[test]
+fn test_paused_time_is_carried_to_finalizing() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(config_at("/tmp/a.mp4")).unwrap();
+    sm.begin_recording().unwrap();
+    assert_eq!(sm.detail().paused_ms(), Some(0));
+
+    sm.pause().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(30));
+    sm.resume().unwrap();
+    let after_first = sm.detail().paused_ms().unwrap();
+    assert!(after_first >= 30, "{}", after_first);
+
+    sm.pause().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(30));
+    sm.stop().unwrap();
+
+    assert!(sm.detail().paused_ms().unwrap() >= after_first + 30);
+}
+
+#[test]
+fn test_each_capture_gets_a_new_session() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    let first = sm.detail().session_id().unwrap().to_string();
+    sm.cancel_selection().unwrap();
+
+    sm.begin_audio_recording(config_at("/tmp/a.ogg")).unwrap();
+
+    let second = sm.detail().session_id().unwrap();
+    assert_ne!(first, second);
+    assert_eq!(sm.detail().config(), Some(&config_at("/tmp/a.ogg")));
+}
+
+#[test]
+fn test_error_keeps_failed_session() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    let session_id = sm.detail().session_id().unwrap().to_string();
+    let error = CaptureError {
+        code: ErrorCode::PipelineError,
+        message: "Pipeline failed".to_string(),
+    };
+
+    sm.set_error(error.clone());
+
+    assert_eq!(sm.last_error(), Some(&error));
+    assert_eq!(sm.detail().session_id(), Some(session_id.as_str()));
+    sm.reset().unwrap();
+    assert_eq!(sm.last_error(), None);
+}
+
+#[test]
+fn test_history_records_transitions() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+    let session_id = sm.detail().session_id().map(str::to_string);
+    sm.begin_recording().unwrap();
+    sm.begin_recording().unwrap();
+    sm.stop().unwrap();
+    sm.finalize_complete().unwrap();
+
+    let steps: Vec<_> = sm.history().records().map(|r| (r.from, r.to)).collect();
+    assert_eq!(
+        steps,
+        vec![
+            (CaptureState::Idle, CaptureState::Selecting),
+            (CaptureState::Selecting, CaptureState::Recording),
+            (CaptureState::Recording, CaptureState::Finalizing),
+            (CaptureState::Finalizing, CaptureState::Idle),
+        ]
+    );
+    assert!(sm
+        .history()
+        .records()
+        .all(|record| record.session_id == session_id));
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let mut sm = StateMachine::new();
+    for _ in 0..HISTORY_LEN {
+        sm.start_selecting(CaptureConfig::default()).unwrap();
+        sm.cancel_selection().unwrap();
+    }
+
+    assert_eq!(sm.history().len(), HISTORY_LEN);
+    let last = sm.history().records().last().unwrap();
+    assert_eq!(last.to, CaptureState::Idle);
+}
+
+#[test]
+fn test_pending_transition_reaches_history_on_commit_only() {
+    let mut sm = StateMachine::new();
+    sm.start_selecting(CaptureConfig::default()).unwrap();
+
+    sm.begin_pending(StateMachine::begin_recording).unwrap();
+    sm.rollback();
+    assert_eq!(sm.history().len(), 1);
+
+    sm.begin_pending(StateMachine::begin_recording).unwrap();
+    sm.commit().unwrap();
+    assert_eq!(sm.history().len(), 2);
+    assert!(sm.detail().started_at_ms().is_some());
+}
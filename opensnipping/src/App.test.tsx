@@ -6,11 +6,24 @@ import App from "./App";
 // Mock is set up in setup.ts
 const mockInvoke = invoke as ReturnType<typeof vi.fn>;
 
+function sessionInfo(state: "idle" | "recording") {
+  const current =
+    state === "recording"
+      ? {
+          state,
+          session_id: "session-1",
+          config: { output_path: "/tmp/in-progress.mp4" },
+          started_at_ms: 0,
+        }
+      : { state };
+  return { current, pending: null, history: [], session_open: false };
+}
+
 describe("App", () => {
   beforeEach(() => {
     mockInvoke.mockClear();
     mockInvoke.mockImplementation((cmd: string) => {
-      if (cmd === "get_state") return Promise.resolve("idle");
+      if (cmd === "get_session_info") return Promise.resolve(sessionInfo("idle"));
       if (cmd === "ping") return Promise.resolve("Pong from Rust!");
       return Promise.resolve("idle");
     });
@@ -63,11 +76,38 @@ describe("App", () => {
     });
   });
 
-  it("fetches initial state on mount", async () => {
+  it("fetches the session on mount", async () => {
+    render(<App />);
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("get_session_info");
+    });
+  });
+
+  it("takes a screenshot from a running recording", async () => {
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "get_session_info") return Promise.resolve(sessionInfo("recording"));
+      return Promise.resolve("idle");
+    });
+    render(<App />);
+
+    fireEvent.click(await screen.findByRole("button", { name: "Screenshot" }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("take_screenshot", expect.anything());
+    });
+  });
+
+  it("restores a recording in progress on mount", async () => {
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "get_session_info") return Promise.resolve(sessionInfo("recording"));
+      return Promise.resolve("idle");
+    });
+
     render(<App />);
 
     await waitFor(() => {
-      expect(mockInvoke).toHaveBeenCalledWith("get_state");
+      expect(screen.getByText("Recording to: /tmp/in-progress.mp4")).toBeInTheDocument();
     });
   });
 
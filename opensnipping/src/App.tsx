@@ -3,7 +3,7 @@ import "./App.css";
 import { CaptureState } from "./types";
 import {
   ping,
-  getState,
+  getSessionInfo,
   startCapture,
   pauseRecording,
   resumeRecording,
@@ -12,8 +12,6 @@ import {
   takeScreenshot,
   startRecordingVideo,
   stopRecordingVideo,
-  pauseRecordingVideo,
-  resumeRecordingVideo,
   useCaptureEvents,
 } from "./tauri";
 import { AnnotationCanvas } from "./components/AnnotationCanvas";
@@ -77,9 +75,17 @@ function App() {
     onRecordingStopped: handleRecordingStopped,
   });
 
-  // Fetch initial state
+  // Rebuild from the backend's session, e.g. after a window reload
   useEffect(() => {
-    getState().then(setCaptureState);
+    getSessionInfo().then(({ current }) => {
+      setCaptureState(current.state);
+      if (current.state === "recording" || current.state === "paused") {
+        setMode("record");
+        setRecordingPath(current.config.output_path ?? null);
+      } else if (current.state === "error") {
+        setError(current.error.message);
+      }
+    });
   }, []);
 
   async function handlePingRust() {
@@ -93,9 +99,6 @@ function App() {
 
   async function handleStartCapture() {
     try {
-      // Generate unique output path
-      const outputPath = `/tmp/opensnipping-${Date.now()}.mp4`;
-      
       // Start capture (shows portal picker, gets selection, and automatically starts recording)
       // Recording is started immediately in Rust while PipeWire stream is valid
       await startCapture({
@@ -104,7 +107,7 @@ function App() {
         include_cursor: true,
         audio: { system: false, mic: false },
         container: "mp4",
-        output_path: outputPath,
+        // No output_path: the recording is named in the Videos directory
       });
       
       // Recording is now started automatically by start_capture
@@ -116,8 +119,8 @@ function App() {
 
   async function handlePauseRecording() {
     try {
+      // Pauses the pipeline; the state only changes once it has paused
       await pauseRecording();
-      await pauseRecordingVideo();
     } catch (e) {
       setError(String(e));
     }
@@ -126,7 +129,6 @@ function App() {
   async function handleResumeRecording() {
     try {
       await resumeRecording();
-      await resumeRecordingVideo();
     } catch (e) {
       setError(String(e));
     }
@@ -168,7 +170,6 @@ function App() {
         include_cursor: true,
         audio: { system: false, mic: false },
         container: "mp4",
-        output_path: "/tmp/screenshot.png",
       });
     } catch (e) {
       setError(String(e));
@@ -199,6 +200,7 @@ function App() {
       selecting: "Selecting...",
       recording: "Recording",
       paused: "Paused",
+      buffering: "Replay buffer",
       finalizing: "Finalizing...",
       error: "Error",
     };
@@ -261,6 +263,13 @@ function App() {
             <button onClick={handlePauseRecording} className="btn">
               Pause
             </button>
+            <button
+              onClick={handleTakeScreenshot}
+              className="btn"
+              disabled={isCapturingScreenshot}
+            >
+              Screenshot
+            </button>
             <button onClick={handleStopRecording} className="btn btn-danger">
               Stop
             </button>
@@ -271,6 +280,13 @@ function App() {
             <button onClick={handleResumeRecording} className="btn btn-primary">
               Resume
             </button>
+            <button
+              onClick={handleTakeScreenshot}
+              className="btn"
+              disabled={isCapturingScreenshot}
+            >
+              Screenshot
+            </button>
             <button onClick={handleStopRecording} className="btn btn-danger">
               Stop
             </button>
@@ -7,7 +7,21 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import { CaptureConfig, CaptureState } from "../types";
+import {
+  Annotations,
+  CaptureConfig,
+  CaptureState,
+  Profile,
+  RecoveredRecording,
+  Redaction,
+  ScheduledCapture,
+  ScreenshotConfig,
+  ScreenshotFormat,
+  ScreenshotResult,
+  SelectionResult,
+  SessionInfo,
+  Settings,
+} from "../types";
 
 // ─────────────────────────────────────────────────────────────
 // Command names (must match Rust #[tauri::command] names)
@@ -15,16 +29,44 @@ import { CaptureConfig, CaptureState } from "../types";
 
 export const CMD_PING = "ping";
 export const CMD_GET_STATE = "get_state";
+export const CMD_GET_SESSION_INFO = "get_session_info";
 export const CMD_START_CAPTURE = "start_capture";
 export const CMD_PAUSE_RECORDING = "pause_recording";
 export const CMD_RESUME_RECORDING = "resume_recording";
 export const CMD_FINALIZE_COMPLETE = "finalize_complete";
 export const CMD_RESET_ERROR = "reset_error";
 export const CMD_TAKE_SCREENSHOT = "take_screenshot";
+export const CMD_GET_SCREENSHOT_FORMATS = "get_screenshot_formats";
+export const CMD_COPY_SCREENSHOT = "copy_screenshot";
+export const CMD_EXPORT_ANNOTATED_SCREENSHOT = "export_annotated_screenshot";
+export const CMD_REDACT_SCREENSHOT = "redact_screenshot";
 export const CMD_START_RECORDING_VIDEO = "start_recording_video";
 export const CMD_STOP_RECORDING_VIDEO = "stop_recording_video";
 export const CMD_PAUSE_RECORDING_VIDEO = "pause_recording_video";
 export const CMD_RESUME_RECORDING_VIDEO = "resume_recording_video";
+export const CMD_START_AUDIO_RECORDING = "start_audio_recording";
+export const CMD_GET_RECOVERED_RECORDINGS = "get_recovered_recordings";
+export const CMD_START_REPLAY_BUFFER = "start_replay_buffer";
+export const CMD_SAVE_REPLAY = "save_replay";
+export const CMD_STOP_REPLAY_BUFFER = "stop_replay_buffer";
+export const CMD_SCHEDULE_CAPTURE = "schedule_capture";
+export const CMD_CANCEL_SCHEDULED_CAPTURE = "cancel_scheduled_capture";
+export const CMD_LIST_SCHEDULED_CAPTURES = "list_scheduled_captures";
+export const CMD_START_TIMELAPSE = "start_timelapse";
+export const CMD_STOP_TIMELAPSE = "stop_timelapse";
+export const CMD_OPEN_CAPTURE_SESSION = "open_capture_session";
+export const CMD_CLOSE_CAPTURE_SESSION = "close_capture_session";
+export const CMD_GET_SETTINGS = "get_settings";
+export const CMD_SAVE_SETTINGS = "save_settings";
+export const CMD_LIST_PROFILES = "list_profiles";
+export const CMD_GET_PROFILE = "get_profile";
+export const CMD_START_PROFILE_CAPTURE = "start_profile_capture";
+export const CMD_CREATE_PROFILE = "create_profile";
+export const CMD_UPDATE_PROFILE = "update_profile";
+export const CMD_DELETE_PROFILE = "delete_profile";
+export const CMD_SET_DEFAULT_PROFILE = "set_default_profile";
+export const CMD_EXPORT_PROFILES = "export_profiles";
+export const CMD_IMPORT_PROFILES = "import_profiles";
 
 // ─────────────────────────────────────────────────────────────
 // Typed command wrappers
@@ -40,17 +82,28 @@ export function getState(): Promise<CaptureState> {
   return invoke<CaptureState>(CMD_GET_STATE);
 }
 
-/** Start capture with the given config. Returns the new state. */
-export function startCapture(config: CaptureConfig): Promise<CaptureState> {
+/**
+ * Get the current state with its session payload and recent transitions.
+ * Use on startup to rebuild the UI after a window reload.
+ */
+export function getSessionInfo(): Promise<SessionInfo> {
+  return invoke<SessionInfo>(CMD_GET_SESSION_INFO);
+}
+
+/**
+ * Start capture with the given config, or the default profile's when
+ * omitted. Returns the new state.
+ */
+export function startCapture(config?: CaptureConfig): Promise<CaptureState> {
   return invoke<CaptureState>(CMD_START_CAPTURE, { config });
 }
 
-/** Pause the current recording (state machine transition). */
+/** Pause the recording pipeline; the state moves to paused once it has. */
 export function pauseRecording(): Promise<void> {
   return invoke(CMD_PAUSE_RECORDING);
 }
 
-/** Resume a paused recording (state machine transition). */
+/** Resume the recording pipeline; the state moves to recording once it has. */
 export function resumeRecording(): Promise<void> {
   return invoke(CMD_RESUME_RECORDING);
 }
@@ -65,14 +118,55 @@ export function resetError(): Promise<void> {
   return invoke(CMD_RESET_ERROR);
 }
 
-/** Take a screenshot with the given config. */
-export function takeScreenshot(config: CaptureConfig): Promise<void> {
-  return invoke(CMD_TAKE_SCREENSHOT, { config });
+/**
+ * Take a screenshot with the given config (the default profile's when
+ * omitted), optionally after a countdown.
+ * While a video recording runs, the frame comes from the recording without
+ * interrupting it.
+ */
+export function takeScreenshot(config?: CaptureConfig, delaySecs?: number): Promise<void> {
+  return invoke(CMD_TAKE_SCREENSHOT, { config, delaySecs });
+}
+
+/** Screenshot formats this system can encode (lossy WebP needs GStreamer's webpenc). */
+export function getScreenshotFormats(): Promise<ScreenshotFormat[]> {
+  return invoke(CMD_GET_SCREENSHOT_FORMATS);
+}
+
+/**
+ * Draw the strokes onto the last screenshot at its native resolution and
+ * save the result (PNG unless `screenshot` says otherwise).
+ */
+export function exportAnnotatedScreenshot(
+  annotations: Annotations,
+  screenshot?: ScreenshotConfig
+): Promise<ScreenshotResult> {
+  return invoke(CMD_EXPORT_ANNOTATED_SCREENSHOT, { annotations, screenshot });
+}
+
+/**
+ * Blur, pixelate or fill areas of the last screenshot and save the result
+ * (PNG unless `screenshot` says otherwise). The redacted copy replaces the
+ * last screenshot for later copies and exports.
+ */
+export function redactScreenshot(
+  redactions: Redaction[],
+  screenshot?: ScreenshotConfig
+): Promise<ScreenshotResult> {
+  return invoke(CMD_REDACT_SCREENSHOT, { redactions, screenshot });
+}
+
+/**
+ * Copy the last screenshot to the clipboard as PNG. With `withFileUri`,
+ * the saved file is offered as a text/uri-list too.
+ */
+export function copyScreenshot(withFileUri?: boolean): Promise<void> {
+  return invoke(CMD_COPY_SCREENSHOT, { withFileUri });
 }
 
-/** Start actual video recording (after portal selection). */
-export function startRecordingVideo(): Promise<void> {
-  return invoke(CMD_START_RECORDING_VIDEO);
+/** Start actual video recording (after portal selection), optionally after a countdown. */
+export function startRecordingVideo(delaySecs?: number): Promise<void> {
+  return invoke(CMD_START_RECORDING_VIDEO, { delaySecs });
 }
 
 /** Stop video recording and finalize output. */
@@ -80,12 +174,162 @@ export function stopRecordingVideo(): Promise<void> {
   return invoke(CMD_STOP_RECORDING_VIDEO);
 }
 
-/** Pause the video recording pipeline. */
+/** Same as pauseRecording, kept for existing callers. */
 export function pauseRecordingVideo(): Promise<void> {
   return invoke(CMD_PAUSE_RECORDING_VIDEO);
 }
 
-/** Resume the video recording pipeline. */
+/** Same as resumeRecording, kept for existing callers. */
 export function resumeRecordingVideo(): Promise<void> {
   return invoke(CMD_RESUME_RECORDING_VIDEO);
 }
+
+/**
+ * Start an audio-only recording (no portal picker) with the given config,
+ * or the default profile's when omitted. Returns the new state.
+ */
+export function startAudioRecording(config?: CaptureConfig): Promise<CaptureState> {
+  return invoke<CaptureState>(CMD_START_AUDIO_RECORDING, { config });
+}
+
+/**
+ * Recordings recovered at startup after a crash. Call after subscribing to
+ * capture:recordings_recovered, which only reaches listeners that were
+ * already subscribed when recovery finished.
+ */
+export function getRecoveredRecordings(): Promise<RecoveredRecording[]> {
+  return invoke<RecoveredRecording[]>(CMD_GET_RECOVERED_RECORDINGS);
+}
+
+/**
+ * Pick a source and keep its last N seconds in memory, configured by the
+ * default profile when `config` is omitted. Returns the new state.
+ */
+export function startReplayBuffer(config?: CaptureConfig): Promise<CaptureState> {
+  return invoke<CaptureState>(CMD_START_REPLAY_BUFFER, { config });
+}
+
+/**
+ * Write the buffered replay to a file; buffering continues. Without a path
+ * each save gets a new file named from the buffer's `naming`.
+ */
+export function saveReplay(outputPath?: string): Promise<void> {
+  return invoke(CMD_SAVE_REPLAY, { outputPath });
+}
+
+/** Stop the replay buffer, discarding anything not saved. Returns the new state. */
+export function stopReplayBuffer(): Promise<CaptureState> {
+  return invoke<CaptureState>(CMD_STOP_REPLAY_BUFFER);
+}
+
+/**
+ * Record at `startAtMs` (Unix epoch ms) for `durationSecs`. A null config
+ * schedules the default profile as it is now.
+ */
+export function scheduleCapture(
+  config: CaptureConfig | null,
+  startAtMs: number,
+  durationSecs: number
+): Promise<ScheduledCapture> {
+  return invoke<ScheduledCapture>(CMD_SCHEDULE_CAPTURE, { config, startAtMs, durationSecs });
+}
+
+/** Cancel a scheduled capture that has not started yet. */
+export function cancelScheduledCapture(id: string): Promise<void> {
+  return invoke(CMD_CANCEL_SCHEDULED_CAPTURE, { id });
+}
+
+/** Scheduled captures still waiting to start, soonest first. */
+export function listScheduledCaptures(): Promise<ScheduledCapture[]> {
+  return invoke<ScheduledCapture[]>(CMD_LIST_SCHEDULED_CAPTURES);
+}
+
+/**
+ * Pick a source and grab a frame every interval, configured by the default
+ * profile when `config` is omitted. Returns the new state.
+ */
+export function startTimelapse(config?: CaptureConfig): Promise<CaptureState> {
+  return invoke<CaptureState>(CMD_START_TIMELAPSE, { config });
+}
+
+/** End a timelapse early; frames are finalized in the background. */
+export function stopTimelapse(): Promise<CaptureState> {
+  return invoke<CaptureState>(CMD_STOP_TIMELAPSE);
+}
+
+/**
+ * Pick a source once (with the default profile's source and cursor when
+ * `config` is omitted); later captures reuse it until the session closes.
+ */
+export function openCaptureSession(config?: CaptureConfig): Promise<SelectionResult> {
+  return invoke<SelectionResult>(CMD_OPEN_CAPTURE_SESSION, { config });
+}
+
+/** Close the session opened with `openCaptureSession`. */
+export function closeCaptureSession(): Promise<void> {
+  return invoke(CMD_CLOSE_CAPTURE_SESSION);
+}
+
+/**
+ * Saved settings, or the defaults when nothing is saved yet. Rejects when the
+ * file is invalid or from a newer version; don't save over it then.
+ */
+export function getSettings(): Promise<Settings> {
+  return invoke<Settings>(CMD_GET_SETTINGS);
+}
+
+/** Validate and save settings. Returns them as stored. */
+export function saveSettings(settings: Settings): Promise<Settings> {
+  return invoke<Settings>(CMD_SAVE_SETTINGS, { settings });
+}
+
+/** Every saved profile, in the order they were created. */
+export function listProfiles(): Promise<Profile[]> {
+  return invoke<Profile[]>(CMD_LIST_PROFILES);
+}
+
+/** The profile called `name` (ignoring case); rejects when there is none. */
+export function getProfile(name: string): Promise<Profile> {
+  return invoke<Profile>(CMD_GET_PROFILE, { name });
+}
+
+/**
+ * Start whatever the profile called `name` captures: video, audio-only,
+ * replay buffer or timelapse. Returns the new state.
+ */
+export function startProfileCapture(name: string): Promise<CaptureState> {
+  return invoke<CaptureState>(CMD_START_PROFILE_CAPTURE, { name });
+}
+
+/** Add a named profile. Returns the saved settings. */
+export function createProfile(profile: Profile): Promise<Settings> {
+  return invoke<Settings>(CMD_CREATE_PROFILE, { profile });
+}
+
+/** Replace the profile called `name`; `profile.name` may rename it. */
+export function updateProfile(name: string, profile: Profile): Promise<Settings> {
+  return invoke<Settings>(CMD_UPDATE_PROFILE, { name, profile });
+}
+
+/** Delete a profile; the default profile can't be deleted. */
+export function deleteProfile(name: string): Promise<Settings> {
+  return invoke<Settings>(CMD_DELETE_PROFILE, { name });
+}
+
+/** Make `name` the profile new captures start from. */
+export function setDefaultProfile(name: string): Promise<Settings> {
+  return invoke<Settings>(CMD_SET_DEFAULT_PROFILE, { name });
+}
+
+/** Profiles as ProfileExport JSON to share; all of them when `names` is empty. */
+export function exportProfiles(names: string[] = []): Promise<string> {
+  return invoke<string>(CMD_EXPORT_PROFILES, { names });
+}
+
+/**
+ * Add the profiles of exported JSON. Taken names reject unless `replace` is
+ * set; nothing is imported when any profile is invalid.
+ */
+export function importProfiles(json: string, replace = false): Promise<Settings> {
+  return invoke<Settings>(CMD_IMPORT_PROFILES, { json, replace });
+}
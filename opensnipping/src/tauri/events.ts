@@ -15,12 +15,38 @@ import {
   ScreenshotCompleteEvent,
   RecordingStartedEvent,
   RecordingStoppedEvent,
+  RecordingsRecoveredEvent,
+  RecoveredRecording,
+  SegmentClosedEvent,
+  RecordingSegment,
+  AvDrift,
+  AvDriftEvent,
+  ReplaySavedEvent,
+  DiskSpaceLowEvent,
+  StopReason,
+  CountdownEvent,
+  ScheduledCapture,
+  ScheduledCaptureStartedEvent,
+  TimelapseFrameEvent,
+  TimelapseCompleteEvent,
+  SessionCloseReason,
+  SessionClosedEvent,
   EVENT_STATE_CHANGED,
   EVENT_ERROR,
   EVENT_SELECTION_COMPLETE,
   EVENT_SCREENSHOT_COMPLETE,
   EVENT_RECORDING_STARTED,
   EVENT_RECORDING_STOPPED,
+  EVENT_RECORDINGS_RECOVERED,
+  EVENT_SEGMENT_CLOSED,
+  EVENT_AV_DRIFT,
+  EVENT_REPLAY_SAVED,
+  EVENT_DISK_SPACE_LOW,
+  EVENT_COUNTDOWN,
+  EVENT_SCHEDULED_CAPTURE_STARTED,
+  EVENT_TIMELAPSE_FRAME,
+  EVENT_TIMELAPSE_COMPLETE,
+  EVENT_SESSION_CLOSED,
 } from "../types";
 
 // ─────────────────────────────────────────────────────────────
@@ -33,7 +59,23 @@ export interface CaptureEventHandlers {
   onSelectionComplete?: (selection: SelectionCompleteEvent["selection"]) => void;
   onScreenshotComplete?: (path: string, width: number, height: number) => void;
   onRecordingStarted?: (outputPath: string) => void;
-  onRecordingStopped?: (path: string, durationMs: number, width: number, height: number) => void;
+  onRecordingStopped?: (
+    path: string,
+    durationMs: number,
+    width: number,
+    height: number,
+    reason: StopReason
+  ) => void;
+  onRecordingsRecovered?: (recordings: RecoveredRecording[]) => void;
+  onSegmentClosed?: (segment: RecordingSegment) => void;
+  onAvDrift?: (drift: AvDrift) => void;
+  onReplaySaved?: (path: string, durationMs: number, width: number, height: number) => void;
+  onDiskSpaceLow?: (freeBytes: number, remainingSecs: number) => void;
+  onCountdown?: (remainingSecs: number, totalSecs: number) => void;
+  onScheduledCaptureStarted?: (capture: ScheduledCapture) => void;
+  onTimelapseFrame?: (index: number, frameCount: number, path: string) => void;
+  onTimelapseComplete?: (event: TimelapseCompleteEvent) => void;
+  onSessionClosed?: (reason: SessionCloseReason) => void;
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -92,6 +134,32 @@ export function useCaptureEvents(handlers: CaptureEventHandlers): void {
     // Recording stopped
     listen<RecordingStoppedEvent>(EVENT_RECORDING_STOPPED, (event) => {
       handlersRef.current.onRecordingStopped?.(
+        event.payload.path,
+        event.payload.duration_ms,
+        event.payload.width,
+        event.payload.height,
+        event.payload.reason
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Recordings recovered after a crash
+    listen<RecordingsRecoveredEvent>(EVENT_RECORDINGS_RECOVERED, (event) => {
+      handlersRef.current.onRecordingsRecovered?.(event.payload.recordings);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Segment file closed during a segmented recording
+    listen<SegmentClosedEvent>(EVENT_SEGMENT_CLOSED, (event) => {
+      handlersRef.current.onSegmentClosed?.(event.payload.segment);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // A/V drift reported about once a second while recording
+    listen<AvDriftEvent>(EVENT_AV_DRIFT, (event) => {
+      handlersRef.current.onAvDrift?.(event.payload.drift);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Replay buffer written to a file
+    listen<ReplaySavedEvent>(EVENT_REPLAY_SAVED, (event) => {
+      handlersRef.current.onReplaySaved?.(
         event.payload.path,
         event.payload.duration_ms,
         event.payload.width,
@@ -99,6 +167,46 @@ export function useCaptureEvents(handlers: CaptureEventHandlers): void {
       );
     }).then((unlisten) => unlisteners.push(unlisten));
 
+    // Free disk space running low during a recording
+    listen<DiskSpaceLowEvent>(EVENT_DISK_SPACE_LOW, (event) => {
+      handlersRef.current.onDiskSpaceLow?.(
+        event.payload.free_bytes,
+        event.payload.remaining_secs
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Delayed capture countdown
+    listen<CountdownEvent>(EVENT_COUNTDOWN, (event) => {
+      handlersRef.current.onCountdown?.(
+        event.payload.remaining_secs,
+        event.payload.total_secs
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Scheduled capture started
+    listen<ScheduledCaptureStartedEvent>(EVENT_SCHEDULED_CAPTURE_STARTED, (event) => {
+      handlersRef.current.onScheduledCaptureStarted?.(event.payload.capture);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Timelapse frame written
+    listen<TimelapseFrameEvent>(EVENT_TIMELAPSE_FRAME, (event) => {
+      handlersRef.current.onTimelapseFrame?.(
+        event.payload.index,
+        event.payload.frame_count,
+        event.payload.path
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Timelapse finished
+    listen<TimelapseCompleteEvent>(EVENT_TIMELAPSE_COMPLETE, (event) => {
+      handlersRef.current.onTimelapseComplete?.(event.payload);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Capture session closed
+    listen<SessionClosedEvent>(EVENT_SESSION_CLOSED, (event) => {
+      handlersRef.current.onSessionClosed?.(event.payload.reason);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
     return () => {
       unlisteners.forEach((unlisten) => unlisten());
     };
@@ -113,4 +221,14 @@ export {
   EVENT_SCREENSHOT_COMPLETE,
   EVENT_RECORDING_STARTED,
   EVENT_RECORDING_STOPPED,
+  EVENT_RECORDINGS_RECOVERED,
+  EVENT_SEGMENT_CLOSED,
+  EVENT_AV_DRIFT,
+  EVENT_REPLAY_SAVED,
+  EVENT_DISK_SPACE_LOW,
+  EVENT_COUNTDOWN,
+  EVENT_SCHEDULED_CAPTURE_STARTED,
+  EVENT_TIMELAPSE_FRAME,
+  EVENT_TIMELAPSE_COMPLETE,
+  EVENT_SESSION_CLOSED,
 } from "../types";
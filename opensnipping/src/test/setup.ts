@@ -5,14 +5,46 @@ import { vi } from "vitest";
 vi.mock("@tauri-apps/api/core", () => ({
   invoke: vi.fn().mockImplementation((cmd: string) => {
     if (cmd === "get_state") return Promise.resolve("idle");
+    if (cmd === "get_session_info") {
+      return Promise.resolve({
+        current: { state: "idle" },
+        pending: null,
+        history: [],
+        session_open: false,
+      });
+    }
     if (cmd === "ping") return Promise.resolve("Pong from Rust!");
     if (cmd === "take_screenshot") {
       return Promise.resolve({
         path: "/tmp/test-screenshot.png",
         width: 800,
         height: 600,
+        format: "png",
+        byte_size: 1024,
       });
     }
+    if (cmd === "get_settings") {
+      return Promise.resolve({
+        version: 2,
+        profiles: [
+          {
+            name: "Default",
+            config: {
+              source: "screen",
+              fps: 30,
+              include_cursor: true,
+              audio: { system: false, mic: false },
+              container: "mp4",
+            },
+          },
+        ],
+        default_profile: "Default",
+        hotkeys: {},
+      });
+    }
+    if (cmd === "get_screenshot_formats") {
+      return Promise.resolve(["png", "jpeg", "webp_lossless", "avif"]);
+    }
     if (cmd === "start_recording_video") {
       return Promise.resolve();
     }
@@ -30,6 +62,49 @@ vi.mock("@tauri-apps/api/core", () => ({
     if (cmd === "resume_recording_video") {
       return Promise.resolve();
     }
+    if (cmd === "start_audio_recording") {
+      return Promise.resolve("recording");
+    }
+    if (cmd === "start_replay_buffer") {
+      return Promise.resolve("buffering");
+    }
+    if (cmd === "save_replay") {
+      return Promise.resolve({
+        path: "/tmp/test-replay.mp4",
+        duration_ms: 30000,
+        width: 1920,
+        height: 1080,
+      });
+    }
+    if (cmd === "stop_replay_buffer") {
+      return Promise.resolve("idle");
+    }
+    if (cmd === "schedule_capture") {
+      return Promise.resolve({
+        id: "test-schedule",
+        start_at_ms: 0,
+        duration_secs: 60,
+        config: null,
+      });
+    }
+    if (cmd === "cancel_scheduled_capture") {
+      return Promise.resolve();
+    }
+    if (cmd === "list_scheduled_captures") {
+      return Promise.resolve([]);
+    }
+    if (cmd === "start_timelapse") {
+      return Promise.resolve("recording");
+    }
+    if (cmd === "stop_timelapse") {
+      return Promise.resolve("finalizing");
+    }
+    if (cmd === "open_capture_session") {
+      return Promise.resolve({ node_id: 42, stream_fd: null, width: 1920, height: 1080 });
+    }
+    if (cmd === "close_capture_session") {
+      return Promise.resolve();
+    }
     return Promise.resolve(null);
   }),
   convertFileSrc: vi.fn().mockImplementation((path: string) => {
@@ -1,12 +1,14 @@
 // Type definitions for capture API
 
 export type CaptureSource = "screen" | "monitor" | "window" | "region";
-export type ContainerFormat = "mp4" | "mkv";
+export type ContainerFormat = "mp4" | "mkv" | "ogg" | "m4a" | "wav";
+export type CaptureMode = "video" | "audio_only" | "replay_buffer" | "timelapse";
 export type CaptureState =
   | "idle"
   | "selecting"
   | "recording"
   | "paused"
+  | "buffering"
   | "finalizing"
   | "error";
 
@@ -15,13 +17,174 @@ export interface AudioConfig {
   mic: boolean;
 }
 
+export interface SegmentConfig {
+  max_duration_secs?: number | null;
+  max_size_bytes?: number | null;
+  /** Path of each segment; `{index}` is replaced by the segment number */
+  filename_template?: string | null;
+}
+
+export interface TimelapseConfig {
+  /** Seconds between frames (1-86400) */
+  interval_secs: number;
+  /** Frames to capture before the timelapse ends (1-99999) */
+  frame_count: number;
+  /** Encode the frames into `output_path`, played back at `fps` */
+  encode_video?: boolean;
+  /** Directory for the numbered PNGs; defaults to `<output path>-frames` */
+  frames_dir?: string | null;
+}
+
+export type ScreenshotFormat = "png" | "jpeg" | "webp" | "webp_lossless" | "avif";
+
+/**
+ * Capture details embedded in saved screenshots: PNG tEXt chunks or JPEG
+ * EXIF (WebP and AVIF never carry any). Included fields default to true.
+ */
+export type MetadataPolicy =
+  | { policy: "strip" }
+  | { policy: "include"; capture_time?: boolean; source?: boolean; app_version?: boolean };
+
+/**
+ * Trimming of transparent shadow margins and solid borders before saving.
+ * "windows" only trims window captures.
+ */
+export interface AutoCropConfig {
+  /** Default "off" */
+  mode?: "off" | "windows" | "always";
+  /** Per-channel difference still counted as border (0-255, default 8) */
+  tolerance?: number;
+  /** Pixels added on every side of the content (0-512, default 0) */
+  padding?: number;
+  /** "#rrggbb" or "#rrggbbaa" behind the padding; transparent when unset */
+  background?: string | null;
+}
+
+/**
+ * Save directory and filename template of captures without an explicit
+ * path. Unset fields default to the XDG Pictures (screenshots) or Videos
+ * (recordings) directory and "Screenshot_{date}_{time}.{ext}" or
+ * "Recording_{date}_{time}.{ext}".
+ */
+export interface OutputNaming {
+  /** A leading "~/" is the home directory; missing directories are created */
+  directory?: string | null;
+  /**
+   * Tokens: {date}, {time}, {source}, {counter}, {ext}; "/" makes
+   * subdirectories. Taken names get the next {counter} or a "-2" suffix.
+   */
+  template?: string | null;
+}
+
+export interface ScreenshotConfig {
+  /** Defaults to "png"; "webp" (lossy) needs GStreamer's webpenc */
+  format?: ScreenshotFormat;
+  /** Quality of JPEG, lossy WebP and AVIF (1-100, default 90) */
+  quality?: number;
+  /** PNG deflate level (0 stores uncompressed, 9 is smallest; default 6) */
+  png_compression?: number;
+  /** Copy each screenshot to the clipboard once it is saved (default false) */
+  copy_to_clipboard?: boolean;
+  /** Offer the saved file as a text/uri-list next to the image (default false) */
+  copy_file_uri?: boolean;
+  /** Defaults to { policy: "strip" }: no metadata at all */
+  metadata?: MetadataPolicy;
+  /** Border trimming; off by default */
+  auto_crop?: AutoCropConfig;
+  /** Where screenshots are saved and how they are named */
+  naming?: OutputNaming;
+}
+
+/** H.264 encoder; "auto" tries VA-API, then NVENC, then x264 */
+export type VideoEncoder = "auto" | "x264" | "vaapi" | "nvenc";
+
+/** "balanced" keeps the encoder's own default preset */
+export type EncoderPreset = "fast" | "balanced" | "quality";
+
+export interface EncoderConfig {
+  /** Defaults to "auto"; an explicit encoder that is not installed fails the capture */
+  encoder?: VideoEncoder;
+  /** Target bitrate in kbit/s (100-100000); omit for the encoder's default */
+  bitrate_kbps?: number | null;
+  /** Defaults to "balanced" */
+  preset?: EncoderPreset;
+}
+
 export interface CaptureConfig {
   source: CaptureSource;
   fps: number;
   include_cursor: boolean;
   audio: AudioConfig;
   container: ContainerFormat;
-  output_path: string;
+  /** Omit (or leave empty) to name the recording from `naming` */
+  output_path?: string;
+  /** Where recordings without an `output_path` are saved and how they are named */
+  naming?: OutputNaming;
+  /** Defaults to "video" when omitted */
+  mode?: CaptureMode;
+  /** Write fragmented MP4 / streamable MKV so a crash leaves a playable file */
+  crash_safe?: boolean;
+  /** Rotate the output into several files; omit for a single file */
+  segments?: SegmentConfig | null;
+  /** Seconds kept in memory in replay buffer mode (5-600, default 30) */
+  replay_buffer_secs?: number;
+  /** Stop after this many seconds of recording (pauses excluded) */
+  max_duration_secs?: number | null;
+  /** Stop once the output reaches this size (all segments together when segmented) */
+  max_file_size_bytes?: number | null;
+  /** Interval and frame count for timelapse mode */
+  timelapse?: TimelapseConfig | null;
+  /** Format, quality and compression of screenshots */
+  screenshot?: ScreenshotConfig;
+  /** Video encoder preferences; omit to use the default profile's */
+  encoder?: EncoderConfig | null;
+}
+
+/** Position in screenshot pixels, from the top-left corner */
+export interface AnnotationPoint {
+  x: number;
+  y: number;
+}
+
+export interface AnnotationStroke {
+  points: AnnotationPoint[];
+  /** "#rrggbb" or "#rrggbbaa" */
+  color: string;
+  /** Pen width in screenshot pixels */
+  width: number;
+}
+
+/** Strokes drawn on a screenshot; the backend renders only `strokes` */
+export interface Annotations {
+  strokes: AnnotationStroke[];
+  /** Strokes taken back by undo, most recent last */
+  undone?: AnnotationStroke[];
+}
+
+/** Area of a screenshot, in screenshot pixels */
+export interface Rect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/**
+ * Area to hide. Blur and pixelate keep only block averages (radius or
+ * block size 2-256), so the original cannot be recovered.
+ */
+export type Redaction = { rect: Rect } & (
+  | { style: "blur"; radius: number }
+  | { style: "pixelate"; block_size: number }
+  | { style: "fill"; color: string }
+);
+
+export interface ScreenshotResult {
+  path: string;
+  width: number;
+  height: number;
+  format: ScreenshotFormat;
+  byte_size: number;
 }
 
 export type ErrorCode =
@@ -38,6 +201,54 @@ export interface CaptureError {
   message: string;
 }
 
+/**
+ * Current state with its payload (`state` is the tag). Times are ms since the
+ * epoch; `paused_ms` is the time spent paused before the current state.
+ */
+export type StateDetail =
+  | { state: "idle" }
+  | { state: "selecting"; session_id: string; config: CaptureConfig }
+  | {
+      state: "recording";
+      session_id: string;
+      config: CaptureConfig;
+      started_at_ms: number;
+      paused_ms: number;
+    }
+  | {
+      state: "paused";
+      session_id: string;
+      config: CaptureConfig;
+      started_at_ms: number;
+      paused_at_ms: number;
+      paused_ms: number;
+    }
+  | { state: "buffering"; session_id: string; config: CaptureConfig; started_at_ms: number }
+  | {
+      state: "finalizing";
+      session_id: string;
+      config: CaptureConfig;
+      started_at_ms: number;
+      paused_ms: number;
+    }
+  | { state: "error"; error: CaptureError; session_id: string | null };
+
+export interface TransitionRecord {
+  from: CaptureState;
+  to: CaptureState;
+  at_ms: number;
+  session_id: string | null;
+}
+
+export interface SessionInfo {
+  current: StateDetail;
+  /** State a transition waiting on the backend moves to */
+  pending: CaptureState | null;
+  /** Last transitions, oldest first */
+  history: TransitionRecord[];
+  session_open: boolean;
+}
+
 export interface StateChangedEvent {
   state: CaptureState;
   previous: CaptureState;
@@ -73,17 +284,157 @@ export interface ScreenshotCompleteEvent {
   path: string;
   width: number;
   height: number;
+  format: ScreenshotFormat;
+  byte_size: number;
 }
 
 export interface RecordingStartedEvent {
   output_path: string;
 }
 
+export type StopReason = "user" | "limit" | "error" | "session_closed";
+
 export interface RecordingStoppedEvent {
+  /** Saved file, or the `{index}` template of a segmented recording's files */
   path: string;
+  /** Time spent recording, not counting pauses */
   duration_ms: number;
   width: number;
   height: number;
+  reason: StopReason;
+}
+
+export interface RecordingSegment {
+  index: number;
+  path: string;
+  duration_ms: number;
+}
+
+export interface SegmentClosedEvent {
+  segment: RecordingSegment;
+}
+
+/** A/V drift of a running recording with both audio and video */
+export interface AvDrift {
+  drift_ms: number;
+  max_drift_ms: number;
+  exceeded_tolerance: boolean;
+}
+
+export interface AvDriftEvent {
+  drift: AvDrift;
+}
+
+export interface DiskSpaceLowEvent {
+  output_path: string;
+  free_bytes: number;
+  /** Estimated recording time left before the recording is stopped */
+  remaining_secs: number;
+}
+
+export interface ReplaySavedEvent {
+  path: string;
+  duration_ms: number;
+  width: number;
+  height: number;
+}
+
+export interface CountdownEvent {
+  /** Seconds left; the final tick is 0, right before the capture */
+  remaining_secs: number;
+  total_secs: number;
+}
+
+export interface ScheduledCapture {
+  id: string;
+  /** Milliseconds since the Unix epoch */
+  start_at_ms: number;
+  duration_secs: number;
+  config: CaptureConfig;
+}
+
+export interface ScheduledCaptureStartedEvent {
+  capture: ScheduledCapture;
+}
+
+export interface TimelapseFrameEvent {
+  /** 1-based frame number */
+  index: number;
+  frame_count: number;
+  path: string;
+}
+
+export interface TimelapseVideo {
+  path: string;
+  duration_ms: number;
+  width: number;
+  height: number;
+}
+
+export interface TimelapseCompleteEvent {
+  frames_dir: string;
+  /** Frames actually captured */
+  frames: number;
+  /** The encoded video, when `encode_video` was set and encoding succeeded */
+  video: TimelapseVideo | null;
+  reason: StopReason;
+}
+
+export type SessionCloseReason = "user" | "portal";
+
+export interface SessionClosedEvent {
+  reason: SessionCloseReason;
+}
+
+export type RecoveryOutcome =
+  | { status: "remuxed" }
+  | { status: "already_playable" }
+  | { status: "missing" }
+  | { status: "failed"; message: string };
+
+/** Global shortcuts such as "Ctrl+Shift+S"; unset actions have none */
+export interface Hotkeys {
+  screenshot?: string | null;
+  toggle_recording?: string | null;
+  toggle_pause?: string | null;
+}
+
+/**
+ * A named capture setup such as "Tutorial" or "Bug report". Output locations
+ * are `config.naming` and `config.screenshot.naming`; `config.output_path`
+ * must stay empty.
+ */
+export interface Profile {
+  /** Unique ignoring case, at most 64 characters */
+  name: string;
+  config: CaptureConfig;
+}
+
+/** Profiles shared between installs (`exportProfiles` / `importProfiles`) */
+export interface ProfileExport {
+  format: "opensnipping-profiles";
+  version: number;
+  profiles: Profile[];
+}
+
+/** Saved profiles and shortcuts, stored in $XDG_CONFIG_HOME/opensnipping/settings.json */
+export interface Settings {
+  /** Schema version, set by the backend on save */
+  version: number;
+  /** Never empty */
+  profiles: Profile[];
+  /** Name of the profile new captures start from */
+  default_profile: string;
+  hotkeys: Hotkeys;
+}
+
+export interface RecoveredRecording {
+  path: string;
+  outcome: RecoveryOutcome;
+}
+
+export interface RecordingsRecoveredEvent {
+  recordings: RecoveredRecording[];
 }
 
 // Event names
@@ -95,3 +446,13 @@ export const EVENT_SELECTION_COMPLETE = "capture:selection_complete";
 export const EVENT_SCREENSHOT_COMPLETE = "capture:screenshot_complete";
 export const EVENT_RECORDING_STARTED = "capture:recording_started";
 export const EVENT_RECORDING_STOPPED = "capture:recording_stopped";
+export const EVENT_RECORDINGS_RECOVERED = "capture:recordings_recovered";
+export const EVENT_SEGMENT_CLOSED = "capture:segment_closed";
+export const EVENT_AV_DRIFT = "capture:av_drift";
+export const EVENT_REPLAY_SAVED = "capture:replay_saved";
+export const EVENT_DISK_SPACE_LOW = "capture:disk_space_low";
+export const EVENT_COUNTDOWN = "capture:countdown";
+export const EVENT_SCHEDULED_CAPTURE_STARTED = "capture:scheduled_capture_started";
+export const EVENT_TIMELAPSE_FRAME = "capture:timelapse_frame";
+export const EVENT_TIMELAPSE_COMPLETE = "capture:timelapse_complete";
+export const EVENT_SESSION_CLOSED = "capture:session_closed";